@@ -26,6 +26,7 @@ use codex_app_server_protocol::ErrorNotification;
 use codex_app_server_protocol::ExecCommandApprovalParams;
 use codex_app_server_protocol::ExecCommandApprovalResponse;
 use codex_app_server_protocol::ExecPolicyAmendment as V2ExecPolicyAmendment;
+use codex_app_server_protocol::ExecPolicyAmendmentScope;
 use codex_app_server_protocol::FileChangeApprovalDecision;
 use codex_app_server_protocol::FileChangeOutputDeltaNotification;
 use codex_app_server_protocol::FileChangeRequestApprovalParams;
@@ -61,6 +62,7 @@ use codex_app_server_protocol::TurnPlanUpdatedNotification;
 use codex_app_server_protocol::TurnStatus;
 use codex_app_server_protocol::build_turns_from_event_msgs;
 use codex_core::CodexThread;
+use codex_core::default_client::originator;
 use codex_core::parse_command::shlex_join;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
 use codex_core::protocol::CodexErrorInfo as CoreCodexErrorInfo;
@@ -1124,7 +1126,7 @@ async fn on_exec_approval_response(
     if let Err(err) = conversation
         .submit(Op::ExecApproval {
             id: event_turn_id,
-            decision: response.decision,
+            decision: attribute_to_client(response.decision),
         })
         .await
     {
@@ -1132,6 +1134,23 @@ async fn on_exec_approval_response(
     }
 }
 
+/// Records the client that answered an approval request, identified by the
+/// `clientInfo.name` it sent in `initialize`, as the approver of an "always allow" rule.
+fn attribute_to_client(decision: ReviewDecision) -> ReviewDecision {
+    match decision {
+        ReviewDecision::ApprovedExecpolicyAmendment {
+            proposed_execpolicy_amendment,
+            scope,
+            approved_by,
+        } => ReviewDecision::ApprovedExecpolicyAmendment {
+            proposed_execpolicy_amendment,
+            scope,
+            approved_by: approved_by.or_else(|| Some(originator().value)),
+        },
+        other => other,
+    }
+}
+
 const REVIEW_FALLBACK_MESSAGE: &str = "Reviewer failed to output a response.";
 
 fn render_review_output_text(output: &ReviewOutputEvent) -> String {
@@ -1299,10 +1318,13 @@ async fn on_command_execution_request_approval_response(
                 }
                 CommandExecutionApprovalDecision::AcceptWithExecpolicyAmendment {
                     execpolicy_amendment,
+                    scope,
                 } => (
-                    ReviewDecision::ApprovedExecpolicyAmendment {
+                    attribute_to_client(ReviewDecision::ApprovedExecpolicyAmendment {
                         proposed_execpolicy_amendment: execpolicy_amendment.into_core(),
-                    },
+                        scope: scope.map(ExecPolicyAmendmentScope::to_core),
+                        approved_by: None,
+                    }),
                     None,
                 ),
                 CommandExecutionApprovalDecision::Decline => (
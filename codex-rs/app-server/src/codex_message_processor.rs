@@ -2489,6 +2489,7 @@ impl CodexMessageProcessor {
             .chain(snapshot.auth_statuses.keys().cloned())
             .chain(snapshot.resources.keys().cloned())
             .chain(snapshot.resource_templates.keys().cloned())
+            .chain(snapshot.prompts.keys().cloned())
             .collect();
         server_names.sort();
         server_names.dedup();
@@ -2535,6 +2536,7 @@ impl CodexMessageProcessor {
                     .get(name)
                     .cloned()
                     .unwrap_or_default(),
+                prompts: snapshot.prompts.get(name).cloned().unwrap_or_default(),
                 auth_status: snapshot
                     .auth_statuses
                     .get(name)
@@ -3249,6 +3251,7 @@ impl CodexMessageProcessor {
                     model: params.model,
                     effort: params.effort.map(Some),
                     summary: params.summary,
+                    sampling: None,
                 })
                 .await;
         }
@@ -37,6 +37,8 @@ fn preset_to_info(preset: &ModelPreset, priority: i32) -> ModelInfo {
         auto_compact_token_limit: None,
         effective_context_window_percent: 95,
         experimental_supported_tools: Vec::new(),
+        requires_reasoning_content: false,
+        tokenizer: None,
     }
 }
 
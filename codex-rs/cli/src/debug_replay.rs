@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+use codex_api::capture::load_capture;
+use codex_api::capture::replay_exchange;
+use futures::StreamExt;
+
+use crate::ReplayCommand;
+
+/// The replayed stream is read from disk in one chunk, so this only guards
+/// against a parser that never finishes.
+const REPLAY_IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Feeds every exchange in a capture directory back through the stream parser
+/// it was recorded with and prints the resulting events.
+pub async fn run_replay(command: ReplayCommand) -> anyhow::Result<()> {
+    let ReplayCommand { dir, sequence } = command;
+    let exchanges = load_capture(&dir)?;
+    if exchanges.is_empty() {
+        anyhow::bail!("no captured requests found in {}", dir.display());
+    }
+
+    for exchange in exchanges
+        .iter()
+        .filter(|exchange| sequence.is_none_or(|sequence| exchange.sequence == sequence))
+    {
+        println!("== {:04} POST {}", exchange.sequence, exchange.path);
+        if let Some(error) = &exchange.error {
+            println!("request failed: {error}");
+            continue;
+        }
+        if exchange.response.is_none() {
+            println!("no response recorded");
+            continue;
+        }
+
+        let mut stream = replay_exchange(exchange, REPLAY_IDLE_TIMEOUT)?;
+        while let Some(event) = stream.next().await {
+            match event {
+                Ok(event) => println!("{event:?}"),
+                Err(err) => println!("stream error: {err}"),
+            }
+        }
+    }
+
+    Ok(())
+}
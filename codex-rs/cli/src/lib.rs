@@ -1,7 +1,10 @@
+pub mod debug_replay;
 pub mod debug_sandbox;
 mod exit_status;
 pub mod login;
 
+use std::path::PathBuf;
+
 use clap::Parser;
 use codex_common::CliConfigOverrides;
 
@@ -50,3 +53,15 @@ pub struct WindowsCommand {
     #[arg(trailing_var_arg = true)]
     pub command: Vec<String>,
 }
+
+#[derive(Debug, Parser)]
+pub struct ReplayCommand {
+    /// Capture directory, usually `$CODEX_HOME/captures/<thread id>` written
+    /// with the `traffic_capture` feature enabled.
+    #[arg(value_name = "DIR")]
+    pub dir: PathBuf,
+
+    /// Replay only the exchange with this sequence number.
+    #[arg(long = "sequence", value_name = "N")]
+    pub sequence: Option<u64>,
+}
@@ -7,6 +7,7 @@ use codex_arg0::arg0_dispatch_or_else;
 use codex_chatgpt::apply_command::ApplyCommand;
 use codex_chatgpt::apply_command::run_apply_command;
 use codex_cli::LandlockCommand;
+use codex_cli::ReplayCommand;
 use codex_cli::SeatbeltCommand;
 use codex_cli::WindowsCommand;
 use codex_cli::login::read_api_key_from_stdin;
@@ -18,11 +19,21 @@ use codex_cli::login::run_login_with_device_code_fallback_to_browser;
 use codex_cli::login::run_logout;
 use codex_cloud_tasks::Cli as CloudTasksCli;
 use codex_common::CliConfigOverrides;
+use codex_core::commands_for_exec_policy;
 use codex_core::env::is_headless_environment;
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::SandboxPolicy;
+use codex_core::render_decision_for_unmatched_command;
+use codex_core::sandboxing::SandboxPermissions;
 use codex_exec::Cli as ExecCli;
 use codex_exec::Command as ExecCommand;
 use codex_exec::ReviewArgs;
+use codex_execpolicy::Decision;
 use codex_execpolicy::ExecPolicyCheckCommand;
+use codex_execpolicy::ExecPolicyListAmendmentsCommand;
+use codex_execpolicy::ExecPolicyRevokeCommand;
+use codex_execpolicy::ExecPolicyTestCommand;
+use codex_mcp_server::Cli as McpServerCli;
 use codex_responses_api_proxy::Args as ResponsesApiProxyArgs;
 use codex_tui::AppExitInfo;
 use codex_tui::Cli as TuiCli;
@@ -97,7 +108,7 @@ enum Subcommand {
     Mcp(McpCli),
 
     /// [experimental] Run the Codex MCP server (stdio transport).
-    McpServer,
+    McpServer(McpServerCli),
 
     /// [experimental] Run the app server or related tooling.
     AppServer(AppServerCommand),
@@ -106,9 +117,11 @@ enum Subcommand {
     Completion(CompletionCommand),
 
     /// Run commands within a Codex-provided sandbox.
-    #[clap(visible_alias = "debug")]
     Sandbox(SandboxArgs),
 
+    /// Debugging tools: sandboxed commands and offline replay of captured model traffic.
+    Debug(DebugArgs),
+
     /// Execpolicy tooling.
     #[clap(hide = true)]
     Execpolicy(ExecpolicyCommand),
@@ -204,6 +217,21 @@ enum SandboxCommand {
     Windows(WindowsCommand),
 }
 
+#[derive(Debug, Parser)]
+struct DebugArgs {
+    #[command(subcommand)]
+    cmd: DebugCommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum DebugCommand {
+    /// Replay a traffic capture through the stream parsers without a live endpoint.
+    Replay(ReplayCommand),
+
+    #[command(flatten)]
+    Sandbox(SandboxCommand),
+}
+
 #[derive(Debug, Parser)]
 struct ExecpolicyCommand {
     #[command(subcommand)]
@@ -215,6 +243,18 @@ enum ExecpolicySubcommand {
     /// Check execpolicy files against a command.
     #[clap(name = "check")]
     Check(ExecPolicyCheckCommand),
+
+    /// Run a corpus of commands against execpolicy files and report rule coverage.
+    #[clap(name = "test")]
+    Test(ExecPolicyTestCommand),
+
+    /// List rules recorded from "always allow" approvals.
+    #[clap(name = "list-amendments")]
+    ListAmendments(ExecPolicyListAmendmentsCommand),
+
+    /// Remove a rule recorded from an "always allow" approval.
+    #[clap(name = "revoke")]
+    Revoke(ExecPolicyRevokeCommand),
 }
 
 #[derive(Debug, Parser)]
@@ -412,6 +452,21 @@ fn run_execpolicycheck(cmd: ExecPolicyCheckCommand) -> anyhow::Result<()> {
     cmd.run()
 }
 
+/// Decision for a command no rule matches, as made under `approval_policy = "untrusted"`.
+fn untrusted_fallback(command: &[String]) -> Decision {
+    render_decision_for_unmatched_command(
+        AskForApproval::UnlessTrusted,
+        &SandboxPolicy::new_read_only_policy(),
+        command,
+        SandboxPermissions::UseDefault,
+    )
+}
+
+/// Rules directory that "always allow" approvals are written to.
+fn default_rules_dir() -> anyhow::Result<PathBuf> {
+    Ok(find_codex_home()?.join("rules"))
+}
+
 #[derive(Debug, Default, Parser, Clone)]
 struct FeatureToggles {
     /// Enable a feature (repeatable). Equivalent to `-c features.<name>=true`.
@@ -513,8 +568,13 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
             );
             codex_exec::run_main(exec_cli, codex_linux_sandbox_exe).await?;
         }
-        Some(Subcommand::McpServer) => {
-            codex_mcp_server::run_main(codex_linux_sandbox_exe, root_config_overrides).await?;
+        Some(Subcommand::McpServer(mcp_server_cli)) => {
+            codex_mcp_server::run_main(
+                codex_linux_sandbox_exe,
+                root_config_overrides,
+                mcp_server_cli,
+            )
+            .await?;
         }
         Some(Subcommand::Mcp(mut mcp_cli)) => {
             // Propagate any root-level config overrides (e.g. `-c key=value`).
@@ -630,43 +690,40 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
             );
             codex_cloud_tasks::run_main(cloud_cli, codex_linux_sandbox_exe).await?;
         }
-        Some(Subcommand::Sandbox(sandbox_args)) => match sandbox_args.cmd {
-            SandboxCommand::Macos(mut seatbelt_cli) => {
-                prepend_config_flags(
-                    &mut seatbelt_cli.config_overrides,
-                    root_config_overrides.clone(),
-                );
-                codex_cli::debug_sandbox::run_command_under_seatbelt(
-                    seatbelt_cli,
-                    codex_linux_sandbox_exe,
-                )
-                .await?;
-            }
-            SandboxCommand::Linux(mut landlock_cli) => {
-                prepend_config_flags(
-                    &mut landlock_cli.config_overrides,
-                    root_config_overrides.clone(),
-                );
-                codex_cli::debug_sandbox::run_command_under_landlock(
-                    landlock_cli,
-                    codex_linux_sandbox_exe,
-                )
-                .await?;
+        Some(Subcommand::Sandbox(sandbox_args)) => {
+            run_sandbox_command(
+                sandbox_args.cmd,
+                &root_config_overrides,
+                codex_linux_sandbox_exe,
+            )
+            .await?;
+        }
+        Some(Subcommand::Debug(DebugArgs { cmd })) => match cmd {
+            DebugCommand::Replay(replay_cli) => {
+                codex_cli::debug_replay::run_replay(replay_cli).await?;
             }
-            SandboxCommand::Windows(mut windows_cli) => {
-                prepend_config_flags(
-                    &mut windows_cli.config_overrides,
-                    root_config_overrides.clone(),
-                );
-                codex_cli::debug_sandbox::run_command_under_windows(
-                    windows_cli,
-                    codex_linux_sandbox_exe,
-                )
-                .await?;
+            DebugCommand::Sandbox(sandbox_cmd) => {
+                run_sandbox_command(sandbox_cmd, &root_config_overrides, codex_linux_sandbox_exe)
+                    .await?;
             }
         },
         Some(Subcommand::Execpolicy(ExecpolicyCommand { sub })) => match sub {
             ExecpolicySubcommand::Check(cmd) => run_execpolicycheck(cmd)?,
+            ExecpolicySubcommand::Test(cmd) => {
+                cmd.run_with(&commands_for_exec_policy, &untrusted_fallback)?
+            }
+            ExecpolicySubcommand::ListAmendments(mut cmd) => {
+                if cmd.rules.is_empty() {
+                    cmd.rules.push(default_rules_dir()?);
+                }
+                cmd.run()?;
+            }
+            ExecpolicySubcommand::Revoke(mut cmd) => {
+                if cmd.rules.is_empty() {
+                    cmd.rules.push(default_rules_dir()?);
+                }
+                cmd.run()?;
+            }
         },
         Some(Subcommand::Apply(mut apply_cli)) => {
             prepend_config_flags(
@@ -734,6 +791,49 @@ fn prepend_config_flags(
         .splice(0..0, cli_config_overrides.raw_overrides);
 }
 
+async fn run_sandbox_command(
+    cmd: SandboxCommand,
+    root_config_overrides: &CliConfigOverrides,
+    codex_linux_sandbox_exe: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    match cmd {
+        SandboxCommand::Macos(mut seatbelt_cli) => {
+            prepend_config_flags(
+                &mut seatbelt_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            codex_cli::debug_sandbox::run_command_under_seatbelt(
+                seatbelt_cli,
+                codex_linux_sandbox_exe,
+            )
+            .await?;
+        }
+        SandboxCommand::Linux(mut landlock_cli) => {
+            prepend_config_flags(
+                &mut landlock_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            codex_cli::debug_sandbox::run_command_under_landlock(
+                landlock_cli,
+                codex_linux_sandbox_exe,
+            )
+            .await?;
+        }
+        SandboxCommand::Windows(mut windows_cli) => {
+            prepend_config_flags(
+                &mut windows_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            codex_cli::debug_sandbox::run_command_under_windows(
+                windows_cli,
+                codex_linux_sandbox_exe,
+            )
+            .await?;
+        }
+    }
+    Ok(())
+}
+
 /// Run the interactive Codex TUI, dispatching to either the legacy implementation or the
 /// experimental TUI v2 shim based on feature flags resolved from config.
 async fn run_interactive_tui(
@@ -117,3 +117,48 @@ prefix_rule(
 
     Ok(())
 }
+
+#[test]
+fn execpolicy_test_fails_on_expectation_mismatch() -> Result<(), Box<dyn std::error::Error>> {
+    let codex_home = TempDir::new()?;
+    let rules_dir = codex_home.path().join("rules");
+    fs::create_dir_all(&rules_dir)?;
+    fs::write(
+        rules_dir.join("policy.rules"),
+        r#"
+prefix_rule(pattern = ["git", "push"], decision = "forbidden")
+prefix_rule(pattern = ["npm", "publish"], decision = "forbidden")
+"#,
+    )?;
+    let corpus_path = codex_home.path().join("corpus.txt");
+
+    let run = |corpus: &str| -> Result<std::process::Output, Box<dyn std::error::Error>> {
+        fs::write(&corpus_path, corpus)?;
+        Ok(Command::new(codex_utils_cargo_bin::cargo_bin("codex")?)
+            .env("CODEX_HOME", codex_home.path())
+            .args([
+                "execpolicy",
+                "test",
+                "--rules",
+                rules_dir.to_str().expect("rules dir should be valid UTF-8"),
+                "--corpus",
+                corpus_path
+                    .to_str()
+                    .expect("corpus path should be valid UTF-8"),
+            ])
+            .output()?)
+    };
+
+    let passing = run("forbidden: git push origin main\n")?;
+    assert!(passing.status.success());
+    let stdout = String::from_utf8(passing.stdout)?;
+    assert!(stdout.contains("rules that never matched:"));
+    assert!(stdout.contains(r#"policy.rules#2 ["npm", "publish"] -> forbidden"#));
+
+    let failing = run("allow: git push origin main\n")?;
+    assert!(!failing.status.success());
+    let stdout = String::from_utf8(failing.stdout)?;
+    assert!(stdout.contains("expected allow"));
+
+    Ok(())
+}
@@ -137,6 +137,40 @@ async fn list_and_get_render_expected_output() -> Result<()> {
     Ok(())
 }
 
+#[test]
+#[cfg(unix)]
+fn get_json_lists_workspace_roots() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let workspace = TempDir::new()?;
+    let workspace_path = workspace.path().canonicalize()?;
+
+    let mut add = codex_command(codex_home.path())?;
+    add.args(["mcp", "add", "docs", "--", "docs-server"])
+        .assert()
+        .success();
+
+    let mut get_cmd = codex_command(codex_home.path())?;
+    let output = get_cmd
+        .current_dir(&workspace_path)
+        .args(["mcp", "get", "docs", "--json"])
+        .output()?;
+    assert!(output.status.success());
+    let parsed: JsonValue = serde_json::from_slice(&output.stdout)?;
+    let name = workspace_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned());
+
+    assert_eq!(
+        parsed["roots"],
+        json!([{
+            "name": name,
+            "uri": format!("file://{}", workspace_path.display()),
+        }])
+    );
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn get_disabled_server_shows_single_line() -> Result<()> {
     let codex_home = TempDir::new()?;
@@ -0,0 +1,379 @@
+//! Records model traffic to disk and replays it offline.
+//!
+//! A capture directory holds one exchange per streaming request:
+//! `NNNN.request.json` with the endpoint path, the parser needed to decode the
+//! stream and the request body, plus `NNNN.response.sse` with the raw response
+//! bytes exactly as received, or `NNNN.error.txt` when the request failed
+//! before a stream was opened. Websocket exchanges store each received event
+//! as an SSE `data:` frame so they replay through the same parser.
+//!
+//! Captures are written with `tokio::fs` so recording never blocks the
+//! runtime thread that is driving the stream.
+
+use crate::common::ResponseEvent;
+use crate::common::ResponseStream;
+use crate::error::ApiError;
+use crate::sse::chat::process_chat_sse;
+use crate::sse::responses::process_sse;
+use bytes::Bytes;
+use codex_client::ByteStream;
+use futures::StreamExt;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+use std::sync::Weak;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use tokio::fs::File;
+use tokio::fs::OpenOptions;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::OnceCell;
+use tokio::sync::mpsc;
+use tracing::warn;
+
+const REQUEST_SUFFIX: &str = ".request.json";
+const RESPONSE_SUFFIX: &str = ".response.sse";
+const ERROR_SUFFIX: &str = ".error.txt";
+
+/// Stream parser that decodes a captured response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CaptureParser {
+    Responses,
+    Chat {
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        strip_tokens: Vec<String>,
+    },
+}
+
+#[derive(Serialize)]
+struct RequestRecord<'a> {
+    path: &'a str,
+    parser: &'a CaptureParser,
+    body: &'a Value,
+}
+
+#[derive(Deserialize)]
+struct OwnedRequestRecord {
+    path: String,
+    parser: CaptureParser,
+    body: Value,
+}
+
+/// Captures that are still in use, keyed by directory.
+static OPEN_CAPTURES: LazyLock<Mutex<HashMap<PathBuf, Weak<TrafficCapture>>>> =
+    LazyLock::new(Mutex::default);
+
+/// Writes request bodies and raw response streams into a capture directory.
+///
+/// Several clients may share a directory; each exchange claims the next free
+/// sequence number, so files are never overwritten.
+#[derive(Debug)]
+pub struct TrafficCapture {
+    dir: PathBuf,
+    next_sequence: AtomicU64,
+    /// Set once `next_sequence` has been moved past exchanges left in `dir`
+    /// by earlier processes.
+    resumed: OnceCell<()>,
+}
+
+impl TrafficCapture {
+    /// The directory is created on the first recorded request.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            next_sequence: AtomicU64::new(1),
+            resumed: OnceCell::new(),
+        }
+    }
+
+    /// Returns the capture for `dir`, shared with every other live caller in
+    /// this process so sequence numbers keep increasing across clients.
+    pub fn shared(dir: impl Into<PathBuf>) -> Arc<Self> {
+        let dir = dir.into();
+        let mut open = OPEN_CAPTURES.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(capture) = open.get(&dir).and_then(Weak::upgrade) {
+            return capture;
+        }
+        open.retain(|_, capture| capture.strong_count() > 0);
+        let capture = Arc::new(Self::new(dir.clone()));
+        open.insert(dir, Arc::downgrade(&capture));
+        capture
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Records an outbound request. Failures are logged and disable capture
+    /// for this exchange rather than failing the request.
+    pub(crate) async fn record_request(
+        &self,
+        path: &str,
+        parser: &CaptureParser,
+        body: &Value,
+    ) -> Option<ExchangeRecorder> {
+        match self.try_record_request(path, parser, body).await {
+            Ok(recorder) => Some(recorder),
+            Err(err) => {
+                warn!(dir = %self.dir.display(), "failed to capture model request: {err}");
+                None
+            }
+        }
+    }
+
+    async fn try_record_request(
+        &self,
+        path: &str,
+        parser: &CaptureParser,
+        body: &Value,
+    ) -> std::io::Result<ExchangeRecorder> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+        self.resumed
+            .get_or_init(|| async {
+                let next = next_free_sequence(&self.dir).await;
+                self.next_sequence.fetch_max(next, Ordering::Relaxed);
+            })
+            .await;
+        let record = serde_json::to_vec_pretty(&RequestRecord { path, parser, body })?;
+        loop {
+            let sequence = self.next_sequence.fetch_add(1, Ordering::Relaxed);
+            let stem = self.dir.join(format!("{sequence:04}"));
+            let request_path = with_suffix(&stem, REQUEST_SUFFIX);
+            match OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&request_path)
+                .await
+            {
+                Ok(mut file) => {
+                    file.write_all(&record).await?;
+                    file.flush().await?;
+                    return Ok(ExchangeRecorder { stem });
+                }
+                Err(err) if err.kind() == ErrorKind::AlreadyExists => continue,
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+/// Records the outcome of a single captured request.
+pub(crate) struct ExchangeRecorder {
+    stem: PathBuf,
+}
+
+impl ExchangeRecorder {
+    /// Copies every chunk of `bytes` into the capture as it is consumed. The
+    /// file is flushed before the returned stream ends.
+    pub(crate) async fn tee(self, bytes: ByteStream) -> ByteStream {
+        let writer = self.response_writer().await;
+        futures::stream::unfold(
+            (bytes, Some(writer)),
+            |(mut bytes, mut writer)| async move {
+                let Some(item) = bytes.next().await else {
+                    if let Some(writer) = writer.take() {
+                        writer.finish().await;
+                    }
+                    return None;
+                };
+                if let (Ok(chunk), Some(writer)) = (&item, writer.as_mut()) {
+                    writer.write(chunk).await;
+                }
+                Some((item, (bytes, writer)))
+            },
+        )
+        .boxed()
+    }
+
+    /// Opens the response file for an exchange whose events arrive one at a
+    /// time rather than as a byte stream.
+    pub(crate) async fn response_writer(self) -> ResponseWriter {
+        let path = with_suffix(&self.stem, RESPONSE_SUFFIX);
+        let file = match File::create(&path).await {
+            Ok(file) => Some(file),
+            Err(err) => {
+                warn!(path = %path.display(), "failed to capture model response: {err}");
+                None
+            }
+        };
+        ResponseWriter {
+            stem: self.stem,
+            path,
+            file,
+        }
+    }
+
+    pub(crate) async fn record_error(self, err: &impl Display) {
+        write_error(&self.stem, err).await;
+    }
+}
+
+/// Appends response bytes for one exchange.
+pub(crate) struct ResponseWriter {
+    stem: PathBuf,
+    path: PathBuf,
+    file: Option<File>,
+}
+
+impl ResponseWriter {
+    pub(crate) async fn write(&mut self, chunk: &[u8]) {
+        if let Some(out) = self.file.as_mut()
+            && let Err(err) = out.write_all(chunk).await
+        {
+            warn!(path = %self.path.display(), "failed to capture model response: {err}");
+            self.file = None;
+        }
+    }
+
+    /// Writes one websocket event as an SSE frame.
+    pub(crate) async fn write_event(&mut self, event: &str) {
+        let mut frame = String::with_capacity(event.len() + 8);
+        for line in event.lines() {
+            frame.push_str("data: ");
+            frame.push_str(line);
+            frame.push('\n');
+        }
+        frame.push('\n');
+        self.write(frame.as_bytes()).await;
+    }
+
+    pub(crate) async fn finish(mut self) {
+        if let Some(mut out) = self.file.take()
+            && let Err(err) = out.flush().await
+        {
+            warn!(path = %self.path.display(), "failed to capture model response: {err}");
+        }
+    }
+
+    /// Flushes what was received and records the error that ended the stream.
+    pub(crate) async fn fail(self, err: &impl Display) {
+        let stem = self.stem.clone();
+        self.finish().await;
+        write_error(&stem, err).await;
+    }
+}
+
+async fn write_error(stem: &Path, err: &impl Display) {
+    let path = with_suffix(stem, ERROR_SUFFIX);
+    if let Err(write_err) = tokio::fs::write(&path, err.to_string()).await {
+        warn!(path = %path.display(), "failed to capture model error: {write_err}");
+    }
+}
+
+/// First sequence number after the exchanges already in `dir`.
+async fn next_free_sequence(dir: &Path) -> u64 {
+    let mut next = 1;
+    let Ok(mut entries) = tokio::fs::read_dir(dir).await else {
+        return next;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        if let Some(sequence) = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| name.strip_suffix(REQUEST_SUFFIX))
+            .and_then(|stem| stem.parse::<u64>().ok())
+        {
+            next = next.max(sequence + 1);
+        }
+    }
+    next
+}
+
+fn with_suffix(stem: &Path, suffix: &str) -> PathBuf {
+    let mut path = stem.as_os_str().to_owned();
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+/// One request/response pair read back from a capture directory.
+#[derive(Debug, Clone)]
+pub struct CapturedExchange {
+    pub sequence: u64,
+    pub path: String,
+    pub parser: CaptureParser,
+    pub body: Value,
+    /// Raw SSE bytes, when a stream was opened.
+    pub response: Option<PathBuf>,
+    /// Transport error recorded instead of a response.
+    pub error: Option<String>,
+}
+
+/// Reads every exchange in `dir`, ordered by sequence number.
+pub fn load_capture(dir: &Path) -> std::io::Result<Vec<CapturedExchange>> {
+    let mut exchanges = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let Some(sequence) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_suffix(REQUEST_SUFFIX))
+            .and_then(|stem| stem.parse::<u64>().ok())
+        else {
+            continue;
+        };
+        let record: OwnedRequestRecord =
+            serde_json::from_slice(&std::fs::read(&path)?).map_err(|err| {
+                std::io::Error::new(ErrorKind::InvalidData, format!("{}: {err}", path.display()))
+            })?;
+        let stem = dir.join(format!("{sequence:04}"));
+        let response = Some(with_suffix(&stem, RESPONSE_SUFFIX)).filter(|path| path.is_file());
+        let error = match std::fs::read_to_string(with_suffix(&stem, ERROR_SUFFIX)) {
+            Ok(error) => Some(error),
+            Err(err) if err.kind() == ErrorKind::NotFound => None,
+            Err(err) => return Err(err),
+        };
+        exchanges.push(CapturedExchange {
+            sequence,
+            path: record.path,
+            parser: record.parser,
+            body: record.body,
+            response,
+            error,
+        });
+    }
+    exchanges.sort_by_key(|exchange| exchange.sequence);
+    Ok(exchanges)
+}
+
+/// Feeds a captured response through the same parser the live request used.
+pub fn replay_exchange(
+    exchange: &CapturedExchange,
+    idle_timeout: Duration,
+) -> Result<ResponseStream, ApiError> {
+    let Some(path) = exchange.response.as_ref() else {
+        return Err(ApiError::Stream(format!(
+            "exchange {} has no recorded response",
+            exchange.sequence
+        )));
+    };
+    let content = std::fs::read(path).map_err(|err| ApiError::Stream(err.to_string()))?;
+    let bytes: ByteStream = futures::stream::iter([Ok(Bytes::from(content))]).boxed();
+
+    let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent, ApiError>>(1600);
+    match exchange.parser.clone() {
+        CaptureParser::Responses => {
+            tokio::spawn(process_sse(bytes, tx_event, idle_timeout, None));
+        }
+        CaptureParser::Chat { strip_tokens } => {
+            tokio::spawn(process_chat_sse(
+                bytes,
+                tx_event,
+                idle_timeout,
+                None,
+                strip_tokens,
+            ));
+        }
+    }
+    Ok(ResponseStream { rx_event })
+}
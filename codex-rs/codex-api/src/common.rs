@@ -1,5 +1,6 @@
 use crate::error::ApiError;
 use codex_protocol::config_types::ReasoningSummary as ReasoningSummaryConfig;
+use codex_protocol::config_types::SamplingParams;
 use codex_protocol::config_types::Verbosity as VerbosityConfig;
 use codex_protocol::models::ResponseItem;
 use codex_protocol::openai_models::ReasoningEffort as ReasoningEffortConfig;
@@ -29,6 +30,8 @@ pub struct Prompt {
     /// For DeepSeek thinking mode, reasoning_content from previous assistant messages
     pub reasoning_content: Option<String>,
     pub output_schema: Option<Value>,
+    /// Sampling controls; unset fields are left to the server.
+    pub sampling: SamplingParams,
 }
 
 /// Canonical input payload for the compaction endpoint.
@@ -136,6 +139,12 @@ pub struct ResponsesApiRequest<'a> {
     pub prompt_cache_key: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub text: Option<TextControls>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<u64>,
 }
 
 #[derive(Debug, Serialize)]
@@ -154,6 +163,12 @@ pub struct ResponseCreateWsRequest {
     pub prompt_cache_key: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub text: Option<TextControls>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<u64>,
 }
 
 #[derive(Debug, Serialize)]
@@ -189,6 +204,62 @@ pub fn create_text_param_for_request(
     })
 }
 
+/// How a Chat Completions provider accepts structured output requests.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ChatResponseFormat {
+    /// `response_format: {"type": "json_schema", ...}` with strict validation.
+    #[default]
+    JsonSchema,
+    /// `response_format: {"type": "json_object"}`; the schema itself is appended to
+    /// the system prompt because the server cannot enforce it.
+    JsonObject,
+}
+
+/// Wire-level quirks of a Chat Completions endpoint. The request builder and
+/// the SSE parser consult this instead of branching on the provider name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChatDialect {
+    /// Whether `developer` messages can be sent as-is; otherwise they are sent
+    /// with the `user` role.
+    pub developer_role: bool,
+    /// Whether assistant messages replay `reasoning_content`.
+    pub echo_reasoning_content: bool,
+    /// Whether tool outputs must be plain strings rather than content parts.
+    pub tool_content_as_string: bool,
+    /// Whether to reject histories where a `tool_calls` message is not
+    /// followed by a response for every call. `None` keeps the historical
+    /// behavior of only checking histories that contain no tool messages.
+    pub validate_tool_calls: Option<bool>,
+    /// How structured output requests are encoded.
+    pub response_format: ChatResponseFormat,
+    /// Special tokens removed from streamed text and reasoning deltas.
+    pub strip_tokens: Vec<String>,
+}
+
+/// Builds the Chat Completions `response_format` value for an output schema.
+pub fn create_chat_response_format(schema: &Value, format: ChatResponseFormat) -> Value {
+    match format {
+        ChatResponseFormat::JsonSchema => serde_json::json!({
+            "type": "json_schema",
+            "json_schema": {
+                "name": "codex_output_schema",
+                "strict": true,
+                "schema": schema,
+            },
+        }),
+        ChatResponseFormat::JsonObject => serde_json::json!({ "type": "json_object" }),
+    }
+}
+
+/// Instructions appended to the system prompt when the provider only supports
+/// `json_object` mode and cannot enforce the schema server-side.
+pub fn chat_output_schema_instructions(schema: &Value) -> String {
+    let schema = serde_json::to_string_pretty(schema).unwrap_or_else(|_| schema.to_string());
+    format!(
+        "Your final answer must be a single JSON object, with no surrounding text or code fences, that conforms to this JSON schema:\n{schema}"
+    )
+}
+
 pub struct ResponseStream {
     pub rx_event: mpsc::Receiver<Result<ResponseEvent, ApiError>>,
 }
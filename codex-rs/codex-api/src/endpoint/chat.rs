@@ -1,5 +1,8 @@
 use crate::ChatRequest;
 use crate::auth::AuthProvider;
+use crate::capture::CaptureParser;
+use crate::capture::TrafficCapture;
+use crate::common::ChatDialect;
 use crate::common::Prompt as ApiPrompt;
 use crate::common::ResponseEvent;
 use crate::common::ResponseStream;
@@ -27,12 +30,16 @@ use std::task::Poll;
 
 pub struct ChatClient<T: HttpTransport, A: AuthProvider> {
     streaming: StreamingClient<T, A>,
+    dialect: ChatDialect,
+    stable_prefix: bool,
 }
 
 impl<T: HttpTransport, A: AuthProvider> ChatClient<T, A> {
     pub fn new(transport: T, provider: Provider, auth: A) -> Self {
         Self {
             streaming: StreamingClient::new(transport, provider, auth),
+            dialect: ChatDialect::default(),
+            stable_prefix: false,
         }
     }
 
@@ -43,9 +50,33 @@ impl<T: HttpTransport, A: AuthProvider> ChatClient<T, A> {
     ) -> Self {
         Self {
             streaming: self.streaming.with_telemetry(request, sse),
+            dialect: self.dialect,
+            stable_prefix: self.stable_prefix,
         }
     }
 
+    /// Selects the wire quirks used to build requests and parse the stream.
+    pub fn with_dialect(mut self, dialect: ChatDialect) -> Self {
+        self.dialect = dialect;
+        self
+    }
+
+    /// Records request bodies and raw streams into `capture`.
+    pub fn with_capture(self, capture: Option<Arc<TrafficCapture>>) -> Self {
+        Self {
+            streaming: self.streaming.with_capture(capture),
+            dialect: self.dialect,
+            stable_prefix: self.stable_prefix,
+        }
+    }
+
+    /// Keeps previously sent messages byte-identical across turns; see
+    /// [`crate::requests::ChatRequestBuilder::stable_prefix`].
+    pub fn with_stable_prefix(mut self, enabled: bool) -> Self {
+        self.stable_prefix = enabled;
+        self
+    }
+
     pub async fn stream_request(&self, request: ChatRequest) -> Result<ResponseStream, ApiError> {
         self.stream(request.body, request.headers).await
     }
@@ -66,6 +97,10 @@ impl<T: HttpTransport, A: AuthProvider> ChatClient<T, A> {
             &prompt.tools,
             prompt.reasoning_content.clone(),
         )
+        .output_schema(prompt.output_schema.as_ref())
+        .dialect(self.dialect.clone())
+        .sampling(prompt.sampling.clone())
+        .stable_prefix(self.stable_prefix)
         .conversation_id(conversation_id)
         .session_source(session_source)
         .build(self.streaming.provider())?;
@@ -85,13 +120,20 @@ impl<T: HttpTransport, A: AuthProvider> ChatClient<T, A> {
         body: Value,
         extra_headers: HeaderMap,
     ) -> Result<ResponseStream, ApiError> {
+        let strip_tokens = self.dialect.strip_tokens.clone();
+        let parser = CaptureParser::Chat {
+            strip_tokens: strip_tokens.clone(),
+        };
         self.streaming
             .stream(
                 self.path(),
                 body,
                 extra_headers,
                 RequestCompression::None,
-                spawn_chat_stream,
+                parser,
+                move |stream_response, idle_timeout, telemetry| {
+                    spawn_chat_stream(stream_response, idle_timeout, telemetry, strip_tokens)
+                },
             )
             .await
     }
@@ -1,4 +1,6 @@
 use crate::auth::AuthProvider;
+use crate::capture::CaptureParser;
+use crate::capture::TrafficCapture;
 use crate::common::Prompt as ApiPrompt;
 use crate::common::Reasoning;
 use crate::common::ResponseStream;
@@ -55,6 +57,13 @@ impl<T: HttpTransport, A: AuthProvider> ResponsesClient<T, A> {
         }
     }
 
+    /// Records request bodies and raw streams into `capture`.
+    pub fn with_capture(self, capture: Option<Arc<TrafficCapture>>) -> Self {
+        Self {
+            streaming: self.streaming.with_capture(capture),
+        }
+    }
+
     pub async fn stream_request(
         &self,
         request: ResponsesRequest,
@@ -89,6 +98,7 @@ impl<T: HttpTransport, A: AuthProvider> ResponsesClient<T, A> {
             .include(include)
             .prompt_cache_key(prompt_cache_key)
             .text(text)
+            .sampling(prompt.sampling.clone())
             .conversation(conversation_id)
             .session_source(session_source)
             .store_override(store_override)
@@ -123,6 +133,7 @@ impl<T: HttpTransport, A: AuthProvider> ResponsesClient<T, A> {
                 body,
                 extra_headers,
                 compression,
+                CaptureParser::Responses,
                 spawn_response_stream,
             )
             .await
@@ -1,4 +1,7 @@
 use crate::auth::AuthProvider;
+use crate::capture::CaptureParser;
+use crate::capture::ResponseWriter;
+use crate::capture::TrafficCapture;
 use crate::common::ResponseEvent;
 use crate::common::ResponseStream;
 use crate::common::ResponsesWsRequest;
@@ -32,13 +35,15 @@ pub struct ResponsesWebsocketConnection {
     stream: Arc<Mutex<Option<WsStream>>>,
     // TODO (pakrym): is this the right place for timeout?
     idle_timeout: Duration,
+    capture: Option<Arc<TrafficCapture>>,
 }
 
 impl ResponsesWebsocketConnection {
-    fn new(stream: WsStream, idle_timeout: Duration) -> Self {
+    fn new(stream: WsStream, idle_timeout: Duration, capture: Option<Arc<TrafficCapture>>) -> Self {
         Self {
             stream: Arc::new(Mutex::new(Some(stream))),
             idle_timeout,
+            capture,
         }
     }
 
@@ -57,29 +62,49 @@ impl ResponsesWebsocketConnection {
         let request_body = serde_json::to_value(&request).map_err(|err| {
             ApiError::Stream(format!("failed to encode websocket request: {err}"))
         })?;
+        let mut capture = match self.capture.as_ref() {
+            Some(capture) => {
+                match capture
+                    .record_request("responses", &CaptureParser::Responses, &request_body)
+                    .await
+                {
+                    Some(recorder) => Some(recorder.response_writer().await),
+                    None => None,
+                }
+            }
+            None => None,
+        };
 
         tokio::spawn(async move {
             let mut guard = stream.lock().await;
             let Some(ws_stream) = guard.as_mut() else {
-                let _ = tx_event
-                    .send(Err(ApiError::Stream(
-                        "websocket connection is closed".to_string(),
-                    )))
-                    .await;
+                let err = ApiError::Stream("websocket connection is closed".to_string());
+                if let Some(capture) = capture {
+                    capture.fail(&err).await;
+                }
+                let _ = tx_event.send(Err(err)).await;
                 return;
             };
 
-            if let Err(err) = run_websocket_response_stream(
+            let result = run_websocket_response_stream(
                 ws_stream,
                 tx_event.clone(),
                 request_body,
                 idle_timeout,
+                capture.as_mut(),
             )
-            .await
-            {
-                let _ = ws_stream.close(None).await;
-                *guard = None;
-                let _ = tx_event.send(Err(err)).await;
+            .await;
+            match (result, capture) {
+                (Ok(()), Some(capture)) => capture.finish().await,
+                (Ok(()), None) => {}
+                (Err(err), capture) => {
+                    if let Some(capture) = capture {
+                        capture.fail(&err).await;
+                    }
+                    let _ = ws_stream.close(None).await;
+                    *guard = None;
+                    let _ = tx_event.send(Err(err)).await;
+                }
             }
         });
 
@@ -90,11 +115,22 @@ impl ResponsesWebsocketConnection {
 pub struct ResponsesWebsocketClient<A: AuthProvider> {
     provider: Provider,
     auth: A,
+    capture: Option<Arc<TrafficCapture>>,
 }
 
 impl<A: AuthProvider> ResponsesWebsocketClient<A> {
     pub fn new(provider: Provider, auth: A) -> Self {
-        Self { provider, auth }
+        Self {
+            provider,
+            auth,
+            capture: None,
+        }
+    }
+
+    /// Records request bodies and received events into `capture`.
+    pub fn with_capture(mut self, capture: Option<Arc<TrafficCapture>>) -> Self {
+        self.capture = capture;
+        self
     }
 
     pub async fn connect(
@@ -112,6 +148,7 @@ impl<A: AuthProvider> ResponsesWebsocketClient<A> {
         Ok(ResponsesWebsocketConnection::new(
             stream,
             self.provider.stream_idle_timeout,
+            self.capture.clone(),
         ))
     }
 }
@@ -172,6 +209,7 @@ async fn run_websocket_response_stream(
     tx_event: mpsc::Sender<std::result::Result<ResponseEvent, ApiError>>,
     request_body: Value,
     idle_timeout: Duration,
+    mut capture: Option<&mut ResponseWriter>,
 ) -> Result<(), ApiError> {
     let request_text = match serde_json::to_string(&request_body) {
         Ok(text) => text,
@@ -210,6 +248,9 @@ async fn run_websocket_response_stream(
         match message {
             Message::Text(text) => {
                 trace!("websocket event: {text}");
+                if let Some(capture) = capture.as_deref_mut() {
+                    capture.write_event(&text).await;
+                }
                 let event = match serde_json::from_str::<ResponsesStreamEvent>(&text) {
                     Ok(event) => event,
                     Err(err) => {
@@ -1,14 +1,19 @@
 use crate::auth::AuthProvider;
 use crate::auth::add_auth_headers;
+use crate::capture::CaptureParser;
+use crate::capture::TrafficCapture;
 use crate::common::ResponseStream;
 use crate::error::ApiError;
 use crate::provider::Provider;
 use crate::telemetry::SseTelemetry;
 use crate::telemetry::run_with_request_telemetry;
 use codex_client::HttpTransport;
+use codex_client::Request;
 use codex_client::RequestCompression;
 use codex_client::RequestTelemetry;
 use codex_client::StreamResponse;
+use codex_client::TransportError;
+use futures::StreamExt;
 use http::HeaderMap;
 use http::Method;
 use serde_json::Value;
@@ -21,6 +26,7 @@ pub(crate) struct StreamingClient<T: HttpTransport, A: AuthProvider> {
     auth: A,
     request_telemetry: Option<Arc<dyn RequestTelemetry>>,
     sse_telemetry: Option<Arc<dyn SseTelemetry>>,
+    capture: Option<Arc<TrafficCapture>>,
 }
 
 impl<T: HttpTransport, A: AuthProvider> StreamingClient<T, A> {
@@ -31,6 +37,7 @@ impl<T: HttpTransport, A: AuthProvider> StreamingClient<T, A> {
             auth,
             request_telemetry: None,
             sse_telemetry: None,
+            capture: None,
         }
     }
 
@@ -44,6 +51,11 @@ impl<T: HttpTransport, A: AuthProvider> StreamingClient<T, A> {
         self
     }
 
+    pub(crate) fn with_capture(mut self, capture: Option<Arc<TrafficCapture>>) -> Self {
+        self.capture = capture;
+        self
+    }
+
     pub(crate) fn provider(&self) -> &Provider {
         &self.provider
     }
@@ -54,7 +66,8 @@ impl<T: HttpTransport, A: AuthProvider> StreamingClient<T, A> {
         body: Value,
         extra_headers: HeaderMap,
         compression: RequestCompression,
-        spawner: fn(StreamResponse, Duration, Option<Arc<dyn SseTelemetry>>) -> ResponseStream,
+        parser: CaptureParser,
+        spawner: impl FnOnce(StreamResponse, Duration, Option<Arc<dyn SseTelemetry>>) -> ResponseStream,
     ) -> Result<ResponseStream, ApiError> {
         let builder = || {
             let mut req = self.provider.build_request(Method::POST, path);
@@ -68,13 +81,32 @@ impl<T: HttpTransport, A: AuthProvider> StreamingClient<T, A> {
             add_auth_headers(&self.auth, req)
         };
 
-        let stream_response = run_with_request_telemetry(
+        let recorder = match self.capture.as_ref() {
+            Some(capture) => capture.record_request(path, &parser, &body).await,
+            None => None,
+        };
+
+        let first_byte_timeout = self.provider.first_byte_timeout;
+        let result = run_with_request_telemetry(
             self.provider.retry.to_policy(),
             self.request_telemetry.clone(),
             builder,
-            |req| self.transport.stream(req),
+            |req| stream_with_first_byte_timeout(&self.transport, req, first_byte_timeout),
         )
-        .await?;
+        .await;
+        let stream_response = match (result, recorder) {
+            (Ok(mut stream_response), Some(recorder)) => {
+                stream_response.bytes = recorder.tee(stream_response.bytes).await;
+                stream_response
+            }
+            (Ok(stream_response), None) => stream_response,
+            (Err(err), recorder) => {
+                if let Some(recorder) = recorder {
+                    recorder.record_error(&err).await;
+                }
+                return Err(err.into());
+            }
+        };
 
         Ok(spawner(
             stream_response,
@@ -83,3 +115,38 @@ impl<T: HttpTransport, A: AuthProvider> StreamingClient<T, A> {
         ))
     }
 }
+
+/// Opens the stream and waits for the first body chunk. A server that accepts
+/// the request but never starts responding fails with the retryable
+/// `TransportError::Timeout` instead of stalling the turn.
+async fn stream_with_first_byte_timeout<T: HttpTransport>(
+    transport: &T,
+    req: Request,
+    first_byte_timeout: Option<Duration>,
+) -> Result<StreamResponse, TransportError> {
+    let Some(first_byte_timeout) = first_byte_timeout else {
+        return transport.stream(req).await;
+    };
+
+    let first_chunk = async {
+        let StreamResponse {
+            status,
+            headers,
+            mut bytes,
+        } = transport.stream(req).await?;
+        let bytes = match bytes.next().await {
+            Some(first) => futures::stream::once(async move { first })
+                .chain(bytes)
+                .boxed(),
+            None => bytes,
+        };
+        Ok(StreamResponse {
+            status,
+            headers,
+            bytes,
+        })
+    };
+    tokio::time::timeout(first_byte_timeout, first_chunk)
+        .await
+        .map_err(|_| TransportError::Timeout)?
+}
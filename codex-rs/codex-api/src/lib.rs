@@ -1,4 +1,5 @@
 pub mod auth;
+pub mod capture;
 pub mod common;
 pub mod endpoint;
 pub mod error;
@@ -14,6 +15,9 @@ pub use codex_client::ReqwestTransport;
 pub use codex_client::TransportError;
 
 pub use crate::auth::AuthProvider;
+pub use crate::capture::TrafficCapture;
+pub use crate::common::ChatDialect;
+pub use crate::common::ChatResponseFormat;
 pub use crate::common::CompactionInput;
 pub use crate::common::Prompt;
 pub use crate::common::ResponseAppendWsRequest;
@@ -21,6 +25,7 @@ pub use crate::common::ResponseCreateWsRequest;
 pub use crate::common::ResponseEvent;
 pub use crate::common::ResponseStream;
 pub use crate::common::ResponsesApiRequest;
+pub use crate::common::create_chat_response_format;
 pub use crate::common::create_text_param_for_request;
 pub use crate::endpoint::chat::AggregateStreamExt;
 pub use crate::endpoint::chat::ChatClient;
@@ -45,7 +45,7 @@ impl RetryConfig {
 /// HTTP endpoint configuration used to talk to a concrete API deployment.
 ///
 /// Encapsulates base URL, default headers, query params, retry policy, and
-/// stream idle timeout, plus helper methods for building requests.
+/// stream timeouts, plus helper methods for building requests.
 #[derive(Debug, Clone)]
 pub struct Provider {
     pub name: String,
@@ -55,6 +55,9 @@ pub struct Provider {
     pub headers: HeaderMap,
     pub retry: RetryConfig,
     pub stream_idle_timeout: Duration,
+    /// How long to wait for the first byte of a streaming response before the
+    /// attempt fails with a retryable timeout. `None` waits indefinitely.
+    pub first_byte_timeout: Option<Duration>,
 }
 
 impl Provider {
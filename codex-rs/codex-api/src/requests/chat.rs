@@ -1,8 +1,13 @@
+use crate::common::ChatDialect;
+use crate::common::ChatResponseFormat;
+use crate::common::chat_output_schema_instructions;
+use crate::common::create_chat_response_format;
 use crate::error::ApiError;
 use crate::provider::Provider;
 use crate::requests::headers::build_conversation_headers;
 use crate::requests::headers::insert_header;
 use crate::requests::headers::subagent_header;
+use codex_protocol::config_types::SamplingParams;
 use codex_protocol::models::ContentItem;
 use codex_protocol::models::FunctionCallOutputContentItem;
 use codex_protocol::models::ReasoningItemContent;
@@ -26,6 +31,10 @@ pub struct ChatRequestBuilder<'a> {
     input: &'a [ResponseItem],
     tools: &'a [Value],
     reasoning_content: Option<String>,
+    output_schema: Option<&'a Value>,
+    dialect: ChatDialect,
+    sampling: SamplingParams,
+    stable_prefix: bool,
     conversation_id: Option<String>,
     session_source: Option<SessionSource>,
 }
@@ -44,11 +53,46 @@ impl<'a> ChatRequestBuilder<'a> {
             input,
             tools,
             reasoning_content,
+            output_schema: None,
+            dialect: ChatDialect::default(),
+            sampling: SamplingParams::default(),
+            stable_prefix: false,
             conversation_id: None,
             session_source: None,
         }
     }
 
+    /// Requests structured output matching `schema`, encoded according to the
+    /// dialect's `response_format`.
+    pub fn output_schema(mut self, schema: Option<&'a Value>) -> Self {
+        self.output_schema = schema;
+        self
+    }
+
+    /// Wire quirks of the target endpoint (roles, `reasoning_content` echo,
+    /// tool output encoding, validation).
+    pub fn dialect(mut self, dialect: ChatDialect) -> Self {
+        self.dialect = dialect;
+        self
+    }
+
+    /// Sampling controls (`temperature`, `top_p`, `max_tokens`, penalties,
+    /// `stop`); unset fields are omitted from the payload.
+    pub fn sampling(mut self, sampling: SamplingParams) -> Self {
+        self.sampling = sampling;
+        self
+    }
+
+    /// Rebuilds messages sent in earlier turns byte-for-byte so providers can
+    /// reuse their cached prefix: reasoning stays attached to every assistant
+    /// turn, each message echoes its own `reasoning_content` rather than the
+    /// latest one, and tools are sent sorted by name so the tool list does not
+    /// depend on the order MCP servers finished starting.
+    pub fn stable_prefix(mut self, enabled: bool) -> Self {
+        self.stable_prefix = enabled;
+        self
+    }
+
     pub fn conversation_id(mut self, id: Option<String>) -> Self {
         self.conversation_id = id;
         self
@@ -61,7 +105,17 @@ impl<'a> ChatRequestBuilder<'a> {
 
     pub fn build(self, _provider: &Provider) -> Result<ChatRequest, ApiError> {
         let mut messages = Vec::<Value>::new();
-        messages.push(json!({"role": "system", "content": self.instructions}));
+        let system_content = match self.output_schema {
+            Some(schema) if self.dialect.response_format == ChatResponseFormat::JsonObject => {
+                format!(
+                    "{}\n\n{}",
+                    self.instructions,
+                    chat_output_schema_instructions(schema)
+                )
+            }
+            _ => self.instructions.to_string(),
+        };
+        messages.push(json!({"role": "system", "content": system_content}));
 
         let input = self.input;
         let mut reasoning_by_anchor_index: HashMap<usize, String> = HashMap::new();
@@ -91,9 +145,10 @@ impl<'a> ChatRequestBuilder<'a> {
             }
         }
 
-        if !matches!(last_emitted_role, Some("user")) {
+        if self.stable_prefix || !matches!(last_emitted_role, Some("user")) {
             for (idx, item) in input.iter().enumerate() {
-                if let Some(u_idx) = last_user_index
+                if !self.stable_prefix
+                    && let Some(u_idx) = last_user_index
                     && idx <= u_idx
                 {
                     continue;
@@ -175,11 +230,11 @@ impl<'a> ChatRequestBuilder<'a> {
                     role,
                     content,
                     id,
-                    reasoning_content: _,
+                    reasoning_content: message_reasoning_content,
                     tool_calls,
                 } => {
-                    // Convert "developer" role to "user" role as Chat Completions API doesn't support "developer" role.
-                    let role = if role == "developer" {
+                    // Most Chat Completions endpoints only know system/user/assistant/tool.
+                    let role = if role == "developer" && !self.dialect.developer_role {
                         "user"
                     } else {
                         role.as_str()
@@ -230,22 +285,29 @@ impl<'a> ChatRequestBuilder<'a> {
 
                     // Special handling for assistant messages in DeepSeek thinking mode
                     if role == "assistant" {
-                        // For DeepSeek reasoner models, assistant messages need reasoning_content
-                        let reasoning_content = match &self.reasoning_content {
-                            Some(content) => json!(content),
-                            None => json!(""), // DeepSeek文档要求必须有reasoning_content
-                        };
-                        tracing::warn!(
-                            "🤖 DeepSeek assistant消息 - reasoning_content长度: {}",
-                            reasoning_content.as_str().unwrap_or("").len()
-                        );
-
                         let mut message = json!({
                             "role": "assistant",
                             "content": text,
-                            "reasoning_content": reasoning_content,
                         });
 
+                        // For DeepSeek reasoner models, assistant messages need reasoning_content
+                        if self.dialect.echo_reasoning_content {
+                            let reasoning_content = if self.stable_prefix {
+                                message_reasoning_content
+                            } else {
+                                &self.reasoning_content
+                            };
+                            let reasoning_content = match reasoning_content {
+                                Some(content) => json!(content),
+                                None => json!(""), // DeepSeek文档要求必须有reasoning_content
+                            };
+                            tracing::warn!(
+                                "🤖 DeepSeek assistant消息 - reasoning_content长度: {}",
+                                reasoning_content.as_str().unwrap_or("").len()
+                            );
+                            message["reasoning_content"] = reasoning_content;
+                        }
+
                         // 如果assistant消息包含tool_calls，添加到消息中
                         if let Some(tool_calls) = tool_calls {
                             if !tool_calls.is_empty() {
@@ -339,7 +401,12 @@ impl<'a> ChatRequestBuilder<'a> {
                         }
                     });
                     pending_tool_call_ids.insert(call_id.clone());
-                    push_tool_call_message(&mut messages, tool_call, reasoning);
+                    push_tool_call_message(
+                        &mut messages,
+                        tool_call,
+                        reasoning,
+                        self.dialect.echo_reasoning_content,
+                    );
                     // Track that we just added an assistant message with tool_calls
                     if let Some(last_msg) = messages.last()
                         && last_msg.get("role").and_then(Value::as_str) == Some("assistant")
@@ -364,7 +431,12 @@ impl<'a> ChatRequestBuilder<'a> {
                         "action": action,
                     });
                     pending_tool_call_ids.insert(call_id_clone);
-                    push_tool_call_message(&mut messages, tool_call, reasoning);
+                    push_tool_call_message(
+                        &mut messages,
+                        tool_call,
+                        reasoning,
+                        self.dialect.echo_reasoning_content,
+                    );
                     // Track that we just added an assistant message with tool_calls
                     if let Some(last_msg) = messages.last()
                         && last_msg.get("role").and_then(Value::as_str) == Some("assistant")
@@ -385,7 +457,11 @@ impl<'a> ChatRequestBuilder<'a> {
                         last_assistant_with_tool_calls_index = None;
                     }
 
-                    let content_value = if let Some(items) = &output.content_items {
+                    let content_items = output
+                        .content_items
+                        .as_ref()
+                        .filter(|_| !self.dialect.tool_content_as_string);
+                    let content_value = if let Some(items) = content_items {
                         let mapped: Vec<Value> = items
                             .iter()
                             .map(|it| match it {
@@ -428,7 +504,12 @@ impl<'a> ChatRequestBuilder<'a> {
                     });
                     pending_tool_call_ids.insert(call_id_for_tracking);
                     let reasoning = reasoning_by_anchor_index.get(&idx).map(String::as_str);
-                    push_tool_call_message(&mut messages, tool_call, reasoning);
+                    push_tool_call_message(
+                        &mut messages,
+                        tool_call,
+                        reasoning,
+                        self.dialect.echo_reasoning_content,
+                    );
                     // Track that we just added an assistant message with tool_calls
                     if let Some(last_msg) = messages.last()
                         && last_msg.get("role").and_then(Value::as_str) == Some("assistant")
@@ -464,24 +545,43 @@ impl<'a> ChatRequestBuilder<'a> {
 
         // Validate that every assistant message with tool_calls (except possibly the last one) is followed by corresponding tool messages
         // The last message may have tool_calls without tool responses if it's the start of the current request
-        //
-        // TODO: For DeepSeek compatibility, we currently skip this validation when we have tool messages
-        // because our conversion creates assistant + tool message pairs that don't have proper tool_calls in assistant
-        let has_tool_messages = messages
-            .iter()
-            .any(|msg| msg.get("role").and_then(Value::as_str) == Some("tool"));
-        if !has_tool_messages {
+        let validate = self.dialect.validate_tool_calls.unwrap_or_else(|| {
+            !messages
+                .iter()
+                .any(|msg| msg.get("role").and_then(Value::as_str) == Some("tool"))
+        });
+        if validate {
             validate_tool_calls_sequence(&messages)?;
         } else {
-            tracing::warn!("⚠️ 检测到tool消息，跳过tool_calls序列验证 (DeepSeek兼容模式)");
+            tracing::warn!("⚠️ 跳过tool_calls序列验证");
+        }
+
+        let mut tools = self.tools.to_vec();
+        if self.stable_prefix {
+            tools.sort_by(|a, b| tool_name(a).cmp(tool_name(b)));
         }
 
-        let payload = json!({
+        // Ask for a trailing usage chunk so the stream can report `TokenUsage`.
+        let mut payload = json!({
             "model": self.model,
             "messages": messages,
             "stream": true,
-            "tools": self.tools,
+            "stream_options": { "include_usage": true },
+            "tools": tools,
         });
+        if let Some(schema) = self.output_schema
+            && let Some(obj) = payload.as_object_mut()
+        {
+            obj.insert(
+                "response_format".to_string(),
+                create_chat_response_format(schema, self.dialect.response_format),
+            );
+        }
+        if let Ok(Value::Object(sampling)) = serde_json::to_value(&self.sampling)
+            && let Some(obj) = payload.as_object_mut()
+        {
+            obj.extend(sampling);
+        }
 
         tracing::warn!("✅ 消息处理完成 - 生成了{}条API消息", messages.len());
 
@@ -514,6 +614,14 @@ impl<'a> ChatRequestBuilder<'a> {
     }
 }
 
+fn tool_name(tool: &Value) -> &str {
+    tool.get("function")
+        .and_then(|function| function.get("name"))
+        .or_else(|| tool.get("name"))
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+}
+
 fn validate_tool_calls_sequence(messages: &[Value]) -> Result<(), ApiError> {
     // Skip the system message (index 0)
     let mut i = 1;
@@ -585,6 +693,7 @@ fn validate_tool_calls_sequence(messages: &[Value]) -> Result<(), ApiError> {
             // that include tool messages without the preceding tool_calls in the conversation history.
             // These tool messages are valid as they represent completed tool executions
             // from previous interactions.
+            i += 1;
             continue;
         }
         i += 1;
@@ -592,7 +701,12 @@ fn validate_tool_calls_sequence(messages: &[Value]) -> Result<(), ApiError> {
     Ok(())
 }
 
-fn push_tool_call_message(messages: &mut Vec<Value>, tool_call: Value, reasoning: Option<&str>) {
+fn push_tool_call_message(
+    messages: &mut Vec<Value>,
+    tool_call: Value,
+    reasoning: Option<&str>,
+    echo_reasoning_content: bool,
+) {
     // Chat Completions requires that tool calls are grouped into a single assistant message
     // (with `tool_calls: [...]`) followed by tool role responses.
     if let Some(Value::Object(obj)) = messages.last_mut()
@@ -601,6 +715,9 @@ fn push_tool_call_message(messages: &mut Vec<Value>, tool_call: Value, reasoning
         && let Some(tool_calls) = obj.get_mut("tool_calls").and_then(Value::as_array_mut)
     {
         tool_calls.push(tool_call);
+        if !echo_reasoning_content {
+            return;
+        }
         // DeepSeek Reasoner requires reasoning_content field when tool_calls are present
         if let Some(reasoning) = reasoning {
             if let Some(Value::String(existing)) = obj.get_mut("reasoning_content") {
@@ -629,7 +746,7 @@ fn push_tool_call_message(messages: &mut Vec<Value>, tool_call: Value, reasoning
         "content": null,
         "tool_calls": [tool_call],
     });
-    if let Some(obj) = msg.as_object_mut() {
+    if echo_reasoning_content && let Some(obj) = msg.as_object_mut() {
         // DeepSeek Reasoner requires reasoning_content field when tool_calls are present
         if let Some(reasoning) = reasoning {
             obj.insert("reasoning_content".to_string(), json!(reasoning));
@@ -670,6 +787,7 @@ mod tests {
                 retry_transport: true,
             },
             stream_idle_timeout: Duration::from_secs(1),
+            first_byte_timeout: None,
         }
     }
 
@@ -681,6 +799,8 @@ mod tests {
             content: vec![ContentItem::InputText {
                 text: "hi".to_string(),
             }],
+            reasoning_content: None,
+            tool_calls: None,
         }];
         let req = ChatRequestBuilder::new("gpt-test", "inst", &prompt_input, &[], None)
             .conversation_id(Some("conv-1".into()))
@@ -698,6 +818,268 @@ mod tests {
         );
     }
 
+    #[test]
+    fn requests_usage_in_stream_options() {
+        let prompt_input = vec![ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: "hi".to_string(),
+            }],
+            reasoning_content: None,
+            tool_calls: None,
+        }];
+        let req = ChatRequestBuilder::new("deepseek-chat", "inst", &prompt_input, &[], None)
+            .build(&provider())
+            .expect("request");
+
+        assert_eq!(req.body["stream_options"], json!({"include_usage": true}));
+    }
+
+    #[test]
+    fn includes_only_configured_sampling_params() {
+        let prompt_input = vec![ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: "hi".to_string(),
+            }],
+            reasoning_content: None,
+            tool_calls: None,
+        }];
+        let req = ChatRequestBuilder::new("gpt-test", "inst", &prompt_input, &[], None)
+            .sampling(SamplingParams {
+                temperature: Some(0.2),
+                max_tokens: Some(512),
+                stop: Some(vec!["END".to_string()]),
+                ..Default::default()
+            })
+            .build(&provider())
+            .expect("request");
+
+        assert_eq!(req.body["temperature"], json!(0.2));
+        assert_eq!(req.body["max_tokens"], json!(512));
+        assert_eq!(req.body["stop"], json!(["END"]));
+        assert!(req.body.get("top_p").is_none());
+        assert!(req.body.get("presence_penalty").is_none());
+    }
+
+    #[test]
+    fn echoes_reasoning_content_only_when_requested() {
+        let prompt_input = vec![
+            ResponseItem::Message {
+                id: None,
+                role: "assistant".to_string(),
+                content: vec![ContentItem::OutputText {
+                    text: "done".to_string(),
+                }],
+                reasoning_content: None,
+                tool_calls: None,
+            },
+            ResponseItem::FunctionCall {
+                id: None,
+                name: "shell".to_string(),
+                arguments: "{}".to_string(),
+                call_id: "call-a".to_string(),
+            },
+        ];
+
+        let echoed = ChatRequestBuilder::new(
+            "deepseek-reasoner",
+            "inst",
+            &prompt_input,
+            &[],
+            Some("thinking".to_string()),
+        )
+        .dialect(ChatDialect {
+            echo_reasoning_content: true,
+            ..Default::default()
+        })
+        .build(&provider())
+        .expect("request");
+        assert_eq!(echoed.body["messages"][1]["reasoning_content"], "thinking");
+        assert_eq!(echoed.body["messages"][2]["reasoning_content"], "");
+
+        let plain = ChatRequestBuilder::new("gpt-test", "inst", &prompt_input, &[], None)
+            .build(&provider())
+            .expect("request");
+        assert_eq!(plain.body["messages"][1].get("reasoning_content"), None);
+        assert_eq!(plain.body["messages"][2].get("reasoning_content"), None);
+    }
+
+    #[test]
+    fn stable_prefix_rebuilds_earlier_turns_identically() {
+        fn user(text: &str) -> ResponseItem {
+            ResponseItem::Message {
+                id: None,
+                role: "user".to_string(),
+                content: vec![ContentItem::InputText {
+                    text: text.to_string(),
+                }],
+                reasoning_content: None,
+                tool_calls: None,
+            }
+        }
+        fn assistant(text: &str, reasoning: &str) -> ResponseItem {
+            ResponseItem::Message {
+                id: None,
+                role: "assistant".to_string(),
+                content: vec![ContentItem::OutputText {
+                    text: text.to_string(),
+                }],
+                reasoning_content: Some(reasoning.to_string()),
+                tool_calls: None,
+            }
+        }
+        fn thinking(text: &str) -> ResponseItem {
+            ResponseItem::Reasoning {
+                id: String::new(),
+                summary: Vec::new(),
+                content: Some(vec![ReasoningItemContent::ReasoningText {
+                    text: text.to_string(),
+                }]),
+                encrypted_content: None,
+            }
+        }
+        fn build(input: &[ResponseItem], latest: &str, stable_prefix: bool) -> Vec<Value> {
+            let req = ChatRequestBuilder::new(
+                "deepseek-reasoner",
+                "inst",
+                input,
+                &[],
+                Some(latest.to_string()),
+            )
+            .dialect(ChatDialect {
+                echo_reasoning_content: true,
+                ..Default::default()
+            })
+            .stable_prefix(stable_prefix)
+            .build(&provider())
+            .expect("request");
+            req.body["messages"].as_array().cloned().unwrap_or_default()
+        }
+
+        let first_turn = vec![
+            user("q1"),
+            thinking("plan"),
+            ResponseItem::FunctionCall {
+                id: None,
+                name: "shell".to_string(),
+                arguments: "{}".to_string(),
+                call_id: "call-a".to_string(),
+            },
+            ResponseItem::FunctionCallOutput {
+                call_id: "call-a".to_string(),
+                output: FunctionCallOutputPayload {
+                    content: "ok".to_string(),
+                    ..Default::default()
+                },
+            },
+            assistant("a1", "r1"),
+        ];
+        let mut second_turn = first_turn.clone();
+        second_turn.extend([user("q2"), assistant("a2", "r2")]);
+
+        let before = build(&first_turn, "r1", true);
+        let after = build(&second_turn, "r2", true);
+        assert_eq!(&after[..before.len()], before.as_slice());
+        assert_eq!(before[2]["reasoning_content"], "plan");
+
+        let before = build(&first_turn, "r1", false);
+        let after = build(&second_turn, "r2", false);
+        assert_ne!(&after[..before.len()], before.as_slice());
+    }
+
+    #[test]
+    fn stable_prefix_sorts_tools_by_name() {
+        let tools = vec![
+            json!({"type": "function", "function": {"name": "shell"}}),
+            json!({"type": "function", "function": {"name": "mcp__docs__search"}}),
+            json!({"type": "function", "function": {"name": "apply_patch"}}),
+        ];
+        let names = |stable_prefix: bool| -> Vec<String> {
+            let req = ChatRequestBuilder::new("gpt-test", "inst", &[], &tools, None)
+                .stable_prefix(stable_prefix)
+                .build(&provider())
+                .expect("request");
+            req.body["tools"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|tool| tool_name(tool).to_string())
+                .collect()
+        };
+
+        assert_eq!(
+            names(false),
+            vec!["shell", "mcp__docs__search", "apply_patch"]
+        );
+        assert_eq!(
+            names(true),
+            vec!["apply_patch", "mcp__docs__search", "shell"]
+        );
+    }
+
+    #[test]
+    fn encodes_output_schema_as_json_schema_response_format() {
+        let prompt_input = vec![ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: "hi".to_string(),
+            }],
+            reasoning_content: None,
+            tool_calls: None,
+        }];
+        let schema = json!({"type": "object", "properties": {"answer": {"type": "string"}}});
+        let req = ChatRequestBuilder::new("gpt-test", "inst", &prompt_input, &[], None)
+            .output_schema(Some(&schema))
+            .build(&provider())
+            .expect("request");
+
+        assert_eq!(
+            req.body["response_format"],
+            json!({
+                "type": "json_schema",
+                "json_schema": {
+                    "name": "codex_output_schema",
+                    "strict": true,
+                    "schema": schema,
+                },
+            })
+        );
+        assert_eq!(req.body["messages"][0]["content"], "inst");
+    }
+
+    #[test]
+    fn falls_back_to_json_object_with_schema_in_instructions() {
+        let prompt_input = vec![ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: "hi".to_string(),
+            }],
+            reasoning_content: None,
+            tool_calls: None,
+        }];
+        let schema = json!({"type": "object", "required": ["answer"]});
+        let req = ChatRequestBuilder::new("deepseek-chat", "inst", &prompt_input, &[], None)
+            .output_schema(Some(&schema))
+            .dialect(ChatDialect {
+                response_format: ChatResponseFormat::JsonObject,
+                ..Default::default()
+            })
+            .build(&provider())
+            .expect("request");
+
+        assert_eq!(req.body["response_format"], json!({"type": "json_object"}));
+        let system = req.body["messages"][0]["content"]
+            .as_str()
+            .expect("system content");
+        assert!(system.starts_with("inst\n\n"));
+        assert!(system.contains("\"required\""));
+    }
+
     #[test]
     fn groups_consecutive_tool_calls_into_a_single_assistant_message() {
         let prompt_input = vec![
@@ -707,6 +1089,8 @@ mod tests {
                 content: vec![ContentItem::InputText {
                     text: "read these".to_string(),
                 }],
+                reasoning_content: None,
+                tool_calls: None,
             },
             ResponseItem::FunctionCall {
                 id: None,
@@ -782,4 +1166,105 @@ mod tests {
         assert_eq!(messages[5]["role"], "tool");
         assert_eq!(messages[5]["tool_call_id"], "call-c");
     }
+
+    #[test]
+    fn maps_developer_role_unless_dialect_supports_it() {
+        let prompt_input = vec![ResponseItem::Message {
+            id: None,
+            role: "developer".to_string(),
+            content: vec![ContentItem::InputText {
+                text: "be brief".to_string(),
+            }],
+            reasoning_content: None,
+            tool_calls: None,
+        }];
+
+        let mapped = ChatRequestBuilder::new("gpt-test", "inst", &prompt_input, &[], None)
+            .build(&provider())
+            .expect("request");
+        assert_eq!(mapped.body["messages"][1]["role"], "user");
+
+        let native = ChatRequestBuilder::new("gpt-test", "inst", &prompt_input, &[], None)
+            .dialect(ChatDialect {
+                developer_role: true,
+                ..Default::default()
+            })
+            .build(&provider())
+            .expect("request");
+        assert_eq!(native.body["messages"][1]["role"], "developer");
+    }
+
+    #[test]
+    fn sends_tool_content_as_string_when_dialect_requires_it() {
+        let prompt_input = vec![
+            ResponseItem::FunctionCall {
+                id: None,
+                name: "view_image".to_string(),
+                arguments: "{}".to_string(),
+                call_id: "call-a".to_string(),
+            },
+            ResponseItem::FunctionCallOutput {
+                call_id: "call-a".to_string(),
+                output: FunctionCallOutputPayload {
+                    content: "image attached".to_string(),
+                    content_items: Some(vec![FunctionCallOutputContentItem::InputText {
+                        text: "image attached".to_string(),
+                    }]),
+                    ..Default::default()
+                },
+            },
+        ];
+
+        let parts = ChatRequestBuilder::new("gpt-test", "inst", &prompt_input, &[], None)
+            .build(&provider())
+            .expect("request");
+        assert_eq!(
+            parts.body["messages"][2]["content"],
+            json!([{"type": "text", "text": "image attached"}])
+        );
+
+        let string = ChatRequestBuilder::new("gpt-test", "inst", &prompt_input, &[], None)
+            .dialect(ChatDialect {
+                tool_content_as_string: true,
+                ..Default::default()
+            })
+            .build(&provider())
+            .expect("request");
+        assert_eq!(string.body["messages"][2]["content"], "image attached");
+    }
+
+    #[test]
+    fn validates_tool_call_sequence_only_when_dialect_asks() {
+        let prompt_input = vec![
+            ResponseItem::FunctionCall {
+                id: None,
+                name: "shell".to_string(),
+                arguments: "{}".to_string(),
+                call_id: "call-a".to_string(),
+            },
+            ResponseItem::Message {
+                id: None,
+                role: "assistant".to_string(),
+                content: vec![ContentItem::OutputText {
+                    text: "done".to_string(),
+                }],
+                reasoning_content: None,
+                tool_calls: None,
+            },
+        ];
+
+        let err = ChatRequestBuilder::new("gpt-test", "inst", &prompt_input, &[], None)
+            .build(&provider())
+            .err()
+            .expect("unanswered tool call should be rejected");
+        assert!(matches!(err, ApiError::Api { status, .. } if status == StatusCode::BAD_REQUEST));
+
+        ChatRequestBuilder::new("gpt-test", "inst", &prompt_input, &[], None)
+            .dialect(ChatDialect {
+                validate_tool_calls: Some(false),
+                ..Default::default()
+            })
+            .build(&provider())
+            .expect("validation disabled");
+    }
 }
@@ -6,6 +6,7 @@ use crate::provider::Provider;
 use crate::requests::headers::build_conversation_headers;
 use crate::requests::headers::insert_header;
 use crate::requests::headers::subagent_header;
+use codex_protocol::config_types::SamplingParams;
 use codex_protocol::models::ResponseItem;
 use codex_protocol::protocol::SessionSource;
 use http::HeaderMap;
@@ -36,6 +37,7 @@ pub struct ResponsesRequestBuilder<'a> {
     include: Vec<String>,
     prompt_cache_key: Option<String>,
     text: Option<TextControls>,
+    sampling: SamplingParams,
     conversation_id: Option<String>,
     session_source: Option<SessionSource>,
     store_override: Option<bool>,
@@ -83,6 +85,13 @@ impl<'a> ResponsesRequestBuilder<'a> {
         self
     }
 
+    /// Only `temperature`, `top_p` and `max_tokens` exist on the Responses
+    /// API; penalties and stop sequences are ignored.
+    pub fn sampling(mut self, sampling: SamplingParams) -> Self {
+        self.sampling = sampling;
+        self
+    }
+
     pub fn conversation(mut self, conversation_id: Option<String>) -> Self {
         self.conversation_id = conversation_id;
         self
@@ -137,6 +146,9 @@ impl<'a> ResponsesRequestBuilder<'a> {
             include: self.include,
             prompt_cache_key: self.prompt_cache_key,
             text: self.text,
+            temperature: self.sampling.temperature,
+            top_p: self.sampling.top_p,
+            max_output_tokens: self.sampling.max_tokens,
         };
 
         let mut body = serde_json::to_value(&req)
@@ -212,6 +224,7 @@ mod tests {
                 retry_transport: true,
             },
             stream_idle_timeout: Duration::from_secs(5),
+            first_byte_timeout: None,
         }
     }
 
@@ -223,11 +236,15 @@ mod tests {
                 id: Some("m1".into()),
                 role: "assistant".into(),
                 content: Vec::new(),
+                reasoning_content: None,
+                tool_calls: None,
             },
             ResponseItem::Message {
                 id: None,
                 role: "assistant".into(),
                 content: Vec::new(),
+                reasoning_content: None,
+                tool_calls: None,
             },
         ];
 
@@ -258,4 +275,25 @@ mod tests {
             Some(&HeaderValue::from_static("review"))
         );
     }
+
+    #[test]
+    fn maps_sampling_params_to_responses_fields() {
+        let provider = provider("openai", "https://api.openai.com/v1");
+        let request = ResponsesRequestBuilder::new("gpt-test", "inst", &[])
+            .sampling(SamplingParams {
+                temperature: Some(0.5),
+                top_p: Some(0.9),
+                max_tokens: Some(1024),
+                stop: Some(vec!["END".to_string()]),
+                ..Default::default()
+            })
+            .build(&provider)
+            .expect("request");
+
+        assert_eq!(request.body["temperature"], serde_json::json!(0.5));
+        assert_eq!(request.body["top_p"], serde_json::json!(0.9));
+        assert_eq!(request.body["max_output_tokens"], serde_json::json!(1024));
+        assert!(request.body.get("max_tokens").is_none());
+        assert!(request.body.get("stop").is_none());
+    }
 }
@@ -6,9 +6,11 @@ use codex_client::StreamResponse;
 use codex_protocol::models::ContentItem;
 use codex_protocol::models::ReasoningItemContent;
 use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::TokenUsage;
 use eventsource_stream::Eventsource;
 use futures::Stream;
 use futures::StreamExt;
+use serde::Deserialize;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::time::Duration;
@@ -22,14 +24,95 @@ pub(crate) fn spawn_chat_stream(
     stream_response: StreamResponse,
     idle_timeout: Duration,
     telemetry: Option<std::sync::Arc<dyn SseTelemetry>>,
+    strip_tokens: Vec<String>,
 ) -> ResponseStream {
     let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent, ApiError>>(1600);
     tokio::spawn(async move {
-        process_chat_sse(stream_response.bytes, tx_event, idle_timeout, telemetry).await;
+        process_chat_sse(
+            stream_response.bytes,
+            tx_event,
+            idle_timeout,
+            telemetry,
+            strip_tokens,
+        )
+        .await;
     });
     ResponseStream { rx_event }
 }
 
+/// Usage block attached to the final chunk when the request sets
+/// `stream_options.include_usage`.
+///
+/// OpenAI reports cache hits under `prompt_tokens_details.cached_tokens`, while
+/// DeepSeek reports them as `prompt_cache_hit_tokens`/`prompt_cache_miss_tokens`.
+#[derive(Debug, Default, Deserialize)]
+struct ChatCompletionUsage {
+    #[serde(default)]
+    prompt_tokens: Option<i64>,
+    #[serde(default)]
+    completion_tokens: i64,
+    #[serde(default)]
+    total_tokens: Option<i64>,
+    #[serde(default)]
+    prompt_tokens_details: Option<ChatPromptTokensDetails>,
+    #[serde(default)]
+    completion_tokens_details: Option<ChatCompletionTokensDetails>,
+    #[serde(default)]
+    prompt_cache_hit_tokens: Option<i64>,
+    #[serde(default)]
+    prompt_cache_miss_tokens: Option<i64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ChatPromptTokensDetails {
+    #[serde(default)]
+    cached_tokens: Option<i64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ChatCompletionTokensDetails {
+    #[serde(default)]
+    reasoning_tokens: Option<i64>,
+}
+
+impl From<ChatCompletionUsage> for TokenUsage {
+    fn from(val: ChatCompletionUsage) -> Self {
+        let cache_hit = val.prompt_cache_hit_tokens;
+        let input_tokens = val
+            .prompt_tokens
+            .unwrap_or_else(|| cache_hit.unwrap_or(0) + val.prompt_cache_miss_tokens.unwrap_or(0));
+        let cached_input_tokens = val
+            .prompt_tokens_details
+            .and_then(|d| d.cached_tokens)
+            .or(cache_hit)
+            .unwrap_or(0);
+        let reasoning_output_tokens = val
+            .completion_tokens_details
+            .and_then(|d| d.reasoning_tokens)
+            .unwrap_or(0);
+        TokenUsage {
+            input_tokens,
+            cached_input_tokens,
+            output_tokens: val.completion_tokens,
+            reasoning_output_tokens,
+            total_tokens: val
+                .total_tokens
+                .unwrap_or(input_tokens + val.completion_tokens),
+        }
+    }
+}
+
+fn parse_usage(value: &serde_json::Value) -> Option<TokenUsage> {
+    let usage = value.get("usage").filter(|u| !u.is_null())?;
+    match serde_json::from_value::<ChatCompletionUsage>(usage.clone()) {
+        Ok(usage) => Some(usage.into()),
+        Err(err) => {
+            debug!("Failed to parse ChatCompletions usage: {err}, usage: {usage}");
+            None
+        }
+    }
+}
+
 /// Processes Server-Sent Events from the legacy Chat Completions streaming API.
 ///
 /// The upstream protocol terminates a streaming response with a final sentinel event
@@ -45,11 +128,20 @@ pub(crate) fn spawn_chat_stream(
 /// Higher-level workflows/tests that wait for completion before issuing subsequent model
 /// calls will then stall, which shows up as "expected N requests, got 1" verification
 /// failures in the mock server.
+///
+/// When the request asks for `stream_options.include_usage`, the usage block arrives
+/// either alongside the `finish_reason` chunk (DeepSeek) or in a trailing chunk with an
+/// empty `choices` array (OpenAI). `ResponseEvent::Completed` is therefore held back
+/// after `finish_reason: "stop"` until usage, the sentinel, or end-of-stream is seen.
+///
+/// `strip_tokens` lists dialect-specific special tokens removed from text and
+/// reasoning deltas before they are surfaced.
 pub async fn process_chat_sse<S>(
     stream: S,
     tx_event: mpsc::Sender<Result<ResponseEvent, ApiError>>,
     idle_timeout: Duration,
     telemetry: Option<std::sync::Arc<dyn SseTelemetry>>,
+    strip_tokens: Vec<String>,
 ) where
     S: Stream<Item = Result<bytes::Bytes, codex_client::TransportError>> + Unpin,
 {
@@ -71,6 +163,8 @@ pub async fn process_chat_sse<S>(
     let mut assistant_item: Option<ResponseItem> = None;
     let mut reasoning_item: Option<ResponseItem> = None;
     let mut completed_sent = false;
+    let mut completion_pending = false;
+    let mut token_usage: Option<TokenUsage> = None;
     let mut assistant_reasoning_content: Option<String> = None; // For DeepSeek reasoning_content
 
     async fn flush_and_complete(
@@ -78,6 +172,7 @@ pub async fn process_chat_sse<S>(
         reasoning_item: &mut Option<ResponseItem>,
         assistant_item: &mut Option<ResponseItem>,
         assistant_reasoning_content: &mut Option<String>,
+        token_usage: Option<TokenUsage>,
     ) {
         if let Some(reasoning) = reasoning_item.take() {
             let _ = tx_event
@@ -104,7 +199,7 @@ pub async fn process_chat_sse<S>(
         let _ = tx_event
             .send(Ok(ResponseEvent::Completed {
                 response_id: String::new(),
-                token_usage: None,
+                token_usage,
             }))
             .await;
     }
@@ -128,12 +223,24 @@ pub async fn process_chat_sse<S>(
                         &mut reasoning_item,
                         &mut assistant_item,
                         &mut assistant_reasoning_content,
+                        token_usage.take(),
                     )
                     .await;
                 }
                 return;
             }
             Err(_) => {
+                if completion_pending {
+                    // The model already finished; only the trailing usage chunk or the
+                    // sentinel is missing, so treat the turn as complete.
+                    let _ = tx_event
+                        .send(Ok(ResponseEvent::Completed {
+                            response_id: String::new(),
+                            token_usage: token_usage.take(),
+                        }))
+                        .await;
+                    return;
+                }
                 let _ = tx_event
                     .send(Err(ApiError::Stream("idle timeout waiting for SSE".into())))
                     .await;
@@ -156,6 +263,7 @@ pub async fn process_chat_sse<S>(
                     &mut reasoning_item,
                     &mut assistant_item,
                     &mut assistant_reasoning_content,
+                    token_usage.take(),
                 )
                 .await;
             }
@@ -173,6 +281,20 @@ pub async fn process_chat_sse<S>(
             }
         };
 
+        if let Some(usage) = parse_usage(&value) {
+            token_usage = Some(usage);
+            if completion_pending {
+                let _ = tx_event
+                    .send(Ok(ResponseEvent::Completed {
+                        response_id: String::new(),
+                        token_usage: token_usage.take(),
+                    }))
+                    .await;
+                completion_pending = false;
+                completed_sent = true;
+            }
+        }
+
         let Some(choices) = value.get("choices").and_then(|c| c.as_array()) else {
             continue;
         };
@@ -181,14 +303,29 @@ pub async fn process_chat_sse<S>(
             if let Some(delta) = choice.get("delta") {
                 if let Some(reasoning) = delta.get("reasoning") {
                     if let Some(text) = reasoning.as_str() {
-                        append_reasoning_text(&tx_event, &mut reasoning_item, text.to_string())
-                            .await;
+                        append_reasoning_text(
+                            &tx_event,
+                            &mut reasoning_item,
+                            text.to_string(),
+                            &strip_tokens,
+                        )
+                        .await;
                     } else if let Some(text) = reasoning.get("text").and_then(|v| v.as_str()) {
-                        append_reasoning_text(&tx_event, &mut reasoning_item, text.to_string())
-                            .await;
+                        append_reasoning_text(
+                            &tx_event,
+                            &mut reasoning_item,
+                            text.to_string(),
+                            &strip_tokens,
+                        )
+                        .await;
                     } else if let Some(text) = reasoning.get("content").and_then(|v| v.as_str()) {
-                        append_reasoning_text(&tx_event, &mut reasoning_item, text.to_string())
-                            .await;
+                        append_reasoning_text(
+                            &tx_event,
+                            &mut reasoning_item,
+                            text.to_string(),
+                            &strip_tokens,
+                        )
+                        .await;
                     }
                 }
 
@@ -200,13 +337,19 @@ pub async fn process_chat_sse<S>(
                                     &tx_event,
                                     &mut assistant_item,
                                     text.to_string(),
+                                    &strip_tokens,
                                 )
                                 .await;
                             }
                         }
                     } else if let Some(text) = content.as_str() {
-                        append_assistant_text(&tx_event, &mut assistant_item, text.to_string())
-                            .await;
+                        append_assistant_text(
+                            &tx_event,
+                            &mut assistant_item,
+                            text.to_string(),
+                            &strip_tokens,
+                        )
+                        .await;
                     }
                 }
 
@@ -269,14 +412,29 @@ pub async fn process_chat_sse<S>(
                 // Handle traditional reasoning
                 if let Some(reasoning) = message.get("reasoning") {
                     if let Some(text) = reasoning.as_str() {
-                        append_reasoning_text(&tx_event, &mut reasoning_item, text.to_string())
-                            .await;
+                        append_reasoning_text(
+                            &tx_event,
+                            &mut reasoning_item,
+                            text.to_string(),
+                            &strip_tokens,
+                        )
+                        .await;
                     } else if let Some(text) = reasoning.get("text").and_then(|v| v.as_str()) {
-                        append_reasoning_text(&tx_event, &mut reasoning_item, text.to_string())
-                            .await;
+                        append_reasoning_text(
+                            &tx_event,
+                            &mut reasoning_item,
+                            text.to_string(),
+                            &strip_tokens,
+                        )
+                        .await;
                     } else if let Some(text) = reasoning.get("content").and_then(|v| v.as_str()) {
-                        append_reasoning_text(&tx_event, &mut reasoning_item, text.to_string())
-                            .await;
+                        append_reasoning_text(
+                            &tx_event,
+                            &mut reasoning_item,
+                            text.to_string(),
+                            &strip_tokens,
+                        )
+                        .await;
                     }
                 }
 
@@ -306,13 +464,17 @@ pub async fn process_chat_sse<S>(
                         .await;
                 }
                 if !completed_sent {
-                    let _ = tx_event
-                        .send(Ok(ResponseEvent::Completed {
-                            response_id: String::new(),
-                            token_usage: None,
-                        }))
-                        .await;
-                    completed_sent = true;
+                    if token_usage.is_some() {
+                        let _ = tx_event
+                            .send(Ok(ResponseEvent::Completed {
+                                response_id: String::new(),
+                                token_usage: token_usage.take(),
+                            }))
+                            .await;
+                        completed_sent = true;
+                    } else {
+                        completion_pending = true;
+                    }
                 }
                 continue;
             }
@@ -394,9 +556,9 @@ async fn append_assistant_text(
     tx_event: &mpsc::Sender<Result<ResponseEvent, ApiError>>,
     assistant_item: &mut Option<ResponseItem>,
     text: String,
+    strip_tokens: &[String],
 ) {
-    // Filter out DeepSeek special tokens that shouldn't be displayed to users
-    let filtered_text = filter_deepseek_special_tokens(&text);
+    let filtered_text = strip_special_tokens(text, strip_tokens);
 
     if assistant_item.is_none() {
         let item = ResponseItem::Message {
@@ -426,9 +588,9 @@ async fn append_reasoning_text(
     tx_event: &mpsc::Sender<Result<ResponseEvent, ApiError>>,
     reasoning_item: &mut Option<ResponseItem>,
     text: String,
+    strip_tokens: &[String],
 ) {
-    // Filter out DeepSeek special tokens that shouldn't be displayed to users
-    let filtered_text = filter_deepseek_special_tokens(&text);
+    let filtered_text = strip_special_tokens(text, strip_tokens);
 
     if reasoning_item.is_none() {
         let item = ResponseItem::Reasoning {
@@ -462,13 +624,15 @@ async fn append_reasoning_text(
     }
 }
 
-/// Filters out DeepSeek special tokens that shouldn't be displayed to users.
-/// These tokens are used internally by the model but are not part of the actual response content.
-fn filter_deepseek_special_tokens(text: &str) -> String {
-    // Remove DeepSeek special tokens
-    text.replace("<｜end▁of▁thinking｜>", "")
-        .replace("<｜begin▁of▁thinking｜>", "")
-    // Add other DeepSeek special tokens here as they are discovered
+/// Removes the dialect's special tokens (e.g. DeepSeek's thinking markers),
+/// which are used internally by the model and should never reach the user.
+fn strip_special_tokens(mut text: String, strip_tokens: &[String]) -> String {
+    for token in strip_tokens.iter().filter(|token| !token.is_empty()) {
+        if text.contains(token.as_str()) {
+            text = text.replace(token.as_str(), "");
+        }
+    }
+    text
 }
 
 #[cfg(test)]
@@ -500,6 +664,10 @@ mod tests {
     }
 
     async fn collect_events(body: &str) -> Vec<ResponseEvent> {
+        collect_events_stripping(body, Vec::new()).await
+    }
+
+    async fn collect_events_stripping(body: &str, strip_tokens: Vec<String>) -> Vec<ResponseEvent> {
         let reader = ReaderStream::new(std::io::Cursor::new(body.to_string()))
             .map_err(|err| codex_client::TransportError::Network(err.to_string()));
         let (tx, mut rx) = mpsc::channel::<Result<ResponseEvent, ApiError>>(16);
@@ -508,6 +676,7 @@ mod tests {
             tx,
             Duration::from_millis(1000),
             None,
+            strip_tokens,
         ));
 
         let mut out = Vec::new();
@@ -803,4 +972,103 @@ mod tests {
         }));
         assert_matches!(events.last(), Some(ResponseEvent::Completed { .. }));
     }
+
+    #[tokio::test]
+    async fn reports_usage_from_trailing_chunk() {
+        let delta = json!({
+            "choices": [{ "delta": { "content": "hi" } }]
+        });
+        let finish = json!({
+            "choices": [{ "delta": {}, "finish_reason": "stop" }]
+        });
+        let usage = json!({
+            "choices": [],
+            "usage": {
+                "prompt_tokens": 100,
+                "completion_tokens": 20,
+                "total_tokens": 120,
+                "prompt_tokens_details": { "cached_tokens": 64 },
+                "completion_tokens_details": { "reasoning_tokens": 5 }
+            }
+        });
+
+        let mut body = build_body(&[delta, finish, usage]);
+        body.push_str("event: message\ndata: [DONE]\n\n");
+        let events = collect_events(&body).await;
+
+        assert_matches!(
+            events.last(),
+            Some(ResponseEvent::Completed {
+                token_usage: Some(usage),
+                ..
+            }) if *usage == TokenUsage {
+                input_tokens: 100,
+                cached_input_tokens: 64,
+                output_tokens: 20,
+                reasoning_output_tokens: 5,
+                total_tokens: 120,
+            }
+        );
+        assert_eq!(
+            events
+                .iter()
+                .filter(|ev| matches!(ev, ResponseEvent::Completed { .. }))
+                .count(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_deepseek_cache_usage_on_finish_chunk() {
+        let finish = json!({
+            "choices": [{ "delta": { "content": "ok" }, "finish_reason": "stop" }],
+            "usage": {
+                "prompt_tokens": 300,
+                "completion_tokens": 40,
+                "total_tokens": 340,
+                "prompt_cache_hit_tokens": 256,
+                "prompt_cache_miss_tokens": 44,
+                "completion_tokens_details": { "reasoning_tokens": 12 }
+            }
+        });
+
+        let events = collect_events(&build_body(&[finish])).await;
+
+        assert_matches!(
+            events.last(),
+            Some(ResponseEvent::Completed {
+                token_usage: Some(usage),
+                ..
+            }) if *usage == TokenUsage {
+                input_tokens: 300,
+                cached_input_tokens: 256,
+                output_tokens: 40,
+                reasoning_output_tokens: 12,
+                total_tokens: 340,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn strips_dialect_special_tokens_from_deltas() {
+        let reasoning = json!({
+            "choices": [{"delta": {"reasoning": "plan<｜end▁of▁thinking｜>"}}]
+        });
+        let content = json!({
+            "choices": [{"delta": {"content": "<｜end▁of▁thinking｜>answer"}}]
+        });
+        let body = build_body(&[reasoning, content]);
+
+        let events =
+            collect_events_stripping(&body, vec!["<｜end▁of▁thinking｜>".to_string()]).await;
+        let deltas: Vec<&str> = events
+            .iter()
+            .filter_map(|ev| match ev {
+                ResponseEvent::ReasoningContentDelta { delta, .. } => Some(delta.as_str()),
+                ResponseEvent::OutputTextDelta(delta) => Some(delta.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(deltas, vec!["plan", "answer"]);
+    }
 }
@@ -64,21 +64,32 @@ where
 {
     // Wraps `run_with_retry` to attach per-attempt request telemetry for both
     // unary and streaming HTTP calls.
-    run_with_retry(policy, make_request, move |req, attempt| {
-        let telemetry = telemetry.clone();
-        let send = send.clone();
-        async move {
-            let start = Instant::now();
-            let result = send(req).await;
-            if let Some(t) = telemetry.as_ref() {
-                let (status, err) = match &result {
-                    Ok(resp) => (Some(resp.status()), None),
-                    Err(err) => (http_status(err), Some(err)),
-                };
-                t.on_request(attempt, status, err, start.elapsed());
+    let max_attempts = policy.max_attempts;
+    let retry_telemetry = telemetry.clone();
+    run_with_retry(
+        policy,
+        make_request,
+        move |req, attempt| {
+            let telemetry = telemetry.clone();
+            let send = send.clone();
+            async move {
+                let start = Instant::now();
+                let result = send(req).await;
+                if let Some(t) = telemetry.as_ref() {
+                    let (status, err) = match &result {
+                        Ok(resp) => (Some(resp.status()), None),
+                        Err(err) => (http_status(err), Some(err)),
+                    };
+                    t.on_request(attempt, status, err, start.elapsed());
+                }
+                result
             }
-            result
-        }
-    })
+        },
+        |attempt, err, delay| {
+            if let Some(t) = retry_telemetry.as_ref() {
+                t.on_retry(attempt, max_attempts, err, delay);
+            }
+        },
+    )
     .await
 }
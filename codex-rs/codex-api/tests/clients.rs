@@ -14,6 +14,7 @@ use codex_api::WireApi;
 use codex_api::requests::responses::Compression;
 use codex_client::HttpTransport;
 use codex_client::Request;
+use codex_client::RequestTelemetry;
 use codex_client::Response;
 use codex_client::StreamResponse;
 use codex_client::TransportError;
@@ -134,6 +135,7 @@ fn provider(name: &str, wire: WireApi) -> Provider {
             retry_transport: true,
         },
         stream_idle_timeout: Duration::from_millis(10),
+        first_byte_timeout: None,
     }
 }
 
@@ -308,10 +310,14 @@ async fn streaming_client_retries_on_transport_error() -> Result<()> {
             content: vec![ContentItem::InputText {
                 text: "hi".to_string(),
             }],
+            reasoning_content: None,
+            tool_calls: None,
         }],
         tools: Vec::<Value>::new(),
         parallel_tool_calls: false,
+        reasoning_content: None,
         output_schema: None,
+        sampling: Default::default(),
     };
 
     let options = ResponsesOptions::default();
@@ -320,3 +326,92 @@ async fn streaming_client_retries_on_transport_error() -> Result<()> {
     assert_eq!(transport.attempts(), 2);
     Ok(())
 }
+
+/// Accepts the request but never sends a byte on the first attempt.
+#[derive(Clone, Default)]
+struct StallingTransport {
+    attempts: Arc<Mutex<i64>>,
+}
+
+#[async_trait]
+impl HttpTransport for StallingTransport {
+    async fn execute(&self, _req: Request) -> Result<Response, TransportError> {
+        Err(TransportError::Build("execute should not run".to_string()))
+    }
+
+    async fn stream(&self, _req: Request) -> Result<StreamResponse, TransportError> {
+        let attempt = {
+            let mut attempts = self
+                .attempts
+                .lock()
+                .unwrap_or_else(|err| panic!("mutex poisoned: {err}"));
+            *attempts += 1;
+            *attempts
+        };
+
+        let bytes: codex_client::ByteStream = if attempt == 1 {
+            Box::pin(futures::stream::pending())
+        } else {
+            Box::pin(futures::stream::iter(vec![Ok(Bytes::from(
+                "event: message\ndata: [DONE]\n\n",
+            ))]))
+        };
+        Ok(StreamResponse {
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            bytes,
+        })
+    }
+}
+
+#[derive(Default)]
+struct RetryRecorder {
+    retries: Mutex<Vec<(u64, u64, String)>>,
+}
+
+impl RequestTelemetry for RetryRecorder {
+    fn on_request(
+        &self,
+        _attempt: u64,
+        _status: Option<StatusCode>,
+        _error: Option<&TransportError>,
+        _duration: Duration,
+    ) {
+    }
+
+    fn on_retry(&self, attempt: u64, max_attempts: u64, error: &TransportError, _delay: Duration) {
+        self.retries
+            .lock()
+            .unwrap_or_else(|err| panic!("mutex poisoned: {err}"))
+            .push((attempt, max_attempts, error.to_string()));
+    }
+}
+
+#[tokio::test]
+async fn streaming_client_retries_when_first_byte_times_out() -> Result<()> {
+    let transport = StallingTransport::default();
+    let recorder = Arc::new(RetryRecorder::default());
+
+    let mut provider = provider("deepseek", WireApi::Chat);
+    provider.retry.max_attempts = 2;
+    provider.first_byte_timeout = Some(Duration::from_millis(50));
+
+    let client = ChatClient::new(transport.clone(), provider, NoAuth)
+        .with_telemetry(Some(recorder.clone()), None);
+    let _stream = client
+        .stream(serde_json::json!({ "echo": true }), HeaderMap::new())
+        .await?;
+
+    let attempts = *transport
+        .attempts
+        .lock()
+        .unwrap_or_else(|err| panic!("mutex poisoned: {err}"));
+    assert_eq!(attempts, 2);
+    let retries = recorder
+        .retries
+        .lock()
+        .unwrap_or_else(|err| panic!("mutex poisoned: {err}"))
+        .clone();
+    assert_eq!(retries, vec![(1, 2, "timeout".to_string())]);
+    Ok(())
+}
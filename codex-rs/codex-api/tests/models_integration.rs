@@ -43,6 +43,7 @@ fn provider(base_url: &str) -> Provider {
             retry_transport: true,
         },
         stream_idle_timeout: std::time::Duration::from_secs(1),
+        first_byte_timeout: None,
     }
 }
 
@@ -87,6 +88,8 @@ async fn models_client_hits_models_endpoint() {
             auto_compact_token_limit: None,
             effective_context_window_percent: 95,
             experimental_supported_tools: Vec::new(),
+            requires_reasoning_content: false,
+            tokenizer: None,
         }],
     };
 
@@ -8,7 +8,11 @@ use codex_api::AuthProvider;
 use codex_api::Provider;
 use codex_api::ResponseEvent;
 use codex_api::ResponsesClient;
+use codex_api::TrafficCapture;
 use codex_api::WireApi;
+use codex_api::capture::CaptureParser;
+use codex_api::capture::load_capture;
+use codex_api::capture::replay_exchange;
 use codex_api::requests::responses::Compression;
 use codex_client::HttpTransport;
 use codex_client::Request;
@@ -22,6 +26,7 @@ use http::HeaderMap;
 use http::StatusCode;
 use pretty_assertions::assert_eq;
 use serde_json::Value;
+use std::sync::Arc;
 
 #[derive(Clone)]
 struct FixtureSseTransport {
@@ -76,6 +81,7 @@ fn provider(name: &str, wire: WireApi) -> Provider {
             retry_transport: true,
         },
         stream_idle_timeout: Duration::from_millis(50),
+        first_byte_timeout: None,
     }
 }
 
@@ -236,3 +242,64 @@ async fn responses_stream_aggregates_output_text_deltas() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn captured_responses_stream_replays_offline() -> Result<()> {
+    let item = serde_json::json!({
+        "type": "response.output_item.done",
+        "item": {
+            "type": "message",
+            "role": "assistant",
+            "content": [{"type": "output_text", "text": "Hello"}]
+        }
+    });
+    let completed = serde_json::json!({
+        "type": "response.completed",
+        "response": { "id": "resp-capture" }
+    });
+
+    let capture_dir = tempfile::tempdir()?;
+    let body = build_responses_body(vec![item, completed]);
+    let transport = FixtureSseTransport::new(body.clone());
+    let client = ResponsesClient::new(transport, provider("openai", WireApi::Responses), NoAuth)
+        .with_capture(Some(Arc::new(TrafficCapture::new(capture_dir.path()))));
+
+    let mut live = client
+        .stream(
+            serde_json::json!({"model": "gpt-test"}),
+            HeaderMap::new(),
+            Compression::None,
+        )
+        .await?;
+    let mut live_events = Vec::new();
+    while let Some(ev) = live.next().await {
+        // Rate limits come from response headers, which are not captured.
+        let ev = ev?;
+        if !matches!(ev, ResponseEvent::RateLimits(_)) {
+            live_events.push(format!("{ev:?}"));
+        }
+    }
+
+    let exchanges = load_capture(capture_dir.path())?;
+    assert_eq!(exchanges.len(), 1);
+    let exchange = &exchanges[0];
+    assert_eq!(exchange.sequence, 1);
+    assert_eq!(exchange.path, "responses");
+    assert_eq!(exchange.parser, CaptureParser::Responses);
+    assert_eq!(exchange.body, serde_json::json!({"model": "gpt-test"}));
+    let response_path = exchange
+        .response
+        .as_ref()
+        .unwrap_or_else(|| panic!("missing captured response"));
+    assert_eq!(std::fs::read_to_string(response_path)?, body);
+
+    let mut replayed = replay_exchange(exchange, Duration::from_millis(50))?;
+    let mut replayed_events = Vec::new();
+    while let Some(ev) = replayed.next().await {
+        replayed_events.push(format!("{:?}", ev?));
+    }
+
+    assert_eq!(replayed_events, live_events);
+
+    Ok(())
+}
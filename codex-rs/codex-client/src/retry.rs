@@ -46,10 +46,14 @@ pub fn backoff(base: Duration, attempt: u64) -> Duration {
     Duration::from_millis((raw as f64 * jitter) as u64)
 }
 
+/// Runs `op` until it succeeds or the policy gives up. `on_retry` is invoked
+/// with the upcoming attempt number, the error that triggered the retry and
+/// the backoff delay, before sleeping.
 pub async fn run_with_retry<T, F, Fut>(
     policy: RetryPolicy,
     mut make_req: impl FnMut() -> Request,
     op: F,
+    mut on_retry: impl FnMut(u64, &TransportError, Duration),
 ) -> Result<T, TransportError>
 where
     F: Fn(Request, u64) -> Fut,
@@ -64,7 +68,9 @@ where
                     .retry_on
                     .should_retry(&err, attempt, policy.max_attempts) =>
             {
-                sleep(backoff(policy.base_delay, attempt + 1)).await;
+                let delay = backoff(policy.base_delay, attempt + 1);
+                on_retry(attempt + 1, &err, delay);
+                sleep(delay).await;
             }
             Err(err) => return Err(err),
         }
@@ -11,4 +11,15 @@ pub trait RequestTelemetry: Send + Sync {
         error: Option<&TransportError>,
         duration: Duration,
     );
+
+    /// Called when a failed attempt is about to be retried. `attempt` is the
+    /// number of the upcoming attempt (1-based) out of `max_attempts` retries.
+    fn on_retry(
+        &self,
+        _attempt: u64,
+        _max_attempts: u64,
+        _error: &TransportError,
+        _delay: Duration,
+    ) {
+    }
 }
@@ -1,5 +1,6 @@
 use codex_core::WireApi;
 use codex_core::config::Config;
+use codex_protocol::config_types::SamplingParams;
 
 use crate::sandbox_summary::summarize_sandbox_policy;
 
@@ -28,6 +29,36 @@ pub fn create_config_summary_entries(config: &Config, model: &str) -> Vec<(&'sta
             config.model_reasoning_summary.to_string(),
         ));
     }
+    let sampling = match &config.model_provider.sampling {
+        Some(defaults) => config.sampling.clone().or(defaults),
+        None => config.sampling.clone(),
+    };
+    if !sampling.is_empty() {
+        entries.push(("sampling", summarize_sampling(&sampling)));
+    }
 
     entries
 }
+
+fn summarize_sampling(sampling: &SamplingParams) -> String {
+    let mut parts = Vec::new();
+    if let Some(temperature) = sampling.temperature {
+        parts.push(format!("temperature {temperature}"));
+    }
+    if let Some(top_p) = sampling.top_p {
+        parts.push(format!("top_p {top_p}"));
+    }
+    if let Some(max_tokens) = sampling.max_tokens {
+        parts.push(format!("max_tokens {max_tokens}"));
+    }
+    if let Some(presence_penalty) = sampling.presence_penalty {
+        parts.push(format!("presence_penalty {presence_penalty}"));
+    }
+    if let Some(frequency_penalty) = sampling.frequency_penalty {
+        parts.push(format!("frequency_penalty {frequency_penalty}"));
+    }
+    if let Some(stop) = &sampling.stop {
+        parts.push(format!("stop {stop:?}"));
+    }
+    parts.join(", ")
+}
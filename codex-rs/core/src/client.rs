@@ -20,6 +20,7 @@ use codex_api::ResponsesOptions as ApiResponsesOptions;
 use codex_api::ResponsesWebsocketClient as ApiWebSocketResponsesClient;
 use codex_api::ResponsesWebsocketConnection as ApiWebSocketConnection;
 use codex_api::SseTelemetry;
+use codex_api::TrafficCapture;
 use codex_api::TransportError;
 use codex_api::build_conversation_headers;
 use codex_api::common::Reasoning;
@@ -53,7 +54,7 @@ use crate::client_common::Prompt;
 use crate::client_common::ResponseEvent;
 use crate::client_common::ResponseStream;
 use crate::config::Config;
-use crate::default_client::build_reqwest_client;
+use crate::default_client::build_reqwest_client_with_connect_timeout;
 use crate::error::CodexErr;
 use crate::error::Result;
 use crate::features::FEATURES;
@@ -64,6 +65,10 @@ use crate::model_provider_info::WireApi;
 use crate::tools::spec::create_tools_json_for_chat_completions_api;
 use crate::tools::spec::create_tools_json_for_responses_api;
 
+/// Directory under `$CODEX_HOME` that holds one traffic capture per thread
+/// when [`Feature::TrafficCapture`] is enabled.
+pub const CAPTURES_SUBDIR: &str = "captures";
+
 #[derive(Debug)]
 struct ModelClientState {
     config: Arc<Config>,
@@ -75,6 +80,7 @@ struct ModelClientState {
     effort: Option<ReasoningEffortConfig>,
     summary: ReasoningSummaryConfig,
     session_source: SessionSource,
+    capture: Option<Arc<TrafficCapture>>,
 }
 
 #[derive(Debug, Clone)]
@@ -86,8 +92,23 @@ pub struct ModelClientSession {
     state: Arc<ModelClientState>,
     connection: Option<ApiWebSocketConnection>,
     websocket_last_items: Vec<ResponseItem>,
+    request_retry_notifier: Option<RequestRetryNotifier>,
+}
+
+/// A transport-level retry scheduled by the provider's request retry policy,
+/// e.g. after a connect or first-byte timeout.
+#[derive(Debug)]
+pub struct RequestRetry {
+    /// Upcoming attempt number (1-based).
+    pub attempt: u64,
+    pub max_attempts: u64,
+    pub delay: Duration,
+    pub error: CodexErr,
 }
 
+/// Callback that surfaces [`RequestRetry`] progress to the user.
+pub type RequestRetryNotifier = Arc<dyn Fn(RequestRetry) + Send + Sync>;
+
 #[allow(clippy::too_many_arguments)]
 impl ModelClient {
     pub fn new(
@@ -101,6 +122,14 @@ impl ModelClient {
         conversation_id: ThreadId,
         session_source: SessionSource,
     ) -> Self {
+        let capture = config.features.enabled(Feature::TrafficCapture).then(|| {
+            TrafficCapture::shared(
+                config
+                    .codex_home
+                    .join(CAPTURES_SUBDIR)
+                    .join(conversation_id.to_string()),
+            )
+        });
         Self {
             state: Arc::new(ModelClientState {
                 config,
@@ -112,6 +141,7 @@ impl ModelClient {
                 effort,
                 summary,
                 session_source,
+                capture,
             }),
         }
     }
@@ -121,6 +151,7 @@ impl ModelClient {
             state: Arc::clone(&self.state),
             connection: None,
             websocket_last_items: Vec::new(),
+            request_retry_notifier: None,
         }
     }
 }
@@ -195,7 +226,7 @@ impl ModelClient {
             .provider
             .to_api_provider(auth.as_ref().map(|a| a.mode))?;
         let api_auth = auth_provider_from_auth(auth.clone(), &self.state.provider)?;
-        let transport = ReqwestTransport::new(build_reqwest_client());
+        let transport = provider_transport(&self.state.provider);
         let request_telemetry = self.build_request_telemetry();
         let client = ApiCompactClient::new(transport, api_provider, api_auth)
             .with_telemetry(Some(request_telemetry));
@@ -232,6 +263,11 @@ impl ModelClient {
 }
 
 impl ModelClientSession {
+    /// Reports transport-level retries of streaming requests to `notifier`.
+    pub fn set_request_retry_notifier(&mut self, notifier: RequestRetryNotifier) {
+        self.request_retry_notifier = Some(notifier);
+    }
+
     /// Streams a single model turn using either the Responses or Chat
     /// Completions wire API, depending on the configured provider.
     ///
@@ -378,6 +414,9 @@ impl ModelClientSession {
             include: include.clone(),
             prompt_cache_key: prompt_cache_key.clone(),
             text: text.clone(),
+            temperature: api_prompt.sampling.temperature,
+            top_p: api_prompt.sampling.top_p,
+            max_output_tokens: api_prompt.sampling.max_tokens,
         };
 
         ResponsesWsRequest::ResponseCreate(payload)
@@ -399,6 +438,7 @@ impl ModelClientSession {
             headers.extend(build_conversation_headers(options.conversation_id.clone()));
             let new_conn: ApiWebSocketConnection =
                 ApiWebSocketResponsesClient::new(api_provider, api_auth)
+                    .with_capture(self.state.capture.clone())
                     .connect(headers)
                     .await?;
             self.connection = Some(new_conn);
@@ -427,14 +467,8 @@ impl ModelClientSession {
     /// Streams a turn via the OpenAI Chat Completions API.
     ///
     /// This path is only used when the provider is configured with
-    /// `WireApi::Chat`; it does not support `output_schema` today.
+    /// `WireApi::Chat`; `output_schema` is sent as `response_format`.
     async fn stream_chat_completions(&self, prompt: &Prompt) -> Result<ApiResponseStream> {
-        if prompt.output_schema.is_some() {
-            return Err(CodexErr::UnsupportedOperation(
-                "output_schema is not supported for Chat Completions API".to_string(),
-            ));
-        }
-
         let auth_manager = self.state.auth_manager.clone();
         let model_info = self.state.model_info.clone();
         let instructions = prompt.get_full_instructions(&model_info).into_owned();
@@ -461,10 +495,18 @@ impl ModelClientSession {
                 .provider
                 .to_api_provider(auth.as_ref().map(|a| a.mode))?;
             let api_auth = auth_provider_from_auth(auth.clone(), &self.state.provider)?;
-            let transport = ReqwestTransport::new(build_reqwest_client());
+            let transport = provider_transport(&self.state.provider);
             let (request_telemetry, sse_telemetry) = self.build_streaming_telemetry();
             let client = ApiChatClient::new(transport, api_provider, api_auth)
-                .with_telemetry(Some(request_telemetry), Some(sse_telemetry));
+                .with_telemetry(Some(request_telemetry), Some(sse_telemetry))
+                .with_dialect(self.state.provider.chat_dialect(&model_info))
+                .with_stable_prefix(
+                    self.state
+                        .config
+                        .features
+                        .enabled(Feature::CacheStablePrefix),
+                )
+                .with_capture(self.state.capture.clone());
 
             // 记录 LLM 请求内容
             let prompt_text = api_prompt
@@ -652,134 +694,16 @@ impl ModelClientSession {
                 }
             }
 
-            // DeepSeek兼容性检查
-            tracing::warn!("🔧 DeepSeek兼容性: 检查请求格式");
-            let has_function_call_output = api_prompt.input.iter().any(|item| {
-                matches!(
-                    item,
-                    codex_protocol::models::ResponseItem::FunctionCallOutput { .. }
-                )
-            });
-            let has_function_call = api_prompt.input.iter().any(|item| {
-                matches!(
-                    item,
-                    codex_protocol::models::ResponseItem::FunctionCall { .. }
-                )
-            });
-
-            if has_function_call_output {
-                tracing::warn!("🔄 检测到工具结果 - DeepSeek: 准备发送工具结果");
-
-                // 按照DeepSeek文档的正确格式：
-                // 1. 保留所有历史消息
-                // 2. 为每个FunctionCallOutput创建对应的tool消息
-                // 3. 确保格式正确：assistant消息 -> tool消息
-
-                let mut messages_with_tools = Vec::new();
-
-                // 复制所有输入项，但转换FunctionCallOutput为tool消息
-                for item in &api_prompt.input {
-                    match item {
-                        codex_protocol::models::ResponseItem::FunctionCallOutput {
-                            call_id,
-                            output,
-                            ..
-                        } => {
-                            // 创建tool消息，按照DeepSeek要求格式
-                            let tool_message = codex_protocol::models::ResponseItem::Message {
-                                id: Some(call_id.clone()),
-                                role: "tool".to_string(),
-                                content: vec![codex_protocol::models::ContentItem::OutputText {
-                                    text: output.content.clone(),
-                                }],
-                                reasoning_content: None,
-                                tool_calls: None,
-                            };
-                            messages_with_tools.push(tool_message);
-
-                            tracing::warn!(
-                                "🔧 DeepSeek: 创建tool消息 - call_id: {}, content长度: {}",
-                                call_id,
-                                output.content.len()
-                            );
-                        }
-                        // 保留所有其他消息，包括FunctionCall（如果有的话）
-                        other => {
-                            messages_with_tools.push(other.clone());
-                        }
-                    }
-                }
-
-                // 创建新的prompt
-                let mut modified_prompt = api_prompt.clone();
-                modified_prompt.input = messages_with_tools;
-
-                tracing::warn!(
-                    "🔄 DeepSeek: 发送工具结果 (输入项数: {})",
-                    modified_prompt.input.len()
-                );
-
-                let stream_future = client.stream_prompt(
+            // Connect and first-byte timeouts are enforced by the transport and
+            // retried there according to `request_max_retries`.
+            let stream_result = client
+                .stream_prompt(
                     &self.state.model_info.slug,
-                    &modified_prompt,
+                    &api_prompt,
                     Some(conversation_id.clone()),
                     Some(session_source.clone()),
-                );
-
-                let stream_result =
-                    match tokio::time::timeout(Duration::from_secs(30), stream_future).await {
-                        Ok(result) => {
-                            tracing::warn!("✅ DeepSeek工具结果发送成功");
-                            result
-                        }
-                        Err(_) => {
-                            tracing::warn!("❌ DeepSeek工具结果发送超时");
-                            return Err(CodexErr::Stream("DeepSeek工具结果发送超时".into(), None));
-                        }
-                    };
-
-                return match stream_result {
-                    Ok(stream) => Ok(stream),
-                    Err(e) => {
-                        tracing::warn!("❌ DeepSeek工具结果发送失败: {:?}", e);
-                        Err(map_api_error(e))
-                    }
-                };
-            }
-            if has_function_call {
-                tracing::warn!("✅ 请求包含工具调用");
-            }
-
-            tracing::warn!("⏳ 开始30秒超时等待API响应");
-            let stream_future = client.stream_prompt(
-                &self.state.model_info.slug,
-                &api_prompt,
-                Some(conversation_id.clone()),
-                Some(session_source.clone()),
-            );
-
-            tracing::warn!("⏳ 开始30秒超时等待API响应");
-
-            let stream_result =
-                match tokio::time::timeout(Duration::from_secs(30), stream_future).await {
-                    Ok(result) => {
-                        tracing::warn!(
-                            "✅ API调用在超时前完成 - 结果: {:?}",
-                            result
-                                .as_ref()
-                                .map(|_| "成功")
-                                .map_err(|e| format!("错误: {}", e))
-                        );
-                        result
-                    }
-                    Err(_) => {
-                        tracing::warn!("❌ API调用超时 (30秒) - 可能是网络问题或API不可用");
-                        return Err(CodexErr::Stream(
-                            "API request timeout after 30 seconds".into(),
-                            None,
-                        ));
-                    }
-                };
+                )
+                .await;
             tracing::warn!(
                 "📨 API stream_prompt调用完成，结果: {:?}",
                 stream_result
@@ -830,12 +754,13 @@ impl ModelClientSession {
                 .provider
                 .to_api_provider(auth.as_ref().map(|a| a.mode))?;
             let api_auth = auth_provider_from_auth(auth.clone(), &self.state.provider)?;
-            let transport = ReqwestTransport::new(build_reqwest_client());
+            let transport = provider_transport(&self.state.provider);
             let (request_telemetry, sse_telemetry) = self.build_streaming_telemetry();
             let compression = self.responses_request_compression(auth.as_ref());
 
             let client = ApiResponsesClient::new(transport, api_provider, api_auth)
-                .with_telemetry(Some(request_telemetry), Some(sse_telemetry));
+                .with_telemetry(Some(request_telemetry), Some(sse_telemetry))
+                .with_capture(self.state.capture.clone());
 
             let options = self.build_responses_options(prompt, compression);
 
@@ -910,7 +835,10 @@ impl ModelClientSession {
 
     /// Builds request and SSE telemetry for streaming API calls (Chat/Responses).
     fn build_streaming_telemetry(&self) -> (Arc<dyn RequestTelemetry>, Arc<dyn SseTelemetry>) {
-        let telemetry = Arc::new(ApiTelemetry::new(self.state.otel_manager.clone()));
+        let telemetry = Arc::new(ApiTelemetry::new(
+            self.state.otel_manager.clone(),
+            self.request_retry_notifier.clone(),
+        ));
         let request_telemetry: Arc<dyn RequestTelemetry> = telemetry.clone();
         let sse_telemetry: Arc<dyn SseTelemetry> = telemetry;
         (request_telemetry, sse_telemetry)
@@ -920,12 +848,19 @@ impl ModelClientSession {
 impl ModelClient {
     /// Builds request telemetry for unary API calls (e.g., Compact endpoint).
     fn build_request_telemetry(&self) -> Arc<dyn RequestTelemetry> {
-        let telemetry = Arc::new(ApiTelemetry::new(self.state.otel_manager.clone()));
+        let telemetry = Arc::new(ApiTelemetry::new(self.state.otel_manager.clone(), None));
         let request_telemetry: Arc<dyn RequestTelemetry> = telemetry;
         request_telemetry
     }
 }
 
+/// HTTP transport that honors the provider's connect timeout.
+fn provider_transport(provider: &ModelProviderInfo) -> ReqwestTransport {
+    ReqwestTransport::new(build_reqwest_client_with_connect_timeout(Some(
+        provider.request_connect_timeout(),
+    )))
+}
+
 /// Adapts the core `Prompt` type into the `codex-api` payload shape.
 fn build_api_prompt(
     prompt: &Prompt,
@@ -940,6 +875,7 @@ fn build_api_prompt(
         parallel_tool_calls: prompt.parallel_tool_calls,
         output_schema: prompt.output_schema.clone(),
         reasoning_content,
+        sampling: prompt.sampling.clone(),
     }
 }
 
@@ -1056,11 +992,15 @@ fn map_unauthorized_status(status: StatusCode) -> CodexErr {
 
 struct ApiTelemetry {
     otel_manager: OtelManager,
+    retry_notifier: Option<RequestRetryNotifier>,
 }
 
 impl ApiTelemetry {
-    fn new(otel_manager: OtelManager) -> Self {
-        Self { otel_manager }
+    fn new(otel_manager: OtelManager, retry_notifier: Option<RequestRetryNotifier>) -> Self {
+        Self {
+            otel_manager,
+            retry_notifier,
+        }
     }
 }
 
@@ -1080,6 +1020,17 @@ impl RequestTelemetry for ApiTelemetry {
             duration,
         );
     }
+
+    fn on_retry(&self, attempt: u64, max_attempts: u64, error: &TransportError, delay: Duration) {
+        if let Some(notifier) = &self.retry_notifier {
+            notifier(RequestRetry {
+                attempt,
+                max_attempts,
+                delay,
+                error: CodexErr::Stream(error.to_string(), Some(delay)),
+            });
+        }
+    }
 }
 
 impl SseTelemetry for ApiTelemetry {
@@ -1,6 +1,7 @@
 use crate::client_common::tools::ToolSpec;
 use crate::error::Result;
 pub use codex_api::common::ResponseEvent;
+use codex_protocol::config_types::SamplingParams;
 use codex_protocol::models::ResponseItem;
 use codex_protocol::openai_models::ModelInfo;
 use futures::Stream;
@@ -42,6 +43,9 @@ pub struct Prompt {
 
     /// Optional the output schema for the model's response.
     pub output_schema: Option<Value>,
+
+    /// Sampling parameters for this request.
+    pub(crate) sampling: SamplingParams,
 }
 
 impl Prompt {
@@ -338,6 +342,9 @@ mod tests {
                 verbosity: Some(OpenAiVerbosity::Low),
                 format: None,
             }),
+            temperature: None,
+            top_p: None,
+            max_output_tokens: None,
         };
 
         let v = serde_json::to_value(&req).expect("json");
@@ -376,6 +383,9 @@ mod tests {
             include: vec![],
             prompt_cache_key: None,
             text: Some(text_controls),
+            temperature: None,
+            top_p: None,
+            max_output_tokens: None,
         };
 
         let v = serde_json::to_value(&req).expect("json");
@@ -412,6 +422,9 @@ mod tests {
             include: vec![],
             prompt_cache_key: None,
             text: None,
+            temperature: None,
+            top_p: None,
+            max_output_tokens: None,
         };
 
         let v = serde_json::to_value(&req).expect("json");
@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fmt::Debug;
 use std::path::PathBuf;
@@ -28,12 +29,14 @@ use crate::stream_events_utils::handle_non_tool_response_item;
 use crate::stream_events_utils::handle_output_item_done;
 use crate::terminal;
 use crate::truncate::TruncationPolicy;
+use crate::truncate::encoding_for;
 use crate::user_notification::UserNotifier;
 use crate::util::error_or_panic;
 use async_channel::Receiver;
 use async_channel::Sender;
 use codex_protocol::ThreadId;
 use codex_protocol::approvals::ExecPolicyAmendment;
+use codex_protocol::approvals::ExecPolicyAmendmentScope;
 use codex_protocol::items::TurnItem;
 use codex_protocol::openai_models::ModelInfo;
 use codex_protocol::protocol::FileChange;
@@ -79,6 +82,8 @@ use crate::ModelProviderInfo;
 use crate::WireApi;
 use crate::client::ModelClient;
 use crate::client::ModelClientSession;
+use crate::client::RequestRetry;
+use crate::client::RequestRetryNotifier;
 use crate::client_common::Prompt;
 use crate::client_common::ResponseEvent;
 use crate::compact::collect_user_messages;
@@ -86,7 +91,10 @@ use crate::config::Config;
 use crate::config::Constrained;
 use crate::config::ConstraintResult;
 use crate::config::GhostSnapshotConfig;
+use crate::config::types::ContainerConfig;
 use crate::config::types::McpServerConfig;
+use crate::config::types::ModelFallback;
+use crate::config::types::SandboxResourceLimits;
 use crate::config::types::ShellEnvironmentPolicy;
 use crate::context_manager::ContextManager;
 use crate::environment_context::EnvironmentContext;
@@ -95,10 +103,15 @@ use crate::error::Result as CodexResult;
 #[cfg(test)]
 use crate::exec::StreamOutput;
 use crate::exec_policy::ExecPolicyUpdateError;
+use crate::exec_policy::amendment_metadata;
 use crate::feedback_tags;
 use crate::mcp::auth::compute_auth_statuses;
+use crate::mcp::workspace_roots;
 use crate::mcp_connection_manager::McpConnectionManager;
+use crate::mcp_connection_manager::RunSampling;
 use crate::model_provider_info::CHAT_WIRE_API_DEPRECATION_SUMMARY;
+use crate::network_proxy::NetworkProxy;
+use crate::persistent_shell::PersistentShell;
 use crate::project_doc::get_user_instructions;
 use crate::protocol::AgentMessageContentDeltaEvent;
 use crate::protocol::AgentReasoningSectionBreakEvent;
@@ -111,12 +124,14 @@ use crate::protocol::Event;
 use crate::protocol::EventMsg;
 use crate::protocol::ExecApprovalRequestEvent;
 use crate::protocol::McpServerRefreshConfig;
+use crate::protocol::ModelFallbackEvent;
 use crate::protocol::Op;
 use crate::protocol::RateLimitSnapshot;
 use crate::protocol::ReasoningContentDeltaEvent;
 use crate::protocol::ReasoningRawContentDeltaEvent;
 use crate::protocol::ReviewDecision;
 use crate::protocol::SandboxPolicy;
+use crate::protocol::SecretsRedactedEvent;
 use crate::protocol::SessionConfiguredEvent;
 use crate::protocol::SkillErrorInfo;
 use crate::protocol::SkillMetadata as ProtocolSkillMetadata;
@@ -130,6 +145,7 @@ use crate::protocol::WarningEvent;
 use crate::rollout::RolloutRecorder;
 use crate::rollout::RolloutRecorderParams;
 use crate::rollout::map_session_init_error;
+use crate::secret_redaction::SecretRedactor;
 use crate::shell;
 use crate::shell_snapshot::ShellSnapshot;
 use crate::skills::SkillError;
@@ -155,9 +171,11 @@ use crate::unified_exec::UnifiedExecProcessManager;
 use crate::user_instructions::UserInstructions;
 use crate::user_notification::UserNotification;
 use crate::util::backoff;
+use crate::workspace_overlay::WorkspaceOverlay;
 use codex_async_utils::OrCancelExt;
 use codex_otel::OtelManager;
 use codex_protocol::config_types::ReasoningSummary as ReasoningSummaryConfig;
+use codex_protocol::config_types::SamplingParams;
 use codex_protocol::models::ContentItem;
 use codex_protocol::models::DeveloperInstructions;
 use codex_protocol::models::ResponseInputItem;
@@ -168,6 +186,7 @@ use codex_protocol::protocol::InitialHistory;
 use codex_protocol::user_input::UserInput;
 use codex_utils_readiness::Readiness;
 use codex_utils_readiness::ReadinessFlag;
+use codex_utils_tokenizer::Encoding;
 use tokio::sync::watch;
 
 /// The high-level interface to the Codex system.
@@ -272,6 +291,7 @@ impl Codex {
             model: model.clone(),
             model_reasoning_effort: config.model_reasoning_effort,
             model_reasoning_summary: config.model_reasoning_summary,
+            sampling: config.sampling.clone(),
             developer_instructions: config.developer_instructions.clone(),
             user_instructions,
             base_instructions: config.base_instructions.clone(),
@@ -279,6 +299,14 @@ impl Codex {
             approval_policy: config.approval_policy.clone(),
             sandbox_policy: config.sandbox_policy.clone(),
             cwd: config.cwd.clone(),
+            fallback_models: config
+                .fallback_models
+                .iter()
+                .filter_map(|fallback| {
+                    let provider = config.model_providers.get(&fallback.provider_id)?;
+                    Some((fallback.clone(), provider.clone()))
+                })
+                .collect(),
             original_config_do_not_use: Arc::clone(&config),
             session_source,
         };
@@ -395,9 +423,17 @@ pub(crate) struct TurnContext {
     pub(crate) tools_config: ToolsConfig,
     pub(crate) ghost_snapshot: GhostSnapshotConfig,
     pub(crate) final_output_json_schema: Option<Value>,
+    /// Effective sampling parameters: session values over provider defaults.
+    pub(crate) sampling: SamplingParams,
     pub(crate) codex_linux_sandbox_exe: Option<PathBuf>,
+    /// Limits applied to commands run under `sandbox_policy`.
+    pub(crate) resource_limits: Option<SandboxResourceLimits>,
+    /// Container that shell and unified_exec commands run in, if configured.
+    pub(crate) container: Option<ContainerConfig>,
     pub(crate) tool_call_gate: Arc<ReadinessFlag>,
     pub(crate) truncation_policy: TruncationPolicy,
+    /// Tokenizer used for local token estimates and token-budget truncation.
+    pub(crate) encoding: Encoding,
 }
 
 impl TurnContext {
@@ -425,6 +461,10 @@ pub(crate) struct SessionConfiguration {
     model_reasoning_effort: Option<ReasoningEffortConfig>,
     model_reasoning_summary: ReasoningSummaryConfig,
 
+    /// Sampling parameters requested by the profile or a turn override. The
+    /// provider's defaults fill in whatever is unset here.
+    sampling: SamplingParams,
+
     /// Developer instructions that supplement the base instructions.
     developer_instructions: Option<String>,
 
@@ -451,6 +491,9 @@ pub(crate) struct SessionConfiguration {
     /// operate deterministically.
     cwd: PathBuf,
 
+    /// `fallback_models` entries, each paired with the provider that serves it.
+    fallback_models: Vec<(ModelFallback, ModelProviderInfo)>,
+
     // TODO(pakrym): Remove config from here
     original_config_do_not_use: Arc<Config>,
     /// Source of the session (cli, vscode, exec, mcp, ...)
@@ -469,6 +512,9 @@ impl SessionConfiguration {
         if let Some(summary) = updates.reasoning_summary {
             next_configuration.model_reasoning_summary = summary;
         }
+        if let Some(sampling) = updates.sampling.clone() {
+            next_configuration.sampling = sampling.or(&next_configuration.sampling);
+        }
         if let Some(approval_policy) = updates.approval_policy {
             next_configuration.approval_policy.set(approval_policy)?;
         }
@@ -490,6 +536,7 @@ pub(crate) struct SessionSettingsUpdate {
     pub(crate) model: Option<String>,
     pub(crate) reasoning_effort: Option<Option<ReasoningEffortConfig>>,
     pub(crate) reasoning_summary: Option<ReasoningSummaryConfig>,
+    pub(crate) sampling: Option<SamplingParams>,
     pub(crate) final_output_json_schema: Option<Option<Value>>,
 }
 
@@ -501,6 +548,7 @@ impl Session {
         let mut per_turn_config = (*config).clone();
         per_turn_config.model_reasoning_effort = session_configuration.model_reasoning_effort;
         per_turn_config.model_reasoning_summary = session_configuration.model_reasoning_summary;
+        per_turn_config.sampling = session_configuration.sampling.clone();
         per_turn_config.features = config.features.clone();
         per_turn_config
     }
@@ -522,6 +570,10 @@ impl Session {
         );
 
         let per_turn_config = Arc::new(per_turn_config);
+        let sampling = match &provider.sampling {
+            Some(defaults) => session_configuration.sampling.clone().or(defaults),
+            None => session_configuration.sampling.clone(),
+        };
         let client = ModelClient::new(
             per_turn_config.clone(),
             auth_manager,
@@ -553,9 +605,14 @@ impl Session {
             tools_config,
             ghost_snapshot: per_turn_config.ghost_snapshot.clone(),
             final_output_json_schema: None,
+            sampling,
             codex_linux_sandbox_exe: per_turn_config.codex_linux_sandbox_exe.clone(),
+            resource_limits: per_turn_config
+                .resource_limits_for(session_configuration.sandbox_policy.get()),
+            container: per_turn_config.container.clone(),
             tool_call_gate: Arc::new(ReadinessFlag::new()),
             truncation_policy: model_info.truncation_policy.into(),
+            encoding: encoding_for(model_info.tokenizer),
         }
     }
 
@@ -693,6 +750,48 @@ impl Session {
         }
         let state = SessionState::new(session_configuration.clone());
 
+        // Only the Linux sandbox can route commands through the proxy; if it
+        // fails to start, sandboxed commands keep having no network at all.
+        let network_proxy = match config.sandbox_network_allowlist.clone() {
+            Some(allowlist) if cfg!(target_os = "linux") => {
+                match NetworkProxy::start(allowlist, tx_event.clone()) {
+                    Ok(proxy) => Some(proxy),
+                    Err(err) => {
+                        warn!("failed to start sandbox network proxy: {err}");
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+
+        // Only the Linux sandbox can mount the overlay; without it, commands
+        // write to the workspace directly.
+        let workspace_overlay = if config.workspace_overlay && cfg!(target_os = "linux") {
+            match WorkspaceOverlay::new(&session_configuration.cwd, &config.codex_home) {
+                Ok(overlay) => Some(overlay),
+                Err(err) => {
+                    warn!("failed to create workspace overlay: {err}");
+                    post_session_configured_events.push(Event {
+                        id: INITIAL_SUBMIT_ID.to_owned(),
+                        msg: EventMsg::Warning(WarningEvent {
+                            message: format!(
+                                "Could not create the workspace overlay ({err}); commands will write to the workspace directly."
+                            ),
+                        }),
+                    });
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let persistent_shell = config
+            .features
+            .enabled(Feature::PersistentShell)
+            .then(PersistentShell::new);
+
         let services = SessionServices {
             mcp_connection_manager: Arc::new(RwLock::new(McpConnectionManager::default())),
             mcp_startup_cancellation_token: Mutex::new(CancellationToken::new()),
@@ -708,6 +807,14 @@ impl Session {
             tool_approvals: Mutex::new(ApprovalStore::default()),
             skills_manager,
             agent_control,
+            network_proxy,
+            workspace_overlay,
+            persistent_shell,
+            secret_redactor: SecretRedactor::new(
+                &config.secret_redaction,
+                &config.shell_environment_policy,
+            )
+            .map(Arc::new),
         };
 
         let sess = Arc::new(Session {
@@ -766,6 +873,7 @@ impl Session {
                 tx_event.clone(),
                 cancel_token,
                 sandbox_state,
+                Some(sess.mcp_sampler()),
             )
             .await;
 
@@ -966,6 +1074,17 @@ impl Session {
                 warn!("Failed to notify sandbox state change to MCP servers: {e:#}");
             }
         }
+        // Servers are only notified when the cwd or the `--add-dir` roots
+        // actually changed.
+        self.services
+            .mcp_connection_manager
+            .read()
+            .await
+            .update_roots(workspace_roots(
+                &per_turn_config.cwd,
+                per_turn_config.sandbox_policy.get(),
+            ))
+            .await;
 
         let model_info = self
             .services
@@ -1010,6 +1129,50 @@ impl Session {
             .await
     }
 
+    async fn fallback_models(&self) -> Vec<(ModelFallback, ModelProviderInfo)> {
+        let state = self.state.lock().await;
+        state.session_configuration.fallback_models.clone()
+    }
+
+    /// Builds a context that serves the rest of `current`'s turn with
+    /// `fallback` instead of the configured model and provider.
+    async fn fallback_turn_context(
+        &self,
+        current: &TurnContext,
+        fallback: &ModelFallback,
+        provider: ModelProviderInfo,
+    ) -> Arc<TurnContext> {
+        let mut session_configuration = {
+            let state = self.state.lock().await;
+            state.session_configuration.clone()
+        };
+        session_configuration.model = fallback.model.clone();
+        session_configuration.provider = provider.clone();
+
+        let mut per_turn_config = Self::build_per_turn_config(&session_configuration);
+        per_turn_config.model = Some(fallback.model.clone());
+        per_turn_config.model_provider_id = fallback.provider_id.clone();
+        per_turn_config.model_provider = provider.clone();
+
+        let model_info = self
+            .services
+            .models_manager
+            .get_model_info(fallback.model.as_str(), &per_turn_config)
+            .await;
+        let mut turn_context = Self::make_turn_context(
+            Some(Arc::clone(&self.services.auth_manager)),
+            &self.services.otel_manager,
+            provider,
+            &session_configuration,
+            per_turn_config,
+            model_info,
+            self.conversation_id,
+            current.sub_id.clone(),
+        );
+        turn_context.final_output_json_schema = current.final_output_json_schema.clone();
+        Arc::new(turn_context)
+    }
+
     fn build_environment_update_item(
         &self,
         previous: Option<&Arc<TurnContext>>,
@@ -1071,7 +1234,8 @@ impl Session {
         }
     }
 
-    pub(crate) async fn send_event_raw(&self, event: Event) {
+    pub(crate) async fn send_event_raw(&self, mut event: Event) {
+        self.redact_event(&mut event);
         // Record the last known agent status.
         if let Some(status) = agent_status_from_event(&event.msg) {
             self.agent_status.send_replace(status);
@@ -1089,7 +1253,8 @@ impl Session {
     /// Most events can be delivered immediately after queueing the rollout write, but some
     /// clients (e.g. app-server thread/rollback) re-read the rollout file synchronously on
     /// receipt of the event and depend on the marker already being visible on disk.
-    pub(crate) async fn send_event_raw_flushed(&self, event: Event) {
+    pub(crate) async fn send_event_raw_flushed(&self, mut event: Event) {
+        self.redact_event(&mut event);
         // Record the last known agent status.
         if let Some(status) = agent_status_from_event(&event.msg) {
             self.agent_status.send_replace(status);
@@ -1102,6 +1267,12 @@ impl Session {
         }
     }
 
+    fn redact_event(&self, event: &mut Event) {
+        if let Some(redactor) = self.services.secret_redactor.as_ref() {
+            redactor.redact_event(&mut event.msg);
+        }
+    }
+
     pub(crate) async fn emit_turn_item_started(&self, turn_context: &TurnContext, item: &TurnItem) {
         self.send_event(
             turn_context,
@@ -1131,29 +1302,41 @@ impl Session {
     }
 
     /// Adds an execpolicy amendment to both the in-memory and on-disk policies so future
-    /// commands can use the newly approved prefix.
+    /// commands can use the newly approved prefix. `scope` overrides
+    /// `exec_policy_amendments.scope` for this approval.
     pub(crate) async fn persist_execpolicy_amendment(
         &self,
         amendment: &ExecPolicyAmendment,
+        scope: Option<ExecPolicyAmendmentScope>,
+        approved_by: Option<String>,
     ) -> Result<(), ExecPolicyUpdateError> {
         let features = self.features.clone();
-        let codex_home = self
-            .state
-            .lock()
-            .await
-            .session_configuration
-            .original_config_do_not_use
-            .codex_home
-            .clone();
+        let (codex_home, settings, cwd) = {
+            let state = self.state.lock().await;
+            let config = &state.session_configuration.original_config_do_not_use;
+            (
+                config.codex_home.clone(),
+                config.exec_policy_amendments.clone(),
+                state.session_configuration.cwd.clone(),
+            )
+        };
 
         if !features.enabled(Feature::ExecPolicy) {
             error!("attempted to append execpolicy rule while execpolicy feature is disabled");
             return Err(ExecPolicyUpdateError::FeatureDisabled);
         }
 
+        let scope = scope.unwrap_or(settings.scope);
+        let metadata = amendment_metadata(
+            &settings,
+            scope,
+            &cwd,
+            self.conversation_id.to_string(),
+            approved_by,
+        );
         self.services
             .exec_policy
-            .append_amendment_and_update(&codex_home, amendment)
+            .append_amendment_and_update(&codex_home, amendment, scope, metadata)
             .await?;
 
         Ok(())
@@ -1285,9 +1468,39 @@ impl Session {
         turn_context: &TurnContext,
         items: &[ResponseItem],
     ) {
-        self.record_into_history(items, turn_context).await;
-        self.persist_rollout_response_items(items).await;
-        self.send_raw_response_items(turn_context, items).await;
+        let items = self.redact_tool_outputs(turn_context, items).await;
+        self.record_into_history(&items, turn_context).await;
+        self.persist_rollout_response_items(&items).await;
+        self.send_raw_response_items(turn_context, &items).await;
+    }
+
+    /// Masks secrets in tool outputs so that neither the model nor the
+    /// rollout file sees them, and tells the user when something was masked.
+    async fn redact_tool_outputs<'a>(
+        &self,
+        turn_context: &TurnContext,
+        items: &'a [ResponseItem],
+    ) -> Cow<'a, [ResponseItem]> {
+        let Some(redactor) = self.services.secret_redactor.as_ref() else {
+            return Cow::Borrowed(items);
+        };
+        let (items, call_ids, redactions) = redactor.redact_tool_outputs(items);
+        if redactions > 0 {
+            self.services.otel_manager.counter(
+                "codex.secret_redaction",
+                i64::try_from(redactions).unwrap_or(i64::MAX),
+                &[],
+            );
+            self.send_event(
+                turn_context,
+                EventMsg::SecretsRedacted(SecretsRedactedEvent {
+                    call_ids,
+                    redactions: u64::try_from(redactions).unwrap_or(u64::MAX),
+                }),
+            )
+            .await;
+        }
+        items
     }
 
     fn reconstruct_history_from_rollout(
@@ -1333,6 +1546,7 @@ impl Session {
         turn_context: &TurnContext,
     ) {
         let mut state = self.state.lock().await;
+        state.set_encoding(turn_context.encoding);
         state.record_items(items.iter(), turn_context.truncation_policy);
     }
 
@@ -1551,18 +1765,28 @@ impl Session {
         message: impl Into<String>,
         codex_error: CodexErr,
     ) {
-        let additional_details = codex_error.to_string();
-        let codex_error_info = CodexErrorInfo::ResponseStreamDisconnected {
-            http_status_code: codex_error.http_status_code_value(),
-        };
-        let event = EventMsg::StreamError(StreamErrorEvent {
-            message: message.into(),
-            codex_error_info: Some(codex_error_info),
-            additional_details: Some(additional_details),
-        });
+        let event = stream_error_event(message.into(), codex_error);
         self.send_event(turn_context, event).await;
     }
 
+    /// Like [`Session::notify_stream_error`], but usable from synchronous
+    /// callbacks. `StreamError` events are never persisted, so queueing on the
+    /// unbounded event channel keeps them ordered with later events.
+    fn notify_stream_error_now(
+        &self,
+        turn_context: &TurnContext,
+        message: impl Into<String>,
+        codex_error: CodexErr,
+    ) {
+        let event = Event {
+            id: turn_context.sub_id.clone(),
+            msg: stream_error_event(message.into(), codex_error),
+        };
+        if let Err(e) = self.tx_event.try_send(event) {
+            error!("failed to send stream error event: {e}");
+        }
+    }
+
     async fn maybe_start_ghost_snapshot(
         self: &Arc<Self>,
         turn_context: Arc<TurnContext>,
@@ -1724,7 +1948,7 @@ impl Session {
         Arc::clone(&self.services.user_shell)
     }
 
-    async fn refresh_mcp_servers_if_requested(&self, turn_context: &TurnContext) {
+    async fn refresh_mcp_servers_if_requested(self: &Arc<Self>, turn_context: &TurnContext) {
         let refresh_config = { self.pending_mcp_server_refresh_config.lock().await.take() };
         let Some(refresh_config) = refresh_config else {
             return;
@@ -1770,6 +1994,7 @@ impl Session {
                 self.get_tx_event(),
                 cancel_token,
                 sandbox_state,
+                Some(self.mcp_sampler()),
             )
             .await;
 
@@ -1777,6 +2002,23 @@ impl Session {
         *manager = refreshed_manager;
     }
 
+    /// Answers MCP sampling requests with the client of a fresh default turn,
+    /// so they follow model and provider changes made during the session.
+    fn mcp_sampler(self: &Arc<Self>) -> RunSampling {
+        let session = Arc::downgrade(self);
+        Arc::new(move |request| {
+            let session = session.clone();
+            async move {
+                let session = session
+                    .upgrade()
+                    .ok_or_else(|| anyhow::anyhow!("session has ended"))?;
+                let turn_context = session.new_default_turn().await;
+                crate::mcp::sampling::create_message(&turn_context, request).await
+            }
+            .boxed()
+        })
+    }
+
     async fn mcp_startup_cancellation_token(&self) -> CancellationToken {
         self.services
             .mcp_startup_cancellation_token
@@ -1832,6 +2074,7 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
                 model,
                 effort,
                 summary,
+                sampling,
             } => {
                 info!(
                     submission_id = %sub.id,
@@ -1841,6 +2084,7 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
                     ?model,
                     ?effort,
                     ?summary,
+                    ?sampling,
                     "Handling turn context override"
                 );
                 handlers::override_turn_context(
@@ -1853,6 +2097,7 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
                         model,
                         reasoning_effort: effort,
                         reasoning_summary: summary,
+                        sampling,
                         ..Default::default()
                     },
                 )
@@ -1891,6 +2136,28 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
             Op::ListCustomPrompts => {
                 handlers::list_custom_prompts(&sess, sub.id.clone()).await;
             }
+            Op::GetMcpPrompt {
+                server,
+                name,
+                arguments,
+            } => {
+                handlers::get_mcp_prompt(&sess, sub.id.clone(), server, name, arguments).await;
+            }
+            Op::CallTool {
+                call_id,
+                tool,
+                arguments,
+            } => {
+                handlers::call_tool(
+                    &sess,
+                    sub.id.clone(),
+                    call_id,
+                    tool,
+                    arguments,
+                    &mut previous_context,
+                )
+                .await;
+            }
             Op::ListSkills { cwds, force_reload } => {
                 handlers::list_skills(&sess, sub.id.clone(), cwds, force_reload).await;
             }
@@ -1903,6 +2170,12 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
             Op::ThreadRollback { num_turns } => {
                 handlers::thread_rollback(&sess, sub.id.clone(), num_turns).await;
             }
+            Op::CommitWorkspaceOverlay => {
+                handlers::resolve_workspace_overlay(&sess, sub.id.clone(), true).await;
+            }
+            Op::DiscardWorkspaceOverlay => {
+                handlers::resolve_workspace_overlay(&sess, sub.id.clone(), false).await;
+            }
             Op::RunUserShellCommand { command } => {
                 handlers::run_user_shell_command(
                     &sess,
@@ -1946,6 +2219,7 @@ mod handlers {
     use crate::mcp::collect_mcp_snapshot_from_manager;
     use crate::review_prompts::resolve_review_request;
     use crate::tasks::CompactTask;
+    use crate::tasks::DirectToolCallTask;
     use crate::tasks::RegularTask;
     use crate::tasks::UndoTask;
     use crate::tasks::UserShellCommandTask;
@@ -1954,6 +2228,7 @@ mod handlers {
     use codex_protocol::protocol::ErrorEvent;
     use codex_protocol::protocol::Event;
     use codex_protocol::protocol::EventMsg;
+    use codex_protocol::protocol::GetMcpPromptResponseEvent;
     use codex_protocol::protocol::ListCustomPromptsResponseEvent;
     use codex_protocol::protocol::ListSkillsResponseEvent;
     use codex_protocol::protocol::McpServerRefreshConfig;
@@ -1964,12 +2239,15 @@ mod handlers {
     use codex_protocol::protocol::ThreadRolledBackEvent;
     use codex_protocol::protocol::TurnAbortReason;
     use codex_protocol::protocol::WarningEvent;
+    use codex_protocol::protocol::WorkspaceOverlayResolvedEvent;
 
     use crate::context_manager::is_user_turn_boundary;
     use codex_protocol::user_input::UserInput;
     use codex_rmcp_client::ElicitationAction;
     use codex_rmcp_client::ElicitationResponse;
+    use mcp_types::GetPromptRequestParams;
     use mcp_types::RequestId;
+    use std::collections::HashMap;
     use std::path::PathBuf;
     use std::sync::Arc;
     use tracing::debug;
@@ -2024,6 +2302,7 @@ mod handlers {
                     model: Some(model),
                     reasoning_effort: Some(effort),
                     reasoning_summary: Some(summary),
+                    sampling: None,
                     final_output_json_schema: Some(final_output_json_schema),
                 },
             ),
@@ -2099,6 +2378,24 @@ mod handlers {
         *previous_context = Some(turn_context);
     }
 
+    pub async fn call_tool(
+        sess: &Arc<Session>,
+        sub_id: String,
+        call_id: String,
+        tool: String,
+        arguments: String,
+        previous_context: &mut Option<Arc<TurnContext>>,
+    ) {
+        let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
+        sess.spawn_task(
+            Arc::clone(&turn_context),
+            Vec::new(),
+            DirectToolCallTask::new(call_id, tool, arguments),
+        )
+        .await;
+        *previous_context = Some(turn_context);
+    }
+
     pub async fn resolve_elicitation(
         sess: &Arc<Session>,
         server_name: String,
@@ -2130,9 +2427,15 @@ mod handlers {
     pub async fn exec_approval(sess: &Arc<Session>, id: String, decision: ReviewDecision) {
         if let ReviewDecision::ApprovedExecpolicyAmendment {
             proposed_execpolicy_amendment,
+            scope,
+            approved_by,
         } = &decision
             && let Err(err) = sess
-                .persist_execpolicy_amendment(proposed_execpolicy_amendment)
+                .persist_execpolicy_amendment(
+                    proposed_execpolicy_amendment,
+                    *scope,
+                    approved_by.clone(),
+                )
                 .await
         {
             let message = format!("Failed to apply execpolicy amendment: {err}");
@@ -2231,21 +2534,70 @@ mod handlers {
         sess.send_event_raw(event).await;
     }
 
-    pub async fn list_custom_prompts(sess: &Session, sub_id: String) {
-        let custom_prompts: Vec<CustomPrompt> =
-            if let Some(dir) = crate::custom_prompts::default_prompts_dir() {
-                crate::custom_prompts::discover_prompts_in(&dir).await
-            } else {
-                Vec::new()
+    /// MCP servers that are still starting are skipped; clients ask again
+    /// once `McpStartupComplete` arrives.
+    pub async fn list_custom_prompts(sess: &Arc<Session>, sub_id: String) {
+        let sess = Arc::clone(sess);
+        // MCP requests can take up to the tool timeout; keep them off the
+        // submission loop.
+        tokio::spawn(async move {
+            let custom_prompts: Vec<CustomPrompt> =
+                if let Some(dir) = crate::custom_prompts::default_prompts_dir() {
+                    crate::custom_prompts::discover_prompts_in(&dir).await
+                } else {
+                    Vec::new()
+                };
+            let mcp_prompts = sess
+                .services
+                .mcp_connection_manager
+                .read()
+                .await
+                .list_ready_prompts()
+                .await;
+
+            let event = Event {
+                id: sub_id,
+                msg: EventMsg::ListCustomPromptsResponse(ListCustomPromptsResponseEvent {
+                    custom_prompts,
+                    mcp_prompts,
+                }),
             };
+            sess.send_event_raw(event).await;
+        });
+    }
 
-        let event = Event {
-            id: sub_id,
-            msg: EventMsg::ListCustomPromptsResponse(ListCustomPromptsResponseEvent {
-                custom_prompts,
-            }),
-        };
-        sess.send_event_raw(event).await;
+    pub async fn get_mcp_prompt(
+        sess: &Arc<Session>,
+        sub_id: String,
+        server: String,
+        name: String,
+        arguments: HashMap<String, String>,
+    ) {
+        let sess = Arc::clone(sess);
+        tokio::spawn(async move {
+            let params = GetPromptRequestParams {
+                arguments: (!arguments.is_empty()).then(|| serde_json::json!(arguments)),
+                name: name.clone(),
+            };
+            let result = sess
+                .services
+                .mcp_connection_manager
+                .read()
+                .await
+                .get_prompt(&server, params)
+                .await
+                .map_err(|err| format!("{err:#}"));
+
+            let event = Event {
+                id: sub_id,
+                msg: EventMsg::GetMcpPromptResponse(GetMcpPromptResponseEvent {
+                    server,
+                    name,
+                    result,
+                }),
+            };
+            sess.send_event_raw(event).await;
+        });
     }
 
     pub async fn list_skills(
@@ -2343,6 +2695,30 @@ mod handlers {
         .await;
     }
 
+    /// Commits the workspace overlay into the workspace, or discards it.
+    pub async fn resolve_workspace_overlay(sess: &Arc<Session>, sub_id: String, commit: bool) {
+        let action = if commit { "commit" } else { "discard" };
+        let has_active_turn = { sess.active_turn.lock().await.is_some() };
+        let result = match sess.services.workspace_overlay.as_ref() {
+            None => Err("the workspace overlay is not enabled".to_string()),
+            // Commands of the running turn may still be writing to the overlay.
+            Some(_) if has_active_turn => Err("a turn is in progress".to_string()),
+            Some(overlay) if commit => overlay.commit().map_err(|err| err.to_string()),
+            Some(overlay) => overlay.discard().map_err(|err| err.to_string()),
+        };
+
+        let msg = match result {
+            Ok(()) => EventMsg::WorkspaceOverlayResolved(WorkspaceOverlayResolvedEvent {
+                committed: commit,
+            }),
+            Err(err) => EventMsg::Error(ErrorEvent {
+                message: format!("Cannot {action} the workspace overlay: {err}."),
+                codex_error_info: Some(CodexErrorInfo::SandboxError),
+            }),
+        };
+        sess.send_event_raw(Event { id: sub_id, msg }).await;
+    }
+
     pub async fn shutdown(sess: &Arc<Session>, sub_id: String) -> bool {
         sess.abort_all_tasks(TurnAbortReason::Interrupted).await;
         sess.services
@@ -2491,9 +2867,13 @@ async fn spawn_review_thread(
         shell_environment_policy: parent_turn_context.shell_environment_policy.clone(),
         cwd: parent_turn_context.cwd.clone(),
         final_output_json_schema: None,
+        sampling: parent_turn_context.sampling.clone(),
         codex_linux_sandbox_exe: parent_turn_context.codex_linux_sandbox_exe.clone(),
+        resource_limits: parent_turn_context.resource_limits,
+        container: parent_turn_context.container.clone(),
         tool_call_gate: Arc::new(ReadinessFlag::new()),
         truncation_policy: model_info.truncation_policy.into(),
+        encoding: encoding_for(model_info.tokenizer),
     };
 
     // Seed the child task with the review prompt as the initial user message.
@@ -2604,7 +2984,11 @@ pub(crate) async fn run_turn(
     // many turns, from the perspective of the user, it is a single turn.
     let turn_diff_tracker = Arc::new(tokio::sync::Mutex::new(TurnDiffTracker::new()));
 
+    let mut turn_context = turn_context;
     let mut client_session = turn_context.client.new_session();
+    client_session.set_request_retry_notifier(request_retry_notifier(&sess, &turn_context));
+    let mut fallback_models = sess.fallback_models().await.into_iter();
+    let mut on_fallback = false;
 
     loop {
         // Note that pending_input would be something like a message the user
@@ -2622,7 +3006,12 @@ pub(crate) async fn run_turn(
         let turn_input: Vec<ResponseItem> = {
             sess.record_conversation_items(&turn_context, &pending_input)
                 .await;
-            sess.clone_history().await.for_prompt()
+            let history = sess.clone_history().await;
+            if on_fallback {
+                history.for_fallback_prompt()
+            } else {
+                history.for_prompt()
+            }
         };
         tracing::warn!(
             "🔍 Turn输入 - turn_input长度: {}, pending_input长度: {}",
@@ -2736,6 +3125,18 @@ pub(crate) async fn run_turn(
                 state.history.replace_last_turn_images("Invalid image");
             }
             Err(e) => {
+                if e.is_provider_failure()
+                    && let Some(fallback_context) =
+                        next_fallback_turn_context(&sess, &turn_context, &mut fallback_models, &e)
+                            .await
+                {
+                    turn_context = fallback_context;
+                    client_session = turn_context.client.new_session();
+                    client_session
+                        .set_request_retry_notifier(request_retry_notifier(&sess, &turn_context));
+                    on_fallback = true;
+                    continue;
+                }
                 tracing::warn!("❌ run_model_turn执行失败: {e:#}");
                 let event = EventMsg::Error(e.to_error_event(None));
                 sess.send_event(&turn_context, event).await;
@@ -2748,6 +3149,41 @@ pub(crate) async fn run_turn(
     last_agent_message
 }
 
+/// Advances `fallback_models` to the next entry that differs from the model
+/// currently serving the turn, records the switch, and returns its context.
+async fn next_fallback_turn_context(
+    sess: &Arc<Session>,
+    turn_context: &Arc<TurnContext>,
+    fallback_models: &mut impl Iterator<Item = (ModelFallback, ModelProviderInfo)>,
+    err: &CodexErr,
+) -> Option<Arc<TurnContext>> {
+    let current_model = turn_context.client.get_model();
+    let current_provider = turn_context.client.config().model_provider_id.clone();
+    let (fallback, provider) = fallback_models.find(|(fallback, _)| {
+        fallback.model != current_model || fallback.provider_id != current_provider
+    })?;
+    let fallback_context = sess
+        .fallback_turn_context(turn_context.as_ref(), &fallback, provider)
+        .await;
+
+    warn!(
+        "{current_model} ({current_provider}) failed: {err}. Falling back to {} ({}).",
+        fallback.model, fallback.provider_id
+    );
+    sess.send_event(
+        turn_context.as_ref(),
+        EventMsg::ModelFallback(ModelFallbackEvent {
+            from_model: current_model,
+            from_provider: current_provider,
+            to_model: fallback.model,
+            to_provider: fallback.provider_id,
+            reason: err.to_string(),
+        }),
+    )
+    .await;
+    Some(fallback_context)
+}
+
 async fn run_auto_compact(sess: &Arc<Session>, turn_context: &Arc<TurnContext>) {
     if should_use_remote_compact_task(sess.as_ref(), &turn_context.client.get_provider()) {
         run_inline_remote_auto_compact_task(Arc::clone(sess), Arc::clone(turn_context)).await;
@@ -2812,6 +3248,7 @@ async fn run_model_turn(
         base_instructions_override: turn_context.base_instructions.clone(),
         output_schema: turn_context.final_output_json_schema.clone(),
         reasoning_content,
+        sampling: turn_context.sampling.clone(),
     };
 
     let mut retries = 0;
@@ -2886,6 +3323,38 @@ async fn run_model_turn(
     }
 }
 
+fn stream_error_event(message: String, codex_error: CodexErr) -> EventMsg {
+    let additional_details = codex_error.to_string();
+    let codex_error_info = CodexErrorInfo::ResponseStreamDisconnected {
+        http_status_code: codex_error.http_status_code_value(),
+    };
+    EventMsg::StreamError(StreamErrorEvent {
+        message,
+        codex_error_info: Some(codex_error_info),
+        additional_details: Some(additional_details),
+    })
+}
+
+/// Surfaces transport-level request retries (e.g. a provider that is slow to
+/// send its first byte) as `StreamError` events, like turn-level reconnects.
+fn request_retry_notifier(
+    sess: &Arc<Session>,
+    turn_context: &Arc<TurnContext>,
+) -> RequestRetryNotifier {
+    let sess = Arc::clone(sess);
+    let turn_context = Arc::clone(turn_context);
+    Arc::new(move |retry: RequestRetry| {
+        sess.notify_stream_error_now(
+            &turn_context,
+            format!(
+                "Retrying request... {}/{}",
+                retry.attempt, retry.max_attempts
+            ),
+            retry.error,
+        );
+    })
+}
+
 #[derive(Debug)]
 struct TurnRunResult {
     needs_follow_up: bool,
@@ -2954,6 +3423,7 @@ async fn try_run_turn(
         developer_instructions: turn_context.developer_instructions.clone(),
         final_output_json_schema: turn_context.final_output_json_schema.clone(),
         truncation_policy: Some(turn_context.truncation_policy.into()),
+        model_provider: Some(turn_context.client.config().model_provider_id.clone()),
     });
 
     feedback_tags!(
@@ -3526,6 +3996,77 @@ mod tests {
         assert_eq!(initial_context, history.raw_items());
     }
 
+    #[tokio::test]
+    async fn resolve_workspace_overlay_fails_when_overlay_disabled() {
+        let (sess, _tc, rx) = make_session_and_context_with_rx().await;
+
+        handlers::resolve_workspace_overlay(&sess, "sub-1".to_string(), true).await;
+
+        let error_event = loop {
+            let evt = tokio::time::timeout(StdDuration::from_secs(2), rx.recv())
+                .await
+                .expect("timeout waiting for event")
+                .expect("event");
+            if let EventMsg::Error(payload) = evt.msg {
+                break payload;
+            }
+        };
+        assert_eq!(
+            error_event.message,
+            "Cannot commit the workspace overlay: the workspace overlay is not enabled."
+        );
+        assert_eq!(
+            error_event.codex_error_info,
+            Some(CodexErrorInfo::SandboxError)
+        );
+    }
+
+    #[tokio::test]
+    async fn sampling_updates_merge_field_by_field() {
+        let codex_home = tempfile::tempdir().expect("create temp dir");
+        let config = Arc::new(build_test_config(codex_home.path()).await);
+        let session_configuration = SessionConfiguration {
+            provider: config.model_provider.clone(),
+            model: ModelsManager::get_model_offline(config.model.as_deref()),
+            model_reasoning_effort: config.model_reasoning_effort,
+            model_reasoning_summary: config.model_reasoning_summary,
+            sampling: SamplingParams {
+                temperature: Some(0.2),
+                max_tokens: Some(1024),
+                ..Default::default()
+            },
+            developer_instructions: config.developer_instructions.clone(),
+            user_instructions: config.user_instructions.clone(),
+            base_instructions: config.base_instructions.clone(),
+            compact_prompt: config.compact_prompt.clone(),
+            approval_policy: config.approval_policy.clone(),
+            sandbox_policy: config.sandbox_policy.clone(),
+            cwd: config.cwd.clone(),
+            fallback_models: Vec::new(),
+            original_config_do_not_use: Arc::clone(&config),
+            session_source: SessionSource::Exec,
+        };
+
+        let updated = session_configuration
+            .apply(&SessionSettingsUpdate {
+                sampling: Some(SamplingParams {
+                    temperature: Some(0.7),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .expect("apply sampling update");
+
+        assert_eq!(
+            updated.sampling,
+            SamplingParams {
+                temperature: Some(0.7),
+                max_tokens: Some(1024),
+                ..Default::default()
+            }
+        );
+    }
+
     #[tokio::test]
     async fn set_rate_limits_retains_previous_credits() {
         let codex_home = tempfile::tempdir().expect("create temp dir");
@@ -3537,6 +4078,7 @@ mod tests {
             model,
             model_reasoning_effort: config.model_reasoning_effort,
             model_reasoning_summary: config.model_reasoning_summary,
+            sampling: config.sampling.clone(),
             developer_instructions: config.developer_instructions.clone(),
             user_instructions: config.user_instructions.clone(),
             base_instructions: config.base_instructions.clone(),
@@ -3544,6 +4086,7 @@ mod tests {
             approval_policy: config.approval_policy.clone(),
             sandbox_policy: config.sandbox_policy.clone(),
             cwd: config.cwd.clone(),
+            fallback_models: Vec::new(),
             original_config_do_not_use: Arc::clone(&config),
             session_source: SessionSource::Exec,
         };
@@ -3603,6 +4146,7 @@ mod tests {
             model,
             model_reasoning_effort: config.model_reasoning_effort,
             model_reasoning_summary: config.model_reasoning_summary,
+            sampling: config.sampling.clone(),
             developer_instructions: config.developer_instructions.clone(),
             user_instructions: config.user_instructions.clone(),
             base_instructions: config.base_instructions.clone(),
@@ -3610,6 +4154,7 @@ mod tests {
             approval_policy: config.approval_policy.clone(),
             sandbox_policy: config.sandbox_policy.clone(),
             cwd: config.cwd.clone(),
+            fallback_models: Vec::new(),
             original_config_do_not_use: Arc::clone(&config),
             session_source: SessionSource::Exec,
         };
@@ -3693,6 +4238,8 @@ mod tests {
             aggregated_output: StreamOutput::new("Command output".to_string()),
             duration: StdDuration::from_secs(1),
             timed_out: true,
+            resource_limit_exceeded: None,
+            shell_state: None,
         };
         let (_, turn_context) = make_session_and_context().await;
 
@@ -3853,6 +4400,7 @@ mod tests {
             model,
             model_reasoning_effort: config.model_reasoning_effort,
             model_reasoning_summary: config.model_reasoning_summary,
+            sampling: config.sampling.clone(),
             developer_instructions: config.developer_instructions.clone(),
             user_instructions: config.user_instructions.clone(),
             base_instructions: config.base_instructions.clone(),
@@ -3860,6 +4408,7 @@ mod tests {
             approval_policy: config.approval_policy.clone(),
             sandbox_policy: config.sandbox_policy.clone(),
             cwd: config.cwd.clone(),
+            fallback_models: Vec::new(),
             original_config_do_not_use: Arc::clone(&config),
             session_source: SessionSource::Exec,
         };
@@ -3893,6 +4442,10 @@ mod tests {
             tool_approvals: Mutex::new(ApprovalStore::default()),
             skills_manager,
             agent_control,
+            network_proxy: None,
+            workspace_overlay: None,
+            persistent_shell: None,
+            secret_redactor: None,
         };
 
         let turn_context = Session::make_turn_context(
@@ -3948,6 +4501,7 @@ mod tests {
             model,
             model_reasoning_effort: config.model_reasoning_effort,
             model_reasoning_summary: config.model_reasoning_summary,
+            sampling: config.sampling.clone(),
             developer_instructions: config.developer_instructions.clone(),
             user_instructions: config.user_instructions.clone(),
             base_instructions: config.base_instructions.clone(),
@@ -3955,6 +4509,7 @@ mod tests {
             approval_policy: config.approval_policy.clone(),
             sandbox_policy: config.sandbox_policy.clone(),
             cwd: config.cwd.clone(),
+            fallback_models: Vec::new(),
             original_config_do_not_use: Arc::clone(&config),
             session_source: SessionSource::Exec,
         };
@@ -3988,6 +4543,10 @@ mod tests {
             tool_approvals: Mutex::new(ApprovalStore::default()),
             skills_manager,
             agent_control,
+            network_proxy: None,
+            workspace_overlay: None,
+            persistent_shell: None,
+            secret_redactor: None,
         };
 
         let turn_context = Arc::new(Session::make_turn_context(
@@ -4019,6 +4578,7 @@ mod tests {
     #[tokio::test]
     async fn refresh_mcp_servers_is_deferred_until_next_turn() {
         let (session, turn_context) = make_session_and_context().await;
+        let session = Arc::new(session);
         let old_token = session.mcp_startup_cancellation_token().await;
         assert!(!old_token.is_cancelled());
 
@@ -91,6 +91,7 @@ async fn run_compact_task_inner(
         developer_instructions: turn_context.developer_instructions.clone(),
         final_output_json_schema: turn_context.final_output_json_schema.clone(),
         truncation_policy: Some(turn_context.truncation_policy.into()),
+        model_provider: Some(turn_context.client.config().model_provider_id.clone()),
     });
     sess.persist_rollout_items(&[rollout_item]).await;
 
@@ -57,6 +57,7 @@ async fn run_remote_compact_task_inner_impl(
         base_instructions_override: turn_context.base_instructions.clone(),
         output_schema: None,
         reasoning_content: None,
+        sampling: Default::default(),
     };
 
     let mut new_history = turn_context
@@ -1,15 +1,23 @@
 use crate::auth::AuthCredentialsStoreMode;
+use crate::config::types::ContainerConfig;
 use crate::config::types::DEFAULT_OTEL_ENVIRONMENT;
+use crate::config::types::ExecPolicyAmendments;
 use crate::config::types::History;
 use crate::config::types::McpServerConfig;
 use crate::config::types::McpServerTransportConfig;
+use crate::config::types::ModelFallback;
 use crate::config::types::Notice;
 use crate::config::types::Notifications;
 use crate::config::types::OtelConfig;
 use crate::config::types::OtelConfigToml;
 use crate::config::types::OtelExporterKind;
+use crate::config::types::SandboxNetworkAllowlist;
+use crate::config::types::SandboxReadOnly;
+use crate::config::types::SandboxResourceLimits;
 use crate::config::types::SandboxWorkspaceWrite;
 use crate::config::types::ScrollInputMode;
+use crate::config::types::SecretRedaction;
+use crate::config::types::SecretRedactionToml;
 use crate::config::types::ShellEnvironmentPolicy;
 use crate::config::types::ShellEnvironmentPolicyToml;
 use crate::config::types::Tui;
@@ -39,6 +47,7 @@ use codex_app_server_protocol::UserSavedConfig;
 use codex_protocol::config_types::AltScreenMode;
 use codex_protocol::config_types::ForcedLoginMethod;
 use codex_protocol::config_types::ReasoningSummary;
+use codex_protocol::config_types::SamplingParams;
 use codex_protocol::config_types::SandboxMode;
 use codex_protocol::config_types::TrustLevel;
 use codex_protocol::config_types::Verbosity;
@@ -128,6 +137,24 @@ pub struct Config {
 
     pub sandbox_policy: Constrained<SandboxPolicy>,
 
+    /// Domains sandboxed commands may reach through the Codex network proxy
+    /// when the sandbox policy does not grant full network access.
+    pub sandbox_network_allowlist: Option<SandboxNetworkAllowlist>,
+
+    /// Limits for commands run under the `read-only` sandbox mode.
+    pub read_only_resource_limits: Option<SandboxResourceLimits>,
+
+    /// Limits for commands run under the `workspace-write` sandbox mode.
+    pub workspace_write_resource_limits: Option<SandboxResourceLimits>,
+
+    /// When true, `workspace-write` commands on Linux write to an overlay of
+    /// the workspace whose changes must be committed explicitly.
+    pub workspace_overlay: bool,
+
+    /// Container that shell and unified_exec commands run in instead of the
+    /// host, from the active profile or the top-level `[container]` table.
+    pub container: Option<ContainerConfig>,
+
     /// True if the user passed in an override or set a value in config.toml
     /// for either of approval_policy or sandbox_mode.
     pub did_user_set_custom_approval_policy_or_sandbox_mode: bool,
@@ -138,6 +165,10 @@ pub struct Config {
 
     pub shell_environment_policy: ShellEnvironmentPolicy,
 
+    /// Masking applied to tool output before it reaches the model or the
+    /// rollout file.
+    pub secret_redaction: SecretRedaction,
+
     /// When `true`, `AgentReasoning` events emitted by the backend will be
     /// suppressed from the frontend output. This can reduce visual noise when
     /// users are only interested in the final agent responses.
@@ -298,6 +329,9 @@ pub struct Config {
     /// Settings that govern if and what will be written to `~/.codex/history.jsonl`.
     pub history: History,
 
+    /// Scope and expiry of rules recorded from "always allow" approvals.
+    pub exec_policy_amendments: ExecPolicyAmendments,
+
     /// Optional URI-based file opener. If set, citations to files in the model
     /// output will be hyperlinked using the specified URI scheme.
     pub file_opener: UriBasedFileOpener,
@@ -324,6 +358,15 @@ pub struct Config {
     /// Optional verbosity control for GPT-5 models (Responses API `text.verbosity`).
     pub model_verbosity: Option<Verbosity>,
 
+    /// Sampling parameters (temperature, top_p, max_tokens, penalties, stop)
+    /// requested by the user. Unset fields fall back to the provider's
+    /// `sampling` defaults.
+    pub sampling: SamplingParams,
+
+    /// Models to switch to, in order, when the active provider keeps failing
+    /// with 5xx/429 responses or exhausts its stream retries.
+    pub fallback_models: Vec<ModelFallback>,
+
     /// Base URL for requests to ChatGPT (as opposed to the OpenAI API).
     pub chatgpt_base_url: String,
 
@@ -791,12 +834,27 @@ pub struct ConfigToml {
     #[serde(default)]
     pub shell_environment_policy: ShellEnvironmentPolicyToml,
 
+    /// Masking applied to tool output before it reaches the model or the
+    /// rollout file.
+    pub secret_redaction: Option<SecretRedactionToml>,
+
     /// Sandbox mode to use.
     pub sandbox_mode: Option<SandboxMode>,
 
     /// Sandbox configuration to apply if `sandbox` is `WorkspaceWrite`.
     pub sandbox_workspace_write: Option<SandboxWorkspaceWrite>,
 
+    /// Sandbox configuration to apply if `sandbox` is `ReadOnly`.
+    pub sandbox_read_only: Option<SandboxReadOnly>,
+
+    /// Domains sandboxed commands may reach through the Codex network proxy
+    /// when the sandbox does not grant full network access.
+    pub sandbox_network_allowlist: Option<SandboxNetworkAllowlist>,
+
+    /// OCI container that shell and unified_exec commands run in instead of
+    /// the host.
+    pub container: Option<ContainerConfig>,
+
     /// Optional external command to spawn for end-user notifications.
     #[serde(default)]
     pub notify: Option<Vec<String>>,
@@ -868,6 +926,10 @@ pub struct ConfigToml {
     #[serde(default)]
     pub history: Option<History>,
 
+    /// Scope and expiry of rules recorded from "always allow" approvals.
+    #[serde(default)]
+    pub exec_policy_amendments: Option<ExecPolicyAmendments>,
+
     /// Optional URI-based file opener. If set, citations to files in the model
     /// output will be hyperlinked using the specified URI scheme.
     pub file_opener: Option<UriBasedFileOpener>,
@@ -888,6 +950,13 @@ pub struct ConfigToml {
     /// Optional verbosity control for GPT-5 models (Responses API `text.verbosity`).
     pub model_verbosity: Option<Verbosity>,
 
+    /// Sampling parameters sent with every request.
+    pub sampling: Option<SamplingParams>,
+
+    /// Ordered fallback chain of `model@provider` entries tried when the
+    /// configured provider fails. `provider` defaults to `model_provider`.
+    pub fallback_models: Option<Vec<String>>,
+
     /// Override to force-enable reasoning summaries for the configured model.
     pub model_supports_reasoning_summaries: Option<bool>,
 
@@ -1055,15 +1124,24 @@ impl ConfigToml {
             })
             .unwrap_or_default();
         let mut sandbox_policy = match resolved_sandbox_mode {
-            SandboxMode::ReadOnly => SandboxPolicy::new_read_only_policy(),
+            SandboxMode::ReadOnly => SandboxPolicy::ReadOnly {
+                readable_roots: self
+                    .sandbox_read_only
+                    .as_ref()
+                    .and_then(|sandbox_read_only| sandbox_read_only.readable_roots.clone()),
+            },
             SandboxMode::WorkspaceWrite => match self.sandbox_workspace_write.as_ref() {
                 Some(SandboxWorkspaceWrite {
                     writable_roots,
+                    readable_roots,
                     network_access,
                     exclude_tmpdir_env_var,
                     exclude_slash_tmp,
+                    resource_limits: _,
+                    overlay: _,
                 }) => SandboxPolicy::WorkspaceWrite {
                     writable_roots: writable_roots.clone(),
+                    readable_roots: readable_roots.clone(),
                     network_access: *network_access,
                     exclude_tmpdir_env_var: *exclude_tmpdir_env_var,
                     exclude_slash_tmp: *exclude_slash_tmp,
@@ -1333,8 +1411,44 @@ impl Config {
             })?
             .clone();
 
+        let container = config_profile
+            .container
+            .clone()
+            .or_else(|| cfg.container.clone());
+
+        let fallback_models = config_profile
+            .fallback_models
+            .or(cfg.fallback_models)
+            .unwrap_or_default()
+            .iter()
+            .map(|spec| {
+                let fallback = ModelFallback::parse(spec, &model_provider_id);
+                if model_providers.contains_key(&fallback.provider_id) {
+                    Ok(fallback)
+                } else {
+                    Err(std::io::Error::new(
+                        std::io::ErrorKind::NotFound,
+                        format!(
+                            "Model provider `{}` in fallback_models entry `{spec}` not found",
+                            fallback.provider_id
+                        ),
+                    ))
+                }
+            })
+            .collect::<std::io::Result<Vec<_>>>()?;
+
         let shell_environment_policy = cfg.shell_environment_policy.into();
 
+        let secret_redaction: SecretRedaction = cfg.secret_redaction.unwrap_or_default().into();
+        for pattern in &secret_redaction.patterns {
+            regex_lite::Regex::new(pattern).map_err(|e| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!("invalid secret_redaction pattern `{pattern}`: {e}"),
+                )
+            })?;
+        }
+
         let history = cfg.history.unwrap_or_default();
 
         let ghost_snapshot = {
@@ -1451,9 +1565,24 @@ impl Config {
             cwd: resolved_cwd,
             approval_policy: constrained_approval_policy,
             sandbox_policy: constrained_sandbox_policy,
+            sandbox_network_allowlist: cfg.sandbox_network_allowlist,
+            read_only_resource_limits: cfg
+                .sandbox_read_only
+                .as_ref()
+                .and_then(|sandbox_read_only| sandbox_read_only.resource_limits),
+            workspace_write_resource_limits: cfg
+                .sandbox_workspace_write
+                .as_ref()
+                .and_then(|sandbox_workspace_write| sandbox_workspace_write.resource_limits),
+            workspace_overlay: cfg
+                .sandbox_workspace_write
+                .as_ref()
+                .is_some_and(|sandbox_workspace_write| sandbox_workspace_write.overlay),
+            container,
             did_user_set_custom_approval_policy_or_sandbox_mode,
             forced_auto_mode_downgraded_on_windows,
             shell_environment_policy,
+            secret_redaction,
             notify: cfg.notify,
             user_instructions,
             base_instructions,
@@ -1486,6 +1615,7 @@ impl Config {
             codex_home,
             config_layer_stack,
             history,
+            exec_policy_amendments: cfg.exec_policy_amendments.unwrap_or_default(),
             file_opener: cfg.file_opener.unwrap_or(UriBasedFileOpener::VsCode),
             codex_linux_sandbox_exe,
 
@@ -1503,6 +1633,11 @@ impl Config {
                 .unwrap_or_default(),
             model_supports_reasoning_summaries: cfg.model_supports_reasoning_summaries,
             model_verbosity: config_profile.model_verbosity.or(cfg.model_verbosity),
+            sampling: config_profile
+                .sampling
+                .unwrap_or_default()
+                .or(&cfg.sampling.unwrap_or_default()),
+            fallback_models,
             chatgpt_base_url: config_profile
                 .chatgpt_base_url
                 .or(cfg.chatgpt_base_url)
@@ -1627,6 +1762,17 @@ impl Config {
         }
     }
 
+    /// Resource limits configured for the sandbox mode that `policy` runs in.
+    /// Unsandboxed policies are never limited.
+    pub fn resource_limits_for(&self, policy: &SandboxPolicy) -> Option<SandboxResourceLimits> {
+        let limits = match policy {
+            SandboxPolicy::ReadOnly { .. } => self.read_only_resource_limits,
+            SandboxPolicy::WorkspaceWrite { .. } => self.workspace_write_resource_limits,
+            SandboxPolicy::DangerFullAccess | SandboxPolicy::ExternalSandbox { .. } => None,
+        };
+        limits.filter(|limits| !limits.is_empty())
+    }
+
     pub fn set_windows_sandbox_globally(&mut self, value: bool) {
         crate::safety::set_windows_sandbox_enabled(value);
         if value {
@@ -1691,6 +1837,7 @@ mod tests {
     use crate::config::edit::ConfigEdit;
     use crate::config::edit::ConfigEditsBuilder;
     use crate::config::edit::apply_blocking;
+    use crate::config::types::ContainerEngine;
     use crate::config::types::FeedbackConfigToml;
     use crate::config::types::HistoryPersistence;
     use crate::config::types::McpServerTransportConfig;
@@ -1720,6 +1867,7 @@ mod tests {
             tool_timeout_sec: None,
             enabled_tools: None,
             disabled_tools: None,
+            sampling: None,
         }
     }
 
@@ -1736,6 +1884,7 @@ mod tests {
             tool_timeout_sec: None,
             enabled_tools: None,
             disabled_tools: None,
+            sampling: None,
         }
     }
 
@@ -1843,7 +1992,7 @@ network_access = true  # This should be ignored.
         assert_eq!(
             resolution,
             SandboxPolicyResolution {
-                policy: SandboxPolicy::ReadOnly,
+                policy: SandboxPolicy::new_read_only_policy(),
                 forced_auto_mode_downgraded_on_windows: false,
             }
         );
@@ -1875,7 +2024,7 @@ exclude_slash_tmp = true
             assert_eq!(
                 resolution,
                 SandboxPolicyResolution {
-                    policy: SandboxPolicy::ReadOnly,
+                    policy: SandboxPolicy::new_read_only_policy(),
                     forced_auto_mode_downgraded_on_windows: true,
                 }
             );
@@ -1885,6 +2034,7 @@ exclude_slash_tmp = true
                 SandboxPolicyResolution {
                     policy: SandboxPolicy::WorkspaceWrite {
                         writable_roots: vec![writable_root.clone()],
+                        readable_roots: None,
                         network_access: false,
                         exclude_tmpdir_env_var: true,
                         exclude_slash_tmp: true,
@@ -1923,7 +2073,7 @@ trust_level = "trusted"
             assert_eq!(
                 resolution,
                 SandboxPolicyResolution {
-                    policy: SandboxPolicy::ReadOnly,
+                    policy: SandboxPolicy::new_read_only_policy(),
                     forced_auto_mode_downgraded_on_windows: true,
                 }
             );
@@ -1933,6 +2083,7 @@ trust_level = "trusted"
                 SandboxPolicyResolution {
                     policy: SandboxPolicy::WorkspaceWrite {
                         writable_roots: vec![writable_root],
+                        readable_roots: None,
                         network_access: false,
                         exclude_tmpdir_env_var: true,
                         exclude_slash_tmp: true,
@@ -2059,6 +2210,110 @@ trust_level = "trusted"
         );
     }
 
+    #[test]
+    fn resource_limits_follow_sandbox_mode() -> std::io::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+[sandbox_read_only.resource_limits]
+max_cpu_seconds = 30
+
+[sandbox_workspace_write.resource_limits]
+max_memory_mb = 2048
+max_file_size_mb = 100
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            temp_dir.path().to_path_buf(),
+        )?;
+
+        assert_eq!(
+            config.resource_limits_for(&SandboxPolicy::new_read_only_policy()),
+            Some(SandboxResourceLimits {
+                max_cpu_seconds: Some(30),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            config.resource_limits_for(&SandboxPolicy::new_workspace_write_policy()),
+            Some(SandboxResourceLimits {
+                max_memory_mb: Some(2048),
+                max_file_size_mb: Some(100),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            config.resource_limits_for(&SandboxPolicy::DangerFullAccess),
+            None
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn secret_redaction_rejects_invalid_pattern() -> std::io::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+[secret_redaction]
+patterns = ["ghp_[A-Za-z0-9"]
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+
+        let err = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            temp_dir.path().to_path_buf(),
+        )
+        .expect_err("invalid pattern should be rejected");
+
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(err.to_string().contains("ghp_[A-Za-z0-9"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn profile_container_replaces_top_level_container() -> std::io::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+profile = "ci"
+
+[container]
+image = "ubuntu:24.04"
+
+[profiles.ci.container]
+engine = "podman"
+image = "ghcr.io/acme/toolchain:1.4"
+run_args = ["--cpus=2"]
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            temp_dir.path().to_path_buf(),
+        )?;
+
+        assert_eq!(
+            config.container,
+            Some(ContainerConfig {
+                engine: ContainerEngine::Podman,
+                image: "ghcr.io/acme/toolchain:1.4".to_string(),
+                run_args: vec!["--cpus=2".to_string()],
+            })
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn add_dir_override_extends_workspace_writable_roots() -> std::io::Result<()> {
         let temp_dir = TempDir::new()?;
@@ -2087,7 +2342,7 @@ trust_level = "trusted"
                 "expected workspace-write request to be downgraded on Windows"
             );
             match config.sandbox_policy.get() {
-                &SandboxPolicy::ReadOnly => {}
+                &SandboxPolicy::ReadOnly { .. } => {}
                 other => panic!("expected read-only policy on Windows, got {other:?}"),
             }
         } else {
@@ -2217,6 +2472,97 @@ trust_level = "trusted"
         Ok(())
     }
 
+    #[test]
+    fn profile_sampling_overrides_base_per_field() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cfg: ConfigToml = toml::from_str(
+            r#"
+profile = "precise"
+
+[sampling]
+temperature = 0.7
+max_tokens = 2048
+
+[profiles.precise.sampling]
+temperature = 0.0
+stop = ["END"]
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+
+        assert_eq!(
+            config.sampling,
+            SamplingParams {
+                temperature: Some(0.0),
+                max_tokens: Some(2048),
+                stop: Some(vec!["END".to_string()]),
+                ..Default::default()
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn profile_fallback_models_parse_model_and_provider() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cfg: ConfigToml = toml::from_str(
+            r#"
+profile = "resilient"
+model_provider = "deepseek"
+fallback_models = ["unused"]
+
+[profiles.resilient]
+fallback_models = ["deepseek-chat", "gpt-5.1@openai"]
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+
+        assert_eq!(
+            config.fallback_models,
+            vec![
+                ModelFallback {
+                    model: "deepseek-chat".to_string(),
+                    provider_id: "deepseek".to_string(),
+                },
+                ModelFallback {
+                    model: "gpt-5.1".to_string(),
+                    provider_id: "openai".to_string(),
+                },
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn fallback_models_reject_unknown_provider() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let cfg: ConfigToml = toml::from_str(r#"fallback_models = ["gpt-5.1@nowhere"]"#)
+            .expect("TOML deserialization should succeed");
+
+        let err = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )
+        .expect_err("unknown fallback provider should be rejected");
+
+        assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+    }
+
     #[test]
     fn profile_sandbox_mode_overrides_base() -> std::io::Result<()> {
         let codex_home = TempDir::new()?;
@@ -2281,7 +2627,7 @@ trust_level = "trusted"
         if cfg!(target_os = "windows") {
             assert!(matches!(
                 config.sandbox_policy.get(),
-                SandboxPolicy::ReadOnly
+                SandboxPolicy::ReadOnly { .. }
             ));
             assert!(config.forced_auto_mode_downgraded_on_windows);
         } else {
@@ -2439,6 +2785,7 @@ trust_level = "trusted"
                 tool_timeout_sec: Some(Duration::from_secs(5)),
                 enabled_tools: None,
                 disabled_tools: None,
+                sampling: None,
             },
         );
 
@@ -2592,6 +2939,7 @@ bearer_token = "secret"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sampling: None,
             },
         )]);
 
@@ -2660,6 +3008,7 @@ ZIG_VAR = "3"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sampling: None,
             },
         )]);
 
@@ -2708,6 +3057,7 @@ ZIG_VAR = "3"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sampling: None,
             },
         )]);
 
@@ -2754,6 +3104,7 @@ ZIG_VAR = "3"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sampling: None,
             },
         )]);
 
@@ -2816,6 +3167,7 @@ startup_timeout_sec = 2.0
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sampling: None,
             },
         )]);
         apply_blocking(
@@ -2890,6 +3242,7 @@ X-Auth = "DOCS_AUTH"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sampling: None,
             },
         )]);
 
@@ -2917,6 +3270,7 @@ X-Auth = "DOCS_AUTH"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sampling: None,
             },
         );
         apply_blocking(
@@ -2982,6 +3336,7 @@ url = "https://example.com/mcp"
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    sampling: None,
                 },
             ),
             (
@@ -2999,6 +3354,7 @@ url = "https://example.com/mcp"
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    sampling: None,
                 },
             ),
         ]);
@@ -3079,6 +3435,7 @@ url = "https://example.com/mcp"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sampling: None,
             },
         )]);
 
@@ -3121,6 +3478,7 @@ url = "https://example.com/mcp"
                 tool_timeout_sec: None,
                 enabled_tools: Some(vec!["allowed".to_string()]),
                 disabled_tools: Some(vec!["blocked".to_string()]),
+                sampling: None,
             },
         )]);
 
@@ -3431,7 +3789,11 @@ model_verbosity = "high"
             request_max_retries: Some(4),
             stream_max_retries: Some(10),
             stream_idle_timeout_ms: Some(300_000),
+            request_connect_timeout_ms: None,
+            first_byte_timeout_ms: None,
             requires_openai_auth: false,
+            chat_dialect: None,
+            sampling: None,
         };
         let model_provider_map = {
             let mut model_provider_map = built_in_model_providers();
@@ -3493,9 +3855,15 @@ model_verbosity = "high"
                 model_provider: fixture.openai_provider.clone(),
                 approval_policy: Constrained::allow_any(AskForApproval::Never),
                 sandbox_policy: Constrained::allow_any(SandboxPolicy::new_read_only_policy()),
+                sandbox_network_allowlist: None,
+                read_only_resource_limits: None,
+                workspace_write_resource_limits: None,
+                workspace_overlay: false,
+                container: None,
                 did_user_set_custom_approval_policy_or_sandbox_mode: true,
                 forced_auto_mode_downgraded_on_windows: false,
                 shell_environment_policy: ShellEnvironmentPolicy::default(),
+                secret_redaction: SecretRedaction::default(),
                 user_instructions: None,
                 notify: None,
                 cwd: fixture.cwd(),
@@ -3510,6 +3878,7 @@ model_verbosity = "high"
                 codex_home: fixture.codex_home(),
                 config_layer_stack: Default::default(),
                 history: History::default(),
+                exec_policy_amendments: ExecPolicyAmendments::default(),
                 file_opener: UriBasedFileOpener::VsCode,
                 codex_linux_sandbox_exe: None,
                 hide_agent_reasoning: false,
@@ -3518,6 +3887,8 @@ model_verbosity = "high"
                 model_reasoning_summary: ReasoningSummary::Detailed,
                 model_supports_reasoning_summaries: None,
                 model_verbosity: None,
+                sampling: SamplingParams::default(),
+                fallback_models: Vec::new(),
                 chatgpt_base_url: "https://chatgpt.com/backend-api/".to_string(),
                 base_instructions: None,
                 developer_instructions: None,
@@ -3580,9 +3951,15 @@ model_verbosity = "high"
             model_provider: fixture.openai_chat_completions_provider.clone(),
             approval_policy: Constrained::allow_any(AskForApproval::UnlessTrusted),
             sandbox_policy: Constrained::allow_any(SandboxPolicy::new_read_only_policy()),
+            sandbox_network_allowlist: None,
+            read_only_resource_limits: None,
+            workspace_write_resource_limits: None,
+            workspace_overlay: false,
+            container: None,
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
             forced_auto_mode_downgraded_on_windows: false,
             shell_environment_policy: ShellEnvironmentPolicy::default(),
+            secret_redaction: SecretRedaction::default(),
             user_instructions: None,
             notify: None,
             cwd: fixture.cwd(),
@@ -3597,6 +3974,7 @@ model_verbosity = "high"
             codex_home: fixture.codex_home(),
             config_layer_stack: Default::default(),
             history: History::default(),
+            exec_policy_amendments: ExecPolicyAmendments::default(),
             file_opener: UriBasedFileOpener::VsCode,
             codex_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
@@ -3605,6 +3983,8 @@ model_verbosity = "high"
             model_reasoning_summary: ReasoningSummary::default(),
             model_supports_reasoning_summaries: None,
             model_verbosity: None,
+            sampling: SamplingParams::default(),
+            fallback_models: Vec::new(),
             chatgpt_base_url: "https://chatgpt.com/backend-api/".to_string(),
             base_instructions: None,
             developer_instructions: None,
@@ -3682,9 +4062,15 @@ model_verbosity = "high"
             model_provider: fixture.openai_provider.clone(),
             approval_policy: Constrained::allow_any(AskForApproval::OnFailure),
             sandbox_policy: Constrained::allow_any(SandboxPolicy::new_read_only_policy()),
+            sandbox_network_allowlist: None,
+            read_only_resource_limits: None,
+            workspace_write_resource_limits: None,
+            workspace_overlay: false,
+            container: None,
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
             forced_auto_mode_downgraded_on_windows: false,
             shell_environment_policy: ShellEnvironmentPolicy::default(),
+            secret_redaction: SecretRedaction::default(),
             user_instructions: None,
             notify: None,
             cwd: fixture.cwd(),
@@ -3699,6 +4085,7 @@ model_verbosity = "high"
             codex_home: fixture.codex_home(),
             config_layer_stack: Default::default(),
             history: History::default(),
+            exec_policy_amendments: ExecPolicyAmendments::default(),
             file_opener: UriBasedFileOpener::VsCode,
             codex_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
@@ -3707,6 +4094,8 @@ model_verbosity = "high"
             model_reasoning_summary: ReasoningSummary::default(),
             model_supports_reasoning_summaries: None,
             model_verbosity: None,
+            sampling: SamplingParams::default(),
+            fallback_models: Vec::new(),
             chatgpt_base_url: "https://chatgpt.com/backend-api/".to_string(),
             base_instructions: None,
             developer_instructions: None,
@@ -3770,9 +4159,15 @@ model_verbosity = "high"
             model_provider: fixture.openai_provider.clone(),
             approval_policy: Constrained::allow_any(AskForApproval::OnFailure),
             sandbox_policy: Constrained::allow_any(SandboxPolicy::new_read_only_policy()),
+            sandbox_network_allowlist: None,
+            read_only_resource_limits: None,
+            workspace_write_resource_limits: None,
+            workspace_overlay: false,
+            container: None,
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
             forced_auto_mode_downgraded_on_windows: false,
             shell_environment_policy: ShellEnvironmentPolicy::default(),
+            secret_redaction: SecretRedaction::default(),
             user_instructions: None,
             notify: None,
             cwd: fixture.cwd(),
@@ -3787,6 +4182,7 @@ model_verbosity = "high"
             codex_home: fixture.codex_home(),
             config_layer_stack: Default::default(),
             history: History::default(),
+            exec_policy_amendments: ExecPolicyAmendments::default(),
             file_opener: UriBasedFileOpener::VsCode,
             codex_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
@@ -3795,6 +4191,8 @@ model_verbosity = "high"
             model_reasoning_summary: ReasoningSummary::Detailed,
             model_supports_reasoning_summaries: None,
             model_verbosity: Some(Verbosity::High),
+            sampling: SamplingParams::default(),
+            fallback_models: Vec::new(),
             chatgpt_base_url: "https://chatgpt.com/backend-api/".to_string(),
             base_instructions: None,
             developer_instructions: None,
@@ -3999,7 +4397,7 @@ trust_level = "untrusted"
         // Verify that untrusted projects get WorkspaceWrite (or ReadOnly on Windows due to downgrade)
         if cfg!(target_os = "windows") {
             assert!(
-                matches!(resolution.policy, SandboxPolicy::ReadOnly),
+                matches!(resolution.policy, SandboxPolicy::ReadOnly { .. }),
                 "Expected ReadOnly on Windows, got {:?}",
                 resolution.policy
             );
@@ -4154,7 +4552,7 @@ mcp_oauth_callback_port = 5678
         // Verify that untrusted projects still get WorkspaceWrite sandbox (or ReadOnly on Windows)
         if cfg!(target_os = "windows") {
             assert!(
-                matches!(config.sandbox_policy.get(), SandboxPolicy::ReadOnly),
+                matches!(config.sandbox_policy.get(), SandboxPolicy::ReadOnly { .. }),
                 "Expected ReadOnly on Windows"
             );
         } else {
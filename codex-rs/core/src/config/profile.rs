@@ -5,6 +5,7 @@ use serde::Serialize;
 
 use crate::protocol::AskForApproval;
 use codex_protocol::config_types::ReasoningSummary;
+use codex_protocol::config_types::SamplingParams;
 use codex_protocol::config_types::SandboxMode;
 use codex_protocol::config_types::Verbosity;
 use codex_protocol::openai_models::ReasoningEffort;
@@ -23,6 +24,10 @@ pub struct ConfigProfile {
     pub model_reasoning_effort: Option<ReasoningEffort>,
     pub model_reasoning_summary: Option<ReasoningSummary>,
     pub model_verbosity: Option<Verbosity>,
+    /// Sampling parameters; set fields override the top-level `[sampling]` table.
+    pub sampling: Option<SamplingParams>,
+    /// Ordered `model@provider` fallback chain; replaces the top-level list.
+    pub fallback_models: Option<Vec<String>>,
     pub chatgpt_base_url: Option<String>,
     pub experimental_instructions_file: Option<AbsolutePathBuf>,
     pub experimental_compact_prompt_file: Option<AbsolutePathBuf>,
@@ -32,6 +37,8 @@ pub struct ConfigProfile {
     pub tools_web_search: Option<bool>,
     pub tools_view_image: Option<bool>,
     pub analytics: Option<crate::config::types::AnalyticsConfigToml>,
+    /// Container that commands run in; replaces the top-level `[container]`.
+    pub container: Option<crate::config::types::ContainerConfig>,
     /// Optional feature toggles scoped to this profile.
     #[serde(default)]
     // Injects known feature keys into the schema and forbids unknown keys.
@@ -3,11 +3,13 @@
 // Note this file should generally be restricted to simple struct/enum
 // definitions that do not contain business logic.
 
+pub use codex_protocol::approvals::ExecPolicyAmendmentScope;
 pub use codex_protocol::config_types::AltScreenMode;
 use codex_utils_absolute_path::AbsolutePathBuf;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::str::FromStr;
 use std::time::Duration;
 use wildmatch::WildMatchPattern;
 
@@ -47,6 +49,11 @@ pub struct McpServerConfig {
     /// Explicit deny-list of tools. These tools will be removed after applying `enabled_tools`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub disabled_tools: Option<Vec<String>>,
+
+    /// How `sampling/createMessage` requests from this server are handled.
+    /// Defaults to [`McpSamplingPolicy::Prompt`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sampling: Option<McpSamplingPolicy>,
 }
 
 // Raw MCP config shape used for deserialization and JSON Schema generation.
@@ -87,6 +94,8 @@ pub(crate) struct RawMcpServerConfig {
     pub enabled_tools: Option<Vec<String>>,
     #[serde(default)]
     pub disabled_tools: Option<Vec<String>>,
+    #[serde(default)]
+    pub sampling: Option<McpSamplingPolicy>,
 }
 
 impl<'de> Deserialize<'de> for McpServerConfig {
@@ -108,6 +117,7 @@ impl<'de> Deserialize<'de> for McpServerConfig {
         let enabled = raw.enabled.unwrap_or_else(default_enabled);
         let enabled_tools = raw.enabled_tools.clone();
         let disabled_tools = raw.disabled_tools.clone();
+        let sampling = raw.sampling;
 
         fn throw_if_set<E, T>(transport: &str, field: &str, value: Option<&T>) -> Result<(), E>
         where
@@ -161,6 +171,7 @@ impl<'de> Deserialize<'de> for McpServerConfig {
             enabled,
             enabled_tools,
             disabled_tools,
+            sampling,
         })
     }
 }
@@ -169,6 +180,30 @@ const fn default_enabled() -> bool {
     true
 }
 
+/// Whether an MCP server may ask Codex to run a model completion on its
+/// behalf via `sampling/createMessage`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum McpSamplingPolicy {
+    /// Ask the user to approve each request.
+    #[default]
+    Prompt,
+    /// Run requests without asking.
+    Allow,
+    /// Do not offer the sampling capability to the server.
+    Deny,
+}
+
+impl McpSamplingPolicy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Prompt => "prompt",
+            Self::Allow => "allow",
+            Self::Deny => "deny",
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
 #[serde(untagged, deny_unknown_fields, rename_all = "snake_case")]
 pub enum McpServerTransportConfig {
@@ -280,6 +315,18 @@ pub enum HistoryPersistence {
     None,
 }
 
+/// Settings for the allow rules recorded when a command is approved with "always allow".
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ExecPolicyAmendments {
+    /// Where approvals are remembered. Defaults to `global`.
+    #[serde(default)]
+    pub scope: ExecPolicyAmendmentScope,
+
+    /// If set, approvals stop applying this many hours after they are granted.
+    pub expires_after_hours: Option<u64>,
+}
+
 // ===== Analytics configuration =====
 
 /// Analytics settings loaded from config.toml. Fields are optional so we can apply defaults.
@@ -380,6 +427,31 @@ impl Default for OtelConfig {
     }
 }
 
+/// One entry of `fallback_models`: a model slug and the `model_providers`
+/// key that serves it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelFallback {
+    pub model: String,
+    pub provider_id: String,
+}
+
+impl ModelFallback {
+    /// Parses `model@provider`. Without an `@`, the model is served by
+    /// `default_provider_id`.
+    pub fn parse(spec: &str, default_provider_id: &str) -> Self {
+        match spec.rsplit_once('@') {
+            Some((model, provider_id)) => Self {
+                model: model.to_string(),
+                provider_id: provider_id.to_string(),
+            },
+            None => Self {
+                model: spec.to_string(),
+                provider_id: default_provider_id.to_string(),
+            },
+        }
+    }
+}
+
 #[derive(Serialize, Debug, Clone, PartialEq, Eq, Deserialize, JsonSchema)]
 #[serde(untagged)]
 pub enum Notifications {
@@ -585,12 +657,174 @@ impl Notice {
 pub struct SandboxWorkspaceWrite {
     #[serde(default)]
     pub writable_roots: Vec<AbsolutePathBuf>,
+    /// When set, sandboxed commands can only read these folders, the
+    /// workspace, and the system and toolchain directories they need to run.
+    /// The Windows sandbox rejects this setting.
+    #[serde(default)]
+    pub readable_roots: Option<Vec<AbsolutePathBuf>>,
     #[serde(default)]
     pub network_access: bool,
     #[serde(default)]
     pub exclude_tmpdir_env_var: bool,
     #[serde(default)]
     pub exclude_slash_tmp: bool,
+    /// Limits applied to each command run in this sandbox mode.
+    #[serde(default)]
+    pub resource_limits: Option<SandboxResourceLimits>,
+    /// Run sandboxed commands against a copy-on-write overlay of the workspace
+    /// (Linux only). Their changes are held back until they are committed.
+    #[serde(default)]
+    pub overlay: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct SandboxReadOnly {
+    /// When set, sandboxed commands can only read these folders, the
+    /// workspace, and the system and toolchain directories they need to run.
+    /// The Windows sandbox rejects this setting.
+    #[serde(default)]
+    pub readable_roots: Option<Vec<AbsolutePathBuf>>,
+    /// Limits applied to each command run in this sandbox mode.
+    #[serde(default)]
+    pub resource_limits: Option<SandboxResourceLimits>,
+}
+
+/// Limits for sandboxed commands. On Linux the sandbox helper applies them
+/// right before it execs the command, so they cover everything the command
+/// spawns. Memory and process limits use a cgroup when cgroup v2 is delegated
+/// to the user; otherwise memory falls back to `setrlimit` and the process
+/// limit is skipped with a warning.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct SandboxResourceLimits {
+    /// Maximum memory of the command, in MiB. Without cgroup v2 this bounds
+    /// the heap (`RLIMIT_DATA`) of each process instead.
+    #[serde(default)]
+    pub max_memory_mb: Option<u64>,
+    /// CPU time each process may consume, in seconds. The process gets
+    /// `SIGXCPU` at the limit and is killed a few seconds later.
+    #[serde(default)]
+    pub max_cpu_seconds: Option<u64>,
+    /// Maximum number of processes of the command. Only enforced with
+    /// cgroup v2; without it the limit is skipped with a warning.
+    #[serde(default)]
+    pub max_processes: Option<u64>,
+    /// Largest file a command may write, in MiB.
+    #[serde(default)]
+    pub max_file_size_mb: Option<u64>,
+}
+
+impl SandboxResourceLimits {
+    pub fn is_empty(&self) -> bool {
+        self.max_memory_mb.is_none()
+            && self.max_cpu_seconds.is_none()
+            && self.max_processes.is_none()
+            && self.max_file_size_mb.is_none()
+    }
+}
+
+impl FromStr for SandboxResourceLimits {
+    type Err = serde_json::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(s)
+    }
+}
+
+/// Per-domain network rules for sandboxed commands. Entries are either exact
+/// host names (`crates.io`) or `*.`-prefixed patterns that match any
+/// subdomain (`*.crates.io`). A host matching `denied_domains` is blocked even
+/// when it also matches `allowed_domains`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct SandboxNetworkAllowlist {
+    #[serde(default)]
+    pub allowed_domains: Vec<String>,
+    #[serde(default)]
+    pub denied_domains: Vec<String>,
+}
+
+/// OCI container that shell and unified_exec commands run in instead of the
+/// host. The workspace is bind-mounted at the same path, and the sandbox
+/// policy decides which mounts are writable and whether networking is on.
+/// Commands retried without the sandbox still run in the container, with the
+/// workspace writable and networking on; `apply_patch` edits the workspace
+/// from the host.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ContainerConfig {
+    /// Container engine CLI to invoke. Defaults to `docker`.
+    #[serde(default)]
+    pub engine: ContainerEngine,
+
+    /// Image that provides the toolchain, e.g. `ghcr.io/acme/dev:1.4`.
+    pub image: String,
+
+    /// Extra arguments passed to `<engine> run` before the image name. Codex
+    /// sets `--name` itself to remove the container when a command is
+    /// interrupted, so it must not be overridden here.
+    #[serde(default)]
+    pub run_args: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ContainerEngine {
+    #[default]
+    Docker,
+    Podman,
+}
+
+impl ContainerEngine {
+    pub fn program(self) -> &'static str {
+        match self {
+            ContainerEngine::Docker => "docker",
+            ContainerEngine::Podman => "podman",
+        }
+    }
+}
+
+/// Masking applied to tool output before it reaches the model or the rollout
+/// file.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct SecretRedactionToml {
+    /// Set to `false` to turn redaction off. Defaults to `true`.
+    pub enabled: Option<bool>,
+
+    /// Regular expressions whose matches are masked.
+    pub patterns: Option<Vec<String>>,
+
+    /// Also mask long random-looking tokens such as API keys. Defaults to
+    /// `false`.
+    pub high_entropy: Option<bool>,
+}
+
+/// Values of environment variables excluded by the
+/// [`ShellEnvironmentPolicy`] are always masked while redaction is enabled;
+/// `patterns` and `high_entropy` add to that.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecretRedaction {
+    pub enabled: bool,
+    pub patterns: Vec<String>,
+    pub high_entropy: bool,
+}
+
+impl From<SecretRedactionToml> for SecretRedaction {
+    fn from(toml: SecretRedactionToml) -> Self {
+        Self {
+            enabled: toml.enabled.unwrap_or(true),
+            patterns: toml.patterns.unwrap_or_default(),
+            high_entropy: toml.high_entropy.unwrap_or(false),
+        }
+    }
+}
+
+impl Default for SecretRedaction {
+    fn default() -> Self {
+        SecretRedactionToml::default().into()
+    }
 }
 
 impl From<SandboxWorkspaceWrite> for codex_app_server_protocol::SandboxSettings {
@@ -930,6 +1164,27 @@ mod tests {
         assert_eq!(cfg.disabled_tools, Some(vec!["blocked".to_string()]));
     }
 
+    #[test]
+    fn deserialize_server_config_with_sampling_policy() {
+        let cfg: McpServerConfig = toml::from_str(
+            r#"
+            command = "echo"
+            sampling = "allow"
+        "#,
+        )
+        .expect("should deserialize sampling policy");
+
+        assert_eq!(cfg.sampling, Some(McpSamplingPolicy::Allow));
+
+        toml::from_str::<McpServerConfig>(
+            r#"
+            command = "echo"
+            sampling = "sometimes"
+        "#,
+        )
+        .expect_err("should reject unknown sampling policy");
+    }
+
     #[test]
     fn deserialize_rejects_command_and_url() {
         toml::from_str::<McpServerConfig>(
@@ -0,0 +1,424 @@
+//! Runs commands inside the OCI container configured under `[container]`.
+//!
+//! The container replaces the host as the place commands execute, so the
+//! [`SandboxPolicy`] is translated into the container's boundary instead of
+//! Landlock/Seatbelt rules: the workspace is bind-mounted at the same path,
+//! read-only or writable as the policy allows, and networking is disabled
+//! unless the policy grants full network access. Nothing else from the host
+//! filesystem is visible.
+//!
+//! The container stays the boundary when a command is retried without the
+//! sandbox after a denial: the retry runs in a fresh container with the
+//! workspace writable and networking on, as under `danger-full-access`, but
+//! still sees nothing else of the host. `apply_patch` is not a command and
+//! keeps editing the workspace from the host, under the regular sandbox.
+//!
+//! Every container gets a unique name so that it can be removed when Codex
+//! gives up on the command (timeout or interrupt): killing the engine client
+//! does not stop the container it started.
+
+use std::collections::HashMap;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+
+use uuid::Uuid;
+
+use crate::config::types::ContainerConfig;
+use crate::config::types::ContainerEngine;
+use crate::protocol::SandboxPolicy;
+use crate::sandboxing::SandboxTransformError;
+use crate::shell::ShellType;
+use crate::shell::detect_shell_type;
+
+/// Variables that describe the host rather than the command and would point
+/// at paths that do not exist in the image.
+const HOST_ONLY_ENV_VARS: &[&str] = &[
+    "HOME", "LOGNAME", "OLDPWD", "PATH", "PWD", "SHELL", "SHLVL", "TMPDIR", "USER",
+];
+
+/// Builds the `<engine> run` invocation that executes `command` in the
+/// container. Variables from `env` are forwarded by name only, so their
+/// values never appear on the engine's command line.
+///
+/// Fails when `command_cwd` is outside every mounted root: mounting it would
+/// expose host paths the policy does not grant.
+pub(crate) fn create_container_command(
+    container: &ContainerConfig,
+    command: Vec<String>,
+    policy: &SandboxPolicy,
+    sandbox_policy_cwd: &Path,
+    command_cwd: &Path,
+    env: &HashMap<String, String>,
+) -> Result<Vec<String>, SandboxTransformError> {
+    let mounts = container_mounts(policy, sandbox_policy_cwd);
+    // `starts_with` compares components, so `..` could climb out of a mount.
+    let climbs_out = command_cwd
+        .components()
+        .any(|component| matches!(component, Component::ParentDir));
+    if climbs_out || !mounts.iter().any(|(path, _)| command_cwd.starts_with(path)) {
+        return Err(SandboxTransformError::ContainerWorkdirNotMounted(
+            command_cwd.to_path_buf(),
+        ));
+    }
+
+    let mut args: Vec<String> = vec![
+        container.engine.program().to_string(),
+        "run".to_string(),
+        "--rm".to_string(),
+        "--interactive".to_string(),
+        "--init".to_string(),
+        format!(
+            "{CONTAINER_NAME_FLAG}{CONTAINER_NAME_PREFIX}{}",
+            Uuid::new_v4()
+        ),
+    ];
+
+    match container.engine {
+        // Rootless podman maps the invoking user into the container.
+        ContainerEngine::Podman => args.push("--userns=keep-id".to_string()),
+        // Docker runs as root by default; files written to the workspace
+        // would end up owned by root on the host.
+        ContainerEngine::Docker => {
+            if let Some(user) = workspace_owner(sandbox_policy_cwd) {
+                args.push(format!("--user={user}"));
+            }
+        }
+    }
+
+    if !policy.has_full_network_access() {
+        args.push("--network=none".to_string());
+    }
+    if matches!(policy, SandboxPolicy::ReadOnly { .. }) {
+        args.push("--read-only".to_string());
+        args.push("--tmpfs=/tmp".to_string());
+    }
+
+    for (path, writable) in mounts {
+        args.push(bind_mount_arg(&path, writable));
+    }
+    args.push(format!("--workdir={}", command_cwd.to_string_lossy()));
+
+    let mut names: Vec<&String> = env
+        .keys()
+        .filter(|name| !HOST_ONLY_ENV_VARS.contains(&name.as_str()))
+        .collect();
+    names.sort();
+    for name in names {
+        args.push(format!("--env={name}"));
+    }
+
+    args.extend(container.run_args.iter().cloned());
+    args.push(container.image.clone());
+
+    let mut command = command.into_iter();
+    if let Some(program) = command.next() {
+        args.push(container_program(program));
+    }
+    args.extend(command);
+    Ok(args)
+}
+
+/// Removes the container started by a [`create_container_command`]
+/// invocation when dropped, unless the command ran to completion, in which
+/// case `--rm` already removed it.
+#[derive(Debug)]
+pub(crate) struct ContainerGuard {
+    remove_command: Option<(String, String)>,
+}
+
+impl ContainerGuard {
+    /// Returns `None` when `command` was not built by
+    /// [`create_container_command`].
+    pub(crate) fn for_command(command: &[String]) -> Option<Self> {
+        let engine = command.first()?;
+        let name = command
+            .iter()
+            .find_map(|arg| arg.strip_prefix(CONTAINER_NAME_FLAG))
+            .filter(|name| name.starts_with(CONTAINER_NAME_PREFIX))?;
+        Some(Self {
+            remove_command: Some((engine.clone(), name.to_string())),
+        })
+    }
+
+    /// The command finished on its own; there is nothing left to remove.
+    pub(crate) fn disarm(&mut self) {
+        self.remove_command = None;
+    }
+}
+
+impl Drop for ContainerGuard {
+    fn drop(&mut self) {
+        let Some((engine, name)) = self.remove_command.take() else {
+            return;
+        };
+        // Dropped from async code, so the removal must not block.
+        std::thread::spawn(move || {
+            let _ = std::process::Command::new(engine)
+                .args(["rm", "--force", &name])
+                .stdin(std::process::Stdio::null())
+                .stdout(std::process::Stdio::null())
+                .stderr(std::process::Stdio::null())
+                .status();
+        });
+    }
+}
+
+const CONTAINER_NAME_FLAG: &str = "--name=";
+const CONTAINER_NAME_PREFIX: &str = "codex-exec-";
+
+/// `--mount` takes comma-separated `key=value` fields and, unlike
+/// `--volume`, lets paths contain `:`. A field containing `,` or `"` is
+/// quoted as a CSV field.
+fn bind_mount_arg(path: &Path, writable: bool) -> String {
+    let path = path.to_string_lossy();
+    let mut fields = vec![
+        "type=bind".to_string(),
+        csv_field(&format!("source={path}")),
+        csv_field(&format!("target={path}")),
+    ];
+    if !writable {
+        fields.push("readonly".to_string());
+    }
+    format!("--mount={}", fields.join(","))
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Bind mounts for `policy`, as `(path, writable)` pairs. Later entries are
+/// mounted on top of earlier ones, which keeps `.git` read-only inside a
+/// writable workspace.
+fn container_mounts(policy: &SandboxPolicy, sandbox_policy_cwd: &Path) -> Vec<(PathBuf, bool)> {
+    let mut mounts: Vec<(PathBuf, bool)> = Vec::new();
+    match policy {
+        SandboxPolicy::DangerFullAccess | SandboxPolicy::ExternalSandbox { .. } => {
+            mounts.push((sandbox_policy_cwd.to_path_buf(), true));
+        }
+        SandboxPolicy::ReadOnly { readable_roots } => {
+            mounts.push((sandbox_policy_cwd.to_path_buf(), false));
+            for root in readable_roots.iter().flatten() {
+                mounts.push((root.to_path_buf(), false));
+            }
+        }
+        SandboxPolicy::WorkspaceWrite { readable_roots, .. } => {
+            for root in readable_roots.iter().flatten() {
+                mounts.push((root.to_path_buf(), false));
+            }
+            let writable_roots = policy.get_writable_roots_with_cwd(sandbox_policy_cwd);
+            // The container has its own temp directories.
+            let tmpdir = std::env::var_os("TMPDIR").map(PathBuf::from);
+            for writable_root in writable_roots.iter().filter(|writable_root| {
+                let root = writable_root.root.as_path();
+                root != Path::new("/tmp") && Some(root) != tmpdir.as_deref()
+            }) {
+                mounts.push((writable_root.root.to_path_buf(), true));
+                for subpath in &writable_root.read_only_subpaths {
+                    mounts.push((subpath.to_path_buf(), false));
+                }
+            }
+        }
+    }
+    mounts
+}
+
+/// The user's shell lives at a host path (e.g. `/opt/homebrew/bin/bash`);
+/// inside the image the shell of the same name is looked up on `PATH`.
+fn container_program(program: String) -> String {
+    let path = PathBuf::from(&program);
+    if path.is_absolute()
+        && matches!(
+            detect_shell_type(&path),
+            Some(ShellType::Bash | ShellType::Zsh | ShellType::Sh)
+        )
+        && let Some(name) = path.file_name()
+    {
+        return name.to_string_lossy().into_owned();
+    }
+    program
+}
+
+#[cfg(unix)]
+fn workspace_owner(workspace: &Path) -> Option<String> {
+    use std::os::unix::fs::MetadataExt;
+
+    let metadata = std::fs::metadata(workspace).ok()?;
+    Some(format!("{}:{}", metadata.uid(), metadata.gid()))
+}
+
+#[cfg(not(unix))]
+fn workspace_owner(_workspace: &Path) -> Option<String> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_utils_absolute_path::AbsolutePathBuf;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn container(engine: ContainerEngine) -> ContainerConfig {
+        ContainerConfig {
+            engine,
+            image: "ghcr.io/acme/toolchain:1.4".to_string(),
+            run_args: vec!["--cpus=2".to_string()],
+        }
+    }
+
+    #[test]
+    fn read_only_policy_mounts_workspace_read_only_without_network() {
+        let workspace = TempDir::new().expect("workspace");
+        let cwd = workspace.path();
+        let env = HashMap::from([
+            ("PATH".to_string(), "/usr/bin".to_string()),
+            ("RUST_LOG".to_string(), "debug".to_string()),
+        ]);
+
+        let mut args = create_container_command(
+            &container(ContainerEngine::Podman),
+            vec![
+                "/opt/homebrew/bin/bash".to_string(),
+                "-lc".to_string(),
+                "ls".to_string(),
+            ],
+            &SandboxPolicy::new_read_only_policy(),
+            cwd,
+            cwd,
+            &env,
+        )
+        .expect("container command");
+
+        let cwd = cwd.to_string_lossy();
+        assert!(args[5].starts_with("--name=codex-exec-"), "{}", args[5]);
+        args.remove(5);
+        assert_eq!(
+            args,
+            vec![
+                "podman".to_string(),
+                "run".to_string(),
+                "--rm".to_string(),
+                "--interactive".to_string(),
+                "--init".to_string(),
+                "--userns=keep-id".to_string(),
+                "--network=none".to_string(),
+                "--read-only".to_string(),
+                "--tmpfs=/tmp".to_string(),
+                format!("--mount=type=bind,source={cwd},target={cwd},readonly"),
+                format!("--workdir={cwd}"),
+                "--env=RUST_LOG".to_string(),
+                "--cpus=2".to_string(),
+                "ghcr.io/acme/toolchain:1.4".to_string(),
+                "bash".to_string(),
+                "-lc".to_string(),
+                "ls".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn workspace_write_policy_keeps_git_read_only() {
+        let workspace = TempDir::new().expect("workspace");
+        std::fs::create_dir(workspace.path().join(".git")).expect("create .git");
+        let extra = TempDir::new().expect("extra root");
+        let policy = SandboxPolicy::WorkspaceWrite {
+            writable_roots: vec![
+                AbsolutePathBuf::from_absolute_path(extra.path()).expect("absolute"),
+            ],
+            readable_roots: None,
+            network_access: true,
+            exclude_tmpdir_env_var: true,
+            exclude_slash_tmp: false,
+        };
+
+        let mounts = container_mounts(&policy, workspace.path());
+
+        assert_eq!(
+            mounts,
+            vec![
+                (extra.path().to_path_buf(), true),
+                (workspace.path().to_path_buf(), true),
+                (workspace.path().join(".git"), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn bind_mount_arg_quotes_commas_and_quotes() {
+        assert_eq!(
+            bind_mount_arg(Path::new("/work/a:b"), true),
+            "--mount=type=bind,source=/work/a:b,target=/work/a:b"
+        );
+        assert_eq!(
+            bind_mount_arg(Path::new("/work/a,\"b\""), false),
+            "--mount=type=bind,\"source=/work/a,\"\"b\"\"\",\"target=/work/a,\"\"b\"\"\",readonly"
+        );
+    }
+
+    #[test]
+    fn container_guard_only_tracks_codex_containers() {
+        let mut guard = ContainerGuard::for_command(&[
+            "docker".to_string(),
+            "run".to_string(),
+            "--name=codex-exec-1234".to_string(),
+            "image".to_string(),
+        ])
+        .expect("guard");
+        assert_eq!(
+            guard.remove_command,
+            Some(("docker".to_string(), "codex-exec-1234".to_string()))
+        );
+        guard.disarm();
+
+        assert!(ContainerGuard::for_command(&["bash".to_string(), "-lc".to_string()]).is_none());
+    }
+
+    #[test]
+    fn workdir_outside_mounted_roots_is_rejected() {
+        let workspace = TempDir::new().expect("workspace");
+        let nested = workspace.path().join("crate");
+        let climbing = workspace.path().join("../..");
+
+        for (policy, workdir) in [
+            (SandboxPolicy::new_workspace_write_policy(), Path::new("/")),
+            (
+                SandboxPolicy::DangerFullAccess,
+                workspace.path().parent().expect("parent"),
+            ),
+            (SandboxPolicy::new_read_only_policy(), climbing.as_path()),
+        ] {
+            let result = create_container_command(
+                &container(ContainerEngine::Docker),
+                vec!["ls".to_string()],
+                &policy,
+                workspace.path(),
+                workdir,
+                &HashMap::new(),
+            );
+            assert!(
+                matches!(
+                    &result,
+                    Err(SandboxTransformError::ContainerWorkdirNotMounted(path)) if path == workdir
+                ),
+                "{result:?}"
+            );
+        }
+
+        assert!(
+            create_container_command(
+                &container(ContainerEngine::Docker),
+                vec!["ls".to_string()],
+                &SandboxPolicy::new_read_only_policy(),
+                workspace.path(),
+                &nested,
+                &HashMap::new(),
+            )
+            .is_ok()
+        );
+    }
+}
@@ -1,10 +1,9 @@
 use crate::codex::TurnContext;
 use crate::context_manager::normalize;
 use crate::truncate::TruncationPolicy;
-use crate::truncate::approx_token_count;
 use crate::truncate::approx_tokens_from_byte_count;
 use crate::truncate::truncate_function_output_items_with_policy;
-use crate::truncate::truncate_text;
+use crate::truncate::truncate_text_with_encoding;
 use crate::user_instructions::SkillInstructions;
 use crate::user_instructions::UserInstructions;
 use crate::user_shell_command::is_user_shell_command_text;
@@ -14,6 +13,7 @@ use codex_protocol::models::FunctionCallOutputPayload;
 use codex_protocol::models::ResponseItem;
 use codex_protocol::protocol::TokenUsage;
 use codex_protocol::protocol::TokenUsageInfo;
+use codex_utils_tokenizer::Encoding;
 use std::ops::Deref;
 
 /// Transcript of thread history
@@ -22,6 +22,11 @@ pub(crate) struct ContextManager {
     /// The oldest items are at the beginning of the vector.
     items: Vec<ResponseItem>,
     token_info: Option<TokenUsageInfo>,
+    /// Tokenizer used when truncating recorded tool output to a token budget.
+    encoding: Encoding,
+    /// Estimated tokens of each entry in `items` under `encoding`, kept in
+    /// step with `items` so estimates do not re-encode the whole history.
+    item_tokens: Vec<i64>,
 }
 
 impl ContextManager {
@@ -29,6 +34,15 @@ impl ContextManager {
         Self {
             items: Vec::new(),
             token_info: TokenUsageInfo::new_or_append(&None, &None, None),
+            encoding: Encoding::default(),
+            item_tokens: Vec::new(),
+        }
+    }
+
+    pub(crate) fn set_encoding(&mut self, encoding: Encoding) {
+        if self.encoding != encoding {
+            self.encoding = encoding;
+            self.recount_item_tokens();
         }
     }
 
@@ -63,12 +77,20 @@ impl ContextManager {
             }
 
             let processed = self.process_item(item_ref, policy);
+            self.item_tokens
+                .push(estimate_item_tokens(&processed, self.encoding));
             self.items.push(processed);
         }
     }
 
     /// Returns the history prepared for sending to the model. This applies a proper
     /// normalization and drop un-suited items.
+    ///
+    /// Items are never reordered or rewritten here, so as long as the session
+    /// only appends to the history (environment and permission changes are
+    /// recorded as new messages) the prompt for one turn is a prefix of the
+    /// prompt for the next. Chat providers with `stable_prefix` rely on this
+    /// to keep their prompt cache warm.
     pub(crate) fn for_prompt(mut self) -> Vec<ResponseItem> {
         self.normalize_history();
         self.items
@@ -76,34 +98,57 @@ impl ContextManager {
         self.items
     }
 
+    /// Like [`Self::for_prompt`], but drops state that only the provider which
+    /// produced it can read (encrypted reasoning and remote compaction
+    /// summaries) so the history can be replayed against a fallback provider.
+    pub(crate) fn for_fallback_prompt(self) -> Vec<ResponseItem> {
+        self.for_prompt()
+            .into_iter()
+            .filter_map(|item| match item {
+                ResponseItem::Reasoning {
+                    id,
+                    summary,
+                    content,
+                    encrypted_content: Some(_),
+                } => {
+                    let has_content = content.as_ref().is_some_and(|c| !c.is_empty());
+                    (has_content || !summary.is_empty()).then_some(ResponseItem::Reasoning {
+                        id,
+                        summary,
+                        content,
+                        encrypted_content: None,
+                    })
+                }
+                ResponseItem::Compaction { .. } => None,
+                item => Some(item),
+            })
+            .collect()
+    }
+
     /// Returns raw items in the history.
     pub(crate) fn raw_items(&self) -> &[ResponseItem] {
         &self.items
     }
 
-    // Estimate token usage using byte-based heuristics from the truncation helpers.
-    // This is a coarse lower bound, not a tokenizer-accurate count.
+    // Estimate token usage with the model's tokenizer. Items are counted in their
+    // serialized form, so this slightly overstates the text the model sees.
+    // Per-item counts are computed once when an item is recorded.
     pub(crate) fn estimate_token_count(&self, turn_context: &TurnContext) -> Option<i64> {
         let model_info = turn_context.client.get_model_info();
+        let encoding = turn_context.encoding;
         let base_instructions = model_info.base_instructions.as_str();
-        let base_tokens = i64::try_from(approx_token_count(base_instructions)).unwrap_or(i64::MAX);
+        let base_tokens =
+            i64::try_from(encoding.tokenizer().count_tokens(base_instructions)).unwrap_or(i64::MAX);
 
-        let items_tokens = self.items.iter().fold(0i64, |acc, item| {
-            acc + match item {
-                ResponseItem::GhostSnapshot { .. } => 0,
-                ResponseItem::Reasoning {
-                    encrypted_content: Some(content),
-                    ..
-                }
-                | ResponseItem::Compaction {
-                    encrypted_content: content,
-                } => estimate_reasoning_length(content.len()) as i64,
-                item => {
-                    let serialized = serde_json::to_string(item).unwrap_or_default();
-                    i64::try_from(approx_token_count(&serialized)).unwrap_or(i64::MAX)
-                }
-            }
-        });
+        let items_tokens = if encoding == self.encoding {
+            self.item_tokens
+                .iter()
+                .fold(0i64, |acc, tokens| acc.saturating_add(*tokens))
+        } else {
+            self.items.iter().fold(0i64, |acc, item| {
+                acc.saturating_add(estimate_item_tokens(item, encoding))
+            })
+        };
 
         Some(base_tokens.saturating_add(items_tokens))
     }
@@ -113,18 +158,29 @@ impl ContextManager {
             // Remove the oldest item (front of the list). Items are ordered from
             // oldest → newest, so index 0 is the first entry recorded.
             let removed = self.items.remove(0);
+            self.item_tokens.remove(0);
             // If the removed item participates in a call/output pair, also remove
             // its corresponding counterpart to keep the invariants intact without
             // running a full normalization pass.
-            normalize::remove_corresponding_for(&mut self.items, &removed);
+            if let Some(pos) = normalize::remove_corresponding_for(&mut self.items, &removed) {
+                self.item_tokens.remove(pos);
+            }
         }
     }
 
     pub(crate) fn replace(&mut self, items: Vec<ResponseItem>) {
         self.items = items;
+        self.recount_item_tokens();
     }
 
     pub(crate) fn replace_last_turn_images(&mut self, placeholder: &str) {
+        self.replace_last_item_images(placeholder);
+        if let (Some(item), Some(tokens)) = (self.items.last(), self.item_tokens.last_mut()) {
+            *tokens = estimate_item_tokens(item, self.encoding);
+        }
+    }
+
+    fn replace_last_item_images(&mut self, placeholder: &str) {
         let Some(last_item) = self.items.last_mut() else {
             return;
         };
@@ -238,6 +294,14 @@ impl ContextManager {
             .saturating_add(self.get_non_last_reasoning_items_tokens() as i64)
     }
 
+    fn recount_item_tokens(&mut self) {
+        self.item_tokens = self
+            .items
+            .iter()
+            .map(|item| estimate_item_tokens(item, self.encoding))
+            .collect();
+    }
+
     /// This function enforces a couple of invariants on the in-memory history:
     /// 1. every call (function/custom) has a corresponding output entry
     /// 2. every output has a corresponding call entry
@@ -253,12 +317,16 @@ impl ContextManager {
         let policy_with_serialization_budget = policy.mul(1.2);
         match item {
             ResponseItem::FunctionCallOutput { call_id, output } => {
-                let truncated =
-                    truncate_text(output.content.as_str(), policy_with_serialization_budget);
+                let truncated = truncate_text_with_encoding(
+                    output.content.as_str(),
+                    policy_with_serialization_budget,
+                    self.encoding,
+                );
                 let truncated_items = output.content_items.as_ref().map(|items| {
                     truncate_function_output_items_with_policy(
                         items,
                         policy_with_serialization_budget,
+                        self.encoding,
                     )
                 });
                 ResponseItem::FunctionCallOutput {
@@ -271,7 +339,11 @@ impl ContextManager {
                 }
             }
             ResponseItem::CustomToolCallOutput { call_id, output } => {
-                let truncated = truncate_text(output, policy_with_serialization_budget);
+                let truncated = truncate_text_with_encoding(
+                    output,
+                    policy_with_serialization_budget,
+                    self.encoding,
+                );
                 ResponseItem::CustomToolCallOutput {
                     call_id: call_id.clone(),
                     output: truncated,
@@ -308,6 +380,23 @@ fn is_api_message(message: &ResponseItem) -> bool {
     }
 }
 
+fn estimate_item_tokens(item: &ResponseItem, encoding: Encoding) -> i64 {
+    match item {
+        ResponseItem::GhostSnapshot { .. } => 0,
+        ResponseItem::Reasoning {
+            encrypted_content: Some(content),
+            ..
+        }
+        | ResponseItem::Compaction {
+            encrypted_content: content,
+        } => estimate_reasoning_length(content.len()) as i64,
+        item => {
+            let serialized = serde_json::to_string(item).unwrap_or_default();
+            i64::try_from(encoding.tokenizer().count_tokens(&serialized)).unwrap_or(i64::MAX)
+        }
+    }
+}
+
 fn estimate_reasoning_length(encoded_len: usize) -> usize {
     encoded_len
         .saturating_mul(3)
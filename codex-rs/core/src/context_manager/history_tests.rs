@@ -165,6 +165,69 @@ fn get_history_for_prompt_drops_ghost_commits() {
     assert_eq!(filtered, vec![]);
 }
 
+#[test]
+fn for_prompt_keeps_previous_turn_as_prefix() {
+    let mut history = create_history_with_items(vec![
+        user_input_text_msg("<user_instructions>agents</user_instructions>"),
+        user_input_text_msg("<environment_context>cwd a</environment_context>"),
+        user_input_text_msg("first"),
+        ResponseItem::GhostSnapshot {
+            ghost_commit: GhostCommit::new("ghost-1".to_string(), None, Vec::new(), Vec::new()),
+        },
+        assistant_msg("done"),
+    ]);
+    let first_turn = history.clone().for_prompt();
+
+    history.record_items(
+        [
+            user_input_text_msg("<environment_context>cwd b</environment_context>"),
+            user_input_text_msg("second"),
+            assistant_msg("done again"),
+        ]
+        .iter(),
+        TruncationPolicy::Tokens(10_000),
+    );
+    let second_turn = history.for_prompt();
+
+    assert_eq!(second_turn[..first_turn.len()], first_turn[..]);
+    assert_eq!(second_turn.len(), first_turn.len() + 3);
+}
+
+#[test]
+fn for_fallback_prompt_drops_provider_specific_state() {
+    let opaque_reasoning = ResponseItem::Reasoning {
+        id: String::new(),
+        summary: Vec::new(),
+        content: None,
+        encrypted_content: Some("opaque".to_string()),
+    };
+    let history = create_history_with_items(vec![
+        user_input_text_msg("hi"),
+        reasoning_with_encrypted_content(8),
+        opaque_reasoning,
+        ResponseItem::Compaction {
+            encrypted_content: "summary".to_string(),
+        },
+        reasoning_msg("plain"),
+    ]);
+
+    assert_eq!(
+        history.for_fallback_prompt(),
+        vec![
+            user_input_text_msg("hi"),
+            ResponseItem::Reasoning {
+                id: String::new(),
+                summary: vec![ReasoningItemReasoningSummary::SummaryText {
+                    text: "summary".to_string(),
+                }],
+                content: None,
+                encrypted_content: None,
+            },
+            reasoning_msg("plain"),
+        ]
+    );
+}
+
 #[test]
 fn remove_first_item_removes_matching_output_for_function_call() {
     let items = vec![
@@ -187,6 +250,43 @@ fn remove_first_item_removes_matching_output_for_function_call() {
     assert_eq!(h.raw_items(), vec![]);
 }
 
+#[test]
+fn item_token_counts_track_history_edits() {
+    let mut h = create_history_with_items(vec![
+        ResponseItem::FunctionCall {
+            id: None,
+            name: "do_it".to_string(),
+            arguments: "{}".to_string(),
+            call_id: "call-1".to_string(),
+        },
+        user_input_text_msg("between"),
+        ResponseItem::FunctionCallOutput {
+            call_id: "call-1".to_string(),
+            output: FunctionCallOutputPayload {
+                content: "ok".to_string(),
+                ..Default::default()
+            },
+        },
+        assistant_msg("done"),
+    ]);
+    let recounted = |h: &ContextManager| {
+        h.raw_items()
+            .iter()
+            .map(|item| estimate_item_tokens(item, h.encoding))
+            .collect::<Vec<_>>()
+    };
+
+    h.remove_first_item();
+    assert_eq!(h.item_tokens, recounted(&h));
+
+    h.set_encoding(Encoding::O200kBase);
+    assert_eq!(h.item_tokens, recounted(&h));
+
+    h.drop_last_n_user_turns(1);
+    assert_eq!(h.raw_items(), vec![]);
+    assert_eq!(h.item_tokens, Vec::<i64>::new());
+}
+
 #[test]
 fn remove_first_item_removes_matching_call_for_output() {
     let items = vec![
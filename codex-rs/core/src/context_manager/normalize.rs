@@ -146,67 +146,67 @@ pub(crate) fn remove_orphan_outputs(items: &mut Vec<ResponseItem>) {
     });
 }
 
-pub(crate) fn remove_corresponding_for(items: &mut Vec<ResponseItem>, item: &ResponseItem) {
+/// Removes the call or output paired with `item`, returning the index it
+/// occupied.
+pub(crate) fn remove_corresponding_for(
+    items: &mut Vec<ResponseItem>,
+    item: &ResponseItem,
+) -> Option<usize> {
     match item {
-        ResponseItem::FunctionCall { call_id, .. } => {
-            remove_first_matching(items, |i| {
-                matches!(
-                    i,
-                    ResponseItem::FunctionCallOutput {
-                        call_id: existing, ..
-                    } if existing == call_id
-                )
-            });
-        }
+        ResponseItem::FunctionCall { call_id, .. } => remove_first_matching(items, |i| {
+            matches!(
+                i,
+                ResponseItem::FunctionCallOutput {
+                    call_id: existing, ..
+                } if existing == call_id
+            )
+        }),
         ResponseItem::FunctionCallOutput { call_id, .. } => {
-            if let Some(pos) = items.iter().position(|i| {
-                matches!(i, ResponseItem::FunctionCall { call_id: existing, .. } if existing == call_id)
-            }) {
-                items.remove(pos);
-            } else if let Some(pos) = items.iter().position(|i| {
-                matches!(i, ResponseItem::LocalShellCall { call_id: Some(existing), .. } if existing == call_id)
-            }) {
-                items.remove(pos);
-            }
-        }
-        ResponseItem::CustomToolCall { call_id, .. } => {
-            remove_first_matching(items, |i| {
-                matches!(
-                    i,
-                    ResponseItem::CustomToolCallOutput {
-                        call_id: existing, ..
-                    } if existing == call_id
-                )
-            });
-        }
-        ResponseItem::CustomToolCallOutput { call_id, .. } => {
-            remove_first_matching(
-                items,
-                |i| matches!(i, ResponseItem::CustomToolCall { call_id: existing, .. } if existing == call_id),
-            );
+            let pos = items
+                .iter()
+                .position(|i| {
+                    matches!(i, ResponseItem::FunctionCall { call_id: existing, .. } if existing == call_id)
+                })
+                .or_else(|| {
+                    items.iter().position(|i| {
+                        matches!(i, ResponseItem::LocalShellCall { call_id: Some(existing), .. } if existing == call_id)
+                    })
+                })?;
+            items.remove(pos);
+            Some(pos)
         }
+        ResponseItem::CustomToolCall { call_id, .. } => remove_first_matching(items, |i| {
+            matches!(
+                i,
+                ResponseItem::CustomToolCallOutput {
+                    call_id: existing, ..
+                } if existing == call_id
+            )
+        }),
+        ResponseItem::CustomToolCallOutput { call_id, .. } => remove_first_matching(
+            items,
+            |i| matches!(i, ResponseItem::CustomToolCall { call_id: existing, .. } if existing == call_id),
+        ),
         ResponseItem::LocalShellCall {
             call_id: Some(call_id),
             ..
-        } => {
-            remove_first_matching(items, |i| {
-                matches!(
-                    i,
-                    ResponseItem::FunctionCallOutput {
-                        call_id: existing, ..
-                    } if existing == call_id
-                )
-            });
-        }
-        _ => {}
+        } => remove_first_matching(items, |i| {
+            matches!(
+                i,
+                ResponseItem::FunctionCallOutput {
+                    call_id: existing, ..
+                } if existing == call_id
+            )
+        }),
+        _ => None,
     }
 }
 
-fn remove_first_matching<F>(items: &mut Vec<ResponseItem>, predicate: F)
+fn remove_first_matching<F>(items: &mut Vec<ResponseItem>, predicate: F) -> Option<usize>
 where
     F: Fn(&ResponseItem) -> bool,
 {
-    if let Some(pos) = items.iter().position(predicate) {
-        items.remove(pos);
-    }
+    let pos = items.iter().position(predicate)?;
+    items.remove(pos);
+    Some(pos)
 }
@@ -160,6 +160,14 @@ pub fn create_client() -> CodexHttpClient {
 }
 
 pub fn build_reqwest_client() -> reqwest::Client {
+    build_reqwest_client_with_connect_timeout(None)
+}
+
+/// Like [`build_reqwest_client`], but fails connection attempts that take
+/// longer than `connect_timeout`.
+pub fn build_reqwest_client_with_connect_timeout(
+    connect_timeout: Option<std::time::Duration>,
+) -> reqwest::Client {
     use reqwest::header::HeaderMap;
 
     let mut headers = HeaderMap::new();
@@ -170,6 +178,9 @@ pub fn build_reqwest_client() -> reqwest::Client {
         // Set UA via dedicated helper to avoid header validation pitfalls
         .user_agent(ua)
         .default_headers(headers);
+    if let Some(connect_timeout) = connect_timeout {
+        builder = builder.connect_timeout(connect_timeout);
+    }
     if is_sandboxed() {
         builder = builder.no_proxy();
     }
@@ -33,6 +33,9 @@ impl EnvironmentContext {
         self.cwd == *cwd
     }
 
+    /// Builds the context describing what changed between two turns. The
+    /// result is recorded as a new message rather than replacing the initial
+    /// one, which keeps earlier history byte-identical across turns.
     pub fn diff(before: &TurnContext, after: &TurnContext, shell: &Shell) -> Self {
         let cwd = if before.cwd != after.cwd {
             Some(after.cwd.clone())
@@ -216,6 +216,26 @@ impl CodexErr {
             CodexErr::LandlockRuleset(_) | CodexErr::LandlockPathFd(_) => false,
         }
     }
+
+    /// Whether the error points at the model provider being unavailable
+    /// (5xx/429 or a broken connection) rather than at the request itself, so
+    /// that retrying against a different provider may succeed.
+    pub fn is_provider_failure(&self) -> bool {
+        match self {
+            CodexErr::RetryLimit(err) => is_provider_failure_status(err.status),
+            CodexErr::UnexpectedStatus(err) => is_provider_failure_status(err.status),
+            CodexErr::Stream(..)
+            | CodexErr::Timeout
+            | CodexErr::ResponseStreamFailed(_)
+            | CodexErr::ConnectionFailed(_)
+            | CodexErr::InternalServerError => true,
+            _ => false,
+        }
+    }
+}
+
+fn is_provider_failure_status(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
 }
 
 #[derive(Debug)]
@@ -636,6 +656,8 @@ mod tests {
             aggregated_output: StreamOutput::new("aggregate detail".to_string()),
             duration: Duration::from_millis(10),
             timed_out: false,
+            resource_limit_exceeded: None,
+            shell_state: None,
         };
         let err = CodexErr::Sandbox(SandboxErr::Denied {
             output: Box::new(output),
@@ -652,6 +674,8 @@ mod tests {
             aggregated_output: StreamOutput::new(String::new()),
             duration: Duration::from_millis(10),
             timed_out: false,
+            resource_limit_exceeded: None,
+            shell_state: None,
         };
         let err = CodexErr::Sandbox(SandboxErr::Denied {
             output: Box::new(output),
@@ -668,6 +692,8 @@ mod tests {
             aggregated_output: StreamOutput::new(String::new()),
             duration: Duration::from_millis(8),
             timed_out: false,
+            resource_limit_exceeded: None,
+            shell_state: None,
         };
         let err = CodexErr::Sandbox(SandboxErr::Denied {
             output: Box::new(output),
@@ -711,6 +737,8 @@ mod tests {
             aggregated_output: StreamOutput::new(String::new()),
             duration: Duration::from_millis(5),
             timed_out: false,
+            resource_limit_exceeded: None,
+            shell_state: None,
         };
         let err = CodexErr::Sandbox(SandboxErr::Denied {
             output: Box::new(output),
@@ -910,4 +938,29 @@ mod tests {
             assert_eq!(err.to_string(), expected);
         });
     }
+
+    #[test]
+    fn provider_failure_covers_5xx_429_and_transport_errors() {
+        let status_err = |status| {
+            CodexErr::UnexpectedStatus(UnexpectedResponseError {
+                status,
+                body: String::new(),
+                url: None,
+                request_id: None,
+            })
+        };
+        assert!(status_err(StatusCode::BAD_GATEWAY).is_provider_failure());
+        assert!(status_err(StatusCode::TOO_MANY_REQUESTS).is_provider_failure());
+        assert!(!status_err(StatusCode::BAD_REQUEST).is_provider_failure());
+        assert!(
+            CodexErr::RetryLimit(RetryLimitReachedError {
+                status: StatusCode::SERVICE_UNAVAILABLE,
+                request_id: None,
+            })
+            .is_provider_failure()
+        );
+        assert!(CodexErr::Stream("disconnected".to_string(), None).is_provider_failure());
+        assert!(!CodexErr::ContextWindowExceeded.is_provider_failure());
+        assert!(!CodexErr::QuotaExceeded.is_provider_failure());
+    }
 }
@@ -6,6 +6,7 @@ use std::io;
 use std::path::Path;
 use std::path::PathBuf;
 use std::process::ExitStatus;
+use std::sync::Arc;
 use std::time::Duration;
 use std::time::Instant;
 
@@ -16,10 +17,14 @@ use tokio::io::BufReader;
 use tokio::process::Child;
 use tokio_util::sync::CancellationToken;
 
+use crate::config::types::SandboxResourceLimits;
+use crate::container::ContainerGuard;
 use crate::error::CodexErr;
 use crate::error::Result;
 use crate::error::SandboxErr;
 use crate::get_platform_sandbox;
+use crate::landlock::take_cgroup_limit_exceeded;
+use crate::persistent_shell::ShellStateChange;
 use crate::protocol::Event;
 use crate::protocol::EventMsg;
 use crate::protocol::ExecCommandOutputDeltaEvent;
@@ -29,6 +34,8 @@ use crate::sandboxing::CommandSpec;
 use crate::sandboxing::ExecEnv;
 use crate::sandboxing::SandboxManager;
 use crate::sandboxing::SandboxPermissions;
+use crate::secret_redaction::SecretRedactor;
+use crate::spawn::InheritedFds;
 use crate::spawn::StdioPolicy;
 use crate::spawn::spawn_child_async;
 use crate::text_encoding::bytes_to_string_smart;
@@ -118,6 +125,9 @@ pub enum SandboxType {
 
     /// Only available on Windows.
     WindowsRestrictedToken,
+
+    /// Runs the command in the OCI container configured under `[container]`.
+    Container,
 }
 
 #[derive(Clone)]
@@ -125,6 +135,9 @@ pub struct StdoutStream {
     pub sub_id: String,
     pub call_id: String,
     pub tx_event: Sender<Event>,
+    /// Masks secrets in the streamed chunks. A secret split across two
+    /// chunks is not recognized here, only in the final output.
+    pub(crate) secret_redactor: Option<Arc<SecretRedactor>>,
 }
 
 pub async fn process_exec_tool_call(
@@ -177,6 +190,10 @@ pub async fn process_exec_tool_call(
             sandbox_type,
             sandbox_cwd,
             codex_linux_sandbox_exe.as_ref(),
+            None,
+            None,
+            None,
+            None,
         )
         .map_err(CodexErr::from)?;
 
@@ -198,6 +215,8 @@ pub(crate) async fn execute_exec_env(
         sandbox_permissions,
         justification,
         arg0,
+        resource_limits,
+        inherited_fds,
     } = env;
 
     let params = ExecParams {
@@ -211,9 +230,21 @@ pub(crate) async fn execute_exec_env(
     };
 
     let start = Instant::now();
-    let raw_output_result = exec(params, sandbox, sandbox_policy, stdout_stream).await;
+    let raw_output_result = exec(
+        params,
+        sandbox,
+        sandbox_policy,
+        inherited_fds,
+        stdout_stream,
+    )
+    .await;
     let duration = start.elapsed();
-    finalize_exec_result(raw_output_result, sandbox, duration)
+    finalize_exec_result(
+        raw_output_result,
+        sandbox,
+        resource_limits.as_ref(),
+        duration,
+    )
 }
 
 #[cfg(target_os = "windows")]
@@ -312,24 +343,35 @@ async fn exec_windows_sandbox(
         stderr,
         aggregated_output,
         timed_out: capture.timed_out,
+        resource_limit_exceeded: None,
     })
 }
 
 fn finalize_exec_result(
     raw_output_result: std::result::Result<RawExecToolCallOutput, CodexErr>,
     sandbox_type: SandboxType,
+    resource_limits: Option<&SandboxResourceLimits>,
     duration: Duration,
 ) -> Result<ExecToolCallOutput> {
     match raw_output_result {
         Ok(raw_output) => {
             #[allow(unused_mut)]
             let mut timed_out = raw_output.timed_out;
+            #[allow(unused_mut)]
+            let mut limit_signal = None;
+            let cgroup_limit = raw_output.resource_limit_exceeded;
 
             #[cfg(target_family = "unix")]
             {
                 if let Some(signal) = raw_output.exit_status.signal() {
                     if signal == TIMEOUT_CODE {
                         timed_out = true;
+                    } else if cgroup_limit.is_some()
+                        || resource_limits
+                            .and_then(|limits| resource_limit_for_signal(limits, signal))
+                            .is_some()
+                    {
+                        limit_signal = Some(signal);
                     } else {
                         return Err(CodexErr::Sandbox(SandboxErr::Signal(signal)));
                     }
@@ -339,18 +381,22 @@ fn finalize_exec_result(
             let mut exit_code = raw_output.exit_status.code().unwrap_or(-1);
             if timed_out {
                 exit_code = EXEC_TIMEOUT_EXIT_CODE;
+            } else if let Some(signal) = limit_signal {
+                exit_code = EXIT_CODE_SIGNAL_BASE + signal;
             }
 
             let stdout = raw_output.stdout.from_utf8_lossy();
             let stderr = raw_output.stderr.from_utf8_lossy();
             let aggregated_output = raw_output.aggregated_output.from_utf8_lossy();
-            let exec_output = ExecToolCallOutput {
+            let mut exec_output = ExecToolCallOutput {
                 exit_code,
                 stdout,
                 stderr,
                 aggregated_output,
                 duration,
                 timed_out,
+                resource_limit_exceeded: None,
+                shell_state: None,
             };
 
             if timed_out {
@@ -359,6 +405,16 @@ fn finalize_exec_result(
                 }));
             }
 
+            // A command stopped by its resource limits is reported as such
+            // rather than as a sandbox denial that should be retried unsandboxed.
+            exec_output.resource_limit_exceeded = cgroup_limit.or_else(|| {
+                resource_limits
+                    .and_then(|limits| detect_resource_limit_exceeded(limits, &exec_output))
+            });
+            if exec_output.resource_limit_exceeded.is_some() {
+                return Ok(exec_output);
+            }
+
             if is_likely_sandbox_denied(sandbox_type, &exec_output) {
                 return Err(CodexErr::Sandbox(SandboxErr::Denied {
                     output: Box::new(exec_output),
@@ -384,6 +440,10 @@ pub(crate) mod errors {
                 SandboxTransformError::MissingLinuxSandboxExecutable => {
                     CodexErr::LandlockSandboxExecutableNotProvided
                 }
+                SandboxTransformError::MissingContainerConfig
+                | SandboxTransformError::ContainerWorkdirNotMounted(_) => {
+                    CodexErr::UnsupportedOperation(err.to_string())
+                }
                 #[cfg(not(target_os = "macos"))]
                 SandboxTransformError::SeatbeltUnavailable => CodexErr::UnsupportedOperation(
                     "seatbelt sandbox is only available on macOS".to_string(),
@@ -455,6 +515,80 @@ pub(crate) fn is_likely_sandbox_denied(
     false
 }
 
+/// The configured resource limit that stopped a sandboxed command.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResourceLimitExceeded {
+    CpuTime,
+    FileSize,
+    Memory,
+    Processes,
+}
+
+impl ResourceLimitExceeded {
+    /// The `resource_limits` setting the command ran into.
+    pub fn config_key(self) -> &'static str {
+        match self {
+            Self::CpuTime => "max_cpu_seconds",
+            Self::FileSize => "max_file_size_mb",
+            Self::Memory => "max_memory_mb",
+            Self::Processes => "max_processes",
+        }
+    }
+}
+
+impl std::fmt::Display for ResourceLimitExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let limit = match self {
+            Self::CpuTime => "CPU time",
+            Self::FileSize => "file size",
+            Self::Memory => "memory",
+            Self::Processes => "process count",
+        };
+        write!(f, "{limit} limit (`{}`)", self.config_key())
+    }
+}
+
+/// Works out whether a failed command was stopped by one of `limits`.
+///
+/// CPU time and file size overruns are delivered as `SIGXCPU`/`SIGXFSZ`,
+/// either to the command itself or to a child whose status the shell passes
+/// on as `128 + signal`. Memory and process limits only surface as failed
+/// allocations or forks; those are read from the command's cgroup instead
+/// (see [`take_cgroup_limit_exceeded`]).
+pub(crate) fn detect_resource_limit_exceeded(
+    limits: &SandboxResourceLimits,
+    exec_output: &ExecToolCallOutput,
+) -> Option<ResourceLimitExceeded> {
+    if exec_output.exit_code == 0 || exec_output.timed_out {
+        return None;
+    }
+
+    #[cfg(unix)]
+    {
+        exec_output
+            .exit_code
+            .checked_sub(EXIT_CODE_SIGNAL_BASE)
+            .and_then(|signal| resource_limit_for_signal(limits, signal))
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = limits;
+        None
+    }
+}
+
+#[cfg(unix)]
+fn resource_limit_for_signal(
+    limits: &SandboxResourceLimits,
+    signal: i32,
+) -> Option<ResourceLimitExceeded> {
+    match signal {
+        libc::SIGXCPU if limits.max_cpu_seconds.is_some() => Some(ResourceLimitExceeded::CpuTime),
+        libc::SIGXFSZ if limits.max_file_size_mb.is_some() => Some(ResourceLimitExceeded::FileSize),
+        _ => None,
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct StreamOutput<T: Clone> {
     pub text: T,
@@ -468,6 +602,8 @@ struct RawExecToolCallOutput {
     pub stderr: StreamOutput<Vec<u8>>,
     pub aggregated_output: StreamOutput<Vec<u8>>,
     pub timed_out: bool,
+    /// Limit recorded in the command's cgroup events, if one was hit.
+    pub resource_limit_exceeded: Option<ResourceLimitExceeded>,
 }
 
 impl StreamOutput<String> {
@@ -501,6 +637,10 @@ pub struct ExecToolCallOutput {
     pub aggregated_output: StreamOutput<String>,
     pub duration: Duration,
     pub timed_out: bool,
+    /// Set when the command was stopped by the sandbox's resource limits.
+    pub resource_limit_exceeded: Option<ResourceLimitExceeded>,
+    /// What the command changed in the persistent shell state, if enabled.
+    pub shell_state: Option<ShellStateChange>,
 }
 
 impl Default for ExecToolCallOutput {
@@ -512,6 +652,8 @@ impl Default for ExecToolCallOutput {
             aggregated_output: StreamOutput::new(String::new()),
             duration: Duration::ZERO,
             timed_out: false,
+            resource_limit_exceeded: None,
+            shell_state: None,
         }
     }
 }
@@ -521,6 +663,7 @@ async fn exec(
     params: ExecParams,
     sandbox: SandboxType,
     sandbox_policy: &SandboxPolicy,
+    inherited_fds: InheritedFds,
     stdout_stream: Option<StdoutStream>,
 ) -> Result<RawExecToolCallOutput> {
     #[cfg(target_os = "windows")]
@@ -556,9 +699,33 @@ async fn exec(
         sandbox_policy,
         StdioPolicy::RedirectForShellTool,
         env,
+        inherited_fds,
     )
     .await?;
-    consume_truncated_output(child, expiration, stdout_stream).await
+    // The Linux sandbox helper enforces memory and process limits through a
+    // cgroup named after its pid; read its events once the command is done.
+    let helper_pid = if sandbox == SandboxType::LinuxSeccomp {
+        child.id()
+    } else {
+        None
+    };
+    // Killing the engine client on timeout or interrupt leaves the container
+    // running; the guard removes it unless the command finished on its own.
+    let mut container_guard = if sandbox == SandboxType::Container {
+        ContainerGuard::for_command(&command)
+    } else {
+        None
+    };
+    let output = consume_truncated_output(child, expiration, stdout_stream).await;
+    if let (Some(guard), Ok(output)) = (container_guard.as_mut(), &output)
+        && !output.timed_out
+    {
+        guard.disarm();
+    }
+    let cgroup_limit = helper_pid.and_then(take_cgroup_limit_exceeded);
+    let mut output = output?;
+    output.resource_limit_exceeded = cgroup_limit;
+    Ok(output)
 }
 
 /// Consumes the output of a child process, truncating it so it is suitable for
@@ -679,6 +846,7 @@ async fn consume_truncated_output(
         stderr,
         aggregated_output,
         timed_out,
+        resource_limit_exceeded: None,
     })
 }
 
@@ -703,7 +871,10 @@ async fn read_capped<R: AsyncRead + Unpin + Send + 'static>(
         if let Some(stream) = &stream
             && emitted_deltas < MAX_EXEC_OUTPUT_DELTAS_PER_CALL
         {
-            let chunk = tmp[..n].to_vec();
+            let mut chunk = tmp[..n].to_vec();
+            if let Some(redactor) = &stream.secret_redactor {
+                redactor.redact_bytes_in_place(&mut chunk);
+            }
             let msg = EventMsg::ExecCommandOutputDelta(ExecCommandOutputDeltaEvent {
                 call_id: stream.call_id.clone(),
                 stream: if is_stderr {
@@ -800,6 +971,8 @@ mod tests {
             aggregated_output: StreamOutput::new(aggregated.to_string()),
             duration: Duration::from_millis(1),
             timed_out: false,
+            resource_limit_exceeded: None,
+            shell_state: None,
         }
     }
 
@@ -855,6 +1028,104 @@ mod tests {
         assert!(is_likely_sandbox_denied(SandboxType::LinuxSeccomp, &output));
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn resource_limit_detection_maps_limit_signals() {
+        let limits = SandboxResourceLimits {
+            max_cpu_seconds: Some(10),
+            ..Default::default()
+        };
+        let cpu = make_exec_output(EXIT_CODE_SIGNAL_BASE + libc::SIGXCPU, "", "", "");
+        assert_eq!(
+            detect_resource_limit_exceeded(&limits, &cpu),
+            Some(ResourceLimitExceeded::CpuTime)
+        );
+
+        // No file size limit is configured, so SIGXFSZ is not attributed to one.
+        let fsize = make_exec_output(EXIT_CODE_SIGNAL_BASE + libc::SIGXFSZ, "", "", "");
+        assert_eq!(detect_resource_limit_exceeded(&limits, &fsize), None);
+    }
+
+    #[test]
+    fn resource_limit_detection_ignores_output_text() {
+        let output = make_exec_output(1, "", "fatal: Out of memory, malloc failed", "");
+        let limits = SandboxResourceLimits {
+            max_memory_mb: Some(512),
+            ..Default::default()
+        };
+        assert_eq!(detect_resource_limit_exceeded(&limits, &output), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn finalize_reports_limit_signal_instead_of_sandbox_error() {
+        let empty = StreamOutput {
+            text: Vec::new(),
+            truncated_after_lines: None,
+        };
+        let raw_output = RawExecToolCallOutput {
+            exit_status: ExitStatus::from_raw(libc::SIGXFSZ),
+            stdout: empty.clone(),
+            stderr: empty.clone(),
+            aggregated_output: empty,
+            timed_out: false,
+            resource_limit_exceeded: None,
+        };
+        let limits = SandboxResourceLimits {
+            max_file_size_mb: Some(1),
+            ..Default::default()
+        };
+
+        let output = finalize_exec_result(
+            Ok(raw_output),
+            SandboxType::LinuxSeccomp,
+            Some(&limits),
+            Duration::from_millis(1),
+        )
+        .expect("limit overruns are reported as command output");
+
+        assert_eq!(output.exit_code, EXIT_CODE_SIGNAL_BASE + libc::SIGXFSZ);
+        assert_eq!(
+            output.resource_limit_exceeded,
+            Some(ResourceLimitExceeded::FileSize)
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn finalize_reports_cgroup_oom_kill_as_memory_limit() {
+        let empty = StreamOutput {
+            text: Vec::new(),
+            truncated_after_lines: None,
+        };
+        let raw_output = RawExecToolCallOutput {
+            exit_status: ExitStatus::from_raw(libc::SIGKILL),
+            stdout: empty.clone(),
+            stderr: empty.clone(),
+            aggregated_output: empty,
+            timed_out: false,
+            resource_limit_exceeded: Some(ResourceLimitExceeded::Memory),
+        };
+        let limits = SandboxResourceLimits {
+            max_memory_mb: Some(64),
+            ..Default::default()
+        };
+
+        let output = finalize_exec_result(
+            Ok(raw_output),
+            SandboxType::LinuxSeccomp,
+            Some(&limits),
+            Duration::from_millis(1),
+        )
+        .expect("limit overruns are reported as command output");
+
+        assert_eq!(output.exit_code, EXIT_CODE_SIGNAL_BASE + libc::SIGKILL);
+        assert_eq!(
+            output.resource_limit_exceeded,
+            Some(ResourceLimitExceeded::Memory)
+        );
+    }
+
     #[cfg(unix)]
     #[tokio::test]
     async fn kill_child_process_group_kills_grandchildren_on_timeout() -> Result<()> {
@@ -883,7 +1154,14 @@ mod tests {
             arg0: None,
         };
 
-        let output = exec(params, SandboxType::None, &SandboxPolicy::ReadOnly, None).await?;
+        let output = exec(
+            params,
+            SandboxType::None,
+            &SandboxPolicy::new_read_only_policy(),
+            InheritedFds::default(),
+            None,
+        )
+        .await?;
         assert!(output.timed_out);
 
         let stdout = output.stdout.from_utf8_lossy().text;
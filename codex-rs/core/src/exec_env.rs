@@ -15,6 +15,35 @@ pub fn create_env(policy: &ShellEnvironmentPolicy) -> HashMap<String, String> {
     populate_env(std::env::vars(), policy)
 }
 
+/// Variables of the Codex process environment that `policy` keeps away from
+/// commands via `exclude` or the default excludes. Their values are treated
+/// as secrets when redacting tool output.
+pub(crate) fn excluded_env_vars(policy: &ShellEnvironmentPolicy) -> Vec<(String, String)> {
+    filter_excluded(std::env::vars(), policy)
+}
+
+fn filter_excluded<I>(vars: I, policy: &ShellEnvironmentPolicy) -> Vec<(String, String)>
+where
+    I: IntoIterator<Item = (String, String)>,
+{
+    let default_excludes = default_excludes();
+    vars.into_iter()
+        .filter(|(name, _)| {
+            (!policy.ignore_default_excludes
+                && default_excludes.iter().any(|pattern| pattern.matches(name)))
+                || policy.exclude.iter().any(|pattern| pattern.matches(name))
+        })
+        .collect()
+}
+
+fn default_excludes() -> Vec<EnvironmentVariablePattern> {
+    vec![
+        EnvironmentVariablePattern::new_case_insensitive("*KEY*"),
+        EnvironmentVariablePattern::new_case_insensitive("*SECRET*"),
+        EnvironmentVariablePattern::new_case_insensitive("*TOKEN*"),
+    ]
+}
+
 fn populate_env<I>(vars: I, policy: &ShellEnvironmentPolicy) -> HashMap<String, String>
 where
     I: IntoIterator<Item = (String, String)>,
@@ -49,11 +78,7 @@ where
 
     // Step 2 – Apply the default exclude if not disabled.
     if !policy.ignore_default_excludes {
-        let default_excludes = vec![
-            EnvironmentVariablePattern::new_case_insensitive("*KEY*"),
-            EnvironmentVariablePattern::new_case_insensitive("*SECRET*"),
-            EnvironmentVariablePattern::new_case_insensitive("*TOKEN*"),
-        ];
+        let default_excludes = default_excludes();
         env_map.retain(|k, _| !matches_any(k, &default_excludes));
     }
 
@@ -205,6 +230,29 @@ mod tests {
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn test_filter_excluded_returns_default_and_custom_excludes() {
+        let vars = make_vars(&[
+            ("PATH", "/usr/bin"),
+            ("API_KEY", "secret"),
+            ("DATABASE_URL", "postgres://u:p@db"),
+        ]);
+
+        let policy = ShellEnvironmentPolicy {
+            ignore_default_excludes: false,
+            exclude: vec![EnvironmentVariablePattern::new_case_insensitive(
+                "DATABASE_*",
+            )],
+            ..Default::default()
+        };
+
+        let result = filter_excluded(vars, &policy);
+        assert_eq!(
+            result,
+            make_vars(&[("API_KEY", "secret"), ("DATABASE_URL", "postgres://u:p@db"),])
+        );
+    }
+
     #[test]
     #[cfg(target_os = "windows")]
     fn test_core_inherit_respects_case_insensitive_names_on_windows() {
@@ -4,19 +4,25 @@ use std::path::PathBuf;
 use std::sync::Arc;
 
 use arc_swap::ArcSwap;
+use chrono::Utc;
+use uuid::Uuid;
 
+use crate::config::types::ExecPolicyAmendmentScope;
+use crate::config::types::ExecPolicyAmendments;
 use crate::config_loader::ConfigLayerStack;
 use crate::config_loader::ConfigLayerStackOrdering;
 use crate::is_dangerous_command::command_might_be_dangerous;
 use crate::is_safe_command::is_known_safe_command;
 use codex_execpolicy::AmendError;
+use codex_execpolicy::AmendmentMetadata;
 use codex_execpolicy::Decision;
 use codex_execpolicy::Error as ExecPolicyRuleError;
 use codex_execpolicy::Evaluation;
+use codex_execpolicy::MatchContext;
 use codex_execpolicy::Policy;
 use codex_execpolicy::PolicyParser;
 use codex_execpolicy::RuleMatch;
-use codex_execpolicy::blocking_append_allow_prefix_rule;
+use codex_execpolicy::blocking_append_amendment;
 use codex_protocol::approvals::ExecPolicyAmendment;
 use codex_protocol::protocol::AskForApproval;
 use codex_protocol::protocol::SandboxPolicy;
@@ -27,6 +33,7 @@ use tokio::task::spawn_blocking;
 use crate::bash::parse_shell_lc_plain_commands;
 use crate::features::Feature;
 use crate::features::Features;
+use crate::git_info::get_git_repo_root;
 use crate::sandboxing::SandboxPermissions;
 use crate::tools::sandboxing::ExecApprovalRequirement;
 use shlex::try_join as shlex_try_join;
@@ -114,13 +121,21 @@ impl ExecPolicyManager {
         &self,
         features: &Features,
         command: &[String],
+        cwd: &Path,
         approval_policy: AskForApproval,
         sandbox_policy: &SandboxPolicy,
         sandbox_permissions: SandboxPermissions,
     ) -> ExecApprovalRequirement {
         let exec_policy = self.current();
-        let commands =
-            parse_shell_lc_plain_commands(command).unwrap_or_else(|| vec![command.to_vec()]);
+        let match_context = MatchContext {
+            cwd: Some(cwd.to_path_buf()),
+            writable_roots: sandbox_policy
+                .get_writable_roots_with_cwd(cwd)
+                .into_iter()
+                .map(|writable_root| writable_root.root.into_path_buf())
+                .collect(),
+        };
+        let commands = commands_for_exec_policy(command);
         let exec_policy_fallback = |cmd: &[String]| {
             render_decision_for_unmatched_command(
                 approval_policy,
@@ -129,7 +144,11 @@ impl ExecPolicyManager {
                 sandbox_permissions,
             )
         };
-        let evaluation = exec_policy.check_multiple(commands.iter(), &exec_policy_fallback);
+        let evaluation = exec_policy.check_multiple_with_context(
+            commands.iter(),
+            &match_context,
+            &exec_policy_fallback,
+        );
 
         match evaluation.decision {
             Decision::Forbidden => ExecApprovalRequirement::Forbidden {
@@ -167,32 +186,64 @@ impl ExecPolicyManager {
         }
     }
 
+    /// Records an "always allow" approval. Session-scoped amendments only update the
+    /// in-memory policy; the others are also appended to `default.rules` with their
+    /// metadata so they can be listed and revoked.
     pub(crate) async fn append_amendment_and_update(
         &self,
         codex_home: &Path,
         amendment: &ExecPolicyAmendment,
+        scope: ExecPolicyAmendmentScope,
+        metadata: AmendmentMetadata,
     ) -> Result<(), ExecPolicyUpdateError> {
-        let policy_path = default_policy_path(codex_home);
         let prefix = amendment.command.clone();
-        spawn_blocking({
-            let policy_path = policy_path.clone();
-            let prefix = prefix.clone();
-            move || blocking_append_allow_prefix_rule(&policy_path, &prefix)
-        })
-        .await
-        .map_err(|source| ExecPolicyUpdateError::JoinBlockingTask { source })?
-        .map_err(|source| ExecPolicyUpdateError::AppendRule {
-            path: policy_path,
-            source,
-        })?;
+        if scope != ExecPolicyAmendmentScope::Session {
+            let policy_path = default_policy_path(codex_home);
+            spawn_blocking({
+                let policy_path = policy_path.clone();
+                let prefix = prefix.clone();
+                let metadata = metadata.clone();
+                move || blocking_append_amendment(&policy_path, &prefix, &metadata)
+            })
+            .await
+            .map_err(|source| ExecPolicyUpdateError::JoinBlockingTask { source })?
+            .map_err(|source| ExecPolicyUpdateError::AppendRule {
+                path: policy_path,
+                source,
+            })?;
+        }
 
         let mut updated_policy = self.current().as_ref().clone();
-        updated_policy.add_prefix_rule(&prefix, Decision::Allow)?;
+        updated_policy.add_amendment(&prefix, &metadata)?;
         self.policy.store(Arc::new(updated_policy));
         Ok(())
     }
 }
 
+/// Builds the metadata recorded with an "always allow" approval granted in `cwd`.
+/// `approved_by` is the identity reported by the approving client, if any.
+pub(crate) fn amendment_metadata(
+    settings: &ExecPolicyAmendments,
+    scope: ExecPolicyAmendmentScope,
+    cwd: &Path,
+    thread_id: String,
+    approved_by: Option<String>,
+) -> AmendmentMetadata {
+    let created_at = Utc::now();
+    AmendmentMetadata {
+        id: Uuid::new_v4().to_string(),
+        created_at,
+        expires_at: settings
+            .expires_after_hours
+            .and_then(|hours| i64::try_from(hours).ok())
+            .map(|hours| created_at + chrono::Duration::hours(hours)),
+        repo: (scope == ExecPolicyAmendmentScope::Repo)
+            .then(|| get_git_repo_root(cwd).unwrap_or_else(|| cwd.to_path_buf())),
+        thread_id: Some(thread_id),
+        approved_by,
+    }
+}
+
 impl Default for ExecPolicyManager {
     fn default() -> Self {
         Self::new(Arc::new(Policy::empty()))
@@ -260,6 +311,12 @@ pub async fn load_exec_policy(config_stack: &ConfigLayerStack) -> Result<Policy,
     Ok(policy)
 }
 
+/// Splits `bash -lc "<script>"` into the plain commands it runs; anything else, including
+/// scripts with constructs beyond word-only commands, is evaluated as a single command.
+pub fn commands_for_exec_policy(command: &[String]) -> Vec<Vec<String>> {
+    parse_shell_lc_plain_commands(command).unwrap_or_else(|| vec![command.to_vec()])
+}
+
 /// If a command is not matched by any execpolicy rule, derive a [`Decision`].
 pub fn render_decision_for_unmatched_command(
     approval_policy: AskForApproval,
@@ -274,7 +331,7 @@ pub fn render_decision_for_unmatched_command(
     // On Windows, ReadOnly sandbox is not a real sandbox, so special-case it
     // here.
     let runtime_sandbox_provides_safety =
-        cfg!(windows) && matches!(sandbox_policy, SandboxPolicy::ReadOnly);
+        cfg!(windows) && matches!(sandbox_policy, SandboxPolicy::ReadOnly { .. });
 
     // If the command is flagged as dangerous or we have no sandbox protection,
     // we should never allow it to run without user approval.
@@ -309,7 +366,7 @@ pub fn render_decision_for_unmatched_command(
                     // command has not been flagged as dangerous.
                     Decision::Allow
                 }
-                SandboxPolicy::ReadOnly | SandboxPolicy::WorkspaceWrite { .. } => {
+                SandboxPolicy::ReadOnly { .. } | SandboxPolicy::WorkspaceWrite { .. } => {
                     // In restricted sandboxes (ReadOnly/WorkspaceWrite), do not prompt for
                     // non‑escalated, non‑dangerous commands — let the sandbox enforce
                     // restrictions (e.g., block network/write) without a user prompt.
@@ -716,6 +773,7 @@ prefix_rule(pattern=["rm"], decision="forbidden")
             .create_exec_approval_requirement_for_command(
                 &Features::with_defaults(),
                 &forbidden_script,
+                Path::new("/workspace"),
                 AskForApproval::OnRequest,
                 &SandboxPolicy::DangerFullAccess,
                 SandboxPermissions::UseDefault,
@@ -754,6 +812,7 @@ prefix_rule(
                     "-rf".to_string(),
                     "/some/important/folder".to_string(),
                 ],
+                Path::new("/workspace"),
                 AskForApproval::OnRequest,
                 &SandboxPolicy::DangerFullAccess,
                 SandboxPermissions::UseDefault,
@@ -783,6 +842,7 @@ prefix_rule(
             .create_exec_approval_requirement_for_command(
                 &Features::with_defaults(),
                 &command,
+                Path::new("/workspace"),
                 AskForApproval::OnRequest,
                 &SandboxPolicy::DangerFullAccess,
                 SandboxPermissions::UseDefault,
@@ -813,6 +873,7 @@ prefix_rule(
             .create_exec_approval_requirement_for_command(
                 &Features::with_defaults(),
                 &command,
+                Path::new("/workspace"),
                 AskForApproval::Never,
                 &SandboxPolicy::DangerFullAccess,
                 SandboxPermissions::UseDefault,
@@ -836,8 +897,9 @@ prefix_rule(
             .create_exec_approval_requirement_for_command(
                 &Features::with_defaults(),
                 &command,
+                Path::new("/workspace"),
                 AskForApproval::UnlessTrusted,
-                &SandboxPolicy::ReadOnly,
+                &SandboxPolicy::new_read_only_policy(),
                 SandboxPermissions::UseDefault,
             )
             .await;
@@ -870,6 +932,7 @@ prefix_rule(
                 .create_exec_approval_requirement_for_command(
                     &Features::with_defaults(),
                     &command,
+                    Path::new("/workspace"),
                     AskForApproval::UnlessTrusted,
                     &SandboxPolicy::DangerFullAccess,
                     SandboxPermissions::UseDefault,
@@ -884,6 +947,17 @@ prefix_rule(
         );
     }
 
+    fn test_amendment_metadata() -> AmendmentMetadata {
+        AmendmentMetadata {
+            id: "a1".to_string(),
+            created_at: "2026-01-01T00:00:00Z".parse().expect("parse timestamp"),
+            expires_at: None,
+            repo: None,
+            thread_id: Some("t1".to_string()),
+            approved_by: Some("alice".to_string()),
+        }
+    }
+
     #[tokio::test]
     async fn append_execpolicy_amendment_updates_policy_and_file() {
         let codex_home = tempdir().expect("create temp dir");
@@ -891,7 +965,12 @@ prefix_rule(
         let manager = ExecPolicyManager::default();
 
         manager
-            .append_amendment_and_update(codex_home.path(), &ExecPolicyAmendment::from(prefix))
+            .append_amendment_and_update(
+                codex_home.path(),
+                &ExecPolicyAmendment::from(prefix),
+                ExecPolicyAmendmentScope::Global,
+                test_amendment_metadata(),
+            )
             .await
             .expect("update policy");
         let updated_policy = manager.current();
@@ -912,9 +991,118 @@ prefix_rule(
             .expect("policy file should have been created");
         assert_eq!(
             contents,
-            r#"prefix_rule(pattern=["echo", "hello"], decision="allow")
+            r#"prefix_rule(pattern=["echo", "hello"], decision="allow", amendment={"id":"a1","created_at":"2026-01-01T00:00:00Z","thread_id":"t1","approved_by":"alice"})
 "#
         );
+
+        let mut parser = PolicyParser::new();
+        parser
+            .parse("default.rules", &contents)
+            .expect("amended policy should parse");
+        let reloaded = parser.build();
+        assert_eq!(
+            reloaded
+                .declarations()
+                .iter()
+                .map(|declaration| declaration.amendment.clone())
+                .collect::<Vec<_>>(),
+            vec![Some(test_amendment_metadata())]
+        );
+    }
+
+    #[tokio::test]
+    async fn session_scoped_amendment_is_not_written_to_disk() {
+        let codex_home = tempdir().expect("create temp dir");
+        let manager = ExecPolicyManager::default();
+
+        manager
+            .append_amendment_and_update(
+                codex_home.path(),
+                &ExecPolicyAmendment::from(vec!["echo".to_string()]),
+                ExecPolicyAmendmentScope::Session,
+                test_amendment_metadata(),
+            )
+            .await
+            .expect("update policy");
+
+        assert!(!default_policy_path(codex_home.path()).exists());
+        let matches = manager.current().matches_for_command(
+            &["echo".to_string(), "hi".to_string()],
+            &MatchContext::default(),
+            None,
+        );
+        assert_eq!(matches.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn amendments_honor_repo_scope_and_expiry() {
+        let codex_home = tempdir().expect("create temp dir");
+        let manager = ExecPolicyManager::default();
+        let repo_scoped = AmendmentMetadata {
+            repo: Some(PathBuf::from("/repo")),
+            ..test_amendment_metadata()
+        };
+        let expired = AmendmentMetadata {
+            id: "a2".to_string(),
+            expires_at: Some(Utc::now() - chrono::Duration::hours(1)),
+            ..test_amendment_metadata()
+        };
+        manager
+            .append_amendment_and_update(
+                codex_home.path(),
+                &ExecPolicyAmendment::from(vec!["cargo".to_string(), "build".to_string()]),
+                ExecPolicyAmendmentScope::Repo,
+                repo_scoped,
+            )
+            .await
+            .expect("add repo-scoped amendment");
+        manager
+            .append_amendment_and_update(
+                codex_home.path(),
+                &ExecPolicyAmendment::from(vec!["make".to_string()]),
+                ExecPolicyAmendmentScope::Global,
+                expired,
+            )
+            .await
+            .expect("add expired amendment");
+
+        let matches_in = |command: &[&str], cwd: &str| {
+            let command: Vec<String> = command.iter().map(ToString::to_string).collect();
+            let ctx = MatchContext {
+                cwd: Some(PathBuf::from(cwd)),
+                writable_roots: Vec::new(),
+            };
+            !manager
+                .current()
+                .matches_for_command(&command, &ctx, None)
+                .is_empty()
+        };
+        assert!(matches_in(&["cargo", "build"], "/repo/crates/core"));
+        assert!(!matches_in(&["cargo", "build"], "/elsewhere"));
+        assert!(!matches_in(&["make"], "/repo"));
+
+        // Both amendments are persisted with their metadata, so a fresh load honors them too.
+        let contents = fs::read_to_string(default_policy_path(codex_home.path()))
+            .expect("policy file should have been created");
+        let mut parser = PolicyParser::new();
+        parser
+            .parse("default.rules", &contents)
+            .expect("amended policy should parse");
+        let reloaded = parser.build();
+        let ctx = MatchContext {
+            cwd: Some(PathBuf::from("/elsewhere")),
+            writable_roots: Vec::new(),
+        };
+        assert!(
+            reloaded
+                .matches_for_command(&["make".to_string()], &ctx, None)
+                .is_empty()
+        );
+        assert!(
+            reloaded
+                .matches_for_command(&["cargo".to_string(), "build".to_string()], &ctx, None)
+                .is_empty()
+        );
     }
 
     #[tokio::test]
@@ -923,7 +1111,12 @@ prefix_rule(
         let manager = ExecPolicyManager::default();
 
         let result = manager
-            .append_amendment_and_update(codex_home.path(), &ExecPolicyAmendment::from(vec![]))
+            .append_amendment_and_update(
+                codex_home.path(),
+                &ExecPolicyAmendment::from(vec![]),
+                ExecPolicyAmendmentScope::Global,
+                test_amendment_metadata(),
+            )
             .await;
 
         assert!(matches!(
@@ -944,8 +1137,9 @@ prefix_rule(
             .create_exec_approval_requirement_for_command(
                 &Features::with_defaults(),
                 &command,
+                Path::new("/workspace"),
                 AskForApproval::UnlessTrusted,
-                &SandboxPolicy::ReadOnly,
+                &SandboxPolicy::new_read_only_policy(),
                 SandboxPermissions::UseDefault,
             )
             .await;
@@ -971,8 +1165,9 @@ prefix_rule(
             .create_exec_approval_requirement_for_command(
                 &features,
                 &command,
+                Path::new("/workspace"),
                 AskForApproval::UnlessTrusted,
-                &SandboxPolicy::ReadOnly,
+                &SandboxPolicy::new_read_only_policy(),
                 SandboxPermissions::UseDefault,
             )
             .await;
@@ -1001,6 +1196,7 @@ prefix_rule(
             .create_exec_approval_requirement_for_command(
                 &Features::with_defaults(),
                 &command,
+                Path::new("/workspace"),
                 AskForApproval::OnRequest,
                 &SandboxPolicy::DangerFullAccess,
                 SandboxPermissions::UseDefault,
@@ -1028,8 +1224,9 @@ prefix_rule(
             .create_exec_approval_requirement_for_command(
                 &Features::with_defaults(),
                 &command,
+                Path::new("/workspace"),
                 AskForApproval::UnlessTrusted,
-                &SandboxPolicy::ReadOnly,
+                &SandboxPolicy::new_read_only_policy(),
                 SandboxPermissions::UseDefault,
             )
             .await;
@@ -1066,8 +1263,9 @@ prefix_rule(
                 .create_exec_approval_requirement_for_command(
                     &Features::with_defaults(),
                     &command,
+                    Path::new("/workspace"),
                     AskForApproval::UnlessTrusted,
-                    &SandboxPolicy::ReadOnly,
+                    &SandboxPolicy::new_read_only_policy(),
                     SandboxPermissions::UseDefault,
                 )
                 .await,
@@ -1089,8 +1287,9 @@ prefix_rule(
             .create_exec_approval_requirement_for_command(
                 &Features::with_defaults(),
                 &command,
+                Path::new("/workspace"),
                 AskForApproval::OnRequest,
-                &SandboxPolicy::ReadOnly,
+                &SandboxPolicy::new_read_only_policy(),
                 SandboxPermissions::UseDefault,
             )
             .await;
@@ -1119,8 +1318,9 @@ prefix_rule(
             .create_exec_approval_requirement_for_command(
                 &Features::with_defaults(),
                 &command,
+                Path::new("/workspace"),
                 AskForApproval::OnRequest,
-                &SandboxPolicy::ReadOnly,
+                &SandboxPolicy::new_read_only_policy(),
                 SandboxPermissions::UseDefault,
             )
             .await;
@@ -1186,8 +1386,9 @@ prefix_rule(
                 .create_exec_approval_requirement_for_command(
                     &features,
                     &sneaky_command,
+                    Path::new("/workspace"),
                     AskForApproval::OnRequest,
-                    &SandboxPolicy::ReadOnly,
+                    &SandboxPolicy::new_read_only_policy(),
                     permissions,
                 )
                 .await,
@@ -1209,8 +1410,9 @@ prefix_rule(
                 .create_exec_approval_requirement_for_command(
                     &features,
                     &dangerous_command,
+                    Path::new("/workspace"),
                     AskForApproval::OnRequest,
-                    &SandboxPolicy::ReadOnly,
+                    &SandboxPolicy::new_read_only_policy(),
                     permissions,
                 )
                 .await,
@@ -1228,8 +1430,9 @@ prefix_rule(
                 .create_exec_approval_requirement_for_command(
                     &features,
                     &dangerous_command,
+                    Path::new("/workspace"),
                     AskForApproval::Never,
-                    &SandboxPolicy::ReadOnly,
+                    &SandboxPolicy::new_read_only_policy(),
                     permissions,
                 )
                 .await,
@@ -88,6 +88,8 @@ pub enum Feature {
     RemoteModels,
     /// Experimental shell snapshotting.
     ShellSnapshot,
+    /// Carry cwd and exported variables from one shell tool call to the next.
+    PersistentShell,
     /// Append additional AGENTS.md guidance to user instructions.
     HierarchicalAgents,
     /// Experimental TUI v2 (viewport) implementation.
@@ -96,6 +98,12 @@ pub enum Feature {
     PowershellUtf8,
     /// Compress request bodies (zstd) when sending streaming requests to codex-backend.
     EnableRequestCompression,
+    /// Keep already-sent messages byte-identical across turns so providers can
+    /// reuse their prompt cache.
+    CacheStablePrefix,
+    /// Record every model request body and raw SSE stream under
+    /// `$CODEX_HOME/captures` for offline replay.
+    TrafficCapture,
     /// Enable collab tools.
     Collab,
     /// Steer feature flag - when enabled, Enter submits immediately instead of queuing.
@@ -358,6 +366,12 @@ pub const FEATURES: &[FeatureSpec] = &[
         },
         default_enabled: false,
     },
+    FeatureSpec {
+        id: Feature::PersistentShell,
+        key: "persistent_shell",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
     FeatureSpec {
         id: Feature::HierarchicalAgents,
         key: "hierarchical_agents",
@@ -412,6 +426,18 @@ pub const FEATURES: &[FeatureSpec] = &[
         stage: Stage::Experimental,
         default_enabled: false,
     },
+    FeatureSpec {
+        id: Feature::CacheStablePrefix,
+        key: "cache_stable_prefix",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::TrafficCapture,
+        key: "traffic_capture",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
     FeatureSpec {
         id: Feature::Collab,
         key: "collab",
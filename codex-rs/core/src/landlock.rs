@@ -1,7 +1,12 @@
+use crate::config::types::SandboxResourceLimits;
+use crate::exec::ResourceLimitExceeded;
 use crate::protocol::SandboxPolicy;
+use crate::spawn::InheritedFds;
 use crate::spawn::StdioPolicy;
 use crate::spawn::spawn_child_async;
+use crate::workspace_overlay::WorkspaceOverlay;
 use std::collections::HashMap;
+use std::fs;
 use std::path::Path;
 use std::path::PathBuf;
 use tokio::process::Child;
@@ -25,7 +30,14 @@ pub async fn spawn_command_under_linux_sandbox<P>(
 where
     P: AsRef<Path>,
 {
-    let args = create_linux_sandbox_command_args(command, sandbox_policy, sandbox_policy_cwd);
+    let args = create_linux_sandbox_command_args(
+        command,
+        sandbox_policy,
+        sandbox_policy_cwd,
+        None,
+        None,
+        None,
+    );
     let arg0 = Some("codex-linux-sandbox");
     spawn_child_async(
         codex_linux_sandbox_exe.as_ref().to_path_buf(),
@@ -35,15 +47,27 @@ where
         sandbox_policy,
         stdio_policy,
         env,
+        InheritedFds::default(),
     )
     .await
 }
 
 /// Converts the sandbox policy into the CLI invocation for `codex-linux-sandbox`.
+///
+/// When `network_proxy_socket` is set, the helper runs the command in a
+/// network namespace whose only egress is the filtering proxy listening on
+/// that socket. `resource_limits` are enforced through the cgroup returned by
+/// [`command_cgroup_dir`] when cgroup v2 is delegated to the user, and with
+/// `setrlimit` otherwise. With a `workspace_overlay`, the helper mounts the
+/// overlay over the workspace so the command's writes land in its upper
+/// directory.
 pub(crate) fn create_linux_sandbox_command_args(
     command: Vec<String>,
     sandbox_policy: &SandboxPolicy,
     sandbox_policy_cwd: &Path,
+    network_proxy_socket: Option<&Path>,
+    resource_limits: Option<&SandboxResourceLimits>,
+    workspace_overlay: Option<&WorkspaceOverlay>,
 ) -> Vec<String> {
     #[expect(clippy::expect_used)]
     let sandbox_policy_cwd = sandbox_policy_cwd
@@ -60,13 +84,88 @@ pub(crate) fn create_linux_sandbox_command_args(
         sandbox_policy_cwd,
         "--sandbox-policy".to_string(),
         sandbox_policy_json,
-        // Separator so that command arguments starting with `-` are not parsed as
-        // options of the helper itself.
-        "--".to_string(),
     ];
 
+    if let Some(socket) = network_proxy_socket {
+        linux_cmd.push("--network-proxy-socket".to_string());
+        linux_cmd.push(socket.to_string_lossy().to_string());
+    }
+
+    if let Some(resource_limits) = resource_limits {
+        #[expect(clippy::expect_used)]
+        let resource_limits_json = serde_json::to_string(resource_limits)
+            .expect("Failed to serialize SandboxResourceLimits to JSON");
+        linux_cmd.push("--resource-limits".to_string());
+        linux_cmd.push(resource_limits_json);
+    }
+
+    if let Some(overlay) = workspace_overlay {
+        for (flag, dir) in [
+            ("--overlay-lower-dir", overlay.lower_dir()),
+            ("--overlay-upper-dir", overlay.upper_dir()),
+            ("--overlay-work-dir", overlay.work_dir()),
+        ] {
+            linux_cmd.push(flag.to_string());
+            linux_cmd.push(dir.to_string_lossy().to_string());
+        }
+    }
+
+    // Separator so that command arguments starting with `-` are not parsed as
+    // options of the helper itself.
+    linux_cmd.push("--".to_string());
+
     // Append the original tool command.
     linux_cmd.extend(command);
 
     linux_cmd
 }
+
+/// Root of the unified (v2) cgroup hierarchy.
+const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+/// The cgroup the sandbox helper with `helper_pid` moves itself into to
+/// enforce memory and process limits: a child of the cgroup Codex runs in,
+/// which the helper inherits. `None` when the host does not use cgroup v2.
+pub fn command_cgroup_dir(helper_pid: u32) -> Option<PathBuf> {
+    let membership = fs::read_to_string("/proc/self/cgroup").ok()?;
+    let current = membership
+        .lines()
+        .find_map(|line| line.strip_prefix("0::"))?;
+    Some(
+        Path::new(CGROUP_ROOT)
+            .join(current.trim_start_matches('/'))
+            .join(format!("codex-sandbox-{helper_pid}")),
+    )
+}
+
+/// Reads which limit, if any, the command run by `helper_pid` ran into from
+/// its cgroup's `memory.events` and `pids.events`, then kills whatever is
+/// left in the cgroup and removes it.
+pub(crate) fn take_cgroup_limit_exceeded(helper_pid: u32) -> Option<ResourceLimitExceeded> {
+    let dir = command_cgroup_dir(helper_pid)?;
+    if !dir.is_dir() {
+        return None;
+    }
+    let exceeded = if cgroup_event_count(&dir, "memory.events", "oom_kill") > 0 {
+        Some(ResourceLimitExceeded::Memory)
+    } else if cgroup_event_count(&dir, "pids.events", "max") > 0 {
+        Some(ResourceLimitExceeded::Processes)
+    } else {
+        None
+    };
+    let _ = fs::write(dir.join("cgroup.kill"), "1");
+    let _ = fs::remove_dir(&dir);
+    exceeded
+}
+
+fn cgroup_event_count(dir: &Path, file: &str, event: &str) -> u64 {
+    fs::read_to_string(dir.join(file))
+        .ok()
+        .and_then(|events| {
+            events.lines().find_map(|line| {
+                let (name, count) = line.split_once(' ')?;
+                (name == event).then(|| count.trim().parse().ok())?
+            })
+        })
+        .unwrap_or(0)
+}
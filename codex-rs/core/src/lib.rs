@@ -20,6 +20,7 @@ mod codex_delegate;
 mod command_safety;
 pub mod config;
 pub mod config_loader;
+mod container;
 mod context_manager;
 pub mod custom_prompts;
 pub mod env;
@@ -41,8 +42,10 @@ pub use mcp_connection_manager::SandboxState;
 mod mcp_tool_call;
 mod message_history;
 mod model_provider_info;
+mod network_proxy;
 pub mod parse_command;
 pub mod path_utils;
+mod persistent_shell;
 pub mod powershell;
 pub mod sandboxing;
 mod stream_events_utils;
@@ -83,6 +86,7 @@ pub mod project_doc;
 mod rollout;
 pub(crate) mod safety;
 pub mod seatbelt;
+mod secret_redaction;
 pub mod shell;
 pub mod shell_snapshot;
 pub mod skills;
@@ -109,17 +113,22 @@ mod tasks;
 mod user_notification;
 mod user_shell_command;
 pub mod util;
+mod workspace_overlay;
 
 pub use apply_patch::CODEX_APPLY_PATCH_ARG1;
 pub use command_safety::is_dangerous_command;
 pub use command_safety::is_safe_command;
 pub use exec_policy::ExecPolicyError;
 pub use exec_policy::check_execpolicy_for_warnings;
+pub use exec_policy::commands_for_exec_policy;
 pub use exec_policy::load_exec_policy;
+pub use exec_policy::render_decision_for_unmatched_command;
 pub use safety::get_platform_sandbox;
 pub use safety::is_windows_elevated_sandbox_enabled;
 pub use safety::set_windows_elevated_sandbox_enabled;
 pub use safety::set_windows_sandbox_enabled;
+pub use tools::spec::DIRECT_TOOL_NAMES;
+pub use tools::spec::direct_mcp_tools;
 // Re-export the protocol types from the standalone `codex-protocol` crate so existing
 // `codex_core::protocol::...` references continue to work across the workspace.
 pub use codex_protocol::protocol;
@@ -127,6 +136,7 @@ pub use codex_protocol::protocol;
 // as those in the protocol crate when constructing protocol messages.
 pub use codex_protocol::config_types as protocol_config_types;
 
+pub use client::CAPTURES_SUBDIR;
 pub use client::ModelClient;
 pub use client::ModelClientSession;
 pub use client_common::Prompt;
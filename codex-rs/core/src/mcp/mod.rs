@@ -1,11 +1,14 @@
 pub mod auth;
+pub(crate) mod sampling;
 use std::collections::HashMap;
 use std::env;
+use std::path::Path;
 use std::path::PathBuf;
 
 use async_channel::unbounded;
 use codex_protocol::protocol::McpListToolsResponseEvent;
 use codex_protocol::protocol::SandboxPolicy;
+use mcp_types::Root;
 use mcp_types::Tool as McpTool;
 use tokio_util::sync::CancellationToken;
 
@@ -23,6 +26,7 @@ pub async fn collect_mcp_snapshot(config: &Config) -> McpListToolsResponseEvent
             tools: HashMap::new(),
             resources: HashMap::new(),
             resource_templates: HashMap::new(),
+            prompts: HashMap::new(),
             auth_statuses: HashMap::new(),
         };
     }
@@ -40,7 +44,7 @@ pub async fn collect_mcp_snapshot(config: &Config) -> McpListToolsResponseEvent
 
     // Use ReadOnly sandbox policy for MCP snapshot collection (safest default)
     let sandbox_state = SandboxState {
-        sandbox_policy: SandboxPolicy::ReadOnly,
+        sandbox_policy: SandboxPolicy::new_read_only_policy(),
         codex_linux_sandbox_exe: config.codex_linux_sandbox_exe.clone(),
         sandbox_cwd: env::current_dir().unwrap_or_else(|_| PathBuf::from("/")),
     };
@@ -53,6 +57,7 @@ pub async fn collect_mcp_snapshot(config: &Config) -> McpListToolsResponseEvent
             tx_event,
             cancel_token.clone(),
             sandbox_state,
+            None,
         )
         .await;
 
@@ -64,6 +69,31 @@ pub async fn collect_mcp_snapshot(config: &Config) -> McpListToolsResponseEvent
     snapshot
 }
 
+/// Roots advertised to MCP servers: the session `cwd` followed by the extra
+/// writable roots of a workspace-write policy (`--add-dir`).
+pub fn workspace_roots(cwd: &Path, sandbox_policy: &SandboxPolicy) -> Vec<Root> {
+    let mut paths: Vec<&Path> = vec![cwd];
+    if let SandboxPolicy::WorkspaceWrite { writable_roots, .. } = sandbox_policy {
+        for root in writable_roots {
+            if !paths.contains(&root.as_path()) {
+                paths.push(root.as_path());
+            }
+        }
+    }
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let uri = url::Url::from_file_path(path).ok()?;
+            Some(Root {
+                name: path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned()),
+                uri: uri.to_string(),
+            })
+        })
+        .collect()
+}
+
 pub fn split_qualified_tool_name(qualified_name: &str) -> Option<(String, String)> {
     let mut parts = qualified_name.split(MCP_TOOL_NAME_DELIMITER);
     let prefix = parts.next()?;
@@ -97,10 +127,11 @@ pub(crate) async fn collect_mcp_snapshot_from_manager(
     mcp_connection_manager: &McpConnectionManager,
     auth_status_entries: HashMap<String, crate::mcp::auth::McpAuthStatusEntry>,
 ) -> McpListToolsResponseEvent {
-    let (tools, resources, resource_templates) = tokio::join!(
+    let (tools, resources, resource_templates, prompts) = tokio::join!(
         mcp_connection_manager.list_all_tools(),
         mcp_connection_manager.list_all_resources(),
         mcp_connection_manager.list_all_resource_templates(),
+        mcp_connection_manager.list_all_prompts(),
     );
 
     let auth_statuses = auth_status_entries
@@ -115,6 +146,7 @@ pub(crate) async fn collect_mcp_snapshot_from_manager(
             .collect(),
         resources,
         resource_templates,
+        prompts,
         auth_statuses,
     }
 }
@@ -122,6 +154,7 @@ pub(crate) async fn collect_mcp_snapshot_from_manager(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use codex_utils_absolute_path::AbsolutePathBuf;
     use mcp_types::ToolInputSchema;
     use pretty_assertions::assert_eq;
 
@@ -140,6 +173,45 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg(unix)]
+    fn workspace_roots_lists_cwd_then_additional_writable_roots() {
+        let policy = SandboxPolicy::WorkspaceWrite {
+            writable_roots: vec![
+                AbsolutePathBuf::from_absolute_path("/work/app").expect("absolute"),
+                AbsolutePathBuf::from_absolute_path("/work/backend").expect("absolute"),
+            ],
+            readable_roots: None,
+            network_access: false,
+            exclude_tmpdir_env_var: false,
+            exclude_slash_tmp: false,
+        };
+
+        assert_eq!(
+            workspace_roots(Path::new("/work/app"), &policy),
+            vec![
+                Root {
+                    name: Some("app".to_string()),
+                    uri: "file:///work/app".to_string(),
+                },
+                Root {
+                    name: Some("backend".to_string()),
+                    uri: "file:///work/backend".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            workspace_roots(
+                Path::new("/work/app"),
+                &SandboxPolicy::new_read_only_policy()
+            ),
+            vec![Root {
+                name: Some("app".to_string()),
+                uri: "file:///work/app".to_string(),
+            }]
+        );
+    }
+
     #[test]
     fn split_qualified_tool_name_returns_server_and_tool() {
         assert_eq!(
@@ -0,0 +1,245 @@
+//! Answers `sampling/createMessage` requests from MCP servers with a
+//! completion from the session's model.
+//!
+//! The request runs as a standalone prompt: no tools, no conversation
+//! history and none of Codex's own instructions, only the messages and system
+//! prompt the server sent.
+
+use anyhow::Result;
+use anyhow::anyhow;
+use codex_protocol::config_types::SamplingParams;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use futures::StreamExt;
+use mcp_types::CreateMessageRequestParams;
+use mcp_types::CreateMessageResult;
+use mcp_types::CreateMessageResultContent;
+use mcp_types::Role;
+use mcp_types::SamplingMessageContent;
+use mcp_types::TextContent;
+
+use crate::client_common::Prompt;
+use crate::client_common::ResponseEvent;
+use crate::codex::TurnContext;
+
+/// Longest excerpt of the request shown when asking the user for approval.
+const APPROVAL_PREVIEW_CHARS: usize = 500;
+
+/// Runs `request` with `turn_context`'s model client and returns the
+/// assistant's reply.
+pub(crate) async fn create_message(
+    turn_context: &TurnContext,
+    request: CreateMessageRequestParams,
+) -> Result<CreateMessageResult> {
+    let prompt = build_prompt(request, &turn_context.sampling)?;
+    let mut client_session = turn_context.client.new_session();
+    let mut stream = client_session.stream(&prompt).await?;
+    let mut text = String::new();
+    loop {
+        let Some(event) = stream.next().await else {
+            return Err(anyhow!("stream closed before response.completed"));
+        };
+        match event? {
+            ResponseEvent::OutputItemDone(ResponseItem::Message { role, content, .. })
+                if role == "assistant" =>
+            {
+                for item in content {
+                    if let ContentItem::OutputText { text: chunk } = item {
+                        text.push_str(&chunk);
+                    }
+                }
+            }
+            ResponseEvent::Completed { .. } => break,
+            _ => {}
+        }
+    }
+
+    Ok(CreateMessageResult {
+        content: CreateMessageResultContent::TextContent(TextContent {
+            annotations: None,
+            text,
+            r#type: "text".to_string(),
+        }),
+        model: turn_context.client.get_model(),
+        role: Role::Assistant,
+        stop_reason: Some("endTurn".to_string()),
+    })
+}
+
+/// Text shown to the user when `server_name` asks for a completion.
+pub(crate) fn approval_message(server_name: &str, request: &CreateMessageRequestParams) -> String {
+    let mut message = format!(
+        "MCP server `{server_name}` wants to run a model completion (up to {} tokens).",
+        request.max_tokens
+    );
+    let last_text = request
+        .messages
+        .iter()
+        .rev()
+        .find_map(|message| match &message.content {
+            SamplingMessageContent::TextContent(content) => Some(content.text.as_str()),
+            _ => None,
+        });
+    if let Some(text) = last_text {
+        let mut preview: String = text.chars().take(APPROVAL_PREVIEW_CHARS).collect();
+        if preview.len() < text.len() {
+            preview.push('…');
+        }
+        message.push_str("\n\n");
+        message.push_str(&preview);
+    }
+    message
+}
+
+fn build_prompt(request: CreateMessageRequestParams, defaults: &SamplingParams) -> Result<Prompt> {
+    let input = request
+        .messages
+        .into_iter()
+        .map(|message| {
+            let (role, content) = match (message.role, message.content) {
+                (Role::User, SamplingMessageContent::TextContent(content)) => {
+                    ("user", ContentItem::InputText { text: content.text })
+                }
+                (Role::User, SamplingMessageContent::ImageContent(content)) => (
+                    "user",
+                    ContentItem::InputImage {
+                        image_url: format!("data:{};base64,{}", content.mime_type, content.data),
+                    },
+                ),
+                (Role::Assistant, SamplingMessageContent::TextContent(content)) => {
+                    ("assistant", ContentItem::OutputText { text: content.text })
+                }
+                (Role::Assistant, SamplingMessageContent::ImageContent(_)) => {
+                    return Err(anyhow!("images in assistant messages are not supported"));
+                }
+                (_, SamplingMessageContent::AudioContent(_)) => {
+                    return Err(anyhow!("audio content is not supported"));
+                }
+            };
+            Ok(ResponseItem::Message {
+                id: None,
+                role: role.to_string(),
+                content: vec![content],
+                reasoning_content: None,
+                tool_calls: None,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let sampling = SamplingParams {
+        temperature: request.temperature,
+        max_tokens: u64::try_from(request.max_tokens).ok(),
+        stop: request.stop_sequences,
+        ..Default::default()
+    };
+    Ok(Prompt {
+        input,
+        // An empty override keeps Codex's agent instructions out of the request.
+        base_instructions_override: Some(request.system_prompt.unwrap_or_default()),
+        sampling: sampling.or(defaults),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_types::AudioContent;
+    use mcp_types::SamplingMessage;
+    use pretty_assertions::assert_eq;
+
+    fn text(role: Role, text: &str) -> SamplingMessage {
+        SamplingMessage {
+            content: SamplingMessageContent::TextContent(TextContent {
+                annotations: None,
+                text: text.to_string(),
+                r#type: "text".to_string(),
+            }),
+            role,
+        }
+    }
+
+    fn request(messages: Vec<SamplingMessage>) -> CreateMessageRequestParams {
+        CreateMessageRequestParams {
+            include_context: None,
+            max_tokens: 256,
+            messages,
+            metadata: None,
+            model_preferences: None,
+            stop_sequences: None,
+            system_prompt: Some("Summarize tersely.".to_string()),
+            temperature: Some(0.2),
+        }
+    }
+
+    #[test]
+    fn build_prompt_maps_messages_and_overrides_sampling() {
+        let defaults = SamplingParams {
+            temperature: Some(1.0),
+            top_p: Some(0.9),
+            ..Default::default()
+        };
+
+        let prompt = build_prompt(
+            request(vec![
+                text(Role::User, "What changed?"),
+                text(Role::Assistant, "Two files."),
+            ]),
+            &defaults,
+        )
+        .expect("prompt");
+
+        assert_eq!(
+            prompt.input,
+            vec![
+                ResponseItem::Message {
+                    id: None,
+                    role: "user".to_string(),
+                    content: vec![ContentItem::InputText {
+                        text: "What changed?".to_string(),
+                    }],
+                    reasoning_content: None,
+                    tool_calls: None,
+                },
+                ResponseItem::Message {
+                    id: None,
+                    role: "assistant".to_string(),
+                    content: vec![ContentItem::OutputText {
+                        text: "Two files.".to_string(),
+                    }],
+                    reasoning_content: None,
+                    tool_calls: None,
+                },
+            ]
+        );
+        assert_eq!(
+            prompt.base_instructions_override.as_deref(),
+            Some("Summarize tersely.")
+        );
+        assert_eq!(
+            prompt.sampling,
+            SamplingParams {
+                temperature: Some(0.2),
+                top_p: Some(0.9),
+                max_tokens: Some(256),
+                ..Default::default()
+            }
+        );
+        assert!(prompt.tools.is_empty());
+    }
+
+    #[test]
+    fn build_prompt_rejects_audio() {
+        let audio = SamplingMessage {
+            content: SamplingMessageContent::AudioContent(AudioContent {
+                annotations: None,
+                data: "AAAA".to_string(),
+                mime_type: "audio/wav".to_string(),
+                r#type: "audio".to_string(),
+            }),
+            role: Role::User,
+        };
+
+        assert!(build_prompt(request(vec![audio]), &SamplingParams::default()).is_err());
+    }
+}
@@ -12,6 +12,8 @@ use std::env;
 use std::ffi::OsString;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::PoisonError;
+use std::sync::RwLock;
 use std::time::Duration;
 
 use crate::mcp::auth::McpAuthStatusEntry;
@@ -29,24 +31,36 @@ use codex_protocol::protocol::McpStartupFailure;
 use codex_protocol::protocol::McpStartupStatus;
 use codex_protocol::protocol::McpStartupUpdateEvent;
 use codex_protocol::protocol::SandboxPolicy;
+use codex_rmcp_client::ElicitationAction;
 use codex_rmcp_client::ElicitationResponse;
+use codex_rmcp_client::ListRoots;
 use codex_rmcp_client::OAuthCredentialsStoreMode;
 use codex_rmcp_client::RmcpClient;
 use codex_rmcp_client::SendElicitation;
+use codex_rmcp_client::SendSampling;
 use futures::future::BoxFuture;
 use futures::future::FutureExt;
 use futures::future::Shared;
 use mcp_types::ClientCapabilities;
+use mcp_types::ClientCapabilitiesRoots;
+use mcp_types::CreateMessageRequestParams;
+use mcp_types::CreateMessageResult;
+use mcp_types::GetPromptRequestParams;
+use mcp_types::GetPromptResult;
 use mcp_types::Implementation;
+use mcp_types::ListPromptsRequestParams;
 use mcp_types::ListResourceTemplatesRequestParams;
 use mcp_types::ListResourceTemplatesResult;
 use mcp_types::ListResourcesRequestParams;
 use mcp_types::ListResourcesResult;
+use mcp_types::ListRootsResult;
+use mcp_types::Prompt;
 use mcp_types::ReadResourceRequestParams;
 use mcp_types::ReadResourceResult;
 use mcp_types::RequestId;
 use mcp_types::Resource;
 use mcp_types::ResourceTemplate;
+use mcp_types::Root;
 use mcp_types::Tool;
 
 use serde::Deserialize;
@@ -62,8 +76,11 @@ use tracing::instrument;
 use tracing::warn;
 
 use crate::codex::INITIAL_SUBMIT_ID;
+use crate::config::types::McpSamplingPolicy;
 use crate::config::types::McpServerConfig;
 use crate::config::types::McpServerTransportConfig;
+use crate::mcp::sampling;
+use crate::mcp::workspace_roots;
 
 /// Delimiter used to separate the server name from the tool name in a fully
 /// qualified tool name.
@@ -157,6 +174,17 @@ pub(crate) struct ToolInfo {
 
 type ResponderMap = HashMap<(String, RequestId), oneshot::Sender<ElicitationResponse>>;
 
+/// Roots returned from `roots/list`, shared by every server of a manager.
+type SharedRoots = Arc<RwLock<Vec<Root>>>;
+
+/// Runs an approved `sampling/createMessage` request against the session's
+/// model.
+pub(crate) type RunSampling = Arc<
+    dyn Fn(CreateMessageRequestParams) -> BoxFuture<'static, Result<CreateMessageResult>>
+        + Send
+        + Sync,
+>;
+
 #[derive(Clone, Default)]
 struct ElicitationRequestManager {
     requests: Arc<Mutex<ResponderMap>>,
@@ -178,30 +206,74 @@ impl ElicitationRequestManager {
             .map_err(|e| anyhow!("failed to send elicitation response: {e:?}"))
     }
 
+    /// Shows `message` to the user and waits for their decision.
+    async fn request(
+        &self,
+        server_name: String,
+        id: RequestId,
+        message: String,
+        tx_event: &Sender<Event>,
+    ) -> Result<ElicitationResponse> {
+        let (tx, rx) = oneshot::channel();
+        {
+            let mut lock = self.requests.lock().await;
+            lock.insert((server_name.clone(), id.clone()), tx);
+        }
+        let _ = tx_event
+            .send(Event {
+                id: "mcp_elicitation_request".to_string(),
+                msg: EventMsg::ElicitationRequest(ElicitationRequestEvent {
+                    server_name,
+                    id,
+                    message,
+                }),
+            })
+            .await;
+        rx.await
+            .context("elicitation request channel closed unexpectedly")
+    }
+
     fn make_sender(&self, server_name: String, tx_event: Sender<Event>) -> SendElicitation {
-        let elicitation_requests = self.requests.clone();
+        let elicitation_requests = self.clone();
         Box::new(move |id, elicitation| {
             let elicitation_requests = elicitation_requests.clone();
             let tx_event = tx_event.clone();
             let server_name = server_name.clone();
             async move {
-                let (tx, rx) = oneshot::channel();
-                {
-                    let mut lock = elicitation_requests.lock().await;
-                    lock.insert((server_name.clone(), id.clone()), tx);
+                elicitation_requests
+                    .request(server_name, id, elicitation.message, &tx_event)
+                    .await
+            }
+            .boxed()
+        })
+    }
+
+    /// Sampling requests reuse the elicitation prompt for approval: the user
+    /// accepts or declines, and only accepted requests reach the model.
+    fn make_sampling_sender(
+        &self,
+        server_name: String,
+        tx_event: Sender<Event>,
+        policy: McpSamplingPolicy,
+        run_sampling: RunSampling,
+    ) -> SendSampling {
+        let elicitation_requests = self.clone();
+        Box::new(move |id, request| {
+            let elicitation_requests = elicitation_requests.clone();
+            let tx_event = tx_event.clone();
+            let server_name = server_name.clone();
+            let run_sampling = Arc::clone(&run_sampling);
+            async move {
+                if policy == McpSamplingPolicy::Prompt {
+                    let message = sampling::approval_message(&server_name, &request);
+                    let response = elicitation_requests
+                        .request(server_name, id, message, &tx_event)
+                        .await?;
+                    if !matches!(response.action, ElicitationAction::Accept) {
+                        return Err(anyhow!("user declined the sampling request"));
+                    }
                 }
-                let _ = tx_event
-                    .send(Event {
-                        id: "mcp_elicitation_request".to_string(),
-                        msg: EventMsg::ElicitationRequest(ElicitationRequestEvent {
-                            server_name,
-                            id,
-                            message: elicitation.message,
-                        }),
-                    })
-                    .await;
-                rx.await
-                    .context("elicitation request channel closed unexpectedly")
+                run_sampling(request).await
             }
             .boxed()
         })
@@ -215,6 +287,7 @@ struct ManagedClient {
     tool_filter: ToolFilter,
     tool_timeout: Option<Duration>,
     server_supports_sandbox_state_capability: bool,
+    server_supports_prompts: bool,
 }
 
 impl ManagedClient {
@@ -241,6 +314,7 @@ struct AsyncManagedClient {
 }
 
 impl AsyncManagedClient {
+    #[allow(clippy::too_many_arguments)]
     fn new(
         server_name: String,
         config: McpServerConfig,
@@ -248,8 +322,12 @@ impl AsyncManagedClient {
         cancel_token: CancellationToken,
         tx_event: Sender<Event>,
         elicitation_requests: ElicitationRequestManager,
+        run_sampling: Option<RunSampling>,
+        roots: SharedRoots,
     ) -> Self {
         let tool_filter = ToolFilter::from_config(&config);
+        let sampling_policy = config.sampling.unwrap_or_default();
+        let run_sampling = run_sampling.filter(|_| sampling_policy != McpSamplingPolicy::Deny);
         let fut = async move {
             if let Err(error) = validate_mcp_server_name(&server_name) {
                 return Err(error.into());
@@ -265,6 +343,8 @@ impl AsyncManagedClient {
                 tool_filter,
                 tx_event,
                 elicitation_requests,
+                run_sampling.map(|run_sampling| (sampling_policy, run_sampling)),
+                roots,
             )
             .or_cancel(&cancel_token)
             .await
@@ -282,6 +362,11 @@ impl AsyncManagedClient {
         self.client.clone().await
     }
 
+    /// The client, if it already started successfully.
+    fn ready_client(&self) -> Option<ManagedClient> {
+        self.client.peek()?.as_ref().ok().cloned()
+    }
+
     async fn notify_sandbox_state_change(&self, sandbox_state: &SandboxState) -> Result<()> {
         let managed = self.client().await?;
         managed.notify_sandbox_state_change(sandbox_state).await
@@ -307,9 +392,11 @@ pub struct SandboxState {
 pub(crate) struct McpConnectionManager {
     clients: HashMap<String, AsyncManagedClient>,
     elicitation_requests: ElicitationRequestManager,
+    roots: SharedRoots,
 }
 
 impl McpConnectionManager {
+    #[allow(clippy::too_many_arguments)]
     pub async fn initialize(
         &mut self,
         mcp_servers: &HashMap<String, McpServerConfig>,
@@ -318,6 +405,7 @@ impl McpConnectionManager {
         tx_event: Sender<Event>,
         cancel_token: CancellationToken,
         initial_sandbox_state: SandboxState,
+        run_sampling: Option<RunSampling>,
     ) {
         if cancel_token.is_cancelled() {
             return;
@@ -325,6 +413,10 @@ impl McpConnectionManager {
         let mut clients = HashMap::new();
         let mut join_set = JoinSet::new();
         let elicitation_requests = ElicitationRequestManager::default();
+        let roots: SharedRoots = Arc::new(RwLock::new(workspace_roots(
+            &initial_sandbox_state.sandbox_cwd,
+            &initial_sandbox_state.sandbox_policy,
+        )));
         let mcp_servers = mcp_servers.clone();
         for (server_name, cfg) in mcp_servers.into_iter().filter(|(_, cfg)| cfg.enabled) {
             let cancel_token = cancel_token.child_token();
@@ -343,6 +435,8 @@ impl McpConnectionManager {
                 cancel_token.clone(),
                 tx_event.clone(),
                 elicitation_requests.clone(),
+                run_sampling.clone(),
+                Arc::clone(&roots),
             );
             clients.insert(server_name.clone(), async_managed_client.clone());
             let tx_event = tx_event.clone();
@@ -390,6 +484,7 @@ impl McpConnectionManager {
         }
         self.clients = clients;
         self.elicitation_requests = elicitation_requests.clone();
+        self.roots = roots;
         tokio::spawn(async move {
             let outcomes = join_set.join_all().await;
             let mut summary = McpStartupCompleteEvent::default();
@@ -586,6 +681,95 @@ impl McpConnectionManager {
         aggregated
     }
 
+    /// Returns a single map that contains all prompts. Each key is the server
+    /// name and the value is a vector of prompts. Servers that do not
+    /// advertise the prompts capability are skipped.
+    pub async fn list_all_prompts(&self) -> HashMap<String, Vec<Prompt>> {
+        let mut clients = Vec::new();
+        for (server_name, async_managed_client) in &self.clients {
+            if let Ok(managed_client) = async_managed_client.client().await {
+                clients.push((server_name.clone(), managed_client));
+            }
+        }
+        Self::list_prompts_of(clients).await
+    }
+
+    /// Like [`Self::list_all_prompts`], but skips servers that are still
+    /// starting instead of waiting for them.
+    pub async fn list_ready_prompts(&self) -> HashMap<String, Vec<Prompt>> {
+        let clients = self
+            .clients
+            .iter()
+            .filter_map(|(server_name, async_managed_client)| {
+                let managed_client = async_managed_client.ready_client()?;
+                Some((server_name.clone(), managed_client))
+            })
+            .collect();
+        Self::list_prompts_of(clients).await
+    }
+
+    async fn list_prompts_of(
+        clients: Vec<(String, ManagedClient)>,
+    ) -> HashMap<String, Vec<Prompt>> {
+        let mut join_set = JoinSet::new();
+
+        for (server_name, managed_client) in clients {
+            if !managed_client.server_supports_prompts {
+                continue;
+            }
+            let timeout = managed_client.tool_timeout;
+            let client = managed_client.client.clone();
+
+            join_set.spawn(async move {
+                let mut collected: Vec<Prompt> = Vec::new();
+                let mut cursor: Option<String> = None;
+
+                loop {
+                    let params = cursor.as_ref().map(|next| ListPromptsRequestParams {
+                        cursor: Some(next.clone()),
+                    });
+                    let response = match client.list_prompts(params, timeout).await {
+                        Ok(result) => result,
+                        Err(err) => return (server_name, Err(err)),
+                    };
+
+                    collected.extend(response.prompts);
+
+                    match response.next_cursor {
+                        Some(next) => {
+                            if cursor.as_ref() == Some(&next) {
+                                return (
+                                    server_name,
+                                    Err(anyhow!("prompts/list returned duplicate cursor")),
+                                );
+                            }
+                            cursor = Some(next);
+                        }
+                        None => return (server_name, Ok(collected)),
+                    }
+                }
+            });
+        }
+
+        let mut aggregated: HashMap<String, Vec<Prompt>> = HashMap::new();
+
+        while let Some(join_res) = join_set.join_next().await {
+            match join_res {
+                Ok((server_name, Ok(prompts))) => {
+                    aggregated.insert(server_name, prompts);
+                }
+                Ok((server_name, Err(err))) => {
+                    warn!("Failed to list prompts for MCP server '{server_name}': {err:#}");
+                }
+                Err(err) => {
+                    warn!("Task panic when listing prompts for MCP server: {err:#}");
+                }
+            }
+        }
+
+        aggregated
+    }
+
     /// Invoke the tool indicated by the (server, tool) pair.
     pub async fn call_tool(
         &self,
@@ -656,6 +840,23 @@ impl McpConnectionManager {
             .with_context(|| format!("resources/read failed for `{server}` ({uri})"))
     }
 
+    /// Fetch a prompt from the specified server.
+    pub async fn get_prompt(
+        &self,
+        server: &str,
+        params: GetPromptRequestParams,
+    ) -> Result<GetPromptResult> {
+        let managed = self.client_by_name(server).await?;
+        let client = managed.client.clone();
+        let timeout = managed.tool_timeout;
+        let name = params.name.clone();
+
+        client
+            .get_prompt(params, timeout)
+            .await
+            .with_context(|| format!("prompts/get failed for `{server}` ({name})"))
+    }
+
     pub async fn parse_tool_name(&self, tool_name: &str) -> Option<(String, String)> {
         self.list_all_tools()
             .await
@@ -690,6 +891,39 @@ impl McpConnectionManager {
 
         Ok(())
     }
+
+    /// Updates the roots returned from `roots/list` and, when they changed,
+    /// sends `notifications/roots/list_changed` to every server.
+    pub async fn update_roots(&self, roots: Vec<Root>) {
+        {
+            let mut current = self.roots.write().unwrap_or_else(PoisonError::into_inner);
+            if *current == roots {
+                return;
+            }
+            *current = roots;
+        }
+
+        let mut join_set = JoinSet::new();
+        for async_managed_client in self.clients.values() {
+            let async_managed_client = async_managed_client.clone();
+            join_set.spawn(async move {
+                let managed = async_managed_client.client().await?;
+                managed.client.notify_roots_list_changed().await
+            });
+        }
+
+        while let Some(join_res) = join_set.join_next().await {
+            match join_res {
+                Ok(Ok(())) => {}
+                Ok(Err(err)) => {
+                    warn!("Failed to notify roots change to MCP server: {err:#}");
+                }
+                Err(err) => {
+                    warn!("Task panic when notifying roots change to MCP server: {err:#}");
+                }
+            }
+        }
+    }
 }
 
 async fn emit_update(
@@ -791,6 +1025,7 @@ impl From<anyhow::Error> for StartupOutcomeError {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn start_server_task(
     server_name: String,
     client: Arc<RmcpClient>,
@@ -799,12 +1034,18 @@ async fn start_server_task(
     tool_filter: ToolFilter,
     tx_event: Sender<Event>,
     elicitation_requests: ElicitationRequestManager,
+    sampling: Option<(McpSamplingPolicy, RunSampling)>,
+    roots: SharedRoots,
 ) -> Result<ManagedClient, StartupOutcomeError> {
     let params = mcp_types::InitializeRequestParams {
         capabilities: ClientCapabilities {
             experimental: None,
-            roots: None,
-            sampling: None,
+            // https://modelcontextprotocol.io/specification/2025-06-18/client/roots#capabilities
+            roots: Some(ClientCapabilitiesRoots {
+                list_changed: Some(true),
+            }),
+            // https://modelcontextprotocol.io/specification/2025-06-18/client/sampling#capabilities
+            sampling: sampling.is_some().then(|| json!({})),
             // https://modelcontextprotocol.io/specification/2025-06-18/client/elicitation#capabilities
             // indicates this should be an empty object.
             elicitation: Some(json!({})),
@@ -821,10 +1062,27 @@ async fn start_server_task(
         protocol_version: mcp_types::MCP_SCHEMA_VERSION.to_owned(),
     };
 
+    let send_sampling = sampling.map(|(policy, run_sampling)| {
+        elicitation_requests.make_sampling_sender(
+            server_name.clone(),
+            tx_event.clone(),
+            policy,
+            run_sampling,
+        )
+    });
     let send_elicitation = elicitation_requests.make_sender(server_name.clone(), tx_event);
+    let list_roots: ListRoots = Box::new(move || ListRootsResult {
+        roots: roots.read().unwrap_or_else(PoisonError::into_inner).clone(),
+    });
 
     let initialize_result = client
-        .initialize(params, startup_timeout, send_elicitation)
+        .initialize(
+            params,
+            startup_timeout,
+            send_elicitation,
+            send_sampling,
+            Some(list_roots),
+        )
         .await
         .map_err(StartupOutcomeError::from)?;
 
@@ -838,6 +1096,7 @@ async fn start_server_task(
         .as_ref()
         .and_then(|exp| exp.get(MCP_SANDBOX_STATE_CAPABILITY))
         .is_some();
+    let server_supports_prompts = initialize_result.capabilities.prompts.is_some();
 
     let managed = ManagedClient {
         client: Arc::clone(&client),
@@ -845,6 +1104,7 @@ async fn start_server_task(
         tool_timeout: Some(tool_timeout),
         tool_filter,
         server_supports_sandbox_state_capability,
+        server_supports_prompts,
     };
 
     Ok(managed)
@@ -1175,6 +1435,7 @@ mod tests {
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sampling: None,
             },
             auth_status: McpAuthStatus::Unsupported,
         };
@@ -1219,6 +1480,7 @@ mod tests {
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sampling: None,
             },
             auth_status: McpAuthStatus::Unsupported,
         };
@@ -5,10 +5,14 @@
 //!   2. User-defined entries inside `~/.codex/config.toml` under the `model_providers`
 //!      key. These override or extend the defaults at runtime.
 
+use codex_api::ChatDialect as ApiChatDialect;
+use codex_api::ChatResponseFormat as ApiChatResponseFormat;
 use codex_api::Provider as ApiProvider;
 use codex_api::WireApi as ApiWireApi;
 use codex_api::provider::RetryConfig as ApiRetryConfig;
 use codex_app_server_protocol::AuthMode;
+use codex_protocol::config_types::SamplingParams;
+use codex_protocol::openai_models::ModelInfo;
 use http::HeaderMap;
 use http::header::HeaderName;
 use http::header::HeaderValue;
@@ -21,6 +25,7 @@ use std::time::Duration;
 
 use crate::error::EnvVarError;
 const DEFAULT_STREAM_IDLE_TIMEOUT_MS: u64 = 300_000;
+const DEFAULT_REQUEST_CONNECT_TIMEOUT_MS: u64 = 30_000;
 const DEFAULT_STREAM_MAX_RETRIES: u64 = 5;
 const DEFAULT_REQUEST_MAX_RETRIES: u64 = 4;
 /// Hard cap for user-configured `stream_max_retries`.
@@ -30,6 +35,8 @@ const MAX_REQUEST_MAX_RETRIES: u64 = 100;
 pub const CHAT_WIRE_API_DEPRECATION_SUMMARY: &str = r#"Support for the "chat" wire API is deprecated and will soon be removed. Update your model provider definition in config.toml to use wire_api = "responses"."#;
 
 const OPENAI_PROVIDER_NAME: &str = "OpenAI";
+const DEEPSEEK_PROVIDER_NAME: &str = "DeepSeek";
+const DEEPSEEK_DEFAULT_BASE_URL: &str = "https://api.deepseek.com";
 
 /// Wire protocol that the provider speaks. Most third-party services only
 /// implement the classic OpenAI Chat Completions JSON schema, whereas OpenAI
@@ -99,12 +106,102 @@ pub struct ModelProviderInfo {
     /// the connection as lost.
     pub stream_idle_timeout_ms: Option<u64>,
 
+    /// Timeout (in milliseconds) for establishing the connection to the provider. Attempts that
+    /// time out are retried according to `request_max_retries`.
+    pub request_connect_timeout_ms: Option<u64>,
+
+    /// Timeout (in milliseconds) to wait for the first byte of a streaming response. Unset by
+    /// default, since reasoning models can take minutes to start answering on long contexts;
+    /// attempts that time out are retried according to `request_max_retries`.
+    pub first_byte_timeout_ms: Option<u64>,
+
     /// Does this provider require an OpenAI API Key or ChatGPT login token? If true,
     /// user is presented with login screen on first run, and login preference and token/key
     /// are stored in auth.json. If false (which is the default), login screen is skipped,
     /// and API key (if needed) comes from the "env_key" environment variable.
     #[serde(default)]
     pub requires_openai_auth: bool,
+
+    /// Wire quirks of a Chat Completions endpoint (`wire_api = "chat"`). Unset
+    /// fields keep the built-in behavior for the provider.
+    pub chat_dialect: Option<ChatDialect>,
+
+    /// Default sampling parameters for requests to this provider. Profile and
+    /// per-turn values take precedence field by field.
+    pub sampling: Option<SamplingParams>,
+}
+
+/// Declarative description of how a Chat Completions endpoint deviates from
+/// the OpenAI reference implementation, so OpenAI-compatible servers (Qwen,
+/// Kimi, GLM, vLLM, ...) can be added from config.toml alone.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ChatDialect {
+    /// Built-in quirk set the other fields are applied on top of. Defaults to
+    /// `openai`.
+    pub base: Option<ChatDialectBase>,
+
+    /// Whether `developer` messages are accepted. When false they are sent
+    /// with the `user` role.
+    pub supports_developer_role: Option<bool>,
+
+    /// Whether replayed assistant messages must carry `reasoning_content`.
+    /// Defaults to the model's metadata.
+    pub echo_reasoning_content: Option<bool>,
+
+    /// Whether tool outputs must be sent as plain strings instead of content
+    /// parts.
+    pub tool_content_as_string: Option<bool>,
+
+    /// Whether to reject histories where a tool call has no matching tool
+    /// response before the next message.
+    pub validate_tool_calls: Option<bool>,
+
+    /// How `--output-schema` is encoded in `response_format`.
+    pub response_format: Option<ChatResponseFormat>,
+
+    /// Special tokens stripped from streamed text and reasoning.
+    pub strip_tokens: Option<Vec<String>>,
+}
+
+/// Built-in Chat Completions quirk sets.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatDialectBase {
+    /// The OpenAI reference behavior.
+    #[default]
+    Openai,
+    /// DeepSeek: `json_object` structured output, string tool results and
+    /// thinking markers stripped from streamed text.
+    Deepseek,
+}
+
+impl ChatDialectBase {
+    fn dialect(self) -> ApiChatDialect {
+        match self {
+            ChatDialectBase::Openai => ApiChatDialect::default(),
+            ChatDialectBase::Deepseek => deepseek_chat_dialect(),
+        }
+    }
+}
+
+/// Structured output modes understood by Chat Completions endpoints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatResponseFormat {
+    /// `{"type": "json_schema"}` with server-side validation.
+    JsonSchema,
+    /// `{"type": "json_object"}`; the schema is added to the instructions.
+    JsonObject,
+}
+
+impl From<ChatResponseFormat> for ApiChatResponseFormat {
+    fn from(value: ChatResponseFormat) -> Self {
+        match value {
+            ChatResponseFormat::JsonSchema => ApiChatResponseFormat::JsonSchema,
+            ChatResponseFormat::JsonObject => ApiChatResponseFormat::JsonObject,
+        }
+    }
 }
 
 impl ModelProviderInfo {
@@ -168,6 +265,7 @@ impl ModelProviderInfo {
             headers,
             retry,
             stream_idle_timeout: self.stream_idle_timeout(),
+            first_byte_timeout: self.first_byte_timeout(),
         })
     }
 
@@ -217,6 +315,20 @@ impl ModelProviderInfo {
             .map(Duration::from_millis)
             .unwrap_or(Duration::from_millis(DEFAULT_STREAM_IDLE_TIMEOUT_MS))
     }
+
+    /// Effective timeout for establishing a connection to this provider.
+    pub fn request_connect_timeout(&self) -> Duration {
+        self.request_connect_timeout_ms
+            .map(Duration::from_millis)
+            .unwrap_or(Duration::from_millis(DEFAULT_REQUEST_CONNECT_TIMEOUT_MS))
+    }
+
+    /// Timeout for the first byte of a streaming response, if the provider
+    /// opts in with `first_byte_timeout_ms`.
+    pub fn first_byte_timeout(&self) -> Option<Duration> {
+        self.first_byte_timeout_ms.map(Duration::from_millis)
+    }
+
     pub fn create_openai_provider() -> ModelProviderInfo {
         ModelProviderInfo {
             name: OPENAI_PROVIDER_NAME.into(),
@@ -253,18 +365,133 @@ impl ModelProviderInfo {
             request_max_retries: None,
             stream_max_retries: None,
             stream_idle_timeout_ms: None,
+            request_connect_timeout_ms: None,
+            first_byte_timeout_ms: None,
             requires_openai_auth: true,
+            chat_dialect: None,
+            sampling: None,
+        }
+    }
+
+    pub fn create_deepseek_provider() -> ModelProviderInfo {
+        ModelProviderInfo {
+            name: DEEPSEEK_PROVIDER_NAME.into(),
+            base_url: Some(
+                std::env::var("DEEPSEEK_BASE_URL")
+                    .ok()
+                    .filter(|v| !v.trim().is_empty())
+                    .unwrap_or_else(|| DEEPSEEK_DEFAULT_BASE_URL.to_string()),
+            ),
+            env_key: Some("DEEPSEEK_API_KEY".into()),
+            env_key_instructions: Some(
+                "Create an API key at https://platform.deepseek.com/api_keys and export it as DEEPSEEK_API_KEY.".into(),
+            ),
+            experimental_bearer_token: None,
+            wire_api: WireApi::Chat,
+            query_params: None,
+            http_headers: None,
+            env_http_headers: None,
+            request_max_retries: None,
+            stream_max_retries: None,
+            stream_idle_timeout_ms: None,
+            request_connect_timeout_ms: None,
+            first_byte_timeout_ms: None,
+            requires_openai_auth: false,
+            chat_dialect: Some(ChatDialect {
+                base: Some(ChatDialectBase::Deepseek),
+                ..Default::default()
+            }),
+            sampling: None,
         }
     }
 
     pub fn is_openai(&self) -> bool {
         self.name == OPENAI_PROVIDER_NAME
     }
+
+    /// Whether this provider serves DeepSeek models: the built-in entry, or a
+    /// user-defined provider that sets `chat_dialect.base = "deepseek"`.
+    pub fn is_deepseek(&self) -> bool {
+        self.chat_dialect_base() == ChatDialectBase::Deepseek
+    }
+
+    /// Whether the server itself enforces `--output-schema`. Chat providers in
+    /// `json_object` mode only receive the schema as instructions, so clients
+    /// must validate the final message themselves.
+    pub fn enforces_output_schema(&self) -> bool {
+        match self.wire_api {
+            WireApi::Chat => self.chat_response_format() == ApiChatResponseFormat::JsonSchema,
+            WireApi::Responses | WireApi::ResponsesWebsocket => true,
+        }
+    }
+
+    fn chat_dialect_base(&self) -> ChatDialectBase {
+        self.chat_dialect
+            .as_ref()
+            .and_then(|dialect| dialect.base)
+            .unwrap_or_default()
+    }
+
+    fn chat_response_format(&self) -> ApiChatResponseFormat {
+        match self
+            .chat_dialect
+            .as_ref()
+            .and_then(|dialect| dialect.response_format)
+        {
+            Some(format) => format.into(),
+            None => self.chat_dialect_base().dialect().response_format,
+        }
+    }
+
+    /// Resolves the Chat Completions dialect for `model_info`: the
+    /// `chat_dialect.base` quirk set, then the model metadata, then the other
+    /// `chat_dialect` overrides.
+    pub(crate) fn chat_dialect(&self, model_info: &ModelInfo) -> ApiChatDialect {
+        let mut dialect = self.chat_dialect_base().dialect();
+        dialect.echo_reasoning_content = model_info.requires_reasoning_content;
+        dialect.response_format = self.chat_response_format();
+
+        let Some(overrides) = &self.chat_dialect else {
+            return dialect;
+        };
+        if let Some(developer_role) = overrides.supports_developer_role {
+            dialect.developer_role = developer_role;
+        }
+        if let Some(echo) = overrides.echo_reasoning_content {
+            dialect.echo_reasoning_content = echo;
+        }
+        if let Some(as_string) = overrides.tool_content_as_string {
+            dialect.tool_content_as_string = as_string;
+        }
+        if let Some(validate) = overrides.validate_tool_calls {
+            dialect.validate_tool_calls = Some(validate);
+        }
+        if let Some(tokens) = &overrides.strip_tokens {
+            dialect.strip_tokens = tokens.clone();
+        }
+        dialect
+    }
+}
+
+/// DeepSeek only accepts `json_object` structured output and string tool
+/// results, and leaks its thinking markers into streamed text.
+fn deepseek_chat_dialect() -> ApiChatDialect {
+    ApiChatDialect {
+        tool_content_as_string: true,
+        validate_tool_calls: Some(false),
+        response_format: ApiChatResponseFormat::JsonObject,
+        strip_tokens: vec![
+            "<｜begin▁of▁thinking｜>".to_string(),
+            "<｜end▁of▁thinking｜>".to_string(),
+        ],
+        ..ApiChatDialect::default()
+    }
 }
 
 pub const DEFAULT_LMSTUDIO_PORT: u16 = 1234;
 pub const DEFAULT_OLLAMA_PORT: u16 = 11434;
 
+pub const DEEPSEEK_PROVIDER_ID: &str = "deepseek";
 pub const LMSTUDIO_OSS_PROVIDER_ID: &str = "lmstudio";
 pub const OLLAMA_OSS_PROVIDER_ID: &str = "ollama";
 pub const OLLAMA_CHAT_PROVIDER_ID: &str = "ollama-chat";
@@ -274,11 +501,13 @@ pub fn built_in_model_providers() -> HashMap<String, ModelProviderInfo> {
     use ModelProviderInfo as P;
 
     // We do not want to be in the business of adjucating which third-party
-    // providers are bundled with Codex CLI, so we only include the OpenAI and
-    // open source ("oss") providers by default. Users are encouraged to add to
-    // `model_providers` in config.toml to add their own providers.
+    // providers are bundled with Codex CLI, so we only include the OpenAI,
+    // DeepSeek and open source ("oss") providers by default. Users are
+    // encouraged to add to `model_providers` in config.toml to add their own
+    // providers.
     [
         ("openai", P::create_openai_provider()),
+        (DEEPSEEK_PROVIDER_ID, P::create_deepseek_provider()),
         (
             OLLAMA_OSS_PROVIDER_ID,
             create_oss_provider(DEFAULT_OLLAMA_PORT, WireApi::Responses),
@@ -331,13 +560,18 @@ pub fn create_oss_provider_with_base_url(base_url: &str, wire_api: WireApi) -> M
         request_max_retries: None,
         stream_max_retries: None,
         stream_idle_timeout_ms: None,
+        request_connect_timeout_ms: None,
+        first_byte_timeout_ms: None,
         requires_openai_auth: false,
+        chat_dialect: None,
+        sampling: None,
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models_manager::model_info::find_model_info_for_slug;
     use pretty_assertions::assert_eq;
 
     #[test]
@@ -359,7 +593,11 @@ base_url = "http://localhost:11434/v1"
             request_max_retries: None,
             stream_max_retries: None,
             stream_idle_timeout_ms: None,
+            request_connect_timeout_ms: None,
+            first_byte_timeout_ms: None,
             requires_openai_auth: false,
+            chat_dialect: None,
+            sampling: None,
         };
 
         let provider: ModelProviderInfo = toml::from_str(azure_provider_toml).unwrap();
@@ -389,7 +627,11 @@ query_params = { api-version = "2025-04-01-preview" }
             request_max_retries: None,
             stream_max_retries: None,
             stream_idle_timeout_ms: None,
+            request_connect_timeout_ms: None,
+            first_byte_timeout_ms: None,
             requires_openai_auth: false,
+            chat_dialect: None,
+            sampling: None,
         };
 
         let provider: ModelProviderInfo = toml::from_str(azure_provider_toml).unwrap();
@@ -422,7 +664,11 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
             request_max_retries: None,
             stream_max_retries: None,
             stream_idle_timeout_ms: None,
+            request_connect_timeout_ms: None,
+            first_byte_timeout_ms: None,
             requires_openai_auth: false,
+            chat_dialect: None,
+            sampling: None,
         };
 
         let provider: ModelProviderInfo = toml::from_str(azure_provider_toml).unwrap();
@@ -453,7 +699,11 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
                 request_max_retries: None,
                 stream_max_retries: None,
                 stream_idle_timeout_ms: None,
+                request_connect_timeout_ms: None,
+                first_byte_timeout_ms: None,
                 requires_openai_auth: false,
+                chat_dialect: None,
+                sampling: None,
             };
             let api = provider.to_api_provider(None).expect("api provider");
             assert!(
@@ -475,7 +725,11 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
             request_max_retries: None,
             stream_max_retries: None,
             stream_idle_timeout_ms: None,
+            request_connect_timeout_ms: None,
+            first_byte_timeout_ms: None,
             requires_openai_auth: false,
+            chat_dialect: None,
+            sampling: None,
         };
         let named_api = named_provider.to_api_provider(None).expect("api provider");
         assert!(named_api.is_azure_responses_endpoint());
@@ -499,7 +753,11 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
                 request_max_retries: None,
                 stream_max_retries: None,
                 stream_idle_timeout_ms: None,
+                request_connect_timeout_ms: None,
+                first_byte_timeout_ms: None,
                 requires_openai_auth: false,
+                chat_dialect: None,
+                sampling: None,
             };
             let api = provider.to_api_provider(None).expect("api provider");
             assert!(
@@ -508,4 +766,133 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
             );
         }
     }
+
+    #[test]
+    fn test_deserialize_chat_dialect_model_provider_toml() {
+        let qwen_provider_toml = r#"
+name = "Qwen"
+base_url = "https://dashscope.aliyuncs.com/compatible-mode/v1"
+env_key = "DASHSCOPE_API_KEY"
+
+[chat_dialect]
+supports_developer_role = true
+tool_content_as_string = true
+response_format = "json_object"
+strip_tokens = ["<|im_end|>"]
+        "#;
+
+        let provider: ModelProviderInfo = toml::from_str(qwen_provider_toml).unwrap();
+        assert_eq!(
+            provider.chat_dialect,
+            Some(ChatDialect {
+                base: None,
+                supports_developer_role: Some(true),
+                echo_reasoning_content: None,
+                tool_content_as_string: Some(true),
+                validate_tool_calls: None,
+                response_format: Some(ChatResponseFormat::JsonObject),
+                strip_tokens: Some(vec!["<|im_end|>".to_string()]),
+            })
+        );
+
+        let dialect = provider.chat_dialect(&find_model_info_for_slug("qwen3-coder"));
+        assert_eq!(
+            dialect,
+            ApiChatDialect {
+                developer_role: true,
+                echo_reasoning_content: false,
+                tool_content_as_string: true,
+                validate_tool_calls: None,
+                response_format: ApiChatResponseFormat::JsonObject,
+                strip_tokens: vec!["<|im_end|>".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn deepseek_chat_dialect_follows_model_and_overrides() {
+        let mut provider = ModelProviderInfo::create_deepseek_provider();
+
+        let chat = provider.chat_dialect(&find_model_info_for_slug("deepseek-chat"));
+        assert_eq!(chat, deepseek_chat_dialect());
+
+        let reasoner = provider.chat_dialect(&find_model_info_for_slug("deepseek-reasoner"));
+        assert!(reasoner.echo_reasoning_content);
+
+        provider.chat_dialect = Some(ChatDialect {
+            base: Some(ChatDialectBase::Deepseek),
+            validate_tool_calls: Some(true),
+            strip_tokens: Some(Vec::new()),
+            ..Default::default()
+        });
+        let overridden = provider.chat_dialect(&find_model_info_for_slug("deepseek-chat"));
+        assert_eq!(overridden.validate_tool_calls, Some(true));
+        assert!(overridden.strip_tokens.is_empty());
+        assert!(overridden.tool_content_as_string);
+    }
+
+    #[test]
+    fn chat_dialect_base_is_explicit() {
+        let provider: ModelProviderInfo = toml::from_str(
+            r#"
+name = "DeepSeek proxy"
+base_url = "https://deepseek.example.com/v1"
+            "#,
+        )
+        .unwrap();
+        let model_info = find_model_info_for_slug("deepseek-chat");
+        assert_eq!(
+            provider.chat_dialect(&model_info).response_format,
+            ApiChatResponseFormat::JsonSchema
+        );
+        assert!(!provider.is_deepseek());
+
+        let provider: ModelProviderInfo = toml::from_str(
+            r#"
+name = "DeepSeek proxy"
+base_url = "https://deepseek.example.com/v1"
+chat_dialect = { base = "deepseek" }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(provider.chat_dialect(&model_info), deepseek_chat_dialect());
+        assert!(provider.is_deepseek());
+    }
+
+    #[test]
+    fn only_json_object_chat_providers_leave_output_schema_to_the_client() {
+        assert!(ModelProviderInfo::create_openai_provider().enforces_output_schema());
+        assert!(!ModelProviderInfo::create_deepseek_provider().enforces_output_schema());
+
+        let mut provider = ModelProviderInfo::create_deepseek_provider();
+        provider.chat_dialect = Some(ChatDialect {
+            base: Some(ChatDialectBase::Deepseek),
+            response_format: Some(ChatResponseFormat::JsonSchema),
+            ..Default::default()
+        });
+        assert!(provider.enforces_output_schema());
+    }
+
+    #[test]
+    fn request_timeouts_use_defaults_unless_configured() {
+        let provider: ModelProviderInfo = toml::from_str(
+            r#"
+name = "DeepSeek"
+base_url = "https://api.deepseek.com"
+first_byte_timeout_ms = 600000
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            provider.request_connect_timeout(),
+            Duration::from_millis(DEFAULT_REQUEST_CONNECT_TIMEOUT_MS)
+        );
+        assert_eq!(
+            provider.first_byte_timeout(),
+            Some(Duration::from_secs(600))
+        );
+        let api = provider.to_api_provider(None).expect("api provider");
+        assert_eq!(api.first_byte_timeout, Some(Duration::from_secs(600)));
+    }
 }
@@ -25,6 +25,7 @@ use crate::features::Feature;
 use crate::model_provider_info::ModelProviderInfo;
 use crate::models_manager::model_info;
 use crate::models_manager::model_presets::builtin_model_presets;
+use crate::models_manager::model_presets::deepseek_model_presets;
 
 const MODEL_CACHE_FILE: &str = "models_cache.json";
 const DEFAULT_MODEL_CACHE_TTL: Duration = Duration::from_secs(300);
@@ -32,6 +33,7 @@ const MODELS_REFRESH_TIMEOUT: Duration = Duration::from_secs(5);
 const OPENAI_DEFAULT_API_MODEL: &str = "gpt-5.1-codex-max";
 const OPENAI_DEFAULT_CHATGPT_MODEL: &str = "gpt-5.2-codex";
 const CODEX_AUTO_BALANCED_MODEL: &str = "codex-auto-balanced";
+const DEEPSEEK_DEFAULT_MODEL: &str = "deepseek-chat";
 
 /// Strategy for refreshing available models.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -75,11 +77,15 @@ impl ModelsManager {
     /// List all available models, refreshing according to the specified strategy.
     ///
     /// Returns model presets sorted by priority and filtered by auth mode and visibility.
+    /// DeepSeek providers only list the DeepSeek presets.
     pub async fn list_models(
         &self,
         config: &Config,
         refresh_strategy: RefreshStrategy,
     ) -> Vec<ModelPreset> {
+        if config.model_provider.is_deepseek() {
+            return deepseek_model_presets();
+        }
         if let Err(err) = self
             .refresh_available_models(config, refresh_strategy)
             .await
@@ -94,6 +100,9 @@ impl ModelsManager {
     ///
     /// Returns an error if the internal lock cannot be acquired.
     pub fn try_list_models(&self, config: &Config) -> Result<Vec<ModelPreset>, TryLockError> {
+        if config.model_provider.is_deepseek() {
+            return Ok(deepseek_model_presets());
+        }
         let remote_models = self.try_get_remote_models(config)?;
         Ok(self.build_available_models(remote_models))
     }
@@ -102,7 +111,8 @@ impl ModelsManager {
     /// Get the model identifier to use, refreshing according to the specified strategy.
     ///
     /// If `model` is provided, returns it directly. Otherwise selects the default based on
-    /// auth mode and available models (prefers `codex-auto-balanced` for ChatGPT auth).
+    /// auth mode and available models (prefers `codex-auto-balanced` for ChatGPT auth, and
+    /// `deepseek-chat` for DeepSeek providers).
     pub async fn get_default_model(
         &self,
         model: &Option<String>,
@@ -112,6 +122,9 @@ impl ModelsManager {
         if let Some(model) = model.as_ref() {
             return model.to_string();
         }
+        if config.model_provider.is_deepseek() {
+            return DEEPSEEK_DEFAULT_MODEL.to_string();
+        }
         if let Err(err) = self
             .refresh_available_models(config, refresh_strategy)
             .await
@@ -398,7 +411,11 @@ mod tests {
             request_max_retries: Some(0),
             stream_max_retries: Some(0),
             stream_idle_timeout_ms: Some(5_000),
+            request_connect_timeout_ms: None,
+            first_byte_timeout_ms: None,
             requires_openai_auth: false,
+            chat_dialect: None,
+            sampling: None,
         }
     }
 
@@ -683,6 +700,40 @@ mod tests {
         assert_eq!(available, vec![expected_hidden, expected_visible]);
     }
 
+    #[tokio::test]
+    async fn deepseek_provider_lists_deepseek_presets() {
+        let codex_home = tempdir().expect("temp dir");
+        let mut config = ConfigBuilder::default()
+            .codex_home(codex_home.path().to_path_buf())
+            .build()
+            .await
+            .expect("load default test config");
+        config.model_provider = ModelProviderInfo::create_deepseek_provider();
+        let auth_manager =
+            AuthManager::from_auth_for_testing(CodexAuth::from_api_key("Test API Key"));
+        let manager = ModelsManager::new(codex_home.path().to_path_buf(), auth_manager);
+
+        let available = manager
+            .list_models(&config, RefreshStrategy::Offline)
+            .await
+            .into_iter()
+            .map(|preset| (preset.model, preset.is_default))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            available,
+            vec![
+                ("deepseek-chat".to_string(), true),
+                ("deepseek-reasoner".to_string(), false),
+            ]
+        );
+        assert_eq!(
+            manager
+                .get_default_model(&None, &config, RefreshStrategy::Offline)
+                .await,
+            "deepseek-chat"
+        );
+    }
+
     #[test]
     fn bundled_models_json_roundtrips() {
         let file_contents = include_str!("../../models.json");
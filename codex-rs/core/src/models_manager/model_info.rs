@@ -5,6 +5,7 @@ use codex_protocol::openai_models::ModelInfo;
 use codex_protocol::openai_models::ModelVisibility;
 use codex_protocol::openai_models::ReasoningEffort;
 use codex_protocol::openai_models::ReasoningEffortPreset;
+use codex_protocol::openai_models::TokenizerKind;
 use codex_protocol::openai_models::TruncationMode;
 use codex_protocol::openai_models::TruncationPolicyConfig;
 
@@ -23,6 +24,7 @@ const GPT_5_1_CODEX_MAX_INSTRUCTIONS: &str = include_str!("../../gpt-5.1-codex-m
 const GPT_5_2_CODEX_INSTRUCTIONS: &str = include_str!("../../gpt-5.2-codex_prompt.md");
 
 pub(crate) const CONTEXT_WINDOW_272K: i64 = 272_000;
+pub(crate) const CONTEXT_WINDOW_128K: i64 = 128_000;
 
 macro_rules! model_info {
     (
@@ -54,6 +56,8 @@ macro_rules! model_info {
             auto_compact_token_limit: None,
             effective_context_window_percent: 95,
             experimental_supported_tools: Vec::new(),
+            requires_reasoning_content: false,
+            tokenizer: Some(TokenizerKind::O200kBase),
         };
 
         $(
@@ -132,6 +136,7 @@ pub(crate) fn find_model_info_for_slug(slug: &str) -> ModelInfo {
             base_instructions: BASE_INSTRUCTIONS_WITH_APPLY_PATCH.to_string(),
             supports_reasoning_summaries: false,
             context_window: Some(16_385),
+            tokenizer: Some(TokenizerKind::Cl100kBase),
         )
     } else if slug.starts_with("test-gpt-5") {
         model_info!(
@@ -275,13 +280,35 @@ pub(crate) fn find_model_info_for_slug(slug: &str) -> ModelInfo {
             truncation_policy: TruncationPolicyConfig::bytes(10_000),
             context_window: Some(CONTEXT_WINDOW_272K),
         )
+    } else if slug.starts_with("deepseek-reasoner") {
+        // Thinking mode: reasoning is always on and cannot be tuned, and the
+        // API rejects tool-call turns that do not echo `reasoning_content`.
+        model_info!(
+            slug,
+            apply_patch_tool_type: Some(ApplyPatchToolType::Function),
+            truncation_policy: TruncationPolicyConfig::tokens(10_000),
+            context_window: Some(CONTEXT_WINDOW_128K),
+            supported_reasoning_levels: Vec::new(),
+            requires_reasoning_content: true,
+            tokenizer: Some(TokenizerKind::DeepSeek),
+        )
+    } else if slug.starts_with("deepseek") {
+        model_info!(
+            slug,
+            apply_patch_tool_type: Some(ApplyPatchToolType::Function),
+            truncation_policy: TruncationPolicyConfig::tokens(10_000),
+            context_window: Some(CONTEXT_WINDOW_128K),
+            supported_reasoning_levels: Vec::new(),
+            tokenizer: Some(TokenizerKind::DeepSeek),
+        )
     } else {
         warn!("Unknown model {slug} is used. This will degrade the performance of Codex.");
         model_info!(
             slug,
             context_window: None,
             supported_reasoning_levels: Vec::new(),
-            default_reasoning_level: None
+            default_reasoning_level: None,
+            tokenizer: None,
         )
     }
 }
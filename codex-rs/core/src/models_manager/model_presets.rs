@@ -309,6 +309,40 @@ static PRESETS: Lazy<Vec<ModelPreset>> = Lazy::new(|| {
     ]
 });
 
+/// Presets offered when the active provider serves DeepSeek models. Neither
+/// model exposes a reasoning effort knob: `deepseek-reasoner` always thinks.
+static DEEPSEEK_PRESETS: Lazy<Vec<ModelPreset>> = Lazy::new(|| {
+    vec![
+        ModelPreset {
+            id: "deepseek-chat".to_string(),
+            model: "deepseek-chat".to_string(),
+            display_name: "deepseek-chat".to_string(),
+            description:
+                "DeepSeek-V3.2 without thinking. Fast and inexpensive for everyday coding."
+                    .to_string(),
+            default_reasoning_effort: ReasoningEffort::None,
+            supported_reasoning_efforts: Vec::new(),
+            is_default: true,
+            upgrade: None,
+            show_in_picker: true,
+            supported_in_api: true,
+        },
+        ModelPreset {
+            id: "deepseek-reasoner".to_string(),
+            model: "deepseek-reasoner".to_string(),
+            display_name: "deepseek-reasoner".to_string(),
+            description: "DeepSeek-V3.2 in thinking mode for complex or ambiguous problems."
+                .to_string(),
+            default_reasoning_effort: ReasoningEffort::None,
+            supported_reasoning_efforts: Vec::new(),
+            is_default: false,
+            upgrade: None,
+            show_in_picker: true,
+            supported_in_api: true,
+        },
+    ]
+});
+
 fn gpt_52_codex_upgrade() -> ModelUpgrade {
     ModelUpgrade {
         id: "gpt-5.2-codex".to_string(),
@@ -336,6 +370,10 @@ pub(super) fn builtin_model_presets(_auth_mode: Option<AuthMode>) -> Vec<ModelPr
     PRESETS.iter().cloned().collect()
 }
 
+pub(super) fn deepseek_model_presets() -> Vec<ModelPreset> {
+    DEEPSEEK_PRESETS.iter().cloned().collect()
+}
+
 #[cfg(any(test, feature = "test-support"))]
 pub fn all_model_presets() -> &'static Vec<ModelPreset> {
     &PRESETS
@@ -344,10 +382,26 @@ pub fn all_model_presets() -> &'static Vec<ModelPreset> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models_manager::model_info::find_model_info_for_slug;
+    use pretty_assertions::assert_eq;
 
     #[test]
     fn only_one_default_model_is_configured() {
         let default_models = PRESETS.iter().filter(|preset| preset.is_default).count();
         assert!(default_models == 1);
     }
+
+    #[test]
+    fn deepseek_presets_have_one_default_and_metadata() {
+        let default_models = DEEPSEEK_PRESETS
+            .iter()
+            .filter(|preset| preset.is_default)
+            .count();
+        assert!(default_models == 1);
+
+        for preset in DEEPSEEK_PRESETS.iter() {
+            let info = find_model_info_for_slug(&preset.model);
+            assert_eq!(info.context_window, Some(128_000), "{}", preset.model);
+        }
+    }
 }
@@ -0,0 +1,390 @@
+//! Filtering HTTP(S) proxy for sandboxed commands that may only reach an
+//! allowlist of domains.
+//!
+//! The proxy listens on a Unix domain socket. `codex-linux-sandbox` runs the
+//! command in a network namespace whose only egress is a loopback bridge to
+//! that socket, and points the usual `HTTP_PROXY`/`HTTPS_PROXY` variables at
+//! the bridge. Requests are either `CONNECT host:port` tunnels (used for TLS)
+//! or absolute-form plain HTTP requests; anything else is rejected.
+
+use std::collections::HashSet;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use async_channel::Sender;
+use tokio::io::AsyncBufReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::io::BufReader;
+use tokio::net::TcpStream;
+use tokio::net::UnixListener;
+use tokio::net::UnixStream;
+use tokio_util::task::AbortOnDropHandle;
+use tracing::debug;
+use tracing::warn;
+
+use crate::config::types::SandboxNetworkAllowlist;
+use crate::protocol::Event;
+use crate::protocol::EventMsg;
+use crate::protocol::WarningEvent;
+
+/// Event id used for warnings about blocked connections, which are not tied
+/// to a particular submission.
+const NETWORK_PROXY_EVENT_ID: &str = "network_proxy";
+
+/// Upper bound on the request line and headers read before a request is
+/// rejected.
+const MAX_REQUEST_HEAD_BYTES: usize = 64 * 1024;
+
+/// A running proxy. Dropping it stops accepting connections and removes the
+/// socket.
+pub(crate) struct NetworkProxy {
+    socket_path: PathBuf,
+    _socket_dir: tempfile::TempDir,
+    _accept_task: AbortOnDropHandle<()>,
+}
+
+impl NetworkProxy {
+    /// Starts the proxy. Blocked connections are reported once per host as
+    /// [`EventMsg::Warning`] on `tx_event`.
+    pub(crate) fn start(
+        allowlist: SandboxNetworkAllowlist,
+        tx_event: Sender<Event>,
+    ) -> io::Result<Self> {
+        let socket_dir = tempfile::Builder::new()
+            .prefix("codex-network-proxy")
+            .tempdir()?;
+        let socket_path = socket_dir.path().join("proxy.sock");
+        let listener = UnixListener::bind(&socket_path)?;
+
+        let state = Arc::new(ProxyState {
+            allowlist,
+            tx_event,
+            reported_hosts: Mutex::new(HashSet::new()),
+        });
+        let accept_task = tokio::spawn(async move {
+            loop {
+                let stream = match listener.accept().await {
+                    Ok((stream, _)) => stream,
+                    Err(err) => {
+                        warn!("network proxy failed to accept connection: {err}");
+                        continue;
+                    }
+                };
+                let state = Arc::clone(&state);
+                tokio::spawn(async move {
+                    if let Err(err) = handle_connection(stream, &state).await {
+                        debug!("network proxy connection ended: {err}");
+                    }
+                });
+            }
+        });
+
+        Ok(Self {
+            socket_path,
+            _socket_dir: socket_dir,
+            _accept_task: AbortOnDropHandle::new(accept_task),
+        })
+    }
+
+    pub(crate) fn socket_path(&self) -> &Path {
+        &self.socket_path
+    }
+}
+
+struct ProxyState {
+    allowlist: SandboxNetworkAllowlist,
+    tx_event: Sender<Event>,
+    reported_hosts: Mutex<HashSet<String>>,
+}
+
+impl ProxyState {
+    async fn report_denied(&self, host: &str, port: u16) {
+        let first_report = match self.reported_hosts.lock() {
+            Ok(mut reported_hosts) => reported_hosts.insert(host.to_string()),
+            Err(_) => false,
+        };
+        if !first_report {
+            return;
+        }
+        let message = format!(
+            "Sandbox blocked a network connection to {host}:{port}. Add the domain to `sandbox_network_allowlist.allowed_domains` to allow it."
+        );
+        let event = Event {
+            id: NETWORK_PROXY_EVENT_ID.to_string(),
+            msg: EventMsg::Warning(WarningEvent { message }),
+        };
+        if let Err(err) = self.tx_event.send(event).await {
+            warn!("failed to report blocked network connection: {err}");
+        }
+    }
+}
+
+/// Returns `true` when `host` may be reached under `allowlist`.
+pub(crate) fn is_host_allowed(allowlist: &SandboxNetworkAllowlist, host: &str) -> bool {
+    let host = normalize_host(host);
+    let matches = |pattern: &String| domain_matches(pattern, &host);
+    !allowlist.denied_domains.iter().any(matches) && allowlist.allowed_domains.iter().any(matches)
+}
+
+fn normalize_host(host: &str) -> String {
+    host.trim_end_matches('.').to_ascii_lowercase()
+}
+
+fn domain_matches(pattern: &str, host: &str) -> bool {
+    let pattern = normalize_host(pattern);
+    match pattern.strip_prefix("*.") {
+        Some(suffix) => host
+            .strip_suffix(suffix)
+            .is_some_and(|prefix| prefix.ends_with('.') && prefix.len() > 1),
+        None => host == pattern,
+    }
+}
+
+/// A request accepted by the proxy.
+#[derive(Debug, PartialEq, Eq)]
+struct ProxyRequest {
+    host: String,
+    port: u16,
+    /// For plain HTTP, the request head rewritten to origin-form that must be
+    /// sent upstream. `None` for `CONNECT` tunnels.
+    forward_head: Option<Vec<u8>>,
+}
+
+fn parse_request_head(head: &[u8]) -> Option<ProxyRequest> {
+    let head = std::str::from_utf8(head).ok()?;
+    let mut lines = head.split("\r\n");
+    let request_line = lines.next()?;
+    let mut parts = request_line.split(' ');
+    let (method, target, version) = (parts.next()?, parts.next()?, parts.next()?);
+    if parts.next().is_some() || !version.starts_with("HTTP/") {
+        return None;
+    }
+
+    if method.eq_ignore_ascii_case("CONNECT") {
+        let (host, port) = split_host_port(target)?;
+        return Some(ProxyRequest {
+            host,
+            port: port?,
+            forward_head: None,
+        });
+    }
+
+    let rest = target.strip_prefix("http://")?;
+    let (authority, path) = match rest.find('/') {
+        Some(index) => rest.split_at(index),
+        None => (rest, "/"),
+    };
+    let (host, port) = split_host_port(authority)?;
+
+    let mut forward_head = format!("{method} {path} {version}\r\n");
+    for header in lines.filter(|line| !line.is_empty()) {
+        let name = header.split(':').next().unwrap_or_default();
+        if name.eq_ignore_ascii_case("proxy-connection")
+            || name.eq_ignore_ascii_case("proxy-authorization")
+        {
+            continue;
+        }
+        forward_head.push_str(header);
+        forward_head.push_str("\r\n");
+    }
+    forward_head.push_str("\r\n");
+
+    Some(ProxyRequest {
+        host,
+        port: port.unwrap_or(80),
+        forward_head: Some(forward_head.into_bytes()),
+    })
+}
+
+/// Splits `host[:port]`, accepting bracketed IPv6 literals. The port is
+/// `None` when absent and the whole result is `None` when it is malformed.
+fn split_host_port(authority: &str) -> Option<(String, Option<u16>)> {
+    let (host, port) = if let Some(rest) = authority.strip_prefix('[') {
+        let (host, rest) = rest.split_once(']')?;
+        (host, rest.strip_prefix(':'))
+    } else {
+        match authority.rsplit_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        }
+    };
+    if host.is_empty() {
+        return None;
+    }
+    let port = match port {
+        Some(port) => Some(port.parse().ok()?),
+        None => None,
+    };
+    Some((host.to_string(), port))
+}
+
+async fn read_request_head(reader: &mut BufReader<UnixStream>) -> io::Result<Vec<u8>> {
+    let mut head = Vec::new();
+    loop {
+        let read = reader.read_until(b'\n', &mut head).await?;
+        if read == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "connection closed before the request head was complete",
+            ));
+        }
+        if head.ends_with(b"\r\n\r\n") {
+            return Ok(head);
+        }
+        if head.len() > MAX_REQUEST_HEAD_BYTES {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "request head is too large",
+            ));
+        }
+    }
+}
+
+async fn respond(stream: &mut UnixStream, status: &str, body: &str) -> io::Result<()> {
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+async fn handle_connection(stream: UnixStream, state: &ProxyState) -> io::Result<()> {
+    let mut reader = BufReader::new(stream);
+    let head = read_request_head(&mut reader).await?;
+    let Some(request) = parse_request_head(&head) else {
+        return respond(
+            reader.get_mut(),
+            "400 Bad Request",
+            "codex network proxy only supports CONNECT and http:// requests\n",
+        )
+        .await;
+    };
+
+    if !is_host_allowed(&state.allowlist, &request.host) {
+        state.report_denied(&request.host, request.port).await;
+        let body = format!(
+            "codex network proxy: {} is not in the sandbox network allowlist\n",
+            request.host
+        );
+        return respond(reader.get_mut(), "403 Forbidden", &body).await;
+    }
+
+    let mut upstream = match TcpStream::connect((request.host.as_str(), request.port)).await {
+        Ok(upstream) => upstream,
+        Err(err) => {
+            let body = format!(
+                "codex network proxy: failed to connect to {}:{}: {err}\n",
+                request.host, request.port
+            );
+            return respond(reader.get_mut(), "502 Bad Gateway", &body).await;
+        }
+    };
+
+    match &request.forward_head {
+        Some(forward_head) => upstream.write_all(forward_head).await?,
+        None => {
+            reader
+                .get_mut()
+                .write_all(b"HTTP/1.1 200 Connection Established\r\n\r\n")
+                .await?;
+        }
+    }
+    // Bytes the client sent after the request head are still buffered.
+    upstream.write_all(reader.buffer()).await?;
+    let mut client = reader.into_inner();
+    tokio::io::copy_bidirectional(&mut client, &mut upstream).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn allowlist(allowed: &[&str], denied: &[&str]) -> SandboxNetworkAllowlist {
+        SandboxNetworkAllowlist {
+            allowed_domains: allowed.iter().map(ToString::to_string).collect(),
+            denied_domains: denied.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    #[test]
+    fn allowlist_matches_exact_and_wildcard_domains() {
+        let allowlist = allowlist(
+            &["crates.io", "*.crates.io", "registry.internal"],
+            &["evil.crates.io"],
+        );
+
+        assert!(is_host_allowed(&allowlist, "crates.io"));
+        assert!(is_host_allowed(&allowlist, "static.crates.io"));
+        assert!(is_host_allowed(&allowlist, "Index.Crates.IO."));
+        assert!(is_host_allowed(&allowlist, "registry.internal"));
+        assert!(!is_host_allowed(&allowlist, "evil.crates.io"));
+        assert!(!is_host_allowed(&allowlist, "notcrates.io"));
+        assert!(!is_host_allowed(&allowlist, "crates.io.example.com"));
+        assert!(!is_host_allowed(&allowlist, "sub.registry.internal"));
+    }
+
+    #[test]
+    fn parses_connect_requests() {
+        let head = b"CONNECT index.crates.io:443 HTTP/1.1\r\nHost: index.crates.io:443\r\n\r\n";
+        assert_eq!(
+            Some(ProxyRequest {
+                host: "index.crates.io".to_string(),
+                port: 443,
+                forward_head: None,
+            }),
+            parse_request_head(head)
+        );
+        assert_eq!(
+            None,
+            parse_request_head(b"CONNECT index.crates.io HTTP/1.1\r\n\r\n")
+        );
+    }
+
+    #[test]
+    fn rewrites_absolute_form_http_requests() {
+        let head = b"GET http://[::1]:8080/simple/?q=1 HTTP/1.1\r\nHost: [::1]:8080\r\nProxy-Connection: keep-alive\r\n\r\n";
+        assert_eq!(
+            Some(ProxyRequest {
+                host: "::1".to_string(),
+                port: 8080,
+                forward_head: Some(
+                    b"GET /simple/?q=1 HTTP/1.1\r\nHost: [::1]:8080\r\n\r\n".to_vec()
+                ),
+            }),
+            parse_request_head(head)
+        );
+        assert_eq!(None, parse_request_head(b"GET /relative HTTP/1.1\r\n\r\n"));
+    }
+
+    #[tokio::test]
+    async fn denied_connect_is_rejected_and_reported_once() -> anyhow::Result<()> {
+        use tokio::io::AsyncReadExt;
+
+        let (tx_event, rx_event) = async_channel::unbounded();
+        let proxy = NetworkProxy::start(allowlist(&["crates.io"], &[]), tx_event)?;
+
+        for _ in 0..2 {
+            let mut stream = UnixStream::connect(proxy.socket_path()).await?;
+            stream
+                .write_all(b"CONNECT example.com:443 HTTP/1.1\r\nHost: example.com:443\r\n\r\n")
+                .await?;
+            let mut response = String::new();
+            stream.read_to_string(&mut response).await?;
+            assert!(response.starts_with("HTTP/1.1 403 Forbidden\r\n"));
+        }
+
+        let event = rx_event.recv().await?;
+        let EventMsg::Warning(WarningEvent { message }) = event.msg else {
+            panic!("expected a warning event, got {:?}", event.msg);
+        };
+        assert!(message.contains("example.com:443"));
+        assert!(rx_event.try_recv().is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,406 @@
+//! Shell state that carries over between `shell` tool calls.
+//!
+//! With `features.persistent_shell`, every call still runs in its own
+//! sandboxed process, but shell scripts are wrapped so the shell records its
+//! working directory and exported variables before and after the model's
+//! script. Codex parses those records and replays the differences in front of
+//! the next call, so `cd`, `export` and the variables set by
+//! `source venv/bin/activate` carry over. Commands given as an argv rather
+//! than a script start in the carried directory with the carried variables.
+//!
+//! Only the working directory and exported variables carry over. Shell
+//! functions (such as the `deactivate` a virtualenv defines), aliases,
+//! unexported variables and `set` options are lost between calls, as is the
+//! state of a script that replaces the shell with `exec` or exits from its
+//! own `EXIT` trap.
+//!
+//! The records are parsed rather than sourced, and they are written to
+//! anonymous files the command inherits as descriptors, so neither this
+//! command nor one left running in the background can tamper with the state
+//! recorded by another command.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+
+use serde::Serialize;
+
+use crate::shell::ShellType;
+use crate::shell::detect_shell_type;
+use crate::spawn::InheritedFds;
+
+/// Variables the shell maintains itself; changes to them are not carried over.
+const SHELL_MANAGED_VARS: &[&str] = &["OLDPWD", "PWD", "SHLVL", "_"];
+
+/// Descriptors a wrapped command writes its state records to. POSIX shells
+/// only accept single-digit descriptors in redirections.
+const BEFORE_RECORD_FD: i32 = 8;
+const AFTER_RECORD_FD: i32 = 9;
+
+/// Shell state accumulated over the calls of one thread.
+#[derive(Default)]
+pub(crate) struct PersistentShell {
+    state: Mutex<ShellStateChange>,
+}
+
+/// Working directory and variable changes left behind by shell commands.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct ShellStateChange {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<PathBuf>,
+    /// Exported variables; `None` marks a variable that was unset.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, Option<String>>,
+}
+
+/// Anonymous files a wrapped command records its shell state in.
+#[derive(Debug)]
+pub(crate) struct StateCapture {
+    before: File,
+    after: File,
+}
+
+impl PersistentShell {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Directory the previous commands left the shell in, if it still exists.
+    pub(crate) fn cwd(&self) -> Option<PathBuf> {
+        self.lock().cwd.clone().filter(|cwd| cwd.is_dir())
+    }
+
+    /// Wraps a `[shell, "-lc" | "-c", script]` command so that it records the
+    /// state it leaves behind and, when `apply_state` is set, starts from the
+    /// variables carried over from earlier commands. Returns `None` for
+    /// commands that are not POSIX shell scripts; see [`Self::apply_env`] for
+    /// those.
+    pub(crate) fn wrap_command(
+        &self,
+        command: &[String],
+        apply_state: bool,
+    ) -> io::Result<Option<(Vec<String>, StateCapture)>> {
+        let [shell, flag, script] = command else {
+            return Ok(None);
+        };
+        if !cfg!(unix)
+            || !matches!(flag.as_str(), "-lc" | "-c")
+            || !matches!(
+                detect_shell_type(&PathBuf::from(shell)),
+                Some(ShellType::Bash | ShellType::Zsh | ShellType::Sh)
+            )
+        {
+            return Ok(None);
+        }
+
+        let capture = StateCapture {
+            before: tempfile::tempfile()?,
+            after: tempfile::tempfile()?,
+        };
+        let record_after = format!(
+            "__codex_record_state() {{ [ -n \"${{__codex_recorded:-}}\" ] && return; __codex_recorded=1; {{ pwd; export -p; }} 2>/dev/null >&{AFTER_RECORD_FD}; }}"
+        );
+
+        let mut wrapped = String::new();
+        if apply_state {
+            wrapped.push_str(&self.restore_script());
+        }
+        wrapped.push_str(&format!(
+            "{{ pwd; export -p; }} 2>/dev/null >&{BEFORE_RECORD_FD}; exec {BEFORE_RECORD_FD}>&-\n"
+        ));
+        wrapped.push_str(&record_after);
+        wrapped.push('\n');
+        // The trap covers scripts that `exit`; the trailing line covers those
+        // that replace the trap with their own.
+        wrapped.push_str(
+            "trap '__codex_status=$?; __codex_record_state; exit $__codex_status' EXIT\n",
+        );
+        wrapped.push_str(script);
+        wrapped.push_str("\n__codex_status=$?; __codex_record_state; exit $__codex_status");
+
+        Ok(Some((vec![shell.clone(), flag.clone(), wrapped], capture)))
+    }
+
+    /// Applies the carried variables to the environment of a command that
+    /// [`Self::wrap_command`] could not wrap.
+    pub(crate) fn apply_env(&self, env: &mut HashMap<String, String>) {
+        for (name, value) in &self.lock().env {
+            match value {
+                Some(value) => env.insert(name.clone(), value.clone()),
+                None => env.remove(name),
+            };
+        }
+    }
+
+    /// Reads what a wrapped command changed, folds it into the carried state
+    /// and returns it. Returns `None` when nothing changed or the command
+    /// could not record its state.
+    pub(crate) fn record(&self, capture: StateCapture) -> Option<ShellStateChange> {
+        let before = read_record(capture.before).ok()?;
+        let after = read_record(capture.after).ok()?;
+
+        let change = diff_records(&before, &after)?;
+        if change == ShellStateChange::default() {
+            return None;
+        }
+        let mut state = self.lock();
+        if let Some(cwd) = &change.cwd {
+            state.cwd = Some(cwd.clone());
+        }
+        state
+            .env
+            .extend(change.env.iter().map(|(k, v)| (k.clone(), v.clone())));
+        Some(change)
+    }
+
+    fn restore_script(&self) -> String {
+        let state = self.lock();
+        let mut script = String::new();
+        for (name, value) in &state.env {
+            match value {
+                Some(value) => {
+                    if let Ok(value) = shlex::try_quote(value) {
+                        script.push_str(&format!("export {name}={value}\n"));
+                    }
+                }
+                None => script.push_str(&format!("unset {name}\n")),
+            }
+        }
+        script
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ShellStateChange> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl StateCapture {
+    /// Descriptors the wrapped command has to be started with.
+    #[cfg(unix)]
+    pub(crate) fn inherited_fds(&self) -> io::Result<InheritedFds> {
+        let mut fds = InheritedFds::default();
+        fds.insert(BEFORE_RECORD_FD, self.before.try_clone()?.into())?;
+        fds.insert(AFTER_RECORD_FD, self.after.try_clone()?.into())?;
+        Ok(fds)
+    }
+
+    #[cfg(not(unix))]
+    pub(crate) fn inherited_fds(&self) -> io::Result<InheritedFds> {
+        Ok(InheritedFds::default())
+    }
+}
+
+fn read_record(mut file: File) -> io::Result<String> {
+    let mut record = String::new();
+    file.seek(SeekFrom::Start(0))?;
+    file.read_to_string(&mut record)?;
+    Ok(record)
+}
+
+impl ShellStateChange {
+    /// Human-readable summary for the model, one change per line.
+    pub(crate) fn render(&self) -> String {
+        let mut lines = Vec::new();
+        if let Some(cwd) = &self.cwd {
+            lines.push(format!("cd {}", cwd.display()));
+        }
+        for (name, value) in &self.env {
+            match value {
+                Some(value) => lines.push(format!("export {name}={value}")),
+                None => lines.push(format!("unset {name}")),
+            }
+        }
+        lines.join("\n")
+    }
+}
+
+/// Compares two `pwd; export -p` records.
+fn diff_records(before: &str, after: &str) -> Option<ShellStateChange> {
+    let (before_cwd, before_env) = parse_record(before)?;
+    let (after_cwd, after_env) = parse_record(after)?;
+
+    let mut env = BTreeMap::new();
+    for (name, value) in &after_env {
+        if before_env.get(name) != Some(value) {
+            env.insert(name.clone(), Some(value.clone()));
+        }
+    }
+    for name in before_env.keys() {
+        if !after_env.contains_key(name) {
+            env.insert(name.clone(), None);
+        }
+    }
+    env.retain(|name, _| !SHELL_MANAGED_VARS.contains(&name.as_str()));
+
+    Some(ShellStateChange {
+        cwd: (after_cwd != before_cwd).then_some(after_cwd),
+        env,
+    })
+}
+
+/// Splits a record into the working directory on its first line and the
+/// variables of the `export -p` listing that follows. The listing format
+/// differs per shell (`declare -x K="v"`, `export K='v'`, `export K=v`), but
+/// all of them are valid shell words.
+fn parse_record(record: &str) -> Option<(PathBuf, BTreeMap<String, String>)> {
+    let (cwd, exports) = record.split_once('\n')?;
+    let mut env = BTreeMap::new();
+    for word in shlex::split(exports)? {
+        let Some((name, value)) = word.split_once('=') else {
+            continue;
+        };
+        if is_variable_name(name) {
+            env.insert(name.to_string(), value.to_string());
+        }
+    }
+    Some((Path::new(cwd).to_path_buf(), env))
+}
+
+fn is_variable_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c == '_' || c.is_ascii_alphabetic())
+        && chars.all(|c| c == '_' || c.is_ascii_alphanumeric())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn diff_records_reports_cwd_and_variable_changes() {
+        let before =
+            "/repo\ndeclare -x HOME=\"/home/me\"\ndeclare -x OLD=\"1\"\ndeclare -x PWD=\"/repo\"\n";
+        let after = "/repo/src\ndeclare -x HOME=\"/home/me\"\ndeclare -x PWD=\"/repo/src\"\ndeclare -x VIRTUAL_ENV=\"/repo/.venv\"\ndeclare -x GREETING=\"a \\\"b\\\" c\"\n";
+
+        let change = diff_records(before, after).expect("records parse");
+
+        assert_eq!(
+            change,
+            ShellStateChange {
+                cwd: Some(PathBuf::from("/repo/src")),
+                env: BTreeMap::from([
+                    ("GREETING".to_string(), Some("a \"b\" c".to_string())),
+                    ("OLD".to_string(), None),
+                    ("VIRTUAL_ENV".to_string(), Some("/repo/.venv".to_string())),
+                ]),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_record_accepts_posix_export_format() {
+        let (cwd, env) =
+            parse_record("/tmp\nexport A='x y'\nexport EMPTY=''\nexport UNSET_VALUE\n")
+                .expect("record parses");
+
+        assert_eq!(cwd, PathBuf::from("/tmp"));
+        assert_eq!(
+            env,
+            BTreeMap::from([
+                ("A".to_string(), "x y".to_string()),
+                ("EMPTY".to_string(), String::new()),
+            ])
+        );
+    }
+
+    #[test]
+    fn wrap_command_only_wraps_posix_shell_scripts() {
+        let shell = PersistentShell::new();
+        let bash = vec!["/bin/bash".to_string(), "-lc".to_string(), "ls".to_string()];
+        let argv = vec!["ls".to_string(), "-la".to_string()];
+
+        let (wrapped, _capture) = shell
+            .wrap_command(&bash, true)
+            .expect("temp files")
+            .expect("bash is wrapped");
+        assert_eq!(wrapped[..2], bash[..2]);
+        assert!(wrapped[2].contains("EXIT\nls\n"), "{}", wrapped[2]);
+        assert!(
+            shell
+                .wrap_command(&argv, true)
+                .expect("temp files")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn apply_env_sets_and_unsets_carried_variables() {
+        let shell = PersistentShell::new();
+        shell.lock().env = BTreeMap::from([
+            ("ADDED".to_string(), Some("1".to_string())),
+            ("REMOVED".to_string(), None),
+        ]);
+        let mut env = HashMap::from([("REMOVED".to_string(), "x".to_string())]);
+
+        shell.apply_env(&mut env);
+
+        assert_eq!(env, HashMap::from([("ADDED".to_string(), "1".to_string())]));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn state_carries_over_between_commands() {
+        use crate::protocol::SandboxPolicy;
+        use crate::spawn::StdioPolicy;
+        use crate::spawn::spawn_child_async;
+
+        let shell = PersistentShell::new();
+        let workdir = tempfile::TempDir::new().expect("workdir");
+        std::fs::create_dir(workdir.path().join("sub")).expect("create sub");
+        let run = async |script: &str| {
+            let command = vec!["/bin/sh".to_string(), "-c".to_string(), script.to_string()];
+            let (wrapped, capture) = shell
+                .wrap_command(&command, true)
+                .expect("temp files")
+                .expect("wrapped");
+            let child = spawn_child_async(
+                PathBuf::from(&wrapped[0]),
+                wrapped[1..].to_vec(),
+                None,
+                shell.cwd().unwrap_or_else(|| workdir.path().to_path_buf()),
+                &SandboxPolicy::DangerFullAccess,
+                StdioPolicy::RedirectForShellTool,
+                std::env::vars().collect(),
+                capture.inherited_fds().expect("inherit fds"),
+            )
+            .await
+            .expect("spawn shell");
+            let output = child.wait_with_output().await.expect("run shell");
+            (output, shell.record(capture))
+        };
+
+        let (_, change) = run("cd sub && export CODEX_TEST_VAR='hello world'").await;
+        let change = change.expect("state changed");
+        assert_eq!(
+            change.env.get("CODEX_TEST_VAR"),
+            Some(&Some("hello world".to_string()))
+        );
+        assert!(change.cwd.is_some_and(|cwd| cwd.ends_with("sub")));
+
+        let (output, change) = run("printf '%s' \"$CODEX_TEST_VAR\"; basename \"$(pwd)\"").await;
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "hello worldsub\n");
+        assert_eq!(change, None);
+
+        // A script's own EXIT trap replaces the recording one; the state is
+        // still recorded when the script runs to its end.
+        let (_, change) = run("trap 'true' EXIT; export CODEX_TRAPPED=1").await;
+        assert_eq!(
+            change.map(|change| change.env),
+            Some(BTreeMap::from([(
+                "CODEX_TRAPPED".to_string(),
+                Some("1".to_string())
+            )]))
+        );
+    }
+}
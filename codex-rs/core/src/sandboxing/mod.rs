@@ -6,6 +6,9 @@ sandbox placement and transformation of portable CommandSpec into a
 ready‑to‑spawn environment.
 */
 
+use crate::config::types::ContainerConfig;
+use crate::config::types::SandboxResourceLimits;
+use crate::container::create_container_command;
 use crate::exec::ExecExpiration;
 use crate::exec::ExecToolCallOutput;
 use crate::exec::SandboxType;
@@ -20,7 +23,9 @@ use crate::seatbelt::create_seatbelt_command_args;
 #[cfg(target_os = "macos")]
 use crate::spawn::CODEX_SANDBOX_ENV_VAR;
 use crate::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR;
+use crate::spawn::InheritedFds;
 use crate::tools::sandboxing::SandboxablePreference;
+use crate::workspace_overlay::WorkspaceOverlay;
 pub use codex_protocol::models::SandboxPermissions;
 use std::collections::HashMap;
 use std::path::Path;
@@ -47,6 +52,10 @@ pub struct ExecEnv {
     pub sandbox_permissions: SandboxPermissions,
     pub justification: Option<String>,
     pub arg0: Option<String>,
+    /// Limits the sandbox helper applies to the command, if any.
+    pub resource_limits: Option<SandboxResourceLimits>,
+    /// Extra file descriptors the command starts with.
+    pub inherited_fds: InheritedFds,
 }
 
 pub enum SandboxPreference {
@@ -59,6 +68,13 @@ pub enum SandboxPreference {
 pub(crate) enum SandboxTransformError {
     #[error("missing codex-linux-sandbox executable path")]
     MissingLinuxSandboxExecutable,
+    #[error("container sandbox requested without a `[container]` configuration")]
+    MissingContainerConfig,
+    #[error(
+        "working directory {} is not mounted in the container; use a directory inside the workspace or a readable root",
+        .0.display()
+    )]
+    ContainerWorkdirNotMounted(PathBuf),
     #[cfg(not(target_os = "macos"))]
     #[error("seatbelt sandbox is only available on macOS")]
     SeatbeltUnavailable,
@@ -76,7 +92,13 @@ impl SandboxManager {
         &self,
         policy: &SandboxPolicy,
         pref: SandboxablePreference,
+        container: bool,
     ) -> SandboxType {
+        // A configured container replaces the platform sandbox entirely; the
+        // policy is enforced through the container's mounts and network.
+        if container && !matches!(pref, SandboxablePreference::Forbid) {
+            return SandboxType::Container;
+        }
         match pref {
             SandboxablePreference::Forbid => SandboxType::None,
             SandboxablePreference::Require => {
@@ -93,6 +115,7 @@ impl SandboxManager {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn transform(
         &self,
         mut spec: CommandSpec,
@@ -100,9 +123,27 @@ impl SandboxManager {
         sandbox: SandboxType,
         sandbox_policy_cwd: &Path,
         codex_linux_sandbox_exe: Option<&PathBuf>,
+        network_proxy_socket: Option<&Path>,
+        resource_limits: Option<&SandboxResourceLimits>,
+        workspace_overlay: Option<&WorkspaceOverlay>,
+        container: Option<&ContainerConfig>,
     ) -> Result<ExecEnv, SandboxTransformError> {
+        // Only the Linux sandbox can route traffic through the allowlist proxy.
+        let network_proxy_socket = network_proxy_socket
+            .filter(|_| sandbox == SandboxType::LinuxSeccomp && !policy.has_full_network_access());
+        // Resource limits are applied by the Linux sandbox helper.
+        let resource_limits = resource_limits
+            .filter(|_| sandbox == SandboxType::LinuxSeccomp)
+            .copied();
+        // The overlay only stands in for the workspace the policy lets
+        // commands write to, and only the Linux sandbox can mount it.
+        let workspace_overlay = workspace_overlay.filter(|overlay| {
+            sandbox == SandboxType::LinuxSeccomp
+                && matches!(policy, SandboxPolicy::WorkspaceWrite { .. })
+                && overlay.covers(sandbox_policy_cwd)
+        });
         let mut env = spec.env;
-        if !policy.has_full_network_access() {
+        if !policy.has_full_network_access() && network_proxy_socket.is_none() {
             env.insert(
                 CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR.to_string(),
                 "1".to_string(),
@@ -131,8 +172,14 @@ impl SandboxManager {
             SandboxType::LinuxSeccomp => {
                 let exe = codex_linux_sandbox_exe
                     .ok_or(SandboxTransformError::MissingLinuxSandboxExecutable)?;
-                let mut args =
-                    create_linux_sandbox_command_args(command.clone(), policy, sandbox_policy_cwd);
+                let mut args = create_linux_sandbox_command_args(
+                    command.clone(),
+                    policy,
+                    sandbox_policy_cwd,
+                    network_proxy_socket,
+                    resource_limits.as_ref(),
+                    workspace_overlay,
+                );
                 let mut full_command = Vec::with_capacity(1 + args.len());
                 full_command.push(exe.to_string_lossy().to_string());
                 full_command.append(&mut args);
@@ -150,6 +197,21 @@ impl SandboxManager {
             // When building for non-Windows targets, this variant is never constructed.
             #[cfg(not(target_os = "windows"))]
             SandboxType::WindowsRestrictedToken => (command, HashMap::new(), None),
+            SandboxType::Container => {
+                let container = container.ok_or(SandboxTransformError::MissingContainerConfig)?;
+                (
+                    create_container_command(
+                        container,
+                        command,
+                        policy,
+                        sandbox_policy_cwd,
+                        &spec.cwd,
+                        &env,
+                    )?,
+                    HashMap::new(),
+                    None,
+                )
+            }
         };
 
         env.extend(sandbox_env);
@@ -163,6 +225,8 @@ impl SandboxManager {
             sandbox_permissions: spec.sandbox_permissions,
             justification: spec.justification,
             arg0: arg0_override,
+            resource_limits,
+            inherited_fds: InheritedFds::default(),
         })
     }
 
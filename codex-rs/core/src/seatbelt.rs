@@ -8,6 +8,7 @@ use tokio::process::Child;
 
 use crate::protocol::SandboxPolicy;
 use crate::spawn::CODEX_SANDBOX_ENV_VAR;
+use crate::spawn::InheritedFds;
 use crate::spawn::StdioPolicy;
 use crate::spawn::spawn_child_async;
 
@@ -39,6 +40,7 @@ pub async fn spawn_command_under_seatbelt(
         sandbox_policy,
         stdio_policy,
         env,
+        InheritedFds::default(),
     )
     .await
 }
@@ -105,10 +107,34 @@ pub(crate) fn create_seatbelt_command_args(
         }
     };
 
-    let file_read_policy = if sandbox_policy.has_full_disk_read_access() {
-        "; allow read-only file operations\n(allow file-read*)"
-    } else {
-        ""
+    let (file_read_policy, file_read_dir_params) = match sandbox_policy
+        .get_readable_roots_with_cwd(sandbox_policy_cwd)
+    {
+        None => (
+            "; allow read-only file operations\n(allow file-read*)".to_string(),
+            Vec::new(),
+        ),
+        Some(readable_roots) => {
+            let mut readable_folder_policies = vec![r#"(literal "/")"#.to_string()];
+            let mut file_read_params = Vec::new();
+            for (index, root) in readable_roots.iter().enumerate() {
+                // Canonicalize to avoid mismatches like /etc vs /private/etc on macOS.
+                let canonical_root = root
+                    .as_path()
+                    .canonicalize()
+                    .unwrap_or_else(|_| root.to_path_buf());
+                let root_param = format!("READABLE_ROOT_{index}");
+                readable_folder_policies.push(format!("(subpath (param \"{root_param}\"))"));
+                file_read_params.push((root_param, canonical_root));
+            }
+            // Metadata stays readable everywhere so that path resolution
+            // (e.g. `realpath`, `stat` of parent folders) keeps working.
+            let file_read_policy = format!(
+                "; allow reads under the readable roots only\n(allow file-read-metadata)\n(allow file-read*\n{}\n)",
+                readable_folder_policies.join(" ")
+            );
+            (file_read_policy, file_read_params)
+        }
     };
 
     // TODO(mbolin): apply_patch calls must also honor the SandboxPolicy.
@@ -122,7 +148,12 @@ pub(crate) fn create_seatbelt_command_args(
         "{MACOS_SEATBELT_BASE_POLICY}\n{file_read_policy}\n{file_write_policy}\n{network_policy}"
     );
 
-    let dir_params = [file_write_dir_params, macos_dir_params()].concat();
+    let dir_params = [
+        file_read_dir_params,
+        file_write_dir_params,
+        macos_dir_params(),
+    ]
+    .concat();
 
     let mut seatbelt_args: Vec<String> = vec!["-p".to_string(), full_policy];
     let definition_args = dir_params
@@ -200,6 +231,7 @@ mod tests {
             network_access: false,
             exclude_tmpdir_env_var: true,
             exclude_slash_tmp: true,
+            readable_roots: None,
         };
 
         // Create the Seatbelt command to wrap a shell command that tries to
@@ -364,6 +396,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn create_seatbelt_args_with_readable_roots() {
+        let tmp = TempDir::new().expect("tempdir");
+        let readable = tmp.path().join("readable");
+        let cwd = tmp.path().join("cwd");
+        fs::create_dir_all(&readable).expect("create readable");
+        fs::create_dir_all(&cwd).expect("create cwd");
+
+        let policy = SandboxPolicy::ReadOnly {
+            readable_roots: Some(vec![readable.as_path().try_into().unwrap()]),
+        };
+        let args = create_seatbelt_command_args(vec!["/bin/true".to_string()], &policy, &cwd);
+
+        let full_policy = &args[1];
+        assert!(!full_policy.contains("(allow file-read*)"));
+        assert!(full_policy.contains("(allow file-read-metadata)"));
+        assert!(full_policy.contains(r#"(subpath (param "READABLE_ROOT_0"))"#));
+        assert!(args.contains(&format!(
+            "-DREADABLE_ROOT_0={}",
+            readable.canonicalize().unwrap().to_string_lossy()
+        )));
+        assert!(args.contains(&format!(
+            "-DREADABLE_ROOT_1={}",
+            cwd.canonicalize().unwrap().to_string_lossy()
+        )));
+    }
+
     #[test]
     fn create_seatbelt_args_for_cwd_as_git_repo() {
         // Create a temporary workspace with two writable roots: one containing
@@ -382,6 +441,7 @@ mod tests {
         // `.codex` checks are done properly for cwd.
         let policy = SandboxPolicy::WorkspaceWrite {
             writable_roots: vec![],
+            readable_roots: None,
             network_access: false,
             exclude_tmpdir_env_var: false,
             exclude_slash_tmp: false,
@@ -0,0 +1,513 @@
+//! Masks secrets in tool output before it is recorded in history, written to
+//! the rollout file or forwarded to clients in tool and command events.
+//!
+//! Output that is streamed while a command runs is masked one chunk at a
+//! time, so a secret split across two chunks can reach clients in the live
+//! stream. The complete output, in `ExecCommandEnd`, history and the rollout,
+//! is always masked as a whole.
+//!
+//! Three sources of secrets are recognized: values of environment variables
+//! that the [`ShellEnvironmentPolicy`] excludes from commands (a command can
+//! still read them from a file such as `.env`), the regular expressions in
+//! `secret_redaction.patterns`, and, with `secret_redaction.high_entropy`,
+//! long random-looking tokens.
+
+use codex_protocol::models::FunctionCallOutputContentItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::EventMsg;
+use mcp_types::ContentBlock;
+use mcp_types::EmbeddedResourceResource;
+use regex_lite::Regex;
+use std::borrow::Cow;
+use tracing::warn;
+
+use crate::config::types::SecretRedaction;
+use crate::config::types::ShellEnvironmentPolicy;
+use crate::exec_env::excluded_env_vars;
+
+const REDACTED: &str = "[REDACTED]";
+
+/// Shorter environment values (`1`, `true`, ...) are too common to mask.
+const MIN_ENV_SECRET_LEN: usize = 8;
+
+/// Candidate tokens for the entropy check: base64/base62-ish runs of at least
+/// 32 characters.
+const HIGH_ENTROPY_TOKEN: &str = r"[A-Za-z0-9+/_=-]{32,}";
+
+/// Shannon entropy per character above which a token counts as random. Hex
+/// digests top out at 4 bits, and identifiers and paths rarely exceed 4.1.
+const HIGH_ENTROPY_BITS_PER_CHAR: f64 = 4.3;
+
+pub(crate) struct SecretRedactor {
+    /// `(variable name, value)` pairs, longest value first so a value that
+    /// contains another one is masked as a whole.
+    env_values: Vec<(String, String)>,
+    patterns: Vec<Regex>,
+    high_entropy: Option<Regex>,
+}
+
+impl SecretRedactor {
+    /// Returns `None` when redaction is disabled or there is nothing to mask.
+    pub(crate) fn new(
+        config: &SecretRedaction,
+        env_policy: &ShellEnvironmentPolicy,
+    ) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+        Self::from_parts(
+            excluded_env_vars(env_policy),
+            &config.patterns,
+            config.high_entropy,
+        )
+    }
+
+    fn from_parts(
+        env_vars: Vec<(String, String)>,
+        patterns: &[String],
+        high_entropy: bool,
+    ) -> Option<Self> {
+        let mut env_values: Vec<(String, String)> = env_vars
+            .into_iter()
+            .filter(|(_, value)| value.len() >= MIN_ENV_SECRET_LEN)
+            .collect();
+        env_values.sort_by(|(_, a), (_, b)| b.len().cmp(&a.len()));
+
+        // Patterns are validated when the config is loaded.
+        let patterns: Vec<Regex> = patterns
+            .iter()
+            .filter_map(|pattern| match Regex::new(pattern) {
+                Ok(regex) => Some(regex),
+                Err(err) => {
+                    warn!("ignoring invalid secret_redaction pattern `{pattern}`: {err}");
+                    None
+                }
+            })
+            .collect();
+
+        let high_entropy = if high_entropy {
+            Regex::new(HIGH_ENTROPY_TOKEN).ok()
+        } else {
+            None
+        };
+
+        if env_values.is_empty() && patterns.is_empty() && high_entropy.is_none() {
+            return None;
+        }
+        Some(Self {
+            env_values,
+            patterns,
+            high_entropy,
+        })
+    }
+
+    /// Masks secrets in the outputs of tool calls. Returns the items to
+    /// record, borrowed when nothing was masked, the ids of the calls whose
+    /// output was masked and the number of masked values.
+    pub(crate) fn redact_tool_outputs<'a>(
+        &self,
+        items: &'a [ResponseItem],
+    ) -> (Cow<'a, [ResponseItem]>, Vec<String>, usize) {
+        let mut redacted: Option<Vec<ResponseItem>> = None;
+        let mut call_ids = Vec::new();
+        let mut total = 0;
+        for (idx, item) in items.iter().enumerate() {
+            if let Some((item, count)) = self.redact_item(item) {
+                if let ResponseItem::FunctionCallOutput { call_id, .. }
+                | ResponseItem::CustomToolCallOutput { call_id, .. } = &item
+                {
+                    call_ids.push(call_id.clone());
+                }
+                redacted.get_or_insert_with(|| items.to_vec())[idx] = item;
+                total += count;
+            }
+        }
+        match redacted {
+            Some(items) => (Cow::Owned(items), call_ids, total),
+            None => (Cow::Borrowed(items), call_ids, 0),
+        }
+    }
+
+    /// Masks secrets in the tool and command output carried by events. The
+    /// masking is recorded by the `SecretsRedacted` event emitted for the tool
+    /// output, so it is not counted here.
+    pub(crate) fn redact_event(&self, msg: &mut EventMsg) {
+        match msg {
+            EventMsg::ExecCommandOutputDelta(event) => {
+                self.redact_bytes_in_place(&mut event.chunk);
+            }
+            EventMsg::ExecCommandEnd(event) => {
+                for text in [
+                    &mut event.stdout,
+                    &mut event.stderr,
+                    &mut event.aggregated_output,
+                    &mut event.formatted_output,
+                ] {
+                    self.redact_in_place(text);
+                }
+            }
+            EventMsg::TerminalInteraction(event) => {
+                self.redact_in_place(&mut event.stdin);
+            }
+            EventMsg::McpToolCallEnd(event) => match &mut event.result {
+                Ok(result) => {
+                    for block in &mut result.content {
+                        match block {
+                            ContentBlock::TextContent(content) => {
+                                self.redact_in_place(&mut content.text);
+                            }
+                            ContentBlock::EmbeddedResource(embedded) => {
+                                if let EmbeddedResourceResource::TextResourceContents(resource) =
+                                    &mut embedded.resource
+                                {
+                                    self.redact_in_place(&mut resource.text);
+                                }
+                            }
+                            ContentBlock::ImageContent(_)
+                            | ContentBlock::AudioContent(_)
+                            | ContentBlock::ResourceLink(_) => {}
+                        }
+                    }
+                    if let Some(structured) = &mut result.structured_content {
+                        self.redact_json(structured);
+                    }
+                }
+                Err(message) => {
+                    self.redact_in_place(message);
+                }
+            },
+            EventMsg::CallToolResponse(event) => match &mut event.result {
+                Ok(text) | Err(text) => {
+                    self.redact_in_place(text);
+                }
+            },
+            _ => {}
+        }
+    }
+
+    fn redact_json(&self, value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::String(text) => {
+                self.redact_in_place(text);
+            }
+            serde_json::Value::Array(values) => {
+                for value in values {
+                    self.redact_json(value);
+                }
+            }
+            serde_json::Value::Object(map) => {
+                for value in map.values_mut() {
+                    self.redact_json(value);
+                }
+            }
+            serde_json::Value::Null | serde_json::Value::Bool(_) | serde_json::Value::Number(_) => {
+            }
+        }
+    }
+
+    /// Masks secrets in raw command output. Output that is not valid UTF-8 is
+    /// only rewritten when it contains a secret.
+    pub(crate) fn redact_bytes_in_place(&self, bytes: &mut Vec<u8>) {
+        if let Some((redacted, _)) = self.redact(&String::from_utf8_lossy(bytes)) {
+            *bytes = redacted.into_bytes();
+        }
+    }
+
+    fn redact_item(&self, item: &ResponseItem) -> Option<(ResponseItem, usize)> {
+        match item {
+            ResponseItem::FunctionCallOutput { call_id, output } => {
+                let mut output = output.clone();
+                let mut count = self.redact_in_place(&mut output.content);
+                for content_item in output.content_items.iter_mut().flatten() {
+                    if let FunctionCallOutputContentItem::InputText { text } = content_item {
+                        count += self.redact_in_place(text);
+                    }
+                }
+                (count > 0).then(|| {
+                    (
+                        ResponseItem::FunctionCallOutput {
+                            call_id: call_id.clone(),
+                            output,
+                        },
+                        count,
+                    )
+                })
+            }
+            ResponseItem::CustomToolCallOutput { call_id, output } => {
+                let (output, count) = self.redact(output)?;
+                Some((
+                    ResponseItem::CustomToolCallOutput {
+                        call_id: call_id.clone(),
+                        output,
+                    },
+                    count,
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    fn redact_in_place(&self, text: &mut String) -> usize {
+        match self.redact(text) {
+            Some((redacted, count)) => {
+                *text = redacted;
+                count
+            }
+            None => 0,
+        }
+    }
+
+    /// Returns the masked text and the number of masked values, or `None`
+    /// when `text` contains no secrets.
+    fn redact(&self, text: &str) -> Option<(String, usize)> {
+        let mut text = Cow::Borrowed(text);
+        let mut count = 0;
+        for (name, value) in &self.env_values {
+            let matches = text.matches(value.as_str()).count();
+            if matches > 0 {
+                text = Cow::Owned(text.replace(value.as_str(), &format!("[REDACTED:{name}]")));
+                count += matches;
+            }
+        }
+        for pattern in &self.patterns {
+            count += mask_matches(&mut text, pattern, |_| true);
+        }
+        if let Some(tokens) = &self.high_entropy {
+            count += mask_matches(&mut text, tokens, is_high_entropy);
+        }
+        (count > 0).then(|| (text.into_owned(), count))
+    }
+}
+
+fn mask_matches(text: &mut Cow<'_, str>, regex: &Regex, is_secret: fn(&str) -> bool) -> usize {
+    let mut count = 0;
+    let masked = regex
+        .replace_all(text, |caps: &regex_lite::Captures<'_>| {
+            let matched = &caps[0];
+            if is_secret(matched) {
+                count += 1;
+                REDACTED.to_string()
+            } else {
+                matched.to_string()
+            }
+        })
+        .into_owned();
+    if count > 0 {
+        *text = Cow::Owned(masked);
+    }
+    count
+}
+
+/// Random keys mix upper case, lower case and digits and spread their
+/// characters evenly; words, paths and hex digests do not.
+fn is_high_entropy(token: &str) -> bool {
+    let has = |class: fn(&char) -> bool| token.chars().any(|c| class(&c));
+    if !(has(char::is_ascii_uppercase)
+        && has(char::is_ascii_lowercase)
+        && has(char::is_ascii_digit))
+    {
+        return false;
+    }
+
+    let mut counts = [0usize; 256];
+    for byte in token.bytes() {
+        counts[usize::from(byte)] += 1;
+    }
+    let len = token.len() as f64;
+    let entropy: f64 = counts
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f64 / len;
+            -p * p.log2()
+        })
+        .sum();
+    entropy > HIGH_ENTROPY_BITS_PER_CHAR
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::FunctionCallOutputPayload;
+    use pretty_assertions::assert_eq;
+
+    fn env(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn masks_excluded_env_values_and_skips_short_ones() {
+        let redactor = SecretRedactor::from_parts(
+            env(&[
+                ("OPENAI_API_KEY", "sk-live-abcdef123456"),
+                ("DEBUG_TOKEN", "1"),
+            ]),
+            &[],
+            false,
+        )
+        .expect("redactor");
+
+        assert_eq!(
+            redactor.redact("OPENAI_API_KEY=sk-live-abcdef123456\nDEBUG_TOKEN=1\n"),
+            Some((
+                "OPENAI_API_KEY=[REDACTED:OPENAI_API_KEY]\nDEBUG_TOKEN=1\n".to_string(),
+                1
+            ))
+        );
+        assert_eq!(redactor.redact("nothing to see"), None);
+    }
+
+    #[test]
+    fn masks_configured_patterns() {
+        let redactor =
+            SecretRedactor::from_parts(Vec::new(), &["ghp_[A-Za-z0-9]{8}".to_string()], false)
+                .expect("redactor");
+
+        assert_eq!(
+            redactor.redact("a ghp_abcd1234 b ghp_wxyz9876"),
+            Some(("a [REDACTED] b [REDACTED]".to_string(), 2))
+        );
+    }
+
+    #[test]
+    fn high_entropy_masks_random_keys_but_not_digests_or_identifiers() {
+        let redactor = SecretRedactor::from_parts(Vec::new(), &[], true).expect("redactor");
+
+        assert_eq!(
+            redactor.redact("aws_secret_access_key = wJalrXUtnFEMI/K7MDENG/bPxRfiCYEXAMPLEKEY"),
+            Some(("aws_secret_access_key = [REDACTED]".to_string(), 1))
+        );
+        assert_eq!(
+            redactor.redact(
+                "commit 4b825dc642cb6eb9a060e54bf8d69288fbee4904 AbstractSingletonProxyFactoryBean2"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn redact_tool_outputs_only_touches_outputs() {
+        let redactor = SecretRedactor::from_parts(env(&[("SECRET", "hunter2hunter2")]), &[], false)
+            .expect("redactor");
+        let call = ResponseItem::FunctionCall {
+            id: None,
+            name: "shell".to_string(),
+            arguments: "{\"command\":[\"echo\",\"hunter2hunter2\"]}".to_string(),
+            call_id: "call-1".to_string(),
+        };
+        let output = ResponseItem::FunctionCallOutput {
+            call_id: "call-1".to_string(),
+            output: FunctionCallOutputPayload {
+                content: "hunter2hunter2".to_string(),
+                ..Default::default()
+            },
+        };
+
+        let items = [call.clone(), output];
+        let (items, call_ids, count) = redactor.redact_tool_outputs(&items);
+
+        assert_eq!(count, 1);
+        assert_eq!(call_ids, vec!["call-1".to_string()]);
+        assert_eq!(
+            items.into_owned(),
+            vec![
+                call.clone(),
+                ResponseItem::FunctionCallOutput {
+                    call_id: "call-1".to_string(),
+                    output: FunctionCallOutputPayload {
+                        content: "[REDACTED:SECRET]".to_string(),
+                        ..Default::default()
+                    },
+                },
+            ]
+        );
+
+        let items = [call];
+        let (unchanged, _, count) = redactor.redact_tool_outputs(&items);
+        assert_eq!(count, 0);
+        assert!(matches!(unchanged, Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn redact_exec_event_masks_streamed_chunks() {
+        use codex_protocol::protocol::ExecCommandOutputDeltaEvent;
+        use codex_protocol::protocol::ExecOutputStream;
+
+        let redactor = SecretRedactor::from_parts(env(&[("SECRET", "hunter2hunter2")]), &[], false)
+            .expect("redactor");
+        let mut msg = EventMsg::ExecCommandOutputDelta(ExecCommandOutputDeltaEvent {
+            call_id: "call-1".to_string(),
+            stream: ExecOutputStream::Stdout,
+            chunk: b"token=hunter2hunter2\n".to_vec(),
+        });
+
+        redactor.redact_event(&mut msg);
+
+        let EventMsg::ExecCommandOutputDelta(event) = msg else {
+            panic!("expected an output delta");
+        };
+        assert_eq!(event.chunk, b"token=[REDACTED:SECRET]\n".to_vec());
+    }
+
+    #[test]
+    fn redact_event_masks_mcp_and_direct_tool_results() {
+        use codex_protocol::protocol::CallToolResponseEvent;
+        use codex_protocol::protocol::McpInvocation;
+        use codex_protocol::protocol::McpToolCallEndEvent;
+        use mcp_types::CallToolResult;
+        use mcp_types::TextContent;
+
+        let redactor = SecretRedactor::from_parts(env(&[("SECRET", "hunter2hunter2")]), &[], false)
+            .expect("redactor");
+        let mut mcp = EventMsg::McpToolCallEnd(McpToolCallEndEvent {
+            call_id: "call-1".to_string(),
+            invocation: McpInvocation {
+                server: "server".to_string(),
+                tool: "tool".to_string(),
+                arguments: None,
+            },
+            duration: std::time::Duration::ZERO,
+            result: Ok(CallToolResult {
+                content: vec![ContentBlock::TextContent(TextContent {
+                    annotations: None,
+                    text: "key hunter2hunter2".to_string(),
+                    r#type: "text".to_string(),
+                })],
+                is_error: None,
+                structured_content: Some(serde_json::json!({ "key": ["hunter2hunter2"] })),
+            }),
+        });
+        let mut direct = EventMsg::CallToolResponse(CallToolResponseEvent {
+            call_id: "call-2".to_string(),
+            result: Err("failed with hunter2hunter2".to_string()),
+        });
+
+        redactor.redact_event(&mut mcp);
+        redactor.redact_event(&mut direct);
+
+        let EventMsg::McpToolCallEnd(McpToolCallEndEvent {
+            result: Ok(result), ..
+        }) = mcp
+        else {
+            panic!("expected a successful MCP tool call");
+        };
+        let [ContentBlock::TextContent(text)] = result.content.as_slice() else {
+            panic!("expected a single text block");
+        };
+        assert_eq!(text.text, "key [REDACTED:SECRET]");
+        assert_eq!(
+            result.structured_content,
+            Some(serde_json::json!({ "key": ["[REDACTED:SECRET]"] }))
+        );
+        let EventMsg::CallToolResponse(direct) = direct else {
+            panic!("expected a tool response");
+        };
+        assert_eq!(
+            direct.result,
+            Err("failed with [REDACTED:SECRET]".to_string())
+        );
+    }
+}
@@ -1,4 +1,10 @@
 use std::collections::HashMap;
+#[cfg(unix)]
+use std::os::fd::AsRawFd;
+#[cfg(unix)]
+use std::os::fd::OwnedFd;
+#[cfg(unix)]
+use std::os::fd::RawFd;
 use std::path::PathBuf;
 use std::process::Stdio;
 use tokio::process::Child;
@@ -22,6 +28,42 @@ pub const CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR: &str = "CODEX_SANDBOX_NETWORK_
 /// accommodate sandboxing configuration and other sandboxing mechanisms.
 pub const CODEX_SANDBOX_ENV_VAR: &str = "CODEX_SANDBOX";
 
+/// Open files a child receives under fixed descriptor numbers, next to its
+/// stdio. Descriptors survive `execvp`, so they also reach commands run by a
+/// sandbox helper.
+#[derive(Debug, Default)]
+pub struct InheritedFds {
+    #[cfg(unix)]
+    fds: Vec<(RawFd, OwnedFd)>,
+}
+
+impl InheritedFds {
+    /// Hands `fd` to the child as descriptor `target`, which must be above 2.
+    #[cfg(unix)]
+    pub(crate) fn insert(&mut self, target: RawFd, fd: OwnedFd) -> std::io::Result<()> {
+        // Keep the source out of the range of targets so that the `dup2`
+        // calls in the child cannot clobber each other.
+        let fd = dup_above(&fd, MIN_INHERITED_SOURCE_FD)?;
+        self.fds.push((target, fd));
+        Ok(())
+    }
+}
+
+/// Sources of inherited descriptors are moved at or above this number.
+#[cfg(unix)]
+const MIN_INHERITED_SOURCE_FD: RawFd = 64;
+
+#[cfg(unix)]
+fn dup_above(fd: &OwnedFd, min: RawFd) -> std::io::Result<OwnedFd> {
+    use std::os::fd::FromRawFd;
+
+    let fd = unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_DUPFD_CLOEXEC, min) };
+    if fd == -1 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum StdioPolicy {
     RedirectForShellTool,
@@ -35,6 +77,7 @@ pub enum StdioPolicy {
 /// For now, we take `SandboxPolicy` as a parameter to spawn_child() because
 /// we need to determine whether to set the
 /// `CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR` environment variable.
+#[allow(clippy::too_many_arguments)]
 pub(crate) async fn spawn_child_async(
     program: PathBuf,
     args: Vec<String>,
@@ -43,6 +86,7 @@ pub(crate) async fn spawn_child_async(
     sandbox_policy: &SandboxPolicy,
     stdio_policy: StdioPolicy,
     env: HashMap<String, String>,
+    #[cfg_attr(not(unix), allow(unused_variables))] inherited_fds: InheritedFds,
 ) -> std::io::Result<Child> {
     trace!(
         "spawn_child_async: {program:?} {args:?} {arg0:?} {cwd:?} {sandbox_policy:?} {stdio_policy:?} {env:?}"
@@ -69,11 +113,23 @@ pub(crate) async fn spawn_child_async(
         let set_process_group = matches!(stdio_policy, StdioPolicy::RedirectForShellTool);
         #[cfg(target_os = "linux")]
         let parent_pid = libc::getpid();
+        let fd_targets = inherited_fds
+            .fds
+            .iter()
+            .map(|(target, fd)| (*target, fd.as_raw_fd()))
+            .collect::<Vec<_>>();
         cmd.pre_exec(move || {
             if set_process_group && libc::setpgid(0, 0) == -1 {
                 return Err(std::io::Error::last_os_error());
             }
 
+            // `dup2` clears close-on-exec on the target.
+            for (target, source) in &fd_targets {
+                if libc::dup2(*source, *target) == -1 {
+                    return Err(std::io::Error::last_os_error());
+                }
+            }
+
             // This relies on prctl(2), so it only works on Linux.
             #[cfg(target_os = "linux")]
             {
@@ -6,10 +6,14 @@ use crate::agent::AgentControl;
 use crate::exec_policy::ExecPolicyManager;
 use crate::mcp_connection_manager::McpConnectionManager;
 use crate::models_manager::manager::ModelsManager;
+use crate::network_proxy::NetworkProxy;
+use crate::persistent_shell::PersistentShell;
+use crate::secret_redaction::SecretRedactor;
 use crate::skills::SkillsManager;
 use crate::tools::sandboxing::ApprovalStore;
 use crate::unified_exec::UnifiedExecProcessManager;
 use crate::user_notification::UserNotifier;
+use crate::workspace_overlay::WorkspaceOverlay;
 use codex_otel::OtelManager;
 use tokio::sync::Mutex;
 use tokio::sync::RwLock;
@@ -30,4 +34,14 @@ pub(crate) struct SessionServices {
     pub(crate) tool_approvals: Mutex<ApprovalStore>,
     pub(crate) skills_manager: Arc<SkillsManager>,
     pub(crate) agent_control: AgentControl,
+    /// Proxy that sandboxed commands use to reach `sandbox_network_allowlist`.
+    pub(crate) network_proxy: Option<NetworkProxy>,
+    /// Copy-on-write layer that sandboxed commands write to when
+    /// `sandbox_workspace_write.overlay` is enabled.
+    pub(crate) workspace_overlay: Option<WorkspaceOverlay>,
+    /// Shell state carried between shell tool calls with
+    /// `features.persistent_shell`.
+    pub(crate) persistent_shell: Option<PersistentShell>,
+    /// Masks secrets in tool output before it is recorded or streamed.
+    pub(crate) secret_redactor: Option<Arc<SecretRedactor>>,
 }
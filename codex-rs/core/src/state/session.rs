@@ -8,6 +8,7 @@ use crate::protocol::RateLimitSnapshot;
 use crate::protocol::TokenUsage;
 use crate::protocol::TokenUsageInfo;
 use crate::truncate::TruncationPolicy;
+use codex_utils_tokenizer::Encoding;
 
 /// Persistent, session-scoped state previously stored directly on `Session`.
 pub(crate) struct SessionState {
@@ -44,6 +45,10 @@ impl SessionState {
         self.current_reasoning_content = None;
     }
 
+    pub(crate) fn set_encoding(&mut self, encoding: Encoding) {
+        self.history.set_encoding(encoding);
+    }
+
     pub(crate) fn record_items<I>(&mut self, items: I, policy: TruncationPolicy)
     where
         I: IntoIterator,
@@ -0,0 +1,202 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use codex_async_utils::CancelErr;
+use codex_async_utils::OrCancelExt;
+use codex_protocol::models::ResponseInputItem;
+use codex_protocol::user_input::UserInput;
+use codex_utils_absolute_path::AbsolutePathBuf;
+use tokio_util::sync::CancellationToken;
+
+use crate::codex::TurnContext;
+use crate::protocol::CallToolResponseEvent;
+use crate::protocol::EventMsg;
+use crate::state::TaskKind;
+use crate::tools::ToolRouter;
+use crate::tools::context::ToolPayload;
+use crate::tools::router::ToolCall;
+use crate::tools::spec::DIRECT_TOOL_NAMES;
+use crate::tools::spec::ToolsConfig;
+use crate::turn_diff_tracker::TurnDiffTracker;
+
+use super::SessionTask;
+use super::SessionTaskContext;
+
+/// Runs a single built-in tool on behalf of a client rather than the model.
+/// The call goes through the regular tool router, so sandboxing, approvals
+/// and exec policy apply exactly as they would during a model turn.
+pub(crate) struct DirectToolCallTask {
+    call_id: String,
+    tool: String,
+    arguments: String,
+}
+
+impl DirectToolCallTask {
+    pub(crate) fn new(call_id: String, tool: String, arguments: String) -> Self {
+        Self {
+            call_id,
+            tool,
+            arguments,
+        }
+    }
+}
+
+#[async_trait]
+impl SessionTask for DirectToolCallTask {
+    fn kind(&self) -> TaskKind {
+        TaskKind::Regular
+    }
+
+    async fn run(
+        self: Arc<Self>,
+        session: Arc<SessionTaskContext>,
+        turn_context: Arc<TurnContext>,
+        _input: Vec<UserInput>,
+        cancellation_token: CancellationToken,
+    ) -> Option<String> {
+        let _ = session
+            .session
+            .services
+            .otel_manager
+            .counter("codex.task.direct_tool", 1, &[]);
+        let session = session.clone_session();
+
+        let result = if !DIRECT_TOOL_NAMES.contains(&self.tool.as_str()) {
+            Err(format!("unsupported tool: {}", self.tool))
+        } else if let Err(message) =
+            check_readable_path(turn_context.as_ref(), &self.tool, &self.arguments)
+        {
+            Err(message)
+        } else {
+            let router = ToolRouter::from_config(&ToolsConfig::for_direct_calls(), None);
+            let call = ToolCall {
+                tool_name: self.tool.clone(),
+                call_id: self.call_id.clone(),
+                payload: ToolPayload::Function {
+                    arguments: self.arguments.clone(),
+                },
+            };
+            let tracker = Arc::new(tokio::sync::Mutex::new(TurnDiffTracker::new()));
+            match router
+                .dispatch_tool_call(
+                    Arc::clone(&session),
+                    Arc::clone(&turn_context),
+                    tracker,
+                    call,
+                )
+                .or_cancel(&cancellation_token)
+                .await
+            {
+                Ok(Ok(output)) => tool_output_to_result(output),
+                Ok(Err(err)) => Err(err.to_string()),
+                Err(CancelErr::Cancelled) => Err("tool call aborted".to_string()),
+            }
+        };
+
+        session
+            .send_event(
+                turn_context.as_ref(),
+                EventMsg::CallToolResponse(CallToolResponseEvent {
+                    call_id: self.call_id.clone(),
+                    result,
+                }),
+            )
+            .await;
+        None
+    }
+}
+
+/// `read_file`, `list_dir` and `grep_files` read from this process rather
+/// than from a sandboxed child, so the path a client hands them is checked
+/// against the sandbox's readable roots here. Policies without readable roots
+/// leave reads unrestricted, as they do for `shell`.
+fn check_readable_path(
+    turn_context: &TurnContext,
+    tool: &str,
+    arguments: &str,
+) -> Result<(), String> {
+    let key = match tool {
+        "read_file" => "file_path",
+        "list_dir" => "dir_path",
+        "grep_files" => "path",
+        _ => return Ok(()),
+    };
+    let Some(roots) = turn_context
+        .sandbox_policy
+        .get_readable_roots_with_cwd(&turn_context.cwd)
+    else {
+        return Ok(());
+    };
+    // Malformed arguments fall through to the handler, which reports them.
+    let path = serde_json::from_str::<serde_json::Value>(arguments)
+        .ok()
+        .and_then(|args| args.get(key)?.as_str().map(str::to_owned));
+    ensure_within_roots(&turn_context.resolve_path(path), &roots)
+}
+
+fn ensure_within_roots(path: &Path, roots: &[AbsolutePathBuf]) -> Result<(), String> {
+    // Resolve symlinks and `..` so neither can step outside a root.
+    let resolved = dunce::canonicalize(path)
+        .map_err(|err| format!("unable to access `{}`: {err}", path.display()))?;
+    let readable = roots.iter().any(|root| {
+        let root = dunce::canonicalize(root).unwrap_or_else(|_| root.to_path_buf());
+        resolved.starts_with(root)
+    });
+    if readable {
+        Ok(())
+    } else {
+        Err(format!(
+            "`{}` is outside the sandbox's readable roots",
+            path.display()
+        ))
+    }
+}
+
+fn tool_output_to_result(output: ResponseInputItem) -> Result<String, String> {
+    match output {
+        ResponseInputItem::FunctionCallOutput { output, .. } => {
+            if output.success == Some(false) {
+                Err(output.content)
+            } else {
+                Ok(output.content)
+            }
+        }
+        ResponseInputItem::CustomToolCallOutput { output, .. } => Ok(output),
+        ResponseInputItem::McpToolCallOutput { .. } | ResponseInputItem::Message { .. } => {
+            Err("tool returned an unexpected output".to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    #[test]
+    fn ensure_within_roots_rejects_paths_outside_roots() {
+        let root = TempDir::new().expect("root dir");
+        let outside = TempDir::new().expect("outside dir");
+        let file = root.path().join("inside.txt");
+        std::fs::write(&file, "x").expect("write inside file");
+        let roots = vec![AbsolutePathBuf::from_absolute_path(root.path()).expect("absolute root")];
+
+        assert_eq!(ensure_within_roots(&file, &roots), Ok(()));
+        assert!(ensure_within_roots(outside.path(), &roots).is_err());
+        assert!(ensure_within_roots(&root.path().join(".."), &roots).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn ensure_within_roots_resolves_symlinks() {
+        let root = TempDir::new().expect("root dir");
+        let outside = TempDir::new().expect("outside dir");
+        let link = root.path().join("escape");
+        std::os::unix::fs::symlink(outside.path(), &link).expect("create symlink");
+        let roots = vec![AbsolutePathBuf::from_absolute_path(root.path()).expect("absolute root")];
+
+        assert!(ensure_within_roots(&link, &roots).is_err());
+    }
+}
@@ -1,4 +1,5 @@
 mod compact;
+mod direct_tool;
 mod ghost_snapshot;
 mod regular;
 mod review;
@@ -24,12 +25,14 @@ use crate::protocol::EventMsg;
 use crate::protocol::TurnAbortReason;
 use crate::protocol::TurnAbortedEvent;
 use crate::protocol::TurnCompleteEvent;
+use crate::protocol::WorkspaceOverlayDiffEvent;
 use crate::state::ActiveTurn;
 use crate::state::RunningTask;
 use crate::state::TaskKind;
 use codex_protocol::user_input::UserInput;
 
 pub(crate) use compact::CompactTask;
+pub(crate) use direct_tool::DirectToolCallTask;
 pub(crate) use ghost_snapshot::GhostSnapshotTask;
 pub(crate) use regular::RegularTask;
 pub(crate) use review::ReviewTask;
@@ -179,11 +182,30 @@ impl Session {
         drop(active);
         if should_close_processes {
             self.close_unified_exec_processes().await;
+            self.send_workspace_overlay_diff(turn_context.as_ref())
+                .await;
         }
         let event = EventMsg::TurnComplete(TurnCompleteEvent { last_agent_message });
         self.send_event(turn_context.as_ref(), event).await;
     }
 
+    /// Shows the changes sandboxed commands left in the workspace overlay so
+    /// the user can commit or discard them.
+    async fn send_workspace_overlay_diff(&self, turn_context: &TurnContext) {
+        let Some(overlay) = self.services.workspace_overlay.as_ref() else {
+            return;
+        };
+        match overlay.unified_diff() {
+            Ok(Some(unified_diff)) => {
+                let event =
+                    EventMsg::WorkspaceOverlayDiff(WorkspaceOverlayDiffEvent { unified_diff });
+                self.send_event(turn_context, event).await;
+            }
+            Ok(None) => {}
+            Err(err) => warn!("failed to compute workspace overlay diff: {err:#}"),
+        }
+    }
+
     async fn register_new_active_task(&self, task: RunningTask) {
         let mut active = self.active_turn.lock().await;
         let mut turn = ActiveTurn::default();
@@ -25,6 +25,7 @@ use crate::protocol::SandboxPolicy;
 use crate::protocol::TurnStartedEvent;
 use crate::sandboxing::ExecEnv;
 use crate::sandboxing::SandboxPermissions;
+use crate::spawn::InheritedFds;
 use crate::state::TaskKind;
 use crate::tools::format_exec_output_str;
 use crate::user_shell_command::user_shell_command_record_item;
@@ -110,12 +111,15 @@ impl SessionTask for UserShellCommandTask {
             sandbox_permissions: SandboxPermissions::UseDefault,
             justification: None,
             arg0: None,
+            resource_limits: None,
+            inherited_fds: InheritedFds::default(),
         };
 
         let stdout_stream = Some(StdoutStream {
             sub_id: turn_context.sub_id.clone(),
             call_id: call_id.clone(),
             tx_event: session.get_tx_event(),
+            secret_redactor: session.services.secret_redactor.clone(),
         });
 
         let sandbox_policy = SandboxPolicy::DangerFullAccess;
@@ -133,6 +137,8 @@ impl SessionTask for UserShellCommandTask {
                     aggregated_output: StreamOutput::new(aborted_message.clone()),
                     duration: Duration::ZERO,
                     timed_out: false,
+                    resource_limit_exceeded: None,
+                    shell_state: None,
                 };
                 let output_items = [user_shell_command_record_item(
                     &raw_command,
@@ -209,6 +215,8 @@ impl SessionTask for UserShellCommandTask {
                     aggregated_output: StreamOutput::new(message.clone()),
                     duration: Duration::ZERO,
                     timed_out: false,
+                    resource_limit_exceeded: None,
+                    shell_state: None,
                 };
                 session
                     .send_event(
@@ -59,6 +59,28 @@ fn to_abs_path(cwd: &Path, path: &Path) -> Option<AbsolutePathBuf> {
     AbsolutePathBuf::resolve_path_against_base(path, cwd).ok()
 }
 
+/// Patches are verified against the workspace on disk, which does not see
+/// the workspace overlay's pending changes, so files the overlay changed are
+/// only patchable once the overlay is committed or discarded.
+fn reject_overlay_conflicts(
+    session: &Session,
+    action: &ApplyPatchAction,
+) -> Result<(), FunctionCallError> {
+    let Some(overlay) = session.services.workspace_overlay.as_ref() else {
+        return Ok(());
+    };
+    match file_paths_for_action(action)
+        .into_iter()
+        .find(|path| overlay.holds_changes_to(path.as_path()))
+    {
+        Some(path) => Err(FunctionCallError::RespondToModel(format!(
+            "patch rejected: {} has uncommitted changes in the workspace overlay; ask the user to commit or discard them first",
+            path.display()
+        ))),
+        None => Ok(()),
+    }
+}
+
 #[async_trait]
 impl ToolHandler for ApplyPatchHandler {
     fn kind(&self) -> ToolKind {
@@ -105,6 +127,7 @@ impl ToolHandler for ApplyPatchHandler {
         let command = vec!["apply_patch".to_string(), patch_input.clone()];
         match codex_apply_patch::maybe_parse_apply_patch_verified(&command, &cwd) {
             codex_apply_patch::MaybeApplyPatchVerified::Body(changes) => {
+                reject_overlay_conflicts(session.as_ref(), &changes)?;
                 match apply_patch::apply_patch(turn.as_ref(), changes).await {
                     InternalApplyPatchInvocation::Output(item) => {
                         let content = item?;
@@ -195,6 +218,7 @@ pub(crate) async fn intercept_apply_patch(
 ) -> Result<Option<ToolOutput>, FunctionCallError> {
     match codex_apply_patch::maybe_parse_apply_patch_verified(command, cwd) {
         codex_apply_patch::MaybeApplyPatchVerified::Body(changes) => {
+            reject_overlay_conflicts(session, &changes)?;
             session
                 .record_model_warning(
                     format!("apply_patch was requested via {tool_name}. Use the apply_patch tool instead of exec_command."),
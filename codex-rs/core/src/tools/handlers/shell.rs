@@ -2,6 +2,7 @@ use async_trait::async_trait;
 use codex_protocol::models::ShellCommandToolCallParams;
 use codex_protocol::models::ShellToolCallParams;
 use serde::Deserialize;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use crate::codex::TurnContext;
@@ -9,6 +10,7 @@ use crate::exec::ExecParams;
 use crate::exec_env::create_env;
 use crate::function_tool::FunctionCallError;
 use crate::is_safe_command::is_known_safe_command;
+use crate::persistent_shell::PersistentShell;
 use crate::protocol::ExecCommandSource;
 use crate::shell::Shell;
 use crate::tools::context::ToolInvocation;
@@ -50,10 +52,14 @@ impl ShellHandler {
         })
     }
 
-    fn to_exec_params(params: ShellToolCallParams, turn_context: &TurnContext) -> ExecParams {
+    fn to_exec_params(
+        params: ShellToolCallParams,
+        session: &crate::codex::Session,
+        turn_context: &TurnContext,
+    ) -> ExecParams {
         ExecParams {
             command: params.command,
-            cwd: turn_context.resolve_path(params.workdir.clone()),
+            cwd: resolve_workdir(params.workdir, session, turn_context),
             expiration: params.timeout_ms.into(),
             env: create_env(&turn_context.shell_environment_policy),
             sandbox_permissions: params.sandbox_permissions.unwrap_or_default(),
@@ -79,7 +85,7 @@ impl ShellCommandHandler {
 
         ExecParams {
             command,
-            cwd: turn_context.resolve_path(params.workdir.clone()),
+            cwd: resolve_workdir(params.workdir, session, turn_context),
             expiration: params.timeout_ms.into(),
             env: create_env(&turn_context.shell_environment_policy),
             sandbox_permissions: params.sandbox_permissions.unwrap_or_default(),
@@ -89,6 +95,25 @@ impl ShellCommandHandler {
     }
 }
 
+/// Without an explicit `workdir`, a persistent shell continues in the
+/// directory the previous command left it in.
+fn resolve_workdir(
+    workdir: Option<String>,
+    session: &crate::codex::Session,
+    turn_context: &TurnContext,
+) -> PathBuf {
+    if workdir.is_none()
+        && let Some(cwd) = session
+            .services
+            .persistent_shell
+            .as_ref()
+            .and_then(PersistentShell::cwd)
+    {
+        return cwd;
+    }
+    turn_context.resolve_path(workdir)
+}
+
 #[async_trait]
 impl ToolHandler for ShellHandler {
     fn kind(&self) -> ToolKind {
@@ -128,7 +153,7 @@ impl ToolHandler for ShellHandler {
         match payload {
             ToolPayload::Function { arguments } => {
                 let params: ShellToolCallParams = Self::parse_shell_arguments(&arguments)?;
-                let exec_params = Self::to_exec_params(params, turn.as_ref());
+                let exec_params = Self::to_exec_params(params, session.as_ref(), turn.as_ref());
                 Self::run_exec_like(
                     tool_name.as_str(),
                     exec_params,
@@ -141,7 +166,7 @@ impl ToolHandler for ShellHandler {
                 .await
             }
             ToolPayload::LocalShell { params } => {
-                let exec_params = Self::to_exec_params(params, turn.as_ref());
+                let exec_params = Self::to_exec_params(params, session.as_ref(), turn.as_ref());
                 Self::run_exec_like(
                     tool_name.as_str(),
                     exec_params,
@@ -273,6 +298,7 @@ impl ShellHandler {
             .create_exec_approval_requirement_for_command(
                 &features,
                 &exec_params.command,
+                &exec_params.cwd,
                 turn.approval_policy,
                 &turn.sandbox_policy,
                 exec_params.sandbox_permissions,
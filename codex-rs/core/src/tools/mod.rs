@@ -10,6 +10,8 @@ pub mod sandboxing;
 pub mod spec;
 
 use crate::exec::ExecToolCallOutput;
+use crate::exec::ResourceLimitExceeded;
+use crate::persistent_shell::ShellStateChange;
 use crate::truncate::TruncationPolicy;
 use crate::truncate::formatted_truncate_text;
 use crate::truncate::truncate_text;
@@ -31,19 +33,25 @@ pub fn format_exec_output_for_model_structured(
     let ExecToolCallOutput {
         exit_code,
         duration,
+        resource_limit_exceeded,
+        shell_state,
         ..
     } = exec_output;
 
     #[derive(Serialize)]
-    struct ExecMetadata {
+    struct ExecMetadata<'a> {
         exit_code: i32,
         duration_seconds: f32,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        resource_limit_exceeded: Option<&'static str>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        shell_state: Option<&'a ShellStateChange>,
     }
 
     #[derive(Serialize)]
     struct ExecOutput<'a> {
         output: &'a str,
-        metadata: ExecMetadata,
+        metadata: ExecMetadata<'a>,
     }
 
     // round to 1 decimal place
@@ -56,6 +64,8 @@ pub fn format_exec_output_for_model_structured(
         metadata: ExecMetadata {
             exit_code: *exit_code,
             duration_seconds,
+            resource_limit_exceeded: resource_limit_exceeded.map(ResourceLimitExceeded::config_key),
+            shell_state: shell_state.as_ref(),
         },
     };
 
@@ -70,7 +80,7 @@ pub fn format_exec_output_for_model_freeform(
     // round to 1 decimal place
     let duration_seconds = ((exec_output.duration.as_secs_f32()) * 10.0).round() / 10.0;
 
-    let content = build_content_with_timeout(exec_output);
+    let content = build_content_with_status(exec_output);
 
     let total_lines = content.lines().count();
 
@@ -83,6 +93,10 @@ pub fn format_exec_output_for_model_freeform(
     if total_lines != formatted_output.lines().count() {
         sections.push(format!("Total output lines: {total_lines}"));
     }
+    if let Some(shell_state) = &exec_output.shell_state {
+        sections.push("Shell state carried over to later commands:".to_string());
+        sections.push(shell_state.render());
+    }
 
     sections.push("Output:".to_string());
     sections.push(formatted_output);
@@ -94,20 +108,26 @@ pub fn format_exec_output_str(
     exec_output: &ExecToolCallOutput,
     truncation_policy: TruncationPolicy,
 ) -> String {
-    let content = build_content_with_timeout(exec_output);
+    let content = build_content_with_status(exec_output);
 
     // Truncate for model consumption before serialization.
     formatted_truncate_text(&content, truncation_policy)
 }
 
-/// Extracts exec output content and prepends a timeout message if the command timed out.
-fn build_content_with_timeout(exec_output: &ExecToolCallOutput) -> String {
+/// Extracts exec output content and prepends a message if the command timed
+/// out or was stopped by a sandbox resource limit.
+fn build_content_with_status(exec_output: &ExecToolCallOutput) -> String {
     if exec_output.timed_out {
         format!(
             "command timed out after {} milliseconds\n{}",
             exec_output.duration.as_millis(),
             exec_output.aggregated_output.text
         )
+    } else if let Some(limit) = exec_output.resource_limit_exceeded {
+        format!(
+            "command was stopped after exceeding the sandbox {limit}\n{}",
+            exec_output.aggregated_output.text
+        )
     } else {
         exec_output.aggregated_output.text.clone()
     }
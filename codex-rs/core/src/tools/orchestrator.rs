@@ -8,6 +8,8 @@ retry without sandbox on denial (no re‑approval thanks to caching).
 use crate::error::CodexErr;
 use crate::error::SandboxErr;
 use crate::exec::ExecToolCallOutput;
+use crate::exec::SandboxType;
+use crate::network_proxy::NetworkProxy;
 use crate::sandboxing::SandboxManager;
 use crate::tools::sandboxing::ApprovalCtx;
 use crate::tools::sandboxing::ExecApprovalRequirement;
@@ -20,6 +22,7 @@ use crate::tools::sandboxing::default_exec_approval_requirement;
 use codex_otel::ToolDecisionSource;
 use codex_protocol::protocol::AskForApproval;
 use codex_protocol::protocol::ReviewDecision;
+use codex_protocol::protocol::SandboxPolicy;
 
 pub(crate) struct ToolOrchestrator {
     sandbox: SandboxManager,
@@ -105,22 +108,49 @@ impl ToolOrchestrator {
             }
         }
 
+        // With a container backend, commands never run on the host: skipping
+        // the sandbox lifts the policy's restrictions inside the container.
+        let container = turn_ctx
+            .container
+            .as_ref()
+            .filter(|_| tool.runs_in_container());
+        let full_access = SandboxPolicy::DangerFullAccess;
+        let (unsandboxed, unsandboxed_policy) = match container {
+            Some(_) => (SandboxType::Container, &full_access),
+            None => (SandboxType::None, &turn_ctx.sandbox_policy),
+        };
+
         // 2) First attempt under the selected sandbox.
-        let initial_sandbox = match tool.sandbox_mode_for_first_attempt(req) {
-            SandboxOverride::BypassSandboxFirstAttempt => crate::exec::SandboxType::None,
-            SandboxOverride::NoOverride => self
-                .sandbox
-                .select_initial(&turn_ctx.sandbox_policy, tool.sandbox_preference()),
+        let (initial_sandbox, initial_policy) = match tool.sandbox_mode_for_first_attempt(req) {
+            SandboxOverride::BypassSandboxFirstAttempt => (unsandboxed, unsandboxed_policy),
+            SandboxOverride::NoOverride => (
+                self.sandbox.select_initial(
+                    &turn_ctx.sandbox_policy,
+                    tool.sandbox_preference(),
+                    container.is_some(),
+                ),
+                &turn_ctx.sandbox_policy,
+            ),
         };
 
         // Platform-specific flag gating is handled by SandboxManager::select_initial
         // via crate::safety::get_platform_sandbox().
+        ensure_overlay_not_bypassed(tool_ctx, turn_ctx, initial_sandbox)?;
         let initial_attempt = SandboxAttempt {
             sandbox: initial_sandbox,
-            policy: &turn_ctx.sandbox_policy,
+            policy: initial_policy,
             manager: &self.sandbox,
             sandbox_cwd: &turn_ctx.cwd,
             codex_linux_sandbox_exe: turn_ctx.codex_linux_sandbox_exe.as_ref(),
+            network_proxy_socket: tool_ctx
+                .session
+                .services
+                .network_proxy
+                .as_ref()
+                .map(NetworkProxy::socket_path),
+            resource_limits: turn_ctx.resource_limits.as_ref(),
+            workspace_overlay: tool_ctx.session.services.workspace_overlay.as_ref(),
+            container,
         };
 
         tracing::warn!(
@@ -194,12 +224,17 @@ impl ToolOrchestrator {
                     }
                 }
 
+                ensure_overlay_not_bypassed(tool_ctx, turn_ctx, unsandboxed)?;
                 let escalated_attempt = SandboxAttempt {
-                    sandbox: crate::exec::SandboxType::None,
-                    policy: &turn_ctx.sandbox_policy,
+                    sandbox: unsandboxed,
+                    policy: unsandboxed_policy,
                     manager: &self.sandbox,
                     sandbox_cwd: &turn_ctx.cwd,
                     codex_linux_sandbox_exe: None,
+                    network_proxy_socket: None,
+                    resource_limits: None,
+                    workspace_overlay: None,
+                    container,
                 };
 
                 // Second attempt.
@@ -234,3 +269,25 @@ fn build_denial_reason_from_output(_output: &ExecToolCallOutput) -> String {
     // output so we can evolve heuristics later without touching call sites.
     "command failed; retry without sandbox?".to_string()
 }
+
+/// Only the Linux sandbox mounts the workspace overlay. Any other attempt
+/// acts on the workspace itself, which lacks the overlay's pending changes,
+/// so it is refused until those changes are committed or discarded.
+fn ensure_overlay_not_bypassed(
+    tool_ctx: &ToolCtx<'_>,
+    turn_ctx: &crate::codex::TurnContext,
+    sandbox: SandboxType,
+) -> Result<(), ToolError> {
+    let Some(overlay) = tool_ctx.session.services.workspace_overlay.as_ref() else {
+        return Ok(());
+    };
+    if sandbox == SandboxType::LinuxSeccomp || !overlay.covers(&turn_ctx.cwd) {
+        return Ok(());
+    }
+    if overlay.has_changes().unwrap_or(true) {
+        return Err(ToolError::Rejected(
+            "the workspace overlay holds uncommitted changes; they must be committed or discarded before running outside the sandbox".to_string(),
+        ));
+    }
+    Ok(())
+}
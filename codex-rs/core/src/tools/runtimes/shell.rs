@@ -5,8 +5,10 @@ Executes shell requests under the orchestrator: asks for approval when needed,
 builds a CommandSpec, and runs it under the current SandboxAttempt.
 */
 use crate::exec::ExecToolCallOutput;
+use crate::exec::SandboxType;
 use crate::features::Feature;
 use crate::powershell::prefix_powershell_script_with_utf8;
+use crate::protocol::SandboxPolicy;
 use crate::sandboxing::SandboxPermissions;
 use crate::sandboxing::execute_env;
 use crate::shell::ShellType;
@@ -58,6 +60,7 @@ impl ShellRuntime {
             sub_id: ctx.turn.sub_id.clone(),
             call_id: ctx.call_id.clone(),
             tx_event: ctx.session.get_tx_event(),
+            secret_redactor: ctx.session.services.secret_redactor.clone(),
         })
     }
 }
@@ -69,6 +72,9 @@ impl Sandboxable for ShellRuntime {
     fn escalate_on_failure(&self) -> bool {
         true
     }
+    fn runs_in_container(&self) -> bool {
+        true
+    }
 }
 
 impl Approvable<ShellRequest> for ShellRuntime {
@@ -144,9 +150,39 @@ impl ToolRuntime<ShellRequest, ExecToolCallOutput> for ShellRuntime {
         attempt: &SandboxAttempt<'_>,
         ctx: &ToolCtx<'_>,
     ) -> Result<ExecToolCallOutput, ToolError> {
-        let base_command = &req.command;
+        let persistent_shell = ctx.session.services.persistent_shell.as_ref();
+        // State recorded by sandboxed commands must not steer a command that
+        // runs outside the sandbox (e.g. via a planted binary on PATH).
+        let apply_state = attempt.sandbox != SandboxType::None
+            || matches!(
+                attempt.policy,
+                SandboxPolicy::DangerFullAccess | SandboxPolicy::ExternalSandbox { .. }
+            );
+        // The records are written through inherited descriptors, which do not
+        // reach a command inside a container.
+        let wrapped = match persistent_shell {
+            Some(shell) if attempt.sandbox != SandboxType::Container => shell
+                .wrap_command(&req.command, apply_state)
+                .map_err(|err| ToolError::Codex(err.into()))?,
+            _ => None,
+        };
+        let mut req_env = req.env.clone();
+        let (base_command, state_capture) = match wrapped {
+            Some((command, capture)) => (command, Some(capture)),
+            None => {
+                if apply_state && let Some(shell) = persistent_shell {
+                    shell.apply_env(&mut req_env);
+                }
+                (req.command.clone(), None)
+            }
+        };
         let session_shell = ctx.session.user_shell();
-        let command = maybe_wrap_shell_lc_with_snapshot(base_command, session_shell.as_ref());
+        // The host's shell snapshot is not visible inside the container.
+        let command = if attempt.sandbox == SandboxType::Container {
+            base_command.clone()
+        } else {
+            maybe_wrap_shell_lc_with_snapshot(&base_command, session_shell.as_ref())
+        };
         let command = if matches!(session_shell.shell_type, ShellType::PowerShell)
             && ctx.session.features().enabled(Feature::PowershellUtf8)
         {
@@ -158,17 +194,25 @@ impl ToolRuntime<ShellRequest, ExecToolCallOutput> for ShellRuntime {
         let spec = build_command_spec(
             &command,
             &req.cwd,
-            &req.env,
+            &req_env,
             req.timeout_ms.into(),
             req.sandbox_permissions,
             req.justification.clone(),
         )?;
-        let env = attempt
+        let mut env = attempt
             .env_for(spec)
             .map_err(|err| ToolError::Codex(err.into()))?;
-        let out = execute_env(env, attempt.policy, Self::stdout_stream(ctx))
-            .await
-            .map_err(ToolError::Codex)?;
+        if let Some(capture) = &state_capture {
+            env.inherited_fds = capture
+                .inherited_fds()
+                .map_err(|err| ToolError::Codex(err.into()))?;
+        }
+        let out = execute_env(env, attempt.policy, Self::stdout_stream(ctx)).await;
+        let shell_state = persistent_shell
+            .zip(state_capture)
+            .and_then(|(shell, capture)| shell.record(capture));
+        let mut out = out.map_err(ToolError::Codex)?;
+        out.shell_state = shell_state;
         Ok(out)
     }
 }
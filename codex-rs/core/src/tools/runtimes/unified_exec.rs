@@ -7,6 +7,7 @@ the process manager to spawn PTYs once an ExecEnv is prepared.
 use crate::error::CodexErr;
 use crate::error::SandboxErr;
 use crate::exec::ExecExpiration;
+use crate::exec::SandboxType;
 use crate::features::Feature;
 use crate::powershell::prefix_powershell_script_with_utf8;
 use crate::sandboxing::SandboxPermissions;
@@ -87,6 +88,10 @@ impl Sandboxable for UnifiedExecRuntime<'_> {
     fn escalate_on_failure(&self) -> bool {
         true
     }
+
+    fn runs_in_container(&self) -> bool {
+        true
+    }
 }
 
 impl Approvable<UnifiedExecRequest> for UnifiedExecRuntime<'_> {
@@ -167,7 +172,12 @@ impl<'a> ToolRuntime<UnifiedExecRequest, UnifiedExecProcess> for UnifiedExecRunt
     ) -> Result<UnifiedExecProcess, ToolError> {
         let base_command = &req.command;
         let session_shell = ctx.session.user_shell();
-        let command = maybe_wrap_shell_lc_with_snapshot(base_command, session_shell.as_ref());
+        // The host's shell snapshot is not visible inside the container.
+        let command = if attempt.sandbox == SandboxType::Container {
+            base_command.to_vec()
+        } else {
+            maybe_wrap_shell_lc_with_snapshot(base_command, session_shell.as_ref())
+        };
         let command = if matches!(session_shell.shell_type, ShellType::PowerShell)
             && ctx.session.features().enabled(Feature::PowershellUtf8)
         {
@@ -6,12 +6,15 @@
 
 use crate::codex::Session;
 use crate::codex::TurnContext;
+use crate::config::types::ContainerConfig;
+use crate::config::types::SandboxResourceLimits;
 use crate::error::CodexErr;
 use crate::protocol::SandboxPolicy;
 use crate::sandboxing::CommandSpec;
 use crate::sandboxing::SandboxManager;
 use crate::sandboxing::SandboxTransformError;
 use crate::state::SessionServices;
+use crate::workspace_overlay::WorkspaceOverlay;
 use codex_protocol::approvals::ExecPolicyAmendment;
 use codex_protocol::protocol::AskForApproval;
 use codex_protocol::protocol::ReviewDecision;
@@ -244,6 +247,11 @@ pub(crate) trait Sandboxable {
     fn escalate_on_failure(&self) -> bool {
         true
     }
+    /// Whether the tool's commands run in the configured `[container]`
+    /// rather than on the host.
+    fn runs_in_container(&self) -> bool {
+        false
+    }
 }
 
 pub(crate) struct ToolCtx<'a> {
@@ -274,6 +282,11 @@ pub(crate) struct SandboxAttempt<'a> {
     pub(crate) manager: &'a SandboxManager,
     pub(crate) sandbox_cwd: &'a Path,
     pub codex_linux_sandbox_exe: Option<&'a std::path::PathBuf>,
+    /// Socket of the proxy that enforces `sandbox_network_allowlist`, if any.
+    pub network_proxy_socket: Option<&'a Path>,
+    pub resource_limits: Option<&'a SandboxResourceLimits>,
+    pub(crate) workspace_overlay: Option<&'a WorkspaceOverlay>,
+    pub(crate) container: Option<&'a ContainerConfig>,
 }
 
 impl<'a> SandboxAttempt<'a> {
@@ -287,6 +300,10 @@ impl<'a> SandboxAttempt<'a> {
             self.sandbox,
             self.sandbox_cwd,
             self.codex_linux_sandbox_exe,
+            self.network_proxy_socket,
+            self.resource_limits,
+            self.workspace_overlay,
+            self.container,
         )
     }
 }
@@ -316,7 +333,10 @@ mod tests {
     #[test]
     fn restricted_sandbox_requires_exec_approval_on_request() {
         assert_eq!(
-            default_exec_approval_requirement(AskForApproval::OnRequest, &SandboxPolicy::ReadOnly),
+            default_exec_approval_requirement(
+                AskForApproval::OnRequest,
+                &SandboxPolicy::new_read_only_policy()
+            ),
             ExecApprovalRequirement::NeedsApproval {
                 reason: None,
                 proposed_execpolicy_amendment: None,
@@ -79,8 +79,33 @@ impl ToolsConfig {
             experimental_supported_tools: model_info.experimental_supported_tools.clone(),
         }
     }
+
+    /// Configuration used when tools are called directly rather than by a
+    /// model, which is independent of the session's model family.
+    pub(crate) fn for_direct_calls() -> Self {
+        Self {
+            shell_type: ConfigShellToolType::Default,
+            apply_patch_tool_type: Some(ApplyPatchToolType::Function),
+            web_search_request: false,
+            web_search_cached: false,
+            collab_tools: false,
+            experimental_supported_tools: DIRECT_TOOL_NAMES
+                .iter()
+                .map(|name| (*name).to_string())
+                .collect(),
+        }
+    }
 }
 
+/// Built-in tools that may be invoked directly via `Op::CallTool`.
+pub const DIRECT_TOOL_NAMES: [&str; 5] = [
+    "read_file",
+    "grep_files",
+    "list_dir",
+    "apply_patch",
+    "shell",
+];
+
 /// Generic JSON‑Schema subset needed for our tool definitions
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "type", rename_all = "lowercase")]
@@ -986,6 +1011,51 @@ pub(crate) fn mcp_tool_to_openai_tool(
     })
 }
 
+/// Describes the tools in [`DIRECT_TOOL_NAMES`] as MCP tools, so hosts such as
+/// `codex mcp-server` can advertise them with the schemas the model sees.
+pub fn direct_mcp_tools() -> Vec<mcp_types::Tool> {
+    let (specs, _) = build_specs(&ToolsConfig::for_direct_calls(), None).build();
+    specs
+        .into_iter()
+        .filter_map(|configured| match configured.spec {
+            ToolSpec::Function(tool) if DIRECT_TOOL_NAMES.contains(&tool.name.as_str()) => {
+                Some(openai_tool_to_mcp_tool(tool))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn openai_tool_to_mcp_tool(tool: ResponsesApiTool) -> mcp_types::Tool {
+    let ResponsesApiTool {
+        name,
+        description,
+        parameters,
+        ..
+    } = tool;
+    let (properties, required) = match parameters {
+        JsonSchema::Object {
+            properties,
+            required,
+            ..
+        } => (serde_json::to_value(properties).ok(), required),
+        _ => (None, None),
+    };
+
+    mcp_types::Tool {
+        annotations: None,
+        description: Some(description),
+        input_schema: mcp_types::ToolInputSchema {
+            properties,
+            required,
+            r#type: "object".to_string(),
+        },
+        name,
+        output_schema: None,
+        title: None,
+    }
+}
+
 /// Sanitize a JSON Schema (as serde_json::Value) so it can fit our limited
 /// JsonSchema enum. This function:
 /// - Ensures every schema object has a "type". If missing, infers it from
@@ -2362,4 +2432,31 @@ Examples of valid command strings:
             })]
         );
     }
+
+    #[test]
+    fn direct_mcp_tools_cover_direct_tool_names() {
+        let tools = direct_mcp_tools();
+        let mut names: Vec<&str> = tools.iter().map(|tool| tool.name.as_str()).collect();
+        names.sort_unstable();
+        let mut expected = DIRECT_TOOL_NAMES.to_vec();
+        expected.sort_unstable();
+        assert_eq!(names, expected);
+
+        let shell = tools
+            .iter()
+            .find(|tool| tool.name == "shell")
+            .expect("shell tool");
+        assert_eq!(
+            shell.input_schema.required,
+            Some(vec!["command".to_string()])
+        );
+        assert!(
+            shell
+                .input_schema
+                .properties
+                .as_ref()
+                .and_then(|properties| properties.get("command"))
+                .is_some()
+        );
+    }
 }
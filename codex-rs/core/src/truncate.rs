@@ -3,9 +3,11 @@
 //! used across the core crate.
 
 use codex_protocol::models::FunctionCallOutputContentItem;
+use codex_protocol::openai_models::TokenizerKind;
 use codex_protocol::openai_models::TruncationMode;
 use codex_protocol::openai_models::TruncationPolicyConfig;
 use codex_protocol::protocol::TruncationPolicy as ProtocolTruncationPolicy;
+use codex_utils_tokenizer::Encoding;
 
 const APPROX_BYTES_PER_TOKEN: usize = 4;
 
@@ -33,6 +35,17 @@ impl From<TruncationPolicyConfig> for TruncationPolicy {
     }
 }
 
+/// Maps model metadata to the encoding used for local token counts, falling
+/// back to the byte heuristic when the model does not declare one.
+pub(crate) fn encoding_for(tokenizer: Option<TokenizerKind>) -> Encoding {
+    match tokenizer {
+        Some(TokenizerKind::O200kBase) => Encoding::O200kBase,
+        Some(TokenizerKind::Cl100kBase) => Encoding::Cl100kBase,
+        Some(TokenizerKind::DeepSeek) => Encoding::DeepSeek,
+        None => Encoding::Approximate,
+    }
+}
+
 impl TruncationPolicy {
     /// Scale the underlying budget by `multiplier`, rounding up to avoid under-budgeting.
     pub fn mul(self, multiplier: f64) -> Self {
@@ -83,20 +96,32 @@ pub(crate) fn formatted_truncate_text(content: &str, policy: TruncationPolicy) -
 }
 
 pub(crate) fn truncate_text(content: &str, policy: TruncationPolicy) -> String {
+    truncate_text_with_encoding(content, policy, Encoding::Approximate)
+}
+
+/// Like [`truncate_text`], but `Tokens` budgets are measured with `encoding`
+/// instead of the bytes-per-token heuristic.
+pub(crate) fn truncate_text_with_encoding(
+    content: &str,
+    policy: TruncationPolicy,
+    encoding: Encoding,
+) -> String {
     match policy {
         TruncationPolicy::Bytes(_) => truncate_with_byte_estimate(content, policy),
         TruncationPolicy::Tokens(_) => {
-            let (truncated, _) = truncate_with_token_budget(content, policy);
+            let (truncated, _) = truncate_with_token_budget(content, policy, encoding);
             truncated
         }
     }
 }
+
 /// Globally truncate function output items to fit within the given
 /// truncation policy's budget, preserving as many text/image items as
 /// possible and appending a summary for any omitted text items.
 pub(crate) fn truncate_function_output_items_with_policy(
     items: &[FunctionCallOutputContentItem],
     policy: TruncationPolicy,
+    encoding: Encoding,
 ) -> Vec<FunctionCallOutputContentItem> {
     let mut out: Vec<FunctionCallOutputContentItem> = Vec::with_capacity(items.len());
     let mut remaining_budget = match policy {
@@ -115,7 +140,7 @@ pub(crate) fn truncate_function_output_items_with_policy(
 
                 let cost = match policy {
                     TruncationPolicy::Bytes(_) => text.len(),
-                    TruncationPolicy::Tokens(_) => approx_token_count(text),
+                    TruncationPolicy::Tokens(_) => encoding.tokenizer().count_tokens(text),
                 };
 
                 if cost <= remaining_budget {
@@ -126,7 +151,7 @@ pub(crate) fn truncate_function_output_items_with_policy(
                         TruncationPolicy::Bytes(_) => TruncationPolicy::Bytes(remaining_budget),
                         TruncationPolicy::Tokens(_) => TruncationPolicy::Tokens(remaining_budget),
                     };
-                    let snippet = truncate_text(text, snippet_policy);
+                    let snippet = truncate_text_with_encoding(text, snippet_policy, encoding);
                     if snippet.is_empty() {
                         omitted_text_items += 1;
                     } else {
@@ -156,12 +181,20 @@ pub(crate) fn truncate_function_output_items_with_policy(
 /// preserving the beginning and the end. Returns the possibly truncated string
 /// and `Some(original_token_count)` if truncation occurred; otherwise returns
 /// the original string and `None`.
-fn truncate_with_token_budget(s: &str, policy: TruncationPolicy) -> (String, Option<u64>) {
+fn truncate_with_token_budget(
+    s: &str,
+    policy: TruncationPolicy,
+    encoding: Encoding,
+) -> (String, Option<u64>) {
     if s.is_empty() {
         return (String::new(), None);
     }
     let max_tokens = policy.token_budget();
 
+    if encoding != Encoding::Approximate {
+        return truncate_with_tokenizer(s, max_tokens, encoding);
+    }
+
     let byte_len = s.len();
     if max_tokens > 0 && byte_len <= approx_bytes_for_tokens(max_tokens) {
         return (s.to_string(), None);
@@ -177,6 +210,38 @@ fn truncate_with_token_budget(s: &str, policy: TruncationPolicy) -> (String, Opt
     }
 }
 
+/// Token-budget truncation backed by a real tokenizer. The kept prefix and
+/// suffix are cut on token boundaries, so exactly `max_tokens` tokens survive
+/// (less any partial character at a cut).
+fn truncate_with_tokenizer(
+    s: &str,
+    max_tokens: usize,
+    encoding: Encoding,
+) -> (String, Option<u64>) {
+    let token_ends = encoding.tokenizer().token_ends(s);
+    let total_tokens = token_ends.len();
+    if max_tokens > 0 && total_tokens <= max_tokens {
+        return (s.to_string(), None);
+    }
+
+    let total = u64::try_from(total_tokens).unwrap_or(u64::MAX);
+    let (left_tokens, right_tokens) = split_budget(max_tokens.min(total_tokens));
+    let prefix_bytes = left_tokens
+        .checked_sub(1)
+        .map_or(0, |last| token_ends[last]);
+    let suffix_start = (total_tokens - right_tokens)
+        .checked_sub(1)
+        .map_or(0, |last| token_ends[last]);
+    let (_, left, right) = split_string(s, prefix_bytes, s.len() - suffix_start);
+    let removed_tokens =
+        u64::try_from(total_tokens - left_tokens - right_tokens).unwrap_or(u64::MAX);
+    let marker = format_truncation_marker(TruncationPolicy::Tokens(max_tokens), removed_tokens);
+    if left.is_empty() && right.is_empty() {
+        return (marker, Some(total));
+    }
+    (assemble_truncated_output(left, right, &marker), Some(total))
+}
+
 /// Truncate a string using a byte budget derived from the token budget, without
 /// performing any real tokenization. This keeps the logic purely byte-based and
 /// uses a bytes placeholder in the truncated output.
@@ -312,6 +377,7 @@ mod tests {
     use super::truncate_text;
     use super::truncate_with_token_budget;
     use codex_protocol::models::FunctionCallOutputContentItem;
+    use codex_utils_tokenizer::Encoding;
     use pretty_assertions::assert_eq;
 
     #[test]
@@ -435,7 +501,8 @@ mod tests {
     fn truncate_with_token_budget_returns_original_when_under_limit() {
         let s = "short output";
         let limit = 100;
-        let (out, original) = truncate_with_token_budget(s, TruncationPolicy::Tokens(limit));
+        let (out, original) =
+            truncate_with_token_budget(s, TruncationPolicy::Tokens(limit), Encoding::Approximate);
         assert_eq!(out, s);
         assert_eq!(original, None);
     }
@@ -443,7 +510,8 @@ mod tests {
     #[test]
     fn truncate_with_token_budget_reports_truncation_at_zero_limit() {
         let s = "abcdef";
-        let (out, original) = truncate_with_token_budget(s, TruncationPolicy::Tokens(0));
+        let (out, original) =
+            truncate_with_token_budget(s, TruncationPolicy::Tokens(0), Encoding::Approximate);
         assert_eq!(out, "…2 tokens truncated…");
         assert_eq!(original, Some(2));
     }
@@ -451,11 +519,38 @@ mod tests {
     #[test]
     fn truncate_middle_tokens_handles_utf8_content() {
         let s = "😀😀😀😀😀😀😀😀😀😀\nsecond line with text\n";
-        let (out, tokens) = truncate_with_token_budget(s, TruncationPolicy::Tokens(8));
+        let (out, tokens) =
+            truncate_with_token_budget(s, TruncationPolicy::Tokens(8), Encoding::Approximate);
         assert_eq!(out, "😀😀😀😀…8 tokens truncated… line with text\n");
         assert_eq!(tokens, Some(16));
     }
 
+    #[test]
+    fn truncate_with_token_budget_cuts_on_token_boundaries() {
+        // o200k_base encodes each "你好" / "世界" pair as a single token.
+        let s = "你好世界".repeat(10);
+        let (out, tokens) =
+            truncate_with_token_budget(&s, TruncationPolicy::Tokens(12), Encoding::O200kBase);
+        assert_eq!(
+            out,
+            format!(
+                "{}…8 tokens truncated…{}",
+                "你好世界".repeat(3),
+                "你好世界".repeat(3)
+            )
+        );
+        assert_eq!(tokens, Some(20));
+
+        let (out, _) =
+            truncate_with_token_budget(&s, TruncationPolicy::Tokens(20), Encoding::O200kBase);
+        assert_eq!(out, s);
+
+        let (out, tokens) =
+            truncate_with_token_budget(&s, TruncationPolicy::Tokens(0), Encoding::O200kBase);
+        assert_eq!(out, "…20 tokens truncated…");
+        assert_eq!(tokens, Some(20));
+    }
+
     #[test]
     fn truncate_middle_bytes_handles_utf8_content() {
         let s = "😀😀😀😀😀😀😀😀😀😀\nsecond line with text\n";
@@ -486,8 +581,11 @@ mod tests {
             FunctionCallOutputContentItem::InputText { text: t5 },
         ];
 
-        let output =
-            truncate_function_output_items_with_policy(&items, TruncationPolicy::Tokens(limit));
+        let output = truncate_function_output_items_with_policy(
+            &items,
+            TruncationPolicy::Tokens(limit),
+            Encoding::Approximate,
+        );
 
         // Expect: t1 (full), t2 (full), image, t3 (truncated), summary mentioning 2 omitted.
         assert_eq!(output.len(), 5);
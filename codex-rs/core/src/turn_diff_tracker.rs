@@ -11,6 +11,7 @@ use sha1::digest::Output;
 use uuid::Uuid;
 
 use crate::protocol::FileChange;
+use crate::workspace_overlay::OverlayLayers;
 
 const ZERO_OID: &str = "0000000000000000000000000000000000000000";
 const DEV_NULL: &str = "/dev/null";
@@ -40,6 +41,9 @@ pub struct TurnDiffTracker {
     temp_name_to_current_path: HashMap<String, PathBuf>,
     /// Cache of known git worktree roots to avoid repeated filesystem walks.
     git_root_cache: Vec<PathBuf>,
+    /// When set, current file contents are read through this overlay view
+    /// instead of directly from disk.
+    overlay: Option<OverlayLayers>,
 }
 
 impl TurnDiffTracker {
@@ -53,45 +57,7 @@ impl TurnDiffTracker {
     /// - Also updates internal mappings for move/rename events.
     pub fn on_patch_begin(&mut self, changes: &HashMap<PathBuf, FileChange>) {
         for (path, change) in changes.iter() {
-            // Ensure a stable internal filename exists for this external path.
-            if !self.external_to_temp_name.contains_key(path) {
-                let internal = Uuid::new_v4().to_string();
-                self.external_to_temp_name
-                    .insert(path.clone(), internal.clone());
-                self.temp_name_to_current_path
-                    .insert(internal.clone(), path.clone());
-
-                // If the file exists on disk now, snapshot as baseline; else leave missing to represent /dev/null.
-                let baseline_file_info = if path.exists() {
-                    let mode = file_mode_for_path(path);
-                    let mode_val = mode.unwrap_or(FileMode::Regular);
-                    let content = blob_bytes(path, mode_val).unwrap_or_default();
-                    let oid = if mode == Some(FileMode::Symlink) {
-                        format!("{:x}", git_blob_sha1_hex_bytes(&content))
-                    } else {
-                        self.git_blob_oid_for_path(path)
-                            .unwrap_or_else(|| format!("{:x}", git_blob_sha1_hex_bytes(&content)))
-                    };
-                    Some(BaselineFileInfo {
-                        path: path.clone(),
-                        content,
-                        mode: mode_val,
-                        oid,
-                    })
-                } else {
-                    Some(BaselineFileInfo {
-                        path: path.clone(),
-                        content: vec![],
-                        mode: FileMode::Regular,
-                        oid: ZERO_OID.to_string(),
-                    })
-                };
-
-                if let Some(baseline_file_info) = baseline_file_info {
-                    self.baseline_file_info
-                        .insert(internal.clone(), baseline_file_info);
-                }
-            }
+            self.track_path(path);
 
             // Track rename/move in current mapping if provided in an Update.
             if let FileChange::Update {
@@ -127,6 +93,59 @@ impl TurnDiffTracker {
         }
     }
 
+    /// Compares `layers.lower` against its overlay view instead of against the
+    /// files on disk. Paths are tracked with [`Self::track_path`].
+    pub(crate) fn for_overlay(layers: OverlayLayers) -> Self {
+        Self {
+            overlay: Some(layers),
+            ..Self::default()
+        }
+    }
+
+    /// Starts tracking `path`, snapshotting its contents as the baseline the
+    /// first time it is seen.
+    pub(crate) fn track_path(&mut self, path: &Path) {
+        // Ensure a stable internal filename exists for this external path.
+        if self.external_to_temp_name.contains_key(path) {
+            return;
+        }
+        let internal = Uuid::new_v4().to_string();
+        self.external_to_temp_name
+            .insert(path.to_path_buf(), internal.clone());
+        self.temp_name_to_current_path
+            .insert(internal.clone(), path.to_path_buf());
+
+        // If the file exists on disk now, snapshot as baseline; else leave missing to represent /dev/null.
+        let baseline_file_info = if path.exists() {
+            let mode = file_mode_for_path(path);
+            let mode_val = mode.unwrap_or(FileMode::Regular);
+            let content = blob_bytes(path, mode_val).unwrap_or_default();
+            let oid = if mode == Some(FileMode::Symlink) {
+                format!("{:x}", git_blob_sha1_hex_bytes(&content))
+            } else {
+                self.git_blob_oid_for_path(path)
+                    .unwrap_or_else(|| format!("{:x}", git_blob_sha1_hex_bytes(&content)))
+            };
+            Some(BaselineFileInfo {
+                path: path.to_path_buf(),
+                content,
+                mode: mode_val,
+                oid,
+            })
+        } else {
+            Some(BaselineFileInfo {
+                path: path.to_path_buf(),
+                content: vec![],
+                mode: FileMode::Regular,
+                oid: ZERO_OID.to_string(),
+            })
+        };
+
+        if let Some(baseline_file_info) = baseline_file_info {
+            self.baseline_file_info.insert(internal, baseline_file_info);
+        }
+    }
+
     fn get_path_for_internal(&self, internal: &str) -> Option<PathBuf> {
         self.temp_name_to_current_path
             .get(internal)
@@ -265,8 +284,17 @@ impl TurnDiffTracker {
             None => return aggregated,
         };
 
-        let current_mode = file_mode_for_path(&current_external_path).unwrap_or(FileMode::Regular);
-        let right_bytes = blob_bytes(&current_external_path, current_mode);
+        let current_source = match &self.overlay {
+            Some(layers) => layers.resolve(&current_external_path),
+            None => Some(current_external_path.clone()),
+        };
+        let current_mode = current_source
+            .as_deref()
+            .and_then(file_mode_for_path)
+            .unwrap_or(FileMode::Regular);
+        let right_bytes = current_source
+            .as_deref()
+            .and_then(|source| blob_bytes(source, current_mode));
 
         // Compute displays with &mut self before borrowing any baseline content.
         let left_display = self.relative_to_git_root_str(&baseline_external_path);
@@ -274,7 +302,10 @@ impl TurnDiffTracker {
 
         // Compute right oid before borrowing baseline content.
         let right_oid = if let Some(b) = right_bytes.as_ref() {
-            if current_mode == FileMode::Symlink {
+            // git can only hash files that are visible on disk at their own path.
+            if current_mode == FileMode::Symlink
+                || current_source.as_deref() != Some(current_external_path.as_path())
+            {
                 format!("{:x}", git_blob_sha1_hex_bytes(b))
             } else {
                 self.git_blob_oid_for_path(&current_external_path)
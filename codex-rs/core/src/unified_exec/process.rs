@@ -9,6 +9,7 @@ use tokio::task::JoinHandle;
 use tokio::time::Duration;
 use tokio_util::sync::CancellationToken;
 
+use crate::container::ContainerGuard;
 use crate::exec::ExecToolCallOutput;
 use crate::exec::SandboxType;
 use crate::exec::StreamOutput;
@@ -38,6 +39,9 @@ pub(crate) struct UnifiedExecProcess {
     output_drained: Arc<Notify>,
     output_task: JoinHandle<()>,
     sandbox_type: SandboxType,
+    /// Removes the container the process runs in once the process is
+    /// dropped before it exited.
+    container_guard: Option<ContainerGuard>,
 }
 
 impl UnifiedExecProcess {
@@ -76,9 +80,14 @@ impl UnifiedExecProcess {
             output_drained,
             output_task,
             sandbox_type,
+            container_guard: None,
         }
     }
 
+    pub(super) fn set_container_guard(&mut self, guard: Option<ContainerGuard>) {
+        self.container_guard = guard;
+    }
+
     pub(super) fn writer_sender(&self) -> mpsc::Sender<Vec<u8>> {
         self.process_handle.writer_sender()
     }
@@ -219,6 +228,11 @@ impl UnifiedExecProcess {
 
 impl Drop for UnifiedExecProcess {
     fn drop(&mut self) {
+        if self.has_exited()
+            && let Some(guard) = self.container_guard.as_mut()
+        {
+            guard.disarm();
+        }
         self.terminate();
     }
 }
@@ -13,6 +13,8 @@ use tokio_util::sync::CancellationToken;
 use crate::bash::extract_bash_command;
 use crate::codex::Session;
 use crate::codex::TurnContext;
+use crate::container::ContainerGuard;
+use crate::exec::SandboxType;
 use crate::exec_env::create_env;
 use crate::protocol::BackgroundEventEvent;
 use crate::protocol::EventMsg;
@@ -486,7 +488,11 @@ impl UnifiedExecProcessManager {
         )
         .await
         .map_err(|err| UnifiedExecError::create_process(err.to_string()))?;
-        UnifiedExecProcess::from_spawned(spawned, env.sandbox).await
+        let mut process = UnifiedExecProcess::from_spawned(spawned, env.sandbox).await?;
+        if env.sandbox == SandboxType::Container {
+            process.set_container_guard(ContainerGuard::for_command(&env.command));
+        }
+        Ok(process)
     }
 
     pub(super) async fn open_session_with_sandbox(
@@ -508,6 +514,7 @@ impl UnifiedExecProcessManager {
             .create_exec_approval_requirement_for_command(
                 &features,
                 command,
+                &cwd,
                 context.turn.approval_policy,
                 &context.turn.sandbox_policy,
                 sandbox_permissions,
@@ -0,0 +1,501 @@
+//! Copy-on-write overlay of the workspace for sandboxed commands.
+//!
+//! With `sandbox_workspace_write.overlay` enabled, the Linux sandbox helper
+//! mounts an overlayfs over the workspace for every command it runs: the real
+//! workspace is the lower layer and all writes land in an upper directory
+//! owned by the session. The pending changes are shown as a diff at the end
+//! of each turn and only reach the workspace once they are committed.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use tempfile::TempDir;
+use uuid::Uuid;
+
+use crate::turn_diff_tracker::TurnDiffTracker;
+
+/// Upper and work directories of the overlay mounted over one workspace.
+pub(crate) struct WorkspaceOverlay {
+    layers: OverlayLayers,
+    work_dir: PathBuf,
+    _state_dir: TempDir,
+}
+
+/// The two layers an overlay view of the workspace is made of.
+#[derive(Clone, Debug)]
+pub(crate) struct OverlayLayers {
+    pub(crate) lower: PathBuf,
+    pub(crate) upper: PathBuf,
+}
+
+/// An entry of the upper layer, relative to the workspace root. Entries are
+/// listed parents first.
+#[derive(Debug, PartialEq, Eq)]
+enum UpperEntry {
+    /// The path was deleted in the overlay.
+    Whiteout(PathBuf),
+    /// A directory that replaces whatever the workspace has at this path.
+    OpaqueDir(PathBuf),
+    /// A directory whose contents merge with the workspace directory.
+    Dir(PathBuf),
+    /// A file or symlink written in the overlay.
+    File(PathBuf),
+}
+
+impl WorkspaceOverlay {
+    /// Creates empty overlay layers for `workspace` under
+    /// `$CODEX_HOME/overlays`. The upper directory has to live on a
+    /// filesystem with user xattr support, which rules out older tmpfs.
+    pub(crate) fn new(workspace: &Path, codex_home: &Path) -> io::Result<Self> {
+        let overlays_dir = codex_home.join("overlays");
+        fs::create_dir_all(&overlays_dir)?;
+        let state_dir = tempfile::Builder::new()
+            .prefix("overlay-")
+            .tempdir_in(&overlays_dir)?;
+        let upper = state_dir.path().join("upper");
+        let work_dir = state_dir.path().join("work");
+        fs::create_dir(&upper)?;
+        fs::create_dir(&work_dir)?;
+        Ok(Self {
+            layers: OverlayLayers {
+                lower: workspace.to_path_buf(),
+                upper,
+            },
+            work_dir,
+            _state_dir: state_dir,
+        })
+    }
+
+    pub(crate) fn lower_dir(&self) -> &Path {
+        &self.layers.lower
+    }
+
+    pub(crate) fn upper_dir(&self) -> &Path {
+        &self.layers.upper
+    }
+
+    pub(crate) fn work_dir(&self) -> &Path {
+        &self.work_dir
+    }
+
+    /// Whether commands covered by `sandbox_policy_cwd` run on this overlay.
+    pub(crate) fn covers(&self, sandbox_policy_cwd: &Path) -> bool {
+        sandbox_policy_cwd.starts_with(&self.layers.lower)
+    }
+
+    /// Whether the overlay holds changes that have not reached the workspace.
+    pub(crate) fn has_changes(&self) -> io::Result<bool> {
+        Ok(fs::read_dir(&self.layers.upper)?.next().is_some())
+    }
+
+    /// Whether the overlay view of workspace `path` differs from the file on
+    /// disk because the overlay changed or deleted it.
+    pub(crate) fn holds_changes_to(&self, path: &Path) -> bool {
+        self.layers.resolve(path).as_deref() != Some(path)
+    }
+
+    /// Aggregated diff between the workspace and its overlay view, or `None`
+    /// when the overlay holds no visible change.
+    pub(crate) fn unified_diff(&self) -> anyhow::Result<Option<String>> {
+        let mut tracker = TurnDiffTracker::for_overlay(self.layers.clone());
+        for path in self.changed_files()? {
+            tracker.track_path(&path);
+        }
+        tracker.get_unified_diff()
+    }
+
+    /// Copies the overlay changes into the workspace and empties the overlay.
+    ///
+    /// Every new file is first staged inside the workspace, and every
+    /// workspace path that gets replaced or deleted is moved aside into the
+    /// same staging directory instead of being removed. If any step fails the
+    /// applied changes are undone in reverse order, so the workspace is left
+    /// as it was and the overlay keeps its changes. Should the rollback fail
+    /// too, the staging directory is kept so nothing is lost.
+    pub(crate) fn commit(&self) -> io::Result<()> {
+        let entries = self.upper_entries()?;
+        let staging_dir = self
+            .layers
+            .lower
+            .join(format!(".codex-overlay-{}", Uuid::new_v4()));
+        fs::create_dir(&staging_dir)?;
+        let mut journal = Vec::new();
+        if let Err(err) = self.stage_and_apply(&entries, &staging_dir, &mut journal) {
+            return match roll_back(journal) {
+                Ok(()) => {
+                    remove_path(&staging_dir)?;
+                    Err(err)
+                }
+                Err(rollback_err) => Err(io::Error::other(format!(
+                    "{err}; restoring the workspace failed as well ({rollback_err}), the replaced files are kept in {}",
+                    staging_dir.display()
+                ))),
+            };
+        }
+        remove_path(&staging_dir)?;
+        self.discard()
+    }
+
+    /// Drops the overlay changes.
+    pub(crate) fn discard(&self) -> io::Result<()> {
+        for dir in [&self.layers.upper, &self.work_dir] {
+            remove_path(dir)?;
+            fs::create_dir(dir)?;
+        }
+        Ok(())
+    }
+
+    fn stage_and_apply(
+        &self,
+        entries: &[UpperEntry],
+        staging_dir: &Path,
+        journal: &mut Vec<JournalEntry>,
+    ) -> io::Result<()> {
+        let mut staged = Vec::new();
+        for (index, entry) in entries.iter().enumerate() {
+            if let UpperEntry::File(rel) = entry {
+                let staged_path = staging_dir.join(index.to_string());
+                copy_file_or_symlink(&self.layers.upper.join(rel), &staged_path)?;
+                staged.push(staged_path);
+            }
+        }
+
+        let backup_dir = staging_dir.join("backup");
+        fs::create_dir(&backup_dir)?;
+        let move_aside = |dest: &Path, journal: &mut Vec<JournalEntry>| -> io::Result<()> {
+            let backup = if fs::symlink_metadata(dest).is_ok() {
+                let backup = backup_dir.join(journal.len().to_string());
+                fs::rename(dest, &backup)?;
+                Some(backup)
+            } else {
+                None
+            };
+            journal.push(JournalEntry {
+                dest: dest.to_path_buf(),
+                backup,
+            });
+            Ok(())
+        };
+
+        let mut staged = staged.into_iter();
+        for entry in entries {
+            match entry {
+                UpperEntry::Whiteout(rel) => move_aside(&self.layers.lower.join(rel), journal)?,
+                UpperEntry::OpaqueDir(rel) => {
+                    let dest = self.layers.lower.join(rel);
+                    move_aside(&dest, journal)?;
+                    self.create_dir_like_upper(rel, &dest)?;
+                }
+                UpperEntry::Dir(rel) => {
+                    let dest = self.layers.lower.join(rel);
+                    if !fs::symlink_metadata(&dest).is_ok_and(|metadata| metadata.is_dir()) {
+                        move_aside(&dest, journal)?;
+                        self.create_dir_like_upper(rel, &dest)?;
+                    }
+                }
+                UpperEntry::File(rel) => {
+                    let dest = self.layers.lower.join(rel);
+                    move_aside(&dest, journal)?;
+                    let Some(staged_path) = staged.next() else {
+                        return Err(io::Error::other("overlay entry was not staged"));
+                    };
+                    fs::rename(staged_path, dest)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn create_dir_like_upper(&self, rel: &Path, dest: &Path) -> io::Result<()> {
+        fs::create_dir(dest)?;
+        let permissions = fs::metadata(self.layers.upper.join(rel))?.permissions();
+        fs::set_permissions(dest, permissions)
+    }
+
+    /// Workspace paths of the files the overlay adds, changes or hides.
+    fn changed_files(&self) -> io::Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        for entry in self.upper_entries()? {
+            match entry {
+                UpperEntry::File(rel) => files.push(self.layers.lower.join(rel)),
+                // Everything the workspace has below a whiteout or an opaque
+                // directory is hidden, i.e. deleted unless the overlay re-adds it.
+                UpperEntry::Whiteout(rel) | UpperEntry::OpaqueDir(rel) => {
+                    collect_files(&self.layers.lower.join(rel), &mut files)?;
+                }
+                UpperEntry::Dir(_) => {}
+            }
+        }
+        files.sort();
+        files.dedup();
+        Ok(files)
+    }
+
+    fn upper_entries(&self) -> io::Result<Vec<UpperEntry>> {
+        let mut entries = Vec::new();
+        collect_upper_entries(&self.layers.upper, Path::new(""), &mut entries)?;
+        Ok(entries)
+    }
+}
+
+impl OverlayLayers {
+    /// Where the current contents of workspace `path` live in the overlay
+    /// view: the upper layer when it has the path, the workspace otherwise.
+    /// Returns `None` when the overlay deleted the path.
+    pub(crate) fn resolve(&self, path: &Path) -> Option<PathBuf> {
+        let Ok(rel) = path.strip_prefix(&self.lower) else {
+            return Some(path.to_path_buf());
+        };
+
+        let mut upper_path = self.upper.clone();
+        let mut below_opaque_dir = false;
+        let mut components = rel.components().peekable();
+        while let Some(component) = components.next() {
+            upper_path.push(component);
+            match fs::symlink_metadata(&upper_path) {
+                Ok(metadata) if is_whiteout(&metadata) => return None,
+                Ok(metadata) if metadata.is_dir() => {
+                    below_opaque_dir |= is_opaque_dir(&upper_path);
+                }
+                // A file cannot have children.
+                Ok(_) if components.peek().is_some() => return None,
+                Ok(_) => {}
+                Err(_) if below_opaque_dir => return None,
+                Err(_) => return Some(path.to_path_buf()),
+            }
+        }
+        Some(upper_path)
+    }
+}
+
+fn collect_upper_entries(
+    upper_root: &Path,
+    rel_dir: &Path,
+    entries: &mut Vec<UpperEntry>,
+) -> io::Result<()> {
+    let mut children = fs::read_dir(upper_root.join(rel_dir))?.collect::<io::Result<Vec<_>>>()?;
+    children.sort_by_key(fs::DirEntry::file_name);
+    for child in children {
+        let rel = rel_dir.join(child.file_name());
+        let path = child.path();
+        let metadata = fs::symlink_metadata(&path)?;
+        if is_whiteout(&metadata) {
+            entries.push(UpperEntry::Whiteout(rel));
+        } else if metadata.is_dir() {
+            if is_opaque_dir(&path) {
+                entries.push(UpperEntry::OpaqueDir(rel.clone()));
+            } else {
+                entries.push(UpperEntry::Dir(rel.clone()));
+            }
+            collect_upper_entries(upper_root, &rel, entries)?;
+        } else {
+            entries.push(UpperEntry::File(rel));
+        }
+    }
+    Ok(())
+}
+
+/// Collects the files and symlinks at or below `path`.
+fn collect_files(path: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    let metadata = match fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err),
+    };
+    if !metadata.is_dir() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+    for child in fs::read_dir(path)? {
+        collect_files(&child?.path(), files)?;
+    }
+    Ok(())
+}
+
+fn copy_file_or_symlink(src: &Path, dest: &Path) -> io::Result<()> {
+    let metadata = fs::symlink_metadata(src)?;
+    if metadata.file_type().is_symlink() {
+        #[cfg(unix)]
+        {
+            return std::os::unix::fs::symlink(fs::read_link(src)?, dest);
+        }
+    }
+    fs::copy(src, dest).map(|_| ())
+}
+
+/// A workspace path that `commit` replaced or deleted, and where its
+/// previous contents were moved, if it existed.
+struct JournalEntry {
+    dest: PathBuf,
+    backup: Option<PathBuf>,
+}
+
+/// Undoes the journaled changes, most recent first.
+fn roll_back(journal: Vec<JournalEntry>) -> io::Result<()> {
+    for JournalEntry { dest, backup } in journal.into_iter().rev() {
+        remove_path(&dest)?;
+        if let Some(backup) = backup {
+            fs::rename(backup, dest)?;
+        }
+    }
+    Ok(())
+}
+
+fn remove_path(path: &Path) -> io::Result<()> {
+    let result = match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.is_dir() => fs::remove_dir_all(path),
+        Ok(_) => fs::remove_file(path),
+        Err(err) => Err(err),
+    };
+    match result {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+/// overlayfs records deletions as 0/0 character devices.
+#[cfg(unix)]
+fn is_whiteout(metadata: &fs::Metadata) -> bool {
+    use std::os::unix::fs::FileTypeExt;
+    use std::os::unix::fs::MetadataExt;
+
+    metadata.file_type().is_char_device() && metadata.rdev() == 0
+}
+
+#[cfg(not(unix))]
+fn is_whiteout(_metadata: &fs::Metadata) -> bool {
+    false
+}
+
+/// overlayfs marks directories that hide the lower layer with an `opaque`
+/// xattr; unprivileged mounts use the `user.` namespace.
+#[cfg(target_os = "linux")]
+fn is_opaque_dir(path: &Path) -> bool {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let Ok(c_path) = CString::new(path.as_os_str().as_bytes()) else {
+        return false;
+    };
+    [c"user.overlay.opaque", c"trusted.overlay.opaque"]
+        .iter()
+        .any(|name| {
+            let mut value = [0u8; 1];
+            let len = unsafe {
+                libc::lgetxattr(
+                    c_path.as_ptr(),
+                    name.as_ptr(),
+                    value.as_mut_ptr().cast(),
+                    value.len(),
+                )
+            };
+            len == 1 && value[0] == b'y'
+        })
+}
+
+#[cfg(not(target_os = "linux"))]
+fn is_opaque_dir(_path: &Path) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn overlay_with_workspace() -> (TempDir, TempDir, WorkspaceOverlay) {
+        let workspace = TempDir::new().expect("workspace");
+        let codex_home = TempDir::new().expect("codex home");
+        fs::write(workspace.path().join("kept.txt"), "kept\n").expect("write kept");
+        fs::write(workspace.path().join("changed.txt"), "before\n").expect("write changed");
+        let overlay =
+            WorkspaceOverlay::new(workspace.path(), codex_home.path()).expect("create overlay");
+        (workspace, codex_home, overlay)
+    }
+
+    fn write_upper(overlay: &WorkspaceOverlay, rel: &str, contents: &str) {
+        let path = overlay.upper_dir().join(rel);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).expect("create upper dirs");
+        }
+        fs::write(path, contents).expect("write upper file");
+    }
+
+    #[test]
+    fn resolve_prefers_upper_layer() {
+        let (workspace, _codex_home, overlay) = overlay_with_workspace();
+        write_upper(&overlay, "changed.txt", "after\n");
+
+        assert_eq!(
+            overlay
+                .layers
+                .resolve(&workspace.path().join("changed.txt")),
+            Some(overlay.upper_dir().join("changed.txt"))
+        );
+        assert_eq!(
+            overlay.layers.resolve(&workspace.path().join("kept.txt")),
+            Some(workspace.path().join("kept.txt"))
+        );
+    }
+
+    #[test]
+    fn unified_diff_reports_overlay_changes() {
+        let (_workspace, _codex_home, overlay) = overlay_with_workspace();
+        assert_eq!(overlay.unified_diff().expect("diff"), None);
+
+        write_upper(&overlay, "changed.txt", "after\n");
+        write_upper(&overlay, "src/new.txt", "new\n");
+
+        let diff = overlay.unified_diff().expect("diff").expect("changes");
+        assert!(diff.contains("-before\n+after\n"), "{diff}");
+        assert!(diff.contains("+++ b/"), "{diff}");
+        assert!(diff.contains("+new\n"), "{diff}");
+        assert!(!diff.contains("kept"), "{diff}");
+    }
+
+    #[test]
+    fn commit_applies_changes_and_empties_overlay() {
+        let (workspace, _codex_home, overlay) = overlay_with_workspace();
+        write_upper(&overlay, "changed.txt", "after\n");
+        write_upper(&overlay, "src/new.txt", "new\n");
+
+        overlay.commit().expect("commit");
+
+        let read = |rel: &str| fs::read_to_string(workspace.path().join(rel)).expect("read");
+        assert_eq!(read("changed.txt"), "after\n");
+        assert_eq!(read("src/new.txt"), "new\n");
+        assert_eq!(read("kept.txt"), "kept\n");
+        assert!(!overlay.has_changes().expect("has changes"));
+        let mut names = fs::read_dir(workspace.path())
+            .expect("read workspace")
+            .map(|entry| entry.expect("entry").file_name())
+            .collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, vec!["changed.txt", "kept.txt", "src"]);
+    }
+
+    #[test]
+    fn holds_changes_to_only_overlay_changed_paths() {
+        let (workspace, _codex_home, overlay) = overlay_with_workspace();
+        write_upper(&overlay, "changed.txt", "after\n");
+
+        assert!(overlay.holds_changes_to(&workspace.path().join("changed.txt")));
+        assert!(!overlay.holds_changes_to(&workspace.path().join("kept.txt")));
+    }
+
+    #[test]
+    fn discard_leaves_workspace_untouched() {
+        let (workspace, _codex_home, overlay) = overlay_with_workspace();
+        write_upper(&overlay, "changed.txt", "after\n");
+
+        overlay.discard().expect("discard");
+
+        assert_eq!(
+            fs::read_to_string(workspace.path().join("changed.txt")).expect("read"),
+            "before\n"
+        );
+        assert!(!overlay.has_changes().expect("has changes"));
+    }
+}
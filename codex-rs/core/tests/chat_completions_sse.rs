@@ -57,7 +57,11 @@ async fn run_stream_with_bytes(sse_body: &[u8]) -> Vec<ResponseEvent> {
         request_max_retries: Some(0),
         stream_max_retries: Some(0),
         stream_idle_timeout_ms: Some(5_000),
+        request_connect_timeout_ms: None,
+        first_byte_timeout_ms: None,
         requires_openai_auth: false,
+        chat_dialect: None,
+        sampling: None,
     };
 
     let codex_home = match TempDir::new() {
@@ -52,7 +52,11 @@ async fn responses_stream_includes_subagent_header_on_review() {
         request_max_retries: Some(0),
         stream_max_retries: Some(0),
         stream_idle_timeout_ms: Some(5_000),
+        request_connect_timeout_ms: None,
+        first_byte_timeout_ms: None,
         requires_openai_auth: false,
+        chat_dialect: None,
+        sampling: None,
     };
 
     let codex_home = TempDir::new().expect("failed to create TempDir");
@@ -147,7 +151,11 @@ async fn responses_stream_includes_subagent_header_on_other() {
         request_max_retries: Some(0),
         stream_max_retries: Some(0),
         stream_idle_timeout_ms: Some(5_000),
+        request_connect_timeout_ms: None,
+        first_byte_timeout_ms: None,
         requires_openai_auth: false,
+        chat_dialect: None,
+        sampling: None,
     };
 
     let codex_home = TempDir::new().expect("failed to create TempDir");
@@ -238,7 +246,11 @@ async fn responses_respects_model_info_overrides_from_config() {
         request_max_retries: Some(0),
         stream_max_retries: Some(0),
         stream_idle_timeout_ms: Some(5_000),
+        request_connect_timeout_ms: None,
+        first_byte_timeout_ms: None,
         requires_openai_auth: false,
+        chat_dialect: None,
+        sampling: None,
     };
 
     let codex_home = TempDir::new().expect("failed to create TempDir");
@@ -1144,7 +1144,11 @@ async fn azure_responses_request_includes_store_and_reasoning_ids() {
         request_max_retries: Some(0),
         stream_max_retries: Some(0),
         stream_idle_timeout_ms: Some(5_000),
+        request_connect_timeout_ms: None,
+        first_byte_timeout_ms: None,
         requires_openai_auth: false,
+        chat_dialect: None,
+        sampling: None,
     };
 
     let codex_home = TempDir::new().unwrap();
@@ -1662,7 +1666,11 @@ async fn azure_overrides_assign_properties_used_for_responses_url() {
         request_max_retries: None,
         stream_max_retries: None,
         stream_idle_timeout_ms: None,
+        request_connect_timeout_ms: None,
+        first_byte_timeout_ms: None,
         requires_openai_auth: false,
+        chat_dialect: None,
+        sampling: None,
     };
 
     // Init session
@@ -1745,7 +1753,11 @@ async fn env_var_overrides_loaded_auth() {
         request_max_retries: None,
         stream_max_retries: None,
         stream_idle_timeout_ms: None,
+        request_connect_timeout_ms: None,
+        first_byte_timeout_ms: None,
         requires_openai_auth: false,
+        chat_dialect: None,
+        sampling: None,
     };
 
     // Init session
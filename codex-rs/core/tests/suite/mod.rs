@@ -52,6 +52,7 @@ mod quota_exceeded;
 mod read_file;
 mod remote_models;
 mod request_compression;
+mod request_timeouts;
 mod resume;
 mod resume_warning;
 mod review;
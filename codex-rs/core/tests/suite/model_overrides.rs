@@ -40,6 +40,7 @@ async fn override_turn_context_does_not_persist_when_config_exists() {
             model: Some("o3".to_string()),
             effort: Some(Some(ReasoningEffort::High)),
             summary: None,
+            sampling: None,
         })
         .await
         .expect("submit override");
@@ -82,6 +83,7 @@ async fn override_turn_context_does_not_create_config_file() {
             model: Some("o3".to_string()),
             effort: Some(Some(ReasoningEffort::Medium)),
             summary: None,
+            sampling: None,
         })
         .await
         .expect("submit override");
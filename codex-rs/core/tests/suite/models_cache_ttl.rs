@@ -180,5 +180,7 @@ fn test_remote_model(slug: &str, priority: i32) -> ModelInfo {
         auto_compact_token_limit: None,
         effective_context_window_percent: 95,
         experimental_supported_tools: Vec::new(),
+        requires_reasoning_content: false,
+        tokenizer: None,
     }
 }
@@ -107,6 +107,7 @@ async fn permissions_message_added_on_override_change() -> Result<()> {
             model: None,
             effort: None,
             summary: None,
+            sampling: None,
         })
         .await?;
 
@@ -218,6 +219,7 @@ async fn resume_replays_permissions_messages() -> Result<()> {
             model: None,
             effort: None,
             summary: None,
+            sampling: None,
         })
         .await?;
 
@@ -291,6 +293,7 @@ async fn resume_and_fork_append_permissions_messages() -> Result<()> {
             model: None,
             effort: None,
             summary: None,
+            sampling: None,
         })
         .await?;
 
@@ -378,6 +381,7 @@ async fn permissions_message_includes_writable_roots() -> Result<()> {
     let writable_root = AbsolutePathBuf::try_from(writable.path())?;
     let sandbox_policy = SandboxPolicy::WorkspaceWrite {
         writable_roots: vec![writable_root],
+        readable_roots: None,
         network_access: false,
         exclude_tmpdir_env_var: false,
         exclude_slash_tmp: false,
@@ -88,6 +88,8 @@ async fn remote_models_remote_model_uses_unified_exec() -> Result<()> {
         auto_compact_token_limit: None,
         effective_context_window_percent: 95,
         experimental_supported_tools: Vec::new(),
+        requires_reasoning_content: false,
+        tokenizer: None,
     };
 
     let models_mock = mount_models_once(
@@ -139,6 +141,7 @@ async fn remote_models_remote_model_uses_unified_exec() -> Result<()> {
             model: Some(REMOTE_MODEL_SLUG.to_string()),
             effort: None,
             summary: None,
+            sampling: None,
         })
         .await?;
 
@@ -318,6 +321,8 @@ async fn remote_models_apply_remote_base_instructions() -> Result<()> {
         auto_compact_token_limit: None,
         effective_context_window_percent: 95,
         experimental_supported_tools: Vec::new(),
+        requires_reasoning_content: false,
+        tokenizer: None,
     };
     mount_models_once(
         &server,
@@ -362,6 +367,7 @@ async fn remote_models_apply_remote_base_instructions() -> Result<()> {
             model: Some(model.to_string()),
             effort: None,
             summary: None,
+            sampling: None,
         })
         .await?;
 
@@ -621,5 +627,7 @@ fn test_remote_model_with_policy(
         auto_compact_token_limit: None,
         effective_context_window_percent: 95,
         experimental_supported_tools: Vec::new(),
+        requires_reasoning_content: false,
+        tokenizer: None,
     }
 }
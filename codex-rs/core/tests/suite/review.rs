@@ -769,6 +769,7 @@ async fn review_uses_overridden_cwd_for_base_branch_merge_base() {
             model: None,
             effort: None,
             summary: None,
+            sampling: None,
         })
         .await
         .unwrap();
@@ -3,6 +3,9 @@ use std::path::Path;
 use codex_core::config::Config;
 use codex_core::protocol::Event;
 use codex_core::protocol::SessionConfiguredEvent;
+use serde_json::Value;
+
+use crate::output_schema::validate_last_message;
 
 pub(crate) enum CodexStatus {
     Running,
@@ -23,6 +26,30 @@ pub(crate) trait EventProcessor {
     fn process_event(&mut self, event: Event) -> CodexStatus;
 
     fn print_final_output(&mut self) {}
+
+    /// Whether the final agent message failed `--output-schema` validation.
+    fn output_schema_violated(&self) -> bool {
+        false
+    }
+}
+
+/// Checks the final agent message against `--output-schema`, reporting any
+/// violation on stderr. Returns `true` when there is nothing to enforce.
+pub(crate) fn check_output_schema(
+    last_agent_message: Option<&str>,
+    schema: Option<&Value>,
+) -> bool {
+    let Some(schema) = schema else {
+        return true;
+    };
+    match validate_last_message(last_agent_message.unwrap_or_default(), schema) {
+        Ok(()) => true,
+        #[allow(clippy::print_stderr)]
+        Err(err) => {
+            eprintln!("Final agent message does not match --output-schema: {err}");
+            false
+        }
+    }
 }
 
 pub(crate) fn handle_last_message(last_agent_message: Option<&str>, output_file: &Path) {
@@ -14,8 +14,10 @@ use codex_core::protocol::FileChange;
 use codex_core::protocol::McpInvocation;
 use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
+use codex_core::protocol::ModelFallbackEvent;
 use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::PatchApplyEndEvent;
+use codex_core::protocol::SecretsRedactedEvent;
 use codex_core::protocol::SessionConfiguredEvent;
 use codex_core::protocol::StreamErrorEvent;
 use codex_core::protocol::TurnAbortReason;
@@ -23,9 +25,11 @@ use codex_core::protocol::TurnCompleteEvent;
 use codex_core::protocol::TurnDiffEvent;
 use codex_core::protocol::WarningEvent;
 use codex_core::protocol::WebSearchEndEvent;
+use codex_core::protocol::WorkspaceOverlayDiffEvent;
 use codex_protocol::num_format::format_with_separators;
 use owo_colors::OwoColorize;
 use owo_colors::Style;
+use serde_json::Value;
 use shlex::try_join;
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -33,6 +37,7 @@ use std::time::Instant;
 
 use crate::event_processor::CodexStatus;
 use crate::event_processor::EventProcessor;
+use crate::event_processor::check_output_schema;
 use crate::event_processor::handle_last_message;
 use codex_common::create_config_summary_entries;
 use codex_protocol::plan_tool::StepStatus;
@@ -61,6 +66,8 @@ pub(crate) struct EventProcessorWithHumanOutput {
     show_agent_reasoning: bool,
     show_raw_agent_reasoning: bool,
     last_message_path: Option<PathBuf>,
+    output_schema: Option<Value>,
+    output_schema_violated: bool,
     last_total_token_usage: Option<codex_core::protocol::TokenUsageInfo>,
     final_message: Option<String>,
 }
@@ -87,6 +94,8 @@ impl EventProcessorWithHumanOutput {
                 show_agent_reasoning: !config.hide_agent_reasoning,
                 show_raw_agent_reasoning: config.show_raw_agent_reasoning,
                 last_message_path,
+                output_schema: None,
+                output_schema_violated: false,
                 last_total_token_usage: None,
                 final_message: None,
             }
@@ -104,11 +113,20 @@ impl EventProcessorWithHumanOutput {
                 show_agent_reasoning: !config.hide_agent_reasoning,
                 show_raw_agent_reasoning: config.show_raw_agent_reasoning,
                 last_message_path,
+                output_schema: None,
+                output_schema_violated: false,
                 last_total_token_usage: None,
                 final_message: None,
             }
         }
     }
+
+    /// Validates the final agent message against `schema` before it is written
+    /// to `--output-last-message`.
+    pub(crate) fn with_output_schema(mut self, schema: Option<Value>) -> Self {
+        self.output_schema = schema;
+        self
+    }
 }
 
 struct PatchApplyBegin {
@@ -173,6 +191,26 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     "warning:".style(self.yellow).style(self.bold)
                 );
             }
+            EventMsg::ModelFallback(ModelFallbackEvent {
+                from_model,
+                to_model,
+                to_provider,
+                reason,
+                ..
+            }) => {
+                ts_msg!(
+                    self,
+                    "{} {from_model} failed ({reason}); continuing with {to_model} ({to_provider})",
+                    "warning:".style(self.yellow).style(self.bold)
+                );
+            }
+            EventMsg::SecretsRedacted(SecretsRedactedEvent { redactions, .. }) => {
+                ts_msg!(
+                    self,
+                    "{} redacted {redactions} secret value(s) from tool output",
+                    "warning:".style(self.yellow).style(self.bold)
+                );
+            }
             EventMsg::DeprecationNotice(DeprecationNoticeEvent { summary, details }) => {
                 ts_msg!(
                     self,
@@ -251,7 +289,11 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             }
             EventMsg::TurnComplete(TurnCompleteEvent { last_agent_message }) => {
                 let last_message = last_agent_message.as_deref();
-                if let Some(output_file) = self.last_message_path.as_deref() {
+                self.output_schema_violated =
+                    !check_output_schema(last_message, self.output_schema.as_ref());
+                if !self.output_schema_violated
+                    && let Some(output_file) = self.last_message_path.as_deref()
+                {
                     handle_last_message(last_message, output_file);
                 }
 
@@ -489,6 +531,16 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                 );
                 eprintln!("{unified_diff}");
             }
+            EventMsg::WorkspaceOverlayDiff(WorkspaceOverlayDiffEvent { unified_diff }) => {
+                ts_msg!(
+                    self,
+                    "{}",
+                    "pending changes (held in the workspace overlay, not applied):"
+                        .style(self.magenta)
+                        .style(self.italic)
+                );
+                eprintln!("{unified_diff}");
+            }
             EventMsg::AgentReasoning(agent_reasoning_event) => {
                 if self.show_agent_reasoning {
                     ts_msg!(
@@ -580,6 +632,8 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             | EventMsg::GetHistoryEntryResponse(_)
             | EventMsg::McpListToolsResponse(_)
             | EventMsg::ListCustomPromptsResponse(_)
+            | EventMsg::GetMcpPromptResponse(_)
+            | EventMsg::CallToolResponse(_)
             | EventMsg::ListSkillsResponse(_)
             | EventMsg::RawResponseItem(_)
             | EventMsg::UserMessage(_)
@@ -596,7 +650,8 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             | EventMsg::SkillsUpdateAvailable
             | EventMsg::UndoCompleted(_)
             | EventMsg::UndoStarted(_)
-            | EventMsg::ThreadRolledBack(_) => {}
+            | EventMsg::ThreadRolledBack(_)
+            | EventMsg::WorkspaceOverlayResolved(_) => {}
         }
         CodexStatus::Running
     }
@@ -623,6 +678,10 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             }
         }
     }
+
+    fn output_schema_violated(&self) -> bool {
+        self.output_schema_violated
+    }
 }
 
 fn escape_command(command: &[String]) -> String {
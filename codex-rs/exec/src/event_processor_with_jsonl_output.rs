@@ -4,6 +4,7 @@ use std::sync::atomic::AtomicU64;
 
 use crate::event_processor::CodexStatus;
 use crate::event_processor::EventProcessor;
+use crate::event_processor::check_output_schema;
 use crate::event_processor::handle_last_message;
 use crate::exec_events::AgentMessageItem;
 use crate::exec_events::CommandExecutionItem;
@@ -43,6 +44,8 @@ use tracing::warn;
 
 pub struct EventProcessorWithJsonOutput {
     last_message_path: Option<PathBuf>,
+    output_schema: Option<JsonValue>,
+    output_schema_violated: bool,
     next_event_id: AtomicU64,
     // Tracks running commands by call_id, including the associated item id.
     running_commands: HashMap<String, RunningCommand>,
@@ -79,6 +82,8 @@ impl EventProcessorWithJsonOutput {
     pub fn new(last_message_path: Option<PathBuf>) -> Self {
         Self {
             last_message_path,
+            output_schema: None,
+            output_schema_violated: false,
             next_event_id: AtomicU64::new(0),
             running_commands: HashMap::new(),
             running_patch_applies: HashMap::new(),
@@ -89,6 +94,13 @@ impl EventProcessorWithJsonOutput {
         }
     }
 
+    /// Validates the final agent message against `schema` before it is written
+    /// to `--output-last-message`.
+    pub fn with_output_schema(mut self, schema: Option<JsonValue>) -> Self {
+        self.output_schema = schema;
+        self
+    }
+
     pub fn collect_thread_events(&mut self, event: &protocol::Event) -> Vec<ThreadEvent> {
         match &event.msg {
             protocol::EventMsg::SessionConfigured(ev) => self.handle_session_configured(ev),
@@ -540,7 +552,11 @@ impl EventProcessor for EventProcessorWithJsonOutput {
             last_agent_message,
         }) = msg
         {
-            if let Some(output_file) = self.last_message_path.as_deref() {
+            self.output_schema_violated =
+                !check_output_schema(last_agent_message.as_deref(), self.output_schema.as_ref());
+            if !self.output_schema_violated
+                && let Some(output_file) = self.last_message_path.as_deref()
+            {
                 handle_last_message(last_agent_message.as_deref(), output_file);
             }
             CodexStatus::InitiateShutdown
@@ -548,4 +564,8 @@ impl EventProcessor for EventProcessorWithJsonOutput {
             CodexStatus::Running
         }
     }
+
+    fn output_schema_violated(&self) -> bool {
+        self.output_schema_violated
+    }
 }
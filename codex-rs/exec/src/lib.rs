@@ -9,6 +9,7 @@ mod event_processor;
 mod event_processor_with_human_output;
 pub mod event_processor_with_jsonl_output;
 pub mod exec_events;
+mod output_schema;
 
 pub use cli::Cli;
 pub use cli::Command;
@@ -57,6 +58,7 @@ use tracing_subscriber::prelude::*;
 use crate::cli::Command as ExecCommand;
 use crate::event_processor::CodexStatus;
 use crate::event_processor::EventProcessor;
+use crate::output_schema::check_supported_schema;
 use codex_core::default_client::set_default_originator;
 use codex_core::find_thread_path_by_id_str;
 
@@ -258,13 +260,37 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         .with(otel_logger_layer)
         .try_init();
 
+    // Reviews never send `--output-schema`, so there is nothing to validate.
+    let output_schema = match command {
+        Some(ExecCommand::Review(_)) => None,
+        _ => load_output_schema(output_schema_path),
+    };
+    // Only validate locally when the provider cannot enforce the schema.
+    let validated_output_schema = output_schema
+        .clone()
+        .filter(|_| !config.model_provider.enforces_output_schema());
+    if let Some(schema) = validated_output_schema.as_ref()
+        && let Err(err) = check_supported_schema(schema)
+    {
+        eprintln!(
+            "Output schema cannot be enforced for model provider `{}`, which leaves validation to Codex: {err}",
+            config.model_provider.name
+        );
+        std::process::exit(1);
+    }
     let mut event_processor: Box<dyn EventProcessor> = match json_mode {
-        true => Box::new(EventProcessorWithJsonOutput::new(last_message_file.clone())),
-        _ => Box::new(EventProcessorWithHumanOutput::create_with_ansi(
-            stdout_with_ansi,
-            &config,
-            last_message_file.clone(),
-        )),
+        true => Box::new(
+            EventProcessorWithJsonOutput::new(last_message_file.clone())
+                .with_output_schema(validated_output_schema.clone()),
+        ),
+        _ => Box::new(
+            EventProcessorWithHumanOutput::create_with_ansi(
+                stdout_with_ansi,
+                &config,
+                last_message_file.clone(),
+            )
+            .with_output_schema(validated_output_schema),
+        ),
     };
     if let Some(notice) = ollama_chat_support_notice {
         event_processor.process_event(Event {
@@ -361,11 +387,10 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
             items.push(UserInput::Text {
                 text: prompt_text.clone(),
             });
-            let output_schema = load_output_schema(output_schema_path.clone());
             (
                 InitialOperation::UserTurn {
                     items,
-                    output_schema,
+                    output_schema: output_schema.clone(),
                 },
                 prompt_text,
             )
@@ -379,11 +404,10 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
             items.push(UserInput::Text {
                 text: prompt_text.clone(),
             });
-            let output_schema = load_output_schema(output_schema_path);
             (
                 InitialOperation::UserTurn {
                     items,
-                    output_schema,
+                    output_schema: output_schema.clone(),
                 },
                 prompt_text,
             )
@@ -492,7 +516,7 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         }
     }
     event_processor.print_final_output();
-    if error_seen {
+    if error_seen || event_processor.output_schema_violated() {
         std::process::exit(1);
     }
 
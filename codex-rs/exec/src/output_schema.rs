@@ -0,0 +1,335 @@
+//! Validation of the final agent message against `--output-schema`.
+//!
+//! Providers that only support `json_object` mode (e.g. DeepSeek over the Chat
+//! Completions API) cannot enforce the schema server-side, so `codex exec`
+//! checks the final message itself before writing `--output-last-message`.
+//! Only the subset of JSON Schema used for structured outputs is supported:
+//! `type`, `enum`, `const`, `properties`, `required`, `additionalProperties`,
+//! `items`, `minItems`/`maxItems`, `minLength`/`maxLength`, `minimum`/`maximum`
+//! and `anyOf`/`oneOf`/`allOf`, plus annotations such as `title` and
+//! `description`. Schemas using any other keyword are rejected up front by
+//! [`check_supported_schema`] rather than partially enforced.
+
+use serde_json::Value;
+
+/// Keywords [`validate_value`] enforces, or that never constrain a value.
+const SUPPORTED_KEYWORDS: &[&str] = &[
+    "type",
+    "enum",
+    "const",
+    "properties",
+    "required",
+    "additionalProperties",
+    "items",
+    "minItems",
+    "maxItems",
+    "minLength",
+    "maxLength",
+    "minimum",
+    "maximum",
+    "anyOf",
+    "oneOf",
+    "allOf",
+    "$schema",
+    "$comment",
+    "title",
+    "description",
+    "default",
+    "examples",
+    "deprecated",
+    "readOnly",
+    "writeOnly",
+];
+
+/// Checks that `schema` only uses keywords that [`validate_last_message`] can
+/// enforce, returning the location of the first one it cannot.
+pub(crate) fn check_supported_schema(schema: &Value) -> Result<(), String> {
+    check_supported_subschema(schema, "$")
+}
+
+fn check_supported_subschema(schema: &Value, path: &str) -> Result<(), String> {
+    let map = match schema {
+        Value::Object(map) => map,
+        Value::Bool(_) => return Ok(()),
+        _ => return Err(format!("{path}: a schema must be an object or a boolean")),
+    };
+    for (keyword, value) in map {
+        if !SUPPORTED_KEYWORDS.contains(&keyword.as_str()) {
+            return Err(format!("{path}: unsupported keyword `{keyword}`"));
+        }
+        let child_path = format!("{path}.{keyword}");
+        match keyword.as_str() {
+            "properties" => {
+                let Some(properties) = value.as_object() else {
+                    return Err(format!("{child_path}: expected an object"));
+                };
+                for (name, child) in properties {
+                    check_supported_subschema(child, &format!("{child_path}.{name}"))?;
+                }
+            }
+            "additionalProperties" | "items" => check_supported_subschema(value, &child_path)?,
+            "anyOf" | "oneOf" | "allOf" => {
+                let Some(options) = value.as_array() else {
+                    return Err(format!("{child_path}: expected an array"));
+                };
+                for (idx, option) in options.iter().enumerate() {
+                    check_supported_subschema(option, &format!("{child_path}[{idx}]"))?;
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Parses `message` as JSON and checks it against `schema`, returning a
+/// human-readable description of the first violation.
+pub(crate) fn validate_last_message(message: &str, schema: &Value) -> Result<(), String> {
+    let value: Value = serde_json::from_str(message.trim())
+        .map_err(|err| format!("final message is not valid JSON: {err}"))?;
+    validate_value(&value, schema, "$")
+}
+
+fn validate_value(value: &Value, schema: &Value, path: &str) -> Result<(), String> {
+    let Some(schema) = schema.as_object() else {
+        // `true`/`false` schemas and anything else we do not understand.
+        return match schema {
+            Value::Bool(false) => Err(format!("{path}: no value is allowed here")),
+            _ => Ok(()),
+        };
+    };
+
+    if let Some(expected) = schema.get("type") {
+        let matches = match expected {
+            Value::String(ty) => type_matches(value, ty),
+            Value::Array(types) => types
+                .iter()
+                .filter_map(Value::as_str)
+                .any(|ty| type_matches(value, ty)),
+            _ => true,
+        };
+        if !matches {
+            return Err(format!(
+                "{path}: expected type {expected}, found {}",
+                type_name(value)
+            ));
+        }
+    }
+
+    if let Some(options) = schema.get("enum").and_then(Value::as_array)
+        && !options.contains(value)
+    {
+        return Err(format!(
+            "{path}: {value} is not one of {}",
+            Value::from(options.clone())
+        ));
+    }
+
+    if let Some(expected) = schema.get("const")
+        && expected != value
+    {
+        return Err(format!("{path}: expected {expected}, found {value}"));
+    }
+
+    for sub in schema
+        .get("allOf")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        validate_value(value, sub, path)?;
+    }
+    for key in ["anyOf", "oneOf"] {
+        if let Some(options) = schema.get(key).and_then(Value::as_array) {
+            let matched = options
+                .iter()
+                .filter(|sub| validate_value(value, sub, path).is_ok())
+                .count();
+            let ok = if key == "oneOf" {
+                matched == 1
+            } else {
+                matched > 0
+            };
+            if !ok {
+                return Err(format!("{path}: value does not match {key}"));
+            }
+        }
+    }
+
+    match value {
+        Value::Object(map) => {
+            let properties = schema.get("properties").and_then(Value::as_object);
+            for required in schema
+                .get("required")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+            {
+                if !map.contains_key(required) {
+                    return Err(format!("{path}: missing required property `{required}`"));
+                }
+            }
+            for (key, child) in map {
+                let child_path = format!("{path}.{key}");
+                match properties.and_then(|props| props.get(key)) {
+                    Some(child_schema) => validate_value(child, child_schema, &child_path)?,
+                    None => match schema.get("additionalProperties") {
+                        Some(Value::Bool(false)) => {
+                            return Err(format!("{path}: unexpected property `{key}`"));
+                        }
+                        Some(additional) => validate_value(child, additional, &child_path)?,
+                        None => {}
+                    },
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(min) = schema.get("minItems").and_then(Value::as_u64)
+                && (items.len() as u64) < min
+            {
+                return Err(format!("{path}: expected at least {min} items"));
+            }
+            if let Some(max) = schema.get("maxItems").and_then(Value::as_u64)
+                && (items.len() as u64) > max
+            {
+                return Err(format!("{path}: expected at most {max} items"));
+            }
+            if let Some(item_schema) = schema.get("items") {
+                for (idx, item) in items.iter().enumerate() {
+                    validate_value(item, item_schema, &format!("{path}[{idx}]"))?;
+                }
+            }
+        }
+        Value::String(text) => {
+            let len = text.chars().count() as u64;
+            if let Some(min) = schema.get("minLength").and_then(Value::as_u64)
+                && len < min
+            {
+                return Err(format!("{path}: expected at least {min} characters"));
+            }
+            if let Some(max) = schema.get("maxLength").and_then(Value::as_u64)
+                && len > max
+            {
+                return Err(format!("{path}: expected at most {max} characters"));
+            }
+        }
+        Value::Number(number) => {
+            let number = number.as_f64().unwrap_or_default();
+            if let Some(min) = schema.get("minimum").and_then(Value::as_f64)
+                && number < min
+            {
+                return Err(format!("{path}: {number} is less than minimum {min}"));
+            }
+            if let Some(max) = schema.get("maximum").and_then(Value::as_f64)
+                && number > max
+            {
+                return Err(format!("{path}: {number} is greater than maximum {max}"));
+            }
+        }
+        Value::Null | Value::Bool(_) => {}
+    }
+
+    Ok(())
+}
+
+fn type_matches(value: &Value, ty: &str) -> bool {
+    match ty {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "answer": { "type": "string" },
+                "confidence": { "type": "number", "minimum": 0, "maximum": 1 },
+                "tags": { "type": "array", "items": { "enum": ["a", "b"] } }
+            },
+            "required": ["answer"],
+            "additionalProperties": false
+        })
+    }
+
+    #[test]
+    fn accepts_matching_message() {
+        let message = r#"{"answer": "42", "confidence": 0.5, "tags": ["a"]}"#;
+        assert_eq!(validate_last_message(message, &schema()), Ok(()));
+    }
+
+    #[test]
+    fn reports_schema_violations() {
+        assert_eq!(
+            validate_last_message(r#"{"confidence": 0.5}"#, &schema()),
+            Err("$: missing required property `answer`".to_string())
+        );
+        assert_eq!(
+            validate_last_message(r#"{"answer": "x", "extra": 1}"#, &schema()),
+            Err("$: unexpected property `extra`".to_string())
+        );
+        assert_eq!(
+            validate_last_message(r#"{"answer": "x", "tags": ["c"]}"#, &schema()),
+            Err(r#"$.tags[0]: "c" is not one of ["a","b"]"#.to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_schemas_with_unsupported_keywords() {
+        assert_eq!(check_supported_schema(&schema()), Ok(()));
+        assert_eq!(
+            check_supported_schema(&json!({
+                "type": "object",
+                "properties": {
+                    "id": { "type": "string", "pattern": "^[a-z]+$" }
+                }
+            })),
+            Err("$.properties.id: unsupported keyword `pattern`".to_string())
+        );
+        assert_eq!(
+            check_supported_schema(&json!({
+                "$defs": { "item": { "type": "string" } },
+                "type": "array",
+                "items": { "$ref": "#/$defs/item" }
+            })),
+            Err("$: unsupported keyword `$defs`".to_string())
+        );
+        assert_eq!(
+            check_supported_schema(&json!({
+                "anyOf": [{ "type": "string" }, { "type": "string", "format": "email" }]
+            })),
+            Err("$.anyOf[1]: unsupported keyword `format`".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_non_json_message() {
+        let err =
+            validate_last_message("Sure! Here is the answer.", &schema()).expect_err("not json");
+        assert!(err.starts_with("final message is not valid JSON"));
+    }
+}
@@ -4,6 +4,11 @@
 use core_test_support::responses;
 use core_test_support::test_codex_exec::test_codex_exec;
 use serde_json::Value;
+use wiremock::Mock;
+use wiremock::MockServer;
+use wiremock::ResponseTemplate;
+use wiremock::matchers::method;
+use wiremock::matchers::path;
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn exec_includes_output_schema_in_request() -> anyhow::Result<()> {
@@ -60,3 +65,61 @@ async fn exec_includes_output_schema_in_request() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Chat providers in `json_object` mode cannot enforce the schema, so
+/// `codex exec` validates the final message itself.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn exec_fails_when_final_message_violates_output_schema() -> anyhow::Result<()> {
+    let test = test_codex_exec();
+
+    let schema_contents = serde_json::json!({
+        "type": "object",
+        "properties": {
+            "answer": { "type": "string" }
+        },
+        "required": ["answer"],
+        "additionalProperties": false
+    });
+    let schema_path = test.cwd_path().join("schema.json");
+    std::fs::write(&schema_path, serde_json::to_vec_pretty(&schema_contents)?)?;
+    let last_message_path = test.cwd_path().join("last_message.json");
+
+    let server = MockServer::start().await;
+    let sse = concat!(
+        "data: {\"choices\":[{\"delta\":{\"content\":\"{\\\"reply\\\": \\\"not the schema\\\"}\"}}]}\n\n",
+        "data: {\"choices\":[{\"delta\":{}}]}\n\n",
+        "data: [DONE]\n\n"
+    );
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(sse, "text/event-stream"))
+        .mount(&server)
+        .await;
+    let provider_override = format!(
+        "model_providers.mock={{ name = \"mock\", base_url = \"{}/v1\", env_key = \"PATH\", wire_api = \"chat\", chat_dialect = {{ response_format = \"json_object\" }} }}",
+        server.uri()
+    );
+
+    test.cmd()
+        .arg("--skip-git-repo-check")
+        .arg("-c")
+        .arg(&provider_override)
+        .arg("-c")
+        .arg("model_provider=\"mock\"")
+        .arg("-C")
+        .arg(test.cwd_path())
+        .arg("--output-schema")
+        .arg(&schema_path)
+        .arg("--output-last-message")
+        .arg(&last_message_path)
+        .arg("tell me a joke")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "Final agent message does not match --output-schema",
+        ));
+
+    assert!(!last_message_path.exists());
+
+    Ok(())
+}
@@ -6,9 +6,15 @@ use std::io::Write;
 use std::path::Path;
 use std::path::PathBuf;
 
+use chrono::DateTime;
+use chrono::Utc;
+use serde::Deserialize;
+use serde::Serialize;
 use serde_json;
 use thiserror::Error;
 
+use crate::rule::Condition;
+
 #[derive(Debug, Error)]
 pub enum AmendError {
     #[error("prefix rule requires at least one token")]
@@ -22,6 +28,8 @@ pub enum AmendError {
     },
     #[error("failed to format prefix tokens: {source}")]
     SerializePrefix { source: serde_json::Error },
+    #[error("failed to format amendment metadata: {source}")]
+    SerializeAmendment { source: serde_json::Error },
     #[error("failed to open policy file {path}: {source}")]
     OpenPolicyFile {
         path: PathBuf,
@@ -54,12 +62,124 @@ pub enum AmendError {
     },
 }
 
+/// Provenance, scope and expiry recorded with an allow rule that was added from an
+/// approval prompt. Written as the `amendment` argument of the rule's `prefix_rule`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct AmendmentMetadata {
+    /// Identifier used to list and revoke the amendment.
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    /// The rule stops matching at this instant.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Repository root the rule is limited to; unset means it applies everywhere.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo: Option<PathBuf>,
+    /// Conversation in which the command was approved.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thread_id: Option<String>,
+    /// Who approved the command.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approved_by: Option<String>,
+}
+
+impl AmendmentMetadata {
+    /// Conditions that enforce the amendment's repository scope and expiry.
+    pub fn conditions(&self) -> Vec<Condition> {
+        self.expires_at
+            .map(Condition::ExpiresAt)
+            .into_iter()
+            .chain(self.repo.clone().map(Condition::CwdWithin))
+            .collect()
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
+
 /// Note this thread uses advisory file locking and performs blocking I/O, so it should be used with
 /// [`tokio::task::spawn_blocking`] when called from an async context.
 pub fn blocking_append_allow_prefix_rule(
     policy_path: &Path,
     prefix: &[String],
 ) -> Result<(), AmendError> {
+    let pattern = render_prefix_pattern(prefix)?;
+    let rule = format!(r#"prefix_rule(pattern={pattern}, decision="allow")"#);
+    create_policy_dir(policy_path)?;
+    append_locked_line(policy_path, &rule)
+}
+
+/// Like [`blocking_append_allow_prefix_rule`], recording `metadata` with the rule so it can be
+/// scoped, expired, listed and revoked.
+pub fn blocking_append_amendment(
+    policy_path: &Path,
+    prefix: &[String],
+    metadata: &AmendmentMetadata,
+) -> Result<(), AmendError> {
+    let pattern = render_prefix_pattern(prefix)?;
+    let metadata = serde_json::to_string(metadata)
+        .map_err(|source| AmendError::SerializeAmendment { source })?;
+    let rule = format!(r#"prefix_rule(pattern={pattern}, decision="allow", amendment={metadata})"#);
+    create_policy_dir(policy_path)?;
+    append_locked_line(policy_path, &rule)
+}
+
+/// Removes the rule recorded for amendment `id`, returning whether one was found.
+///
+/// Amendments are written one rule per line by [`blocking_append_amendment`], so the
+/// line is located by the serialized `id` that leads its metadata.
+pub fn blocking_revoke_amendment(policy_path: &Path, id: &str) -> Result<bool, AmendError> {
+    let id =
+        serde_json::to_string(id).map_err(|source| AmendError::SerializeAmendment { source })?;
+    let marker = format!(r#"amendment={{"id":{id},"#);
+
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(policy_path)
+        .map_err(|source| AmendError::OpenPolicyFile {
+            path: policy_path.to_path_buf(),
+            source,
+        })?;
+    file.lock().map_err(|source| AmendError::LockPolicyFile {
+        path: policy_path.to_path_buf(),
+        source,
+    })?;
+
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)
+        .map_err(|source| AmendError::ReadPolicyFile {
+            path: policy_path.to_path_buf(),
+            source,
+        })?;
+    let kept: Vec<&str> = contents
+        .lines()
+        .filter(|line| !line.contains(&marker))
+        .collect();
+    if kept.len() == contents.lines().count() {
+        return Ok(false);
+    }
+
+    let mut updated = kept.join("\n");
+    if !updated.is_empty() {
+        updated.push('\n');
+    }
+    file.seek(SeekFrom::Start(0))
+        .map_err(|source| AmendError::SeekPolicyFile {
+            path: policy_path.to_path_buf(),
+            source,
+        })?;
+    file.set_len(0)
+        .and_then(|()| file.write_all(updated.as_bytes()))
+        .map_err(|source| AmendError::WritePolicyFile {
+            path: policy_path.to_path_buf(),
+            source,
+        })?;
+    Ok(true)
+}
+
+fn render_prefix_pattern(prefix: &[String]) -> Result<String, AmendError> {
     if prefix.is_empty() {
         return Err(AmendError::EmptyPrefix);
     }
@@ -69,25 +189,23 @@ pub fn blocking_append_allow_prefix_rule(
         .map(serde_json::to_string)
         .collect::<Result<Vec<_>, _>>()
         .map_err(|source| AmendError::SerializePrefix { source })?;
-    let pattern = format!("[{}]", tokens.join(", "));
-    let rule = format!(r#"prefix_rule(pattern={pattern}, decision="allow")"#);
+    Ok(format!("[{}]", tokens.join(", ")))
+}
 
+fn create_policy_dir(policy_path: &Path) -> Result<(), AmendError> {
     let dir = policy_path
         .parent()
         .ok_or_else(|| AmendError::MissingParent {
             path: policy_path.to_path_buf(),
         })?;
     match std::fs::create_dir(dir) {
-        Ok(()) => {}
-        Err(ref source) if source.kind() == std::io::ErrorKind::AlreadyExists => {}
-        Err(source) => {
-            return Err(AmendError::CreatePolicyDir {
-                dir: dir.to_path_buf(),
-                source,
-            });
-        }
+        Ok(()) => Ok(()),
+        Err(ref source) if source.kind() == std::io::ErrorKind::AlreadyExists => Ok(()),
+        Err(source) => Err(AmendError::CreatePolicyDir {
+            dir: dir.to_path_buf(),
+            source,
+        }),
     }
-    append_locked_line(policy_path, &rule)
 }
 
 fn append_locked_line(policy_path: &Path, line: &str) -> Result<(), AmendError> {
@@ -219,6 +337,38 @@ prefix_rule(pattern=["echo", "Hello, world!"], decision="allow")
             contents,
             r#"prefix_rule(pattern=["ls"], decision="allow")
 prefix_rule(pattern=["echo", "Hello, world!"], decision="allow")
+"#
+        );
+    }
+
+    #[test]
+    fn revoke_removes_only_the_matching_amendment() {
+        let tmp = tempdir().expect("create temp dir");
+        let policy_path = tmp.path().join("rules").join("default.rules");
+        let metadata = |id: &str| AmendmentMetadata {
+            id: id.to_string(),
+            created_at: "2026-01-01T00:00:00Z".parse().expect("parse timestamp"),
+            expires_at: None,
+            repo: Some(PathBuf::from("/repo")),
+            thread_id: None,
+            approved_by: None,
+        };
+
+        blocking_append_allow_prefix_rule(&policy_path, &[String::from("ls")])
+            .expect("append plain rule");
+        blocking_append_amendment(&policy_path, &[String::from("echo")], &metadata("a1"))
+            .expect("append first amendment");
+        blocking_append_amendment(&policy_path, &[String::from("cat")], &metadata("a12"))
+            .expect("append second amendment");
+
+        assert!(blocking_revoke_amendment(&policy_path, "a1").expect("revoke amendment"));
+        assert!(!blocking_revoke_amendment(&policy_path, "a1").expect("revoke again"));
+
+        let contents = std::fs::read_to_string(&policy_path).expect("read policy");
+        assert_eq!(
+            contents,
+            r#"prefix_rule(pattern=["ls"], decision="allow")
+prefix_rule(pattern=["cat"], decision="allow", amendment={"id":"a12","created_at":"2026-01-01T00:00:00Z","repo":"/repo"})
 "#
         );
     }
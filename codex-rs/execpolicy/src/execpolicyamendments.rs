@@ -0,0 +1,163 @@
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use chrono::Utc;
+use clap::Parser;
+use serde::Serialize;
+
+use crate::AmendmentMetadata;
+use crate::PolicyParser;
+use crate::blocking_revoke_amendment;
+use crate::execpolicytest::expand_rule_paths;
+
+/// Arguments for listing the rules recorded from "always allow" approvals.
+#[derive(Debug, Parser, Clone)]
+pub struct ExecPolicyListAmendmentsCommand {
+    /// Rule files, or directories whose `*.rules` files are searched (repeatable).
+    /// `codex execpolicy` defaults to `$CODEX_HOME/rules`.
+    #[arg(short = 'r', long = "rules", value_name = "PATH")]
+    pub rules: Vec<PathBuf>,
+
+    /// Print the amendments as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+impl ExecPolicyListAmendmentsCommand {
+    pub fn run(&self) -> Result<()> {
+        let amendments = load_amendments(&self.rules)?;
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&amendments)?);
+            return Ok(());
+        }
+
+        if amendments.is_empty() {
+            println!("No amendments found.");
+            return Ok(());
+        }
+        let now = Utc::now();
+        for amendment in &amendments {
+            let metadata = &amendment.metadata;
+            println!("{} {}", metadata.id, amendment.pattern);
+            match &metadata.repo {
+                Some(repo) => println!("    scope: repo {}", repo.display()),
+                None => println!("    scope: global"),
+            }
+            let expiry = match metadata.expires_at {
+                Some(expires_at) if metadata.is_expired(now) => {
+                    format!("{} (expired)", expires_at.to_rfc3339())
+                }
+                Some(expires_at) => expires_at.to_rfc3339(),
+                None => "never".to_string(),
+            };
+            println!(
+                "    created: {}, expires: {expiry}",
+                metadata.created_at.to_rfc3339()
+            );
+            println!(
+                "    approved by: {}, thread: {}",
+                metadata.approved_by.as_deref().unwrap_or("unknown"),
+                metadata.thread_id.as_deref().unwrap_or("unknown")
+            );
+            println!("    file: {}", amendment.file.display());
+        }
+        Ok(())
+    }
+}
+
+/// Arguments for removing a rule recorded from an "always allow" approval.
+#[derive(Debug, Parser, Clone)]
+pub struct ExecPolicyRevokeCommand {
+    /// Amendment id, or a prefix that identifies exactly one amendment.
+    #[arg(value_name = "ID")]
+    pub id: String,
+
+    /// Rule files, or directories whose `*.rules` files are searched (repeatable).
+    /// `codex execpolicy` defaults to `$CODEX_HOME/rules`.
+    #[arg(short = 'r', long = "rules", value_name = "PATH")]
+    pub rules: Vec<PathBuf>,
+}
+
+impl ExecPolicyRevokeCommand {
+    /// Sessions that are already running keep the amendment until they restart.
+    pub fn run(&self) -> Result<()> {
+        let amendments = load_amendments(&self.rules)?;
+        let mut ids: Vec<&str> = amendments
+            .iter()
+            .map(|amendment| amendment.metadata.id.as_str())
+            .filter(|id| id.starts_with(self.id.as_str()))
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+
+        let id = match ids.as_slice() {
+            [] => anyhow::bail!("no amendment matches id `{}`", self.id),
+            [id] => *id,
+            _ => anyhow::bail!(
+                "id `{}` matches several amendments: {}",
+                self.id,
+                ids.join(", ")
+            ),
+        };
+
+        for amendment in amendments
+            .iter()
+            .filter(|amendment| amendment.metadata.id == id)
+        {
+            let removed = blocking_revoke_amendment(&amendment.file, id).with_context(|| {
+                format!("failed to revoke amendment in {}", amendment.file.display())
+            })?;
+            if !removed {
+                // The rule parses but was not written on one line by
+                // `blocking_append_amendment`, e.g. after hand edits.
+                anyhow::bail!(
+                    "could not locate the rule for amendment {id} in {}; remove it by hand",
+                    amendment.file.display()
+                );
+            }
+            println!("Revoked {id} {}", amendment.pattern);
+        }
+        Ok(())
+    }
+}
+
+/// A rule recorded from an approval prompt and the file it was found in.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ListedAmendment {
+    pub file: PathBuf,
+    pub pattern: String,
+    #[serde(flatten)]
+    pub metadata: AmendmentMetadata,
+}
+
+/// Parses each rules file on its own and collects the amendments it declares.
+pub fn load_amendments(rule_paths: &[PathBuf]) -> Result<Vec<ListedAmendment>> {
+    if rule_paths.is_empty() {
+        anyhow::bail!("no rules files given; pass --rules");
+    }
+
+    let mut amendments = Vec::new();
+    for path in expand_rule_paths(rule_paths)? {
+        let contents = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read policy at {}", path.display()))?;
+        let mut parser = PolicyParser::new();
+        parser
+            .parse(&path.to_string_lossy(), &contents)
+            .with_context(|| format!("failed to parse policy at {}", path.display()))?;
+        let policy = parser.build();
+
+        amendments.extend(policy.declarations().iter().filter_map(|declaration| {
+            declaration
+                .amendment
+                .clone()
+                .map(|metadata| ListedAmendment {
+                    file: path.clone(),
+                    pattern: declaration.pattern.clone(),
+                    metadata,
+                })
+        }));
+    }
+    Ok(amendments)
+}
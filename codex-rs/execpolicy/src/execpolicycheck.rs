@@ -10,6 +10,7 @@ use crate::Decision;
 use crate::Policy;
 use crate::PolicyParser;
 use crate::RuleMatch;
+use crate::rule::MatchContext;
 
 /// Arguments for evaluating a command against one or more execpolicy files.
 #[derive(Debug, Parser, Clone)]
@@ -18,6 +19,14 @@ pub struct ExecPolicyCheckCommand {
     #[arg(short = 'r', long = "rules", value_name = "PATH", required = true)]
     pub rules: Vec<PathBuf>,
 
+    /// Working directory used to resolve path arguments for `paths_within` conditions.
+    #[arg(long = "cwd", value_name = "DIR")]
+    pub cwd: Option<PathBuf>,
+
+    /// Writable root for `paths_within(["writable_roots"])` conditions (repeatable).
+    #[arg(long = "writable-root", value_name = "DIR")]
+    pub writable_roots: Vec<PathBuf>,
+
     /// Pretty-print the JSON output.
     #[arg(long)]
     pub pretty: bool,
@@ -36,7 +45,11 @@ impl ExecPolicyCheckCommand {
     /// Load the policies for this command, evaluate the command, and render JSON output.
     pub fn run(&self) -> Result<()> {
         let policy = load_policies(&self.rules)?;
-        let matched_rules = policy.matches_for_command(&self.command, None);
+        let ctx = MatchContext {
+            cwd: self.cwd.clone(),
+            writable_roots: self.writable_roots.clone(),
+        };
+        let matched_rules = policy.matches_for_command(&self.command, &ctx, None);
 
         let json = format_matches_json(&matched_rules, self.pretty)?;
         println!("{json}");
@@ -0,0 +1,451 @@
+use std::cmp::Reverse;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use clap::Parser;
+use serde_json::Value;
+use shlex::try_join;
+
+use crate::Decision;
+use crate::Policy;
+use crate::execpolicycheck::load_policies;
+use crate::policy::RuleDeclaration;
+use crate::rule::MatchContext;
+
+const RULE_EXTENSION: &str = "rules";
+const ROLLOUT_EXTENSION: &str = "jsonl";
+
+/// Arguments for running a corpus of commands against a set of execpolicy rules.
+#[derive(Debug, Parser, Clone)]
+pub struct ExecPolicyTestCommand {
+    /// Rule files, or directories whose `*.rules` files are loaded in sorted order (repeatable).
+    #[arg(short = 'r', long = "rules", value_name = "PATH", required = true)]
+    pub rules: Vec<PathBuf>,
+
+    /// Command corpus (repeatable). `.jsonl` files are read as rollouts; anything else is
+    /// read as one command per line, optionally prefixed with `allow:`, `prompt:`,
+    /// `forbidden:` or `unmatched:` to state the expected outcome.
+    #[arg(short = 'c', long = "corpus", value_name = "PATH", required = true)]
+    pub corpus: Vec<PathBuf>,
+
+    /// Working directory used to resolve path arguments for `paths_within` conditions.
+    #[arg(long = "cwd", value_name = "DIR")]
+    pub cwd: Option<PathBuf>,
+
+    /// Writable root for `paths_within(["writable_roots"])` conditions (repeatable).
+    #[arg(long = "writable-root", value_name = "DIR")]
+    pub writable_roots: Vec<PathBuf>,
+}
+
+impl ExecPolicyTestCommand {
+    /// Runs the corpus treating every command as a single argv. `codex execpolicy test` uses
+    /// [`Self::run_with`] instead so shell scripts are split the way Codex splits them.
+    pub fn run(&self) -> Result<()> {
+        self.run_with(
+            &|command: &[String]| vec![command.to_vec()],
+            &|_: &[String]| Decision::Prompt,
+        )
+    }
+
+    /// Evaluate every corpus command, print the results and the rules that never matched, and
+    /// fail if any command's outcome differs from its expectation.
+    ///
+    /// `split_command` turns a command such as `bash -lc "<script>"` into the commands it
+    /// runs, and `fallback` decides the ones no rule matches, as Codex does when it asks for
+    /// approval.
+    pub fn run_with<S, F>(&self, split_command: &S, fallback: &F) -> Result<()>
+    where
+        S: Fn(&[String]) -> Vec<Vec<String>>,
+        F: Fn(&[String]) -> Decision,
+    {
+        let policy = load_policies(&expand_rule_paths(&self.rules)?)?;
+        let mut corpus = Corpus::default();
+        for path in &self.corpus {
+            corpus.extend(load_corpus(path)?);
+        }
+        let ctx = MatchContext {
+            cwd: self.cwd.clone(),
+            writable_roots: self.writable_roots.clone(),
+        };
+
+        let report = TestReport::evaluate(&policy, &corpus, &ctx, split_command, fallback);
+        print!("{}", report.render(&policy));
+
+        let mismatches = report.mismatches().count();
+        if mismatches > 0 {
+            anyhow::bail!("{mismatches} command(s) did not match their expected outcome");
+        }
+        Ok(())
+    }
+}
+
+/// Replaces each directory with the `*.rules` files it contains, sorted by path.
+pub fn expand_rule_paths(paths: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let mut expanded = Vec::new();
+    for path in paths {
+        if !path.is_dir() {
+            expanded.push(path.clone());
+            continue;
+        }
+
+        let mut rule_files = Vec::new();
+        for entry in fs::read_dir(path)
+            .with_context(|| format!("failed to read rules directory {}", path.display()))?
+        {
+            let entry_path = entry
+                .with_context(|| format!("failed to read rules directory {}", path.display()))?
+                .path();
+            if entry_path.is_file()
+                && entry_path
+                    .extension()
+                    .is_some_and(|ext| ext == RULE_EXTENSION)
+            {
+                rule_files.push(entry_path);
+            }
+        }
+        rule_files.sort();
+        expanded.extend(rule_files);
+    }
+    Ok(expanded)
+}
+
+/// What happened to a command: no rule matched, or the strictest matching decision.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Outcome {
+    Unmatched,
+    Decision(Decision),
+}
+
+impl Outcome {
+    fn parse(raw: &str) -> Option<Self> {
+        match raw {
+            "unmatched" => Some(Self::Unmatched),
+            other => Decision::parse(other).ok().map(Self::Decision),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Unmatched => "unmatched",
+            Self::Decision(Decision::Allow) => "allow",
+            Self::Decision(Decision::Prompt) => "prompt",
+            Self::Decision(Decision::Forbidden) => "forbidden",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CorpusCommand {
+    /// `path:line` the command was read from.
+    pub origin: String,
+    pub command: Vec<String>,
+    pub expected: Option<Outcome>,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Corpus {
+    pub commands: Vec<CorpusCommand>,
+}
+
+impl Corpus {
+    fn extend(&mut self, other: Corpus) {
+        self.commands.extend(other.commands);
+    }
+}
+
+/// Reads a corpus file, choosing the rollout or plain-text format by extension.
+pub fn load_corpus(path: &Path) -> Result<Corpus> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("failed to read corpus at {}", path.display()))?;
+    let origin = path.display().to_string();
+    if path.extension().is_some_and(|ext| ext == ROLLOUT_EXTENSION) {
+        parse_rollout_corpus(&origin, &contents)
+    } else {
+        parse_text_corpus(&origin, &contents)
+    }
+}
+
+/// One command per line; blank lines and `#` comments are ignored.
+pub fn parse_text_corpus(origin: &str, contents: &str) -> Result<Corpus> {
+    let mut commands = Vec::new();
+    for (line_index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let origin = format!("{origin}:{}", line_index + 1);
+
+        let (expected, raw_command) = match line.split_once(':') {
+            Some((prefix, rest)) => match Outcome::parse(prefix.trim()) {
+                Some(expected) => (Some(expected), rest),
+                None => (None, line),
+            },
+            None => (None, line),
+        };
+        let command = shlex::split(raw_command)
+            .filter(|tokens| !tokens.is_empty())
+            .with_context(|| format!("{origin}: expected a shell-quoted command"))?;
+
+        commands.push(CorpusCommand {
+            origin,
+            command,
+            expected,
+        });
+    }
+    Ok(Corpus { commands })
+}
+
+/// Extracts the commands the model ran from a rollout file. Rollouts carry no
+/// expectations, so these commands only contribute decisions and coverage.
+pub fn parse_rollout_corpus(origin: &str, contents: &str) -> Result<Corpus> {
+    let mut corpus = Corpus::default();
+    for (line_index, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let origin = format!("{origin}:{}", line_index + 1);
+        let item: Value = serde_json::from_str(line)
+            .with_context(|| format!("{origin}: invalid rollout line"))?;
+        if item.get("type").and_then(Value::as_str) != Some("response_item") {
+            continue;
+        }
+        let Some(command) = item
+            .get("payload")
+            .and_then(rollout_command)
+            .and_then(RawCommand::into_argv)
+        else {
+            continue;
+        };
+
+        corpus.commands.push(CorpusCommand {
+            origin,
+            command,
+            expected: None,
+        });
+    }
+    Ok(corpus)
+}
+
+enum RawCommand {
+    Argv(Vec<String>),
+    Script(String),
+}
+
+impl RawCommand {
+    /// Scripts are wrapped as `bash -lc "<script>"`, the form Codex evaluates them in; they
+    /// are split into individual commands at evaluation time.
+    fn into_argv(self) -> Option<Vec<String>> {
+        match self {
+            Self::Argv(argv) if argv.is_empty() => None,
+            Self::Argv(argv) => Some(argv),
+            Self::Script(script) if script.trim().is_empty() => None,
+            Self::Script(script) => Some(vec!["bash".to_string(), "-lc".to_string(), script]),
+        }
+    }
+}
+
+/// Reads the command from a `local_shell_call` or from a shell-like `function_call`.
+fn rollout_command(payload: &Value) -> Option<RawCommand> {
+    match payload.get("type").and_then(Value::as_str)? {
+        "local_shell_call" => {
+            string_array(payload.get("action")?.get("command")?).map(RawCommand::Argv)
+        }
+        "function_call" => {
+            let name = payload.get("name").and_then(Value::as_str)?;
+            let arguments: Value =
+                serde_json::from_str(payload.get("arguments").and_then(Value::as_str)?).ok()?;
+            match name {
+                "shell" | "container.exec" => {
+                    string_array(arguments.get("command")?).map(RawCommand::Argv)
+                }
+                "shell_command" => arguments
+                    .get("command")
+                    .and_then(Value::as_str)
+                    .map(|script| RawCommand::Script(script.to_string())),
+                "exec_command" => arguments
+                    .get("cmd")
+                    .and_then(Value::as_str)
+                    .map(|script| RawCommand::Script(script.to_string())),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn string_array(value: &Value) -> Option<Vec<String>> {
+    value
+        .as_array()?
+        .iter()
+        .map(|token| token.as_str().map(str::to_string))
+        .collect()
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CommandResult {
+    pub origin: String,
+    pub command: Vec<String>,
+    pub expected: Option<Outcome>,
+    pub outcome: Outcome,
+    /// Index into [`Policy::declarations`] of the rule that decided the command; `None` when
+    /// no rule matched or the fallback decided an unmatched part of the command.
+    pub winner: Option<usize>,
+}
+
+impl CommandResult {
+    pub fn is_mismatch(&self) -> bool {
+        self.expected
+            .is_some_and(|expected| expected != self.outcome)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TestReport {
+    pub results: Vec<CommandResult>,
+    /// Indices into [`Policy::declarations`] of rules no corpus command matched.
+    pub unused: Vec<usize>,
+}
+
+impl TestReport {
+    /// Splits each command with `split_command` and evaluates every part against every rule
+    /// declaration. Parts no rule matches are decided by `fallback`, as they are when Codex
+    /// asks for approval. The winner is the strictest decision, with rules winning ties over
+    /// the fallback and earlier rules winning ties over later ones. A command is
+    /// [`Outcome::Unmatched`] when no part of it matches a rule.
+    pub fn evaluate<S, F>(
+        policy: &Policy,
+        corpus: &Corpus,
+        ctx: &MatchContext,
+        split_command: &S,
+        fallback: &F,
+    ) -> Self
+    where
+        S: Fn(&[String]) -> Vec<Vec<String>>,
+        F: Fn(&[String]) -> Decision,
+    {
+        let declarations = policy.declarations();
+        let mut used = vec![false; declarations.len()];
+
+        let results = corpus
+            .commands
+            .iter()
+            .map(|entry| {
+                let mut candidates: Vec<(Decision, Option<usize>)> = Vec::new();
+                let mut matched = false;
+                for command in split_command(&entry.command) {
+                    let before = candidates.len();
+                    for (index, declaration) in declarations.iter().enumerate() {
+                        if let Some(rule_match) = declaration.matches(&command, ctx) {
+                            used[index] = true;
+                            candidates.push((rule_match.decision(), Some(index)));
+                        }
+                    }
+                    if candidates.len() == before {
+                        candidates.push((fallback(&command), None));
+                    } else {
+                        matched = true;
+                    }
+                }
+                let winner = if matched {
+                    candidates.into_iter().min_by_key(|(decision, index)| {
+                        (Reverse(*decision), index.is_none(), *index)
+                    })
+                } else {
+                    None
+                };
+
+                CommandResult {
+                    origin: entry.origin.clone(),
+                    command: entry.command.clone(),
+                    expected: entry.expected,
+                    outcome: winner.map_or(Outcome::Unmatched, |(decision, _)| {
+                        Outcome::Decision(decision)
+                    }),
+                    winner: winner.and_then(|(_, index)| index),
+                }
+            })
+            .collect();
+
+        let unused = used
+            .iter()
+            .enumerate()
+            .filter_map(|(index, used)| (!used).then_some(index))
+            .collect();
+
+        Self { results, unused }
+    }
+
+    pub fn mismatches(&self) -> impl Iterator<Item = &CommandResult> {
+        self.results.iter().filter(|result| result.is_mismatch())
+    }
+
+    pub fn render(&self, policy: &Policy) -> String {
+        let declarations = policy.declarations();
+        let mut out = String::new();
+
+        for result in &self.results {
+            let status = match result.expected {
+                Some(_) if result.is_mismatch() => "FAIL",
+                Some(_) => "ok",
+                None => "-",
+            };
+            let command = try_join(result.command.iter().map(String::as_str))
+                .unwrap_or_else(|_| result.command.join(" "));
+            out.push_str(&format!(
+                "{status:<4} {:<9} {command}\n",
+                result.outcome.label()
+            ));
+            match result.winner.and_then(|index| declarations.get(index)) {
+                Some(declaration) => {
+                    out.push_str(&format!("       by {}\n", describe(declaration)));
+                }
+                None if result.outcome != Outcome::Unmatched => {
+                    out.push_str("       by fallback for an unmatched part of the command\n");
+                }
+                None => {}
+            }
+            if let Some(expected) = result.expected
+                && result.is_mismatch()
+            {
+                out.push_str(&format!(
+                    "       expected {} ({})\n",
+                    expected.label(),
+                    result.origin
+                ));
+            }
+        }
+
+        if !self.unused.is_empty() {
+            out.push_str("\nrules that never matched:\n");
+            for declaration in self
+                .unused
+                .iter()
+                .filter_map(|index| declarations.get(*index))
+            {
+                out.push_str(&format!("  {}\n", describe(declaration)));
+            }
+        }
+
+        out.push_str(&format!(
+            "\n{} command(s), {} mismatch(es); {} of {} rule(s) never matched\n",
+            self.results.len(),
+            self.mismatches().count(),
+            self.unused.len(),
+            declarations.len()
+        ));
+        out
+    }
+}
+
+fn describe(declaration: &RuleDeclaration) -> String {
+    format!(
+        "{}#{} {} -> {}",
+        declaration.policy,
+        declaration.index,
+        declaration.pattern,
+        Outcome::Decision(declaration.decision).label()
+    )
+}
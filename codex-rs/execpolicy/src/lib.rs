@@ -1,20 +1,30 @@
 pub mod amend;
 pub mod decision;
 pub mod error;
+pub mod execpolicyamendments;
 pub mod execpolicycheck;
+pub mod execpolicytest;
 pub mod parser;
 pub mod policy;
 pub mod rule;
 
 pub use amend::AmendError;
+pub use amend::AmendmentMetadata;
 pub use amend::blocking_append_allow_prefix_rule;
+pub use amend::blocking_append_amendment;
+pub use amend::blocking_revoke_amendment;
 pub use decision::Decision;
 pub use error::Error;
 pub use error::Result;
+pub use execpolicyamendments::ExecPolicyListAmendmentsCommand;
+pub use execpolicyamendments::ExecPolicyRevokeCommand;
 pub use execpolicycheck::ExecPolicyCheckCommand;
+pub use execpolicytest::ExecPolicyTestCommand;
 pub use parser::PolicyParser;
 pub use policy::Evaluation;
 pub use policy::Policy;
+pub use policy::RuleDeclaration;
+pub use rule::MatchContext;
 pub use rule::Rule;
 pub use rule::RuleMatch;
 pub use rule::RuleRef;
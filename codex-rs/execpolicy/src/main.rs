@@ -1,6 +1,9 @@
 use anyhow::Result;
 use clap::Parser;
+use codex_execpolicy::execpolicyamendments::ExecPolicyListAmendmentsCommand;
+use codex_execpolicy::execpolicyamendments::ExecPolicyRevokeCommand;
 use codex_execpolicy::execpolicycheck::ExecPolicyCheckCommand;
+use codex_execpolicy::execpolicytest::ExecPolicyTestCommand;
 
 /// CLI for evaluating exec policies
 #[derive(Parser)]
@@ -8,11 +11,20 @@ use codex_execpolicy::execpolicycheck::ExecPolicyCheckCommand;
 enum Cli {
     /// Evaluate a command against a policy.
     Check(ExecPolicyCheckCommand),
+    /// Run a corpus of commands against a policy and report rule coverage.
+    Test(ExecPolicyTestCommand),
+    /// List rules recorded from "always allow" approvals.
+    ListAmendments(ExecPolicyListAmendmentsCommand),
+    /// Remove a rule recorded from an "always allow" approval.
+    Revoke(ExecPolicyRevokeCommand),
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
     match cli {
         Cli::Check(cmd) => cmd.run(),
+        Cli::Test(cmd) => cmd.run(),
+        Cli::ListAmendments(cmd) => cmd.run(),
+        Cli::Revoke(cmd) => cmd.run(),
     }
 }
@@ -7,21 +7,30 @@ use starlark::eval::Evaluator;
 use starlark::starlark_module;
 use starlark::syntax::AstModule;
 use starlark::syntax::Dialect;
+use starlark::values::Heap;
 use starlark::values::Value;
+use starlark::values::dict::AllocDict;
+use starlark::values::dict::DictRef;
 use starlark::values::list::ListRef;
 use starlark::values::list::UnpackList;
 use starlark::values::none::NoneType;
 use std::cell::RefCell;
 use std::cell::RefMut;
+use std::path::PathBuf;
 use std::sync::Arc;
 
+use crate::amend::AmendmentMetadata;
 use crate::decision::Decision;
 use crate::error::Error;
 use crate::error::Result;
+use crate::policy::RuleDeclaration;
+use crate::rule::Condition;
+use crate::rule::PathRoot;
 use crate::rule::PatternToken;
 use crate::rule::PrefixPattern;
 use crate::rule::PrefixRule;
 use crate::rule::RuleRef;
+use crate::rule::TokenMatcher;
 use crate::rule::validate_match_examples;
 use crate::rule::validate_not_match_examples;
 
@@ -55,6 +64,7 @@ impl PolicyParser {
         .map_err(Error::Starlark)?;
         let globals = GlobalsBuilder::standard().with(policy_builtins).build();
         let module = Module::new();
+        self.builder.borrow_mut().begin_policy(policy_identifier);
         {
             let mut eval = Evaluator::new(&module);
             eval.extra = Some(&self.builder);
@@ -71,22 +81,55 @@ impl PolicyParser {
 #[derive(Debug, ProvidesStaticType)]
 struct PolicyBuilder {
     rules_by_program: MultiMap<String, RuleRef>,
+    declarations: Vec<RuleDeclaration>,
+    current_policy: String,
+    declarations_in_current_policy: usize,
 }
 
 impl PolicyBuilder {
     fn new() -> Self {
         Self {
             rules_by_program: MultiMap::new(),
+            declarations: Vec::new(),
+            current_policy: String::new(),
+            declarations_in_current_policy: 0,
         }
     }
 
-    fn add_rule(&mut self, rule: RuleRef) {
-        self.rules_by_program
-            .insert(rule.program().to_string(), rule);
+    fn begin_policy(&mut self, policy_identifier: &str) {
+        self.current_policy = policy_identifier.to_string();
+        self.declarations_in_current_policy = 0;
+    }
+
+    fn add_declaration(
+        &mut self,
+        pattern: &[PatternToken],
+        decision: Decision,
+        rules: Vec<RuleRef>,
+        amendment: Option<AmendmentMetadata>,
+    ) {
+        for rule in &rules {
+            self.rules_by_program
+                .insert(rule.program().to_string(), rule.clone());
+        }
+        self.declarations_in_current_policy += 1;
+        let pattern = pattern
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.declarations.push(RuleDeclaration {
+            policy: self.current_policy.clone(),
+            index: self.declarations_in_current_policy,
+            pattern: format!("[{pattern}]"),
+            decision,
+            rules,
+            amendment,
+        });
     }
 
     fn build(self) -> crate::policy::Policy {
-        crate::policy::Policy::new(self.rules_by_program)
+        crate::policy::Policy::with_declarations(self.rules_by_program, self.declarations)
     }
 }
 
@@ -104,7 +147,19 @@ fn parse_pattern<'v>(pattern: UnpackList<Value<'v>>) -> Result<Vec<PatternToken>
 }
 
 fn parse_pattern_token<'v>(value: Value<'v>) -> Result<PatternToken> {
-    if let Some(s) = value.unpack_str() {
+    if let Some(dict) = DictRef::from_value(value) {
+        let (kind, payload) = unpack_builtin_value(&dict)?;
+        let source = payload
+            .unpack_str()
+            .ok_or_else(|| Error::InvalidPattern(format!("{kind}() pattern must be a string")))?;
+        match kind {
+            "glob" => Ok(PatternToken::Glob(TokenMatcher::glob(source)?)),
+            "regex" => Ok(PatternToken::Regex(TokenMatcher::regex(source)?)),
+            other => Err(Error::InvalidPattern(format!(
+                "{other}() cannot be used as a pattern element"
+            ))),
+        }
+    } else if let Some(s) = value.unpack_str() {
         Ok(PatternToken::Single(s.to_string()))
     } else if let Some(list) = ListRef::from_value(value) {
         let tokens: Vec<String> = list
@@ -132,12 +187,118 @@ fn parse_pattern_token<'v>(value: Value<'v>) -> Result<PatternToken> {
         }
     } else {
         Err(Error::InvalidPattern(format!(
-            "pattern element must be a string or list of strings (got {})",
+            "pattern element must be a string, list of strings, glob() or regex() (got {})",
             value.get_type()
         )))
     }
 }
 
+/// Values produced by the `glob`, `regex`, `flag` and `paths_within` builtins are
+/// represented as two-entry dicts tagged with this key.
+const BUILTIN_KIND_KEY: &str = "execpolicy";
+const BUILTIN_PAYLOAD_KEY: &str = "value";
+
+fn alloc_builtin_value<'v>(heap: &'v Heap, kind: &str, payload: Value<'v>) -> Value<'v> {
+    heap.alloc(AllocDict([
+        (BUILTIN_KIND_KEY, heap.alloc(kind)),
+        (BUILTIN_PAYLOAD_KEY, payload),
+    ]))
+}
+
+fn unpack_builtin_value<'v>(dict: &DictRef<'v>) -> Result<(&'v str, Value<'v>)> {
+    let kind = dict
+        .get_str(BUILTIN_KIND_KEY)
+        .and_then(Value::unpack_str)
+        .ok_or_else(|| {
+            Error::InvalidRule(
+                "expected a value created by glob(), regex(), flag() or paths_within()".to_string(),
+            )
+        })?;
+    let payload = dict
+        .get_str(BUILTIN_PAYLOAD_KEY)
+        .ok_or_else(|| Error::InvalidRule(format!("{kind}() value is missing its payload")))?;
+    Ok((kind, payload))
+}
+
+fn parse_conditions<'v>(conditions: UnpackList<Value<'v>>) -> Result<Vec<Condition>> {
+    conditions.items.into_iter().map(parse_condition).collect()
+}
+
+fn parse_condition<'v>(value: Value<'v>) -> Result<Condition> {
+    let dict = DictRef::from_value(value).ok_or_else(|| {
+        Error::InvalidRule(format!(
+            "condition must be created by flag() or paths_within() (got {})",
+            value.get_type()
+        ))
+    })?;
+    let (kind, payload) = unpack_builtin_value(&dict)?;
+    match kind {
+        "flag" => Ok(Condition::Flag(parse_string_list(payload, "flag() names")?)),
+        "paths_within" => Ok(Condition::PathsWithin(
+            parse_string_list(payload, "paths_within() roots")?
+                .iter()
+                .map(|root| parse_path_root(root))
+                .collect::<Result<_>>()?,
+        )),
+        other => Err(Error::InvalidRule(format!(
+            "{other}() cannot be used as a condition"
+        ))),
+    }
+}
+
+fn parse_path_root(raw: &str) -> Result<PathRoot> {
+    match raw {
+        "cwd" => Ok(PathRoot::Cwd),
+        "writable_roots" => Ok(PathRoot::WritableRoots),
+        path if PathBuf::from(path).is_absolute() => Ok(PathRoot::Path(PathBuf::from(path))),
+        other => Err(Error::InvalidRule(format!(
+            "paths_within() root must be \"cwd\", \"writable_roots\" or an absolute path (got {other:?})"
+        ))),
+    }
+}
+
+/// Accepts a single string or a non-empty list of strings.
+fn parse_string_list<'v>(value: Value<'v>, what: &str) -> Result<Vec<String>> {
+    let values: Vec<String> = if let Some(single) = value.unpack_str() {
+        vec![single.to_string()]
+    } else if let Some(list) = ListRef::from_value(value) {
+        list.content()
+            .iter()
+            .map(|value| {
+                value
+                    .unpack_str()
+                    .ok_or_else(|| {
+                        Error::InvalidRule(format!(
+                            "{what} must be strings (got {})",
+                            value.get_type()
+                        ))
+                    })
+                    .map(str::to_string)
+            })
+            .collect::<Result<_>>()?
+    } else {
+        return Err(Error::InvalidRule(format!(
+            "{what} must be a string or list of strings (got {})",
+            value.get_type()
+        )));
+    };
+
+    if values.is_empty() || values.iter().any(String::is_empty) {
+        Err(Error::InvalidRule(format!("{what} cannot be empty")))
+    } else {
+        Ok(values)
+    }
+}
+
+/// Reads the metadata dict written by [`crate::amend::blocking_append_amendment`].
+fn parse_amendment<'v>(value: Value<'v>) -> Result<AmendmentMetadata> {
+    let json = value
+        .to_json()
+        .map_err(|err| Error::InvalidRule(format!("invalid amendment metadata: {err}")))?;
+    serde_json::from_str(&json)
+        .map_err(|err| Error::InvalidRule(format!("invalid amendment metadata: {err}")))
+}
+
 fn parse_examples<'v>(examples: UnpackList<Value<'v>>) -> Result<Vec<Vec<String>>> {
     examples.items.into_iter().map(parse_example).collect()
 }
@@ -207,12 +368,16 @@ fn policy_builder<'v, 'a>(eval: &Evaluator<'v, 'a, '_>) -> RefMut<'a, PolicyBuil
 
 #[starlark_module]
 fn policy_builtins(builder: &mut GlobalsBuilder) {
+    #[allow(clippy::too_many_arguments)]
     fn prefix_rule<'v>(
         pattern: UnpackList<Value<'v>>,
         decision: Option<&'v str>,
         r#match: Option<UnpackList<Value<'v>>>,
         not_match: Option<UnpackList<Value<'v>>>,
         justification: Option<&'v str>,
+        when: Option<UnpackList<Value<'v>>>,
+        unless: Option<UnpackList<Value<'v>>>,
+        amendment: Option<Value<'v>>,
         eval: &mut Evaluator<'v, '_, '_>,
     ) -> anyhow::Result<NoneType> {
         let decision = match decision {
@@ -229,6 +394,17 @@ fn policy_builtins(builder: &mut GlobalsBuilder) {
         };
 
         let pattern_tokens = parse_pattern(pattern)?;
+        let amendment = amendment.map(parse_amendment).transpose()?;
+        let mut when = when.map(parse_conditions).transpose()?.unwrap_or_default();
+        if let Some(amendment) = &amendment {
+            when.extend(amendment.conditions());
+        }
+        let when: Arc<[Condition]> = when.into();
+        let unless: Arc<[Condition]> = unless
+            .map(parse_conditions)
+            .transpose()?
+            .unwrap_or_default()
+            .into();
 
         let matches: Vec<Vec<String>> =
             r#match.map(parse_examples).transpose()?.unwrap_or_default();
@@ -243,6 +419,13 @@ fn policy_builtins(builder: &mut GlobalsBuilder) {
             .split_first()
             .ok_or_else(|| Error::InvalidPattern("pattern cannot be empty".to_string()))?;
 
+        if first_token.alternatives().is_empty() {
+            return Err(Error::InvalidPattern(
+                "first pattern element must be a string or list of strings".to_string(),
+            )
+            .into());
+        }
+
         let rest: Arc<[PatternToken]> = remaining_tokens.to_vec().into();
 
         let rules: Vec<RuleRef> = first_token
@@ -256,6 +439,8 @@ fn policy_builtins(builder: &mut GlobalsBuilder) {
                     },
                     decision,
                     justification: justification.clone(),
+                    when: when.clone(),
+                    unless: unless.clone(),
                 }) as RuleRef
             })
             .collect();
@@ -263,7 +448,41 @@ fn policy_builtins(builder: &mut GlobalsBuilder) {
         validate_not_match_examples(&rules, &not_matches)?;
         validate_match_examples(&rules, &matches)?;
 
-        rules.into_iter().for_each(|rule| builder.add_rule(rule));
+        builder.add_declaration(&pattern_tokens, decision, rules, amendment);
         Ok(NoneType)
     }
+
+    /// Pattern element matching any token that fits a shell-style glob (`*`, `?`).
+    fn glob<'v>(pattern: &'v str, eval: &mut Evaluator<'v, '_, '_>) -> anyhow::Result<Value<'v>> {
+        TokenMatcher::glob(pattern)?;
+        let heap = eval.heap();
+        Ok(alloc_builtin_value(heap, "glob", heap.alloc(pattern)))
+    }
+
+    /// Pattern element matching any token that fully matches a regular expression.
+    fn regex<'v>(pattern: &'v str, eval: &mut Evaluator<'v, '_, '_>) -> anyhow::Result<Value<'v>> {
+        TokenMatcher::regex(pattern)?;
+        let heap = eval.heap();
+        Ok(alloc_builtin_value(heap, "regex", heap.alloc(pattern)))
+    }
+
+    /// Condition that holds when any of `names` appears among the arguments.
+    fn flag<'v>(names: Value<'v>, eval: &mut Evaluator<'v, '_, '_>) -> anyhow::Result<Value<'v>> {
+        let names = parse_string_list(names, "flag() names")?;
+        let heap = eval.heap();
+        Ok(alloc_builtin_value(heap, "flag", heap.alloc(names)))
+    }
+
+    /// Condition that holds when every positional argument stays inside one of `roots`.
+    fn paths_within<'v>(
+        roots: Value<'v>,
+        eval: &mut Evaluator<'v, '_, '_>,
+    ) -> anyhow::Result<Value<'v>> {
+        let roots = parse_string_list(roots, "paths_within() roots")?;
+        for root in &roots {
+            parse_path_root(root)?;
+        }
+        let heap = eval.heap();
+        Ok(alloc_builtin_value(heap, "paths_within", heap.alloc(roots)))
+    }
 }
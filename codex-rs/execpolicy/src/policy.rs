@@ -1,6 +1,9 @@
+use crate::amend::AmendmentMetadata;
 use crate::decision::Decision;
 use crate::error::Error;
 use crate::error::Result;
+use crate::rule::Condition;
+use crate::rule::MatchContext;
 use crate::rule::PatternToken;
 use crate::rule::PrefixPattern;
 use crate::rule::PrefixRule;
@@ -16,11 +19,45 @@ type HeuristicsFallback<'a> = Option<&'a dyn Fn(&[String]) -> Decision>;
 #[derive(Clone, Debug)]
 pub struct Policy {
     rules_by_program: MultiMap<String, RuleRef>,
+    declarations: Vec<RuleDeclaration>,
+}
+
+/// A single `prefix_rule(...)` call and the rules it expanded to (one per
+/// alternative of the first pattern token).
+#[derive(Clone, Debug)]
+pub struct RuleDeclaration {
+    /// Identifier the declaring file was parsed under.
+    pub policy: String,
+    /// 1-based position of the declaration within its file.
+    pub index: usize,
+    /// The pattern as written, e.g. `["git", ["push", "pull"]]`.
+    pub pattern: String,
+    pub decision: Decision,
+    pub rules: Vec<RuleRef>,
+    /// Set when the rule was recorded from an approval prompt.
+    pub amendment: Option<AmendmentMetadata>,
+}
+
+impl RuleDeclaration {
+    /// Returns the first match produced by any of this declaration's rules.
+    pub fn matches(&self, cmd: &[String], ctx: &MatchContext) -> Option<RuleMatch> {
+        self.rules.iter().find_map(|rule| rule.matches(cmd, ctx))
+    }
 }
 
 impl Policy {
     pub fn new(rules_by_program: MultiMap<String, RuleRef>) -> Self {
-        Self { rules_by_program }
+        Self::with_declarations(rules_by_program, Vec::new())
+    }
+
+    pub(crate) fn with_declarations(
+        rules_by_program: MultiMap<String, RuleRef>,
+        declarations: Vec<RuleDeclaration>,
+    ) -> Self {
+        Self {
+            rules_by_program,
+            declarations,
+        }
     }
 
     pub fn empty() -> Self {
@@ -31,7 +68,31 @@ impl Policy {
         &self.rules_by_program
     }
 
+    /// Rule declarations in the order they were parsed. Rules added with
+    /// [`Policy::add_prefix_rule`] are not listed.
+    pub fn declarations(&self) -> &[RuleDeclaration] {
+        &self.declarations
+    }
+
     pub fn add_prefix_rule(&mut self, prefix: &[String], decision: Decision) -> Result<()> {
+        self.insert_prefix_rule(prefix, decision, Arc::from([]))
+    }
+
+    /// Adds the allow rule for an amendment, limited by its repository scope and expiry.
+    pub fn add_amendment(
+        &mut self,
+        prefix: &[String],
+        amendment: &AmendmentMetadata,
+    ) -> Result<()> {
+        self.insert_prefix_rule(prefix, Decision::Allow, amendment.conditions().into())
+    }
+
+    fn insert_prefix_rule(
+        &mut self,
+        prefix: &[String],
+        decision: Decision,
+        when: Arc<[Condition]>,
+    ) -> Result<()> {
         let (first_token, rest) = prefix
             .split_first()
             .ok_or_else(|| Error::InvalidPattern("prefix cannot be empty".to_string()))?;
@@ -47,6 +108,8 @@ impl Policy {
             },
             decision,
             justification: None,
+            when,
+            unless: Arc::from([]),
         });
 
         self.rules_by_program.insert(first_token.clone(), rule);
@@ -57,7 +120,8 @@ impl Policy {
     where
         F: Fn(&[String]) -> Decision,
     {
-        let matched_rules = self.matches_for_command(cmd, Some(heuristics_fallback));
+        let matched_rules =
+            self.matches_for_command(cmd, &MatchContext::default(), Some(heuristics_fallback));
         Evaluation::from_matches(matched_rules)
     }
 
@@ -67,6 +131,21 @@ impl Policy {
         commands: Commands,
         heuristics_fallback: &F,
     ) -> Evaluation
+    where
+        Commands: IntoIterator,
+        Commands::Item: AsRef<[String]>,
+        F: Fn(&[String]) -> Decision,
+    {
+        self.check_multiple_with_context(commands, &MatchContext::default(), heuristics_fallback)
+    }
+
+    /// Like [`Policy::check_multiple`], resolving path conditions against `ctx`.
+    pub fn check_multiple_with_context<Commands, F>(
+        &self,
+        commands: Commands,
+        ctx: &MatchContext,
+        heuristics_fallback: &F,
+    ) -> Evaluation
     where
         Commands: IntoIterator,
         Commands::Item: AsRef<[String]>,
@@ -75,7 +154,7 @@ impl Policy {
         let matched_rules: Vec<RuleMatch> = commands
             .into_iter()
             .flat_map(|command| {
-                self.matches_for_command(command.as_ref(), Some(heuristics_fallback))
+                self.matches_for_command(command.as_ref(), ctx, Some(heuristics_fallback))
             })
             .collect();
 
@@ -92,13 +171,19 @@ impl Policy {
     pub fn matches_for_command(
         &self,
         cmd: &[String],
+        ctx: &MatchContext,
         heuristics_fallback: HeuristicsFallback<'_>,
     ) -> Vec<RuleMatch> {
         let matched_rules: Vec<RuleMatch> = match cmd.first() {
             Some(first) => self
                 .rules_by_program
                 .get_vec(first)
-                .map(|rules| rules.iter().filter_map(|rule| rule.matches(cmd)).collect())
+                .map(|rules| {
+                    rules
+                        .iter()
+                        .filter_map(|rule| rule.matches(cmd, ctx))
+                        .collect()
+                })
                 .unwrap_or_default(),
             None => Vec::new(),
         };
@@ -1,18 +1,28 @@
 use crate::decision::Decision;
 use crate::error::Error;
 use crate::error::Result;
+use chrono::DateTime;
+use chrono::Utc;
+use regex_lite::Regex;
 use serde::Deserialize;
 use serde::Serialize;
 use shlex::try_join;
 use std::any::Any;
+use std::fmt;
 use std::fmt::Debug;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
 use std::sync::Arc;
 
-/// Matches a single command token, either a fixed string or one of several allowed alternatives.
+/// Matches a single command token: a fixed string, one of several alternatives, or a
+/// glob/regex that must match the whole token.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum PatternToken {
     Single(String),
     Alts(Vec<String>),
+    Glob(TokenMatcher),
+    Regex(TokenMatcher),
 }
 
 impl PatternToken {
@@ -20,17 +30,87 @@ impl PatternToken {
         match self {
             Self::Single(expected) => expected == token,
             Self::Alts(alternatives) => alternatives.iter().any(|alt| alt == token),
+            Self::Glob(matcher) | Self::Regex(matcher) => matcher.matches(token),
         }
     }
 
+    /// Literal values this token accepts; empty for glob and regex tokens.
     pub fn alternatives(&self) -> &[String] {
         match self {
             Self::Single(expected) => std::slice::from_ref(expected),
             Self::Alts(alternatives) => alternatives,
+            Self::Glob(_) | Self::Regex(_) => &[],
         }
     }
 }
 
+/// Renders the token the way it is written in a policy file.
+impl fmt::Display for PatternToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Single(expected) => write!(f, "{expected:?}"),
+            Self::Alts(alternatives) => write!(f, "{alternatives:?}"),
+            Self::Glob(matcher) => write!(f, "glob({:?})", matcher.source()),
+            Self::Regex(matcher) => write!(f, "regex({:?})", matcher.source()),
+        }
+    }
+}
+
+/// A compiled glob or regex token, compared by its source text.
+#[derive(Clone, Debug)]
+pub struct TokenMatcher {
+    source: String,
+    regex: Regex,
+}
+
+impl TokenMatcher {
+    /// `*` matches any run of characters, `?` matches one character; everything else is
+    /// literal.
+    pub fn glob(source: &str) -> Result<Self> {
+        let mut translated = String::from("^");
+        for ch in source.chars() {
+            match ch {
+                '*' => translated.push_str(".*"),
+                '?' => translated.push('.'),
+                ch => translated.push_str(&regex_lite::escape(&ch.to_string())),
+            }
+        }
+        translated.push('$');
+        Self::compile(source, &translated)
+    }
+
+    /// The regex must match the entire token.
+    pub fn regex(source: &str) -> Result<Self> {
+        Self::compile(source, &format!("^(?:{source})$"))
+    }
+
+    fn compile(source: &str, pattern: &str) -> Result<Self> {
+        let regex = Regex::new(pattern).map_err(|err| {
+            Error::InvalidPattern(format!("invalid token pattern `{source}`: {err}"))
+        })?;
+        Ok(Self {
+            source: source.to_string(),
+            regex,
+        })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    fn matches(&self, token: &str) -> bool {
+        self.regex.is_match(token)
+    }
+}
+
+impl PartialEq for TokenMatcher {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl Eq for TokenMatcher {}
+
 /// Prefix matcher for commands with support for alternative match tokens.
 /// First token is fixed since we key by the first token in policy.
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -56,6 +136,213 @@ impl PrefixPattern {
     }
 }
 
+/// Where a command runs, used to resolve path arguments for [`Condition::PathsWithin`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MatchContext {
+    /// Working directory of the command. When unknown, relative paths are still treated as
+    /// inside the working directory unless they escape it with `..`.
+    pub cwd: Option<PathBuf>,
+    pub writable_roots: Vec<PathBuf>,
+}
+
+impl MatchContext {
+    /// Context used to check `match`/`not_match` examples at load time: commands run from
+    /// `/workspace`, which is also the only writable root.
+    pub(crate) fn for_examples() -> Self {
+        let workspace = PathBuf::from("/workspace");
+        Self {
+            cwd: Some(workspace.clone()),
+            writable_roots: vec![workspace],
+        }
+    }
+}
+
+/// Directory a path argument may be required to stay within.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PathRoot {
+    Cwd,
+    WritableRoots,
+    Path(PathBuf),
+}
+
+/// Predicate over the arguments that follow a rule's matched prefix.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Condition {
+    /// Holds when any of `names` appears before a bare `--`. Long flags also match
+    /// `--name=value`; single-letter short flags also match inside a combined group such
+    /// as `-xf`.
+    Flag(Vec<String>),
+    /// Holds when there is at least one positional argument and every one, resolved
+    /// against the working directory, stays inside one of `roots`. Values attached to
+    /// flags (`--out=/tmp/x`, `-C/`) must stay inside as well. Symlinks in the part of a
+    /// path that exists are resolved. Arguments the shell would expand (`~`, `$`, globs,
+    /// backticks) never count as inside.
+    PathsWithin(Vec<PathRoot>),
+    /// Holds until the given instant. Produced from an amendment's `expires_at`.
+    ExpiresAt(DateTime<Utc>),
+    /// Holds when the working directory is inside the given directory. Produced from an
+    /// amendment's `repo`; never holds when the working directory is unknown.
+    CwdWithin(PathBuf),
+}
+
+impl Condition {
+    pub fn holds(&self, args: &[String], ctx: &MatchContext) -> bool {
+        match self {
+            Self::Flag(names) => flag_tokens(args).any(|token| {
+                names
+                    .iter()
+                    .any(|name| flag_matches(name.as_str(), token.as_str()))
+            }),
+            Self::PathsWithin(roots) => {
+                let mut positional = positional_args(args).map(String::as_str).peekable();
+                positional.peek().is_some()
+                    && positional
+                        .chain(flag_values(args))
+                        .all(|arg| roots.iter().any(|root| path_within(arg, root, ctx)))
+            }
+            Self::ExpiresAt(expires_at) => Utc::now() < *expires_at,
+            Self::CwdWithin(root) => ctx.cwd.as_deref().is_some_and(|cwd| is_within(cwd, root)),
+        }
+    }
+}
+
+fn flag_tokens(args: &[String]) -> impl Iterator<Item = &String> {
+    args.iter()
+        .take_while(|arg| arg.as_str() != "--")
+        .filter(|arg| arg.starts_with('-'))
+}
+
+/// Values attached to flags before a bare `--`: what follows `=` in a long flag, and
+/// what follows the first letter of a short flag (`-t/etc`). There is no telling a
+/// short value from a group of short flags, so `-rf` yields `f`, which resolves inside
+/// the working directory like any relative name.
+fn flag_values(args: &[String]) -> impl Iterator<Item = &str> {
+    flag_tokens(args).filter_map(|token| match token.strip_prefix("--") {
+        Some(long) => long.split_once('=').map(|(_, value)| value),
+        None => {
+            let mut rest = token[1..].chars();
+            rest.next()?;
+            Some(rest.as_str()).filter(|value| !value.is_empty())
+        }
+    })
+}
+
+fn positional_args(args: &[String]) -> impl Iterator<Item = &String> {
+    let mut after_separator = false;
+    args.iter().filter(move |arg| {
+        if after_separator {
+            return true;
+        }
+        if arg.as_str() == "--" {
+            after_separator = true;
+            return false;
+        }
+        !arg.starts_with('-')
+    })
+}
+
+fn flag_matches(name: &str, token: &str) -> bool {
+    if token == name {
+        return true;
+    }
+    if name.starts_with("--") {
+        return token
+            .strip_prefix(name)
+            .is_some_and(|rest| rest.starts_with('='));
+    }
+    match (name.strip_prefix('-'), token.strip_prefix('-')) {
+        (Some(short), Some(group)) if short.chars().count() == 1 && !group.starts_with('-') => {
+            group.chars().all(char::is_alphanumeric) && group.contains(short)
+        }
+        _ => false,
+    }
+}
+
+/// Characters that make the shell rewrite an argument before the command sees it, so
+/// the literal text says nothing about the path that is eventually touched.
+const SHELL_EXPANSION_CHARS: &[char] = &['$', '`', '*', '?', '[', '{'];
+
+fn path_within(arg: &str, root: &PathRoot, ctx: &MatchContext) -> bool {
+    if arg.starts_with('~') || arg.contains(SHELL_EXPANSION_CHARS) {
+        return false;
+    }
+    let arg = Path::new(arg);
+    match root {
+        PathRoot::Cwd => match &ctx.cwd {
+            Some(cwd) => is_within(&cwd.join(arg), cwd),
+            None => arg.is_relative() && normalize(arg).is_some(),
+        },
+        PathRoot::WritableRoots => {
+            let resolved = match &ctx.cwd {
+                Some(cwd) => cwd.join(arg),
+                None if arg.is_absolute() => arg.to_path_buf(),
+                None => return false,
+            };
+            ctx.writable_roots
+                .iter()
+                .any(|root| is_within(&resolved, root))
+        }
+        PathRoot::Path(root) => {
+            let resolved = match &ctx.cwd {
+                Some(cwd) => cwd.join(arg),
+                None => arg.to_path_buf(),
+            };
+            resolved.is_absolute() && is_within(&resolved, root)
+        }
+    }
+}
+
+fn is_within(path: &Path, root: &Path) -> bool {
+    match (resolve(path), resolve(root)) {
+        (Some(path), Some(root)) => path.starts_with(root),
+        _ => false,
+    }
+}
+
+/// Resolves an absolute path the way the kernel would: symlinks and `..` in the longest
+/// existing ancestor are resolved by the filesystem, and the remainder, which does not
+/// exist yet, is normalized lexically. Relative paths are only normalized.
+fn resolve(path: &Path) -> Option<PathBuf> {
+    if path.is_relative() {
+        return normalize(path);
+    }
+    for ancestor in path.ancestors() {
+        if let Ok(canonical) = std::fs::canonicalize(ancestor) {
+            let remainder = path.strip_prefix(ancestor).ok()?;
+            return normalize(&canonical.join(remainder));
+        }
+    }
+    normalize(path)
+}
+
+/// Resolves `.` and `..` lexically. Returns `None` when a relative path climbs above
+/// its starting point.
+fn normalize(path: &Path) -> Option<PathBuf> {
+    let mut normalized = PathBuf::new();
+    let mut depth = 0usize;
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if depth == 0 {
+                    if path.is_relative() {
+                        return None;
+                    }
+                } else {
+                    normalized.pop();
+                    depth -= 1;
+                }
+            }
+            Component::Normal(part) => {
+                normalized.push(part);
+                depth += 1;
+            }
+            Component::RootDir | Component::Prefix(_) => normalized.push(component),
+        }
+    }
+    Some(normalized)
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum RuleMatch {
@@ -90,12 +377,16 @@ pub struct PrefixRule {
     pub pattern: PrefixPattern,
     pub decision: Decision,
     pub justification: Option<String>,
+    /// Conditions that must all hold for the rule to match.
+    pub when: Arc<[Condition]>,
+    /// Conditions that each prevent the rule from matching.
+    pub unless: Arc<[Condition]>,
 }
 
 pub trait Rule: Any + Debug + Send + Sync {
     fn program(&self) -> &str;
 
-    fn matches(&self, cmd: &[String]) -> Option<RuleMatch>;
+    fn matches(&self, cmd: &[String], ctx: &MatchContext) -> Option<RuleMatch>;
 }
 
 pub type RuleRef = Arc<dyn Rule>;
@@ -105,23 +396,35 @@ impl Rule for PrefixRule {
         self.pattern.first.as_ref()
     }
 
-    fn matches(&self, cmd: &[String]) -> Option<RuleMatch> {
-        self.pattern
-            .matches_prefix(cmd)
-            .map(|matched_prefix| RuleMatch::PrefixRuleMatch {
-                matched_prefix,
-                decision: self.decision,
-                justification: self.justification.clone(),
-            })
+    fn matches(&self, cmd: &[String], ctx: &MatchContext) -> Option<RuleMatch> {
+        let matched_prefix = self.pattern.matches_prefix(cmd)?;
+        let args = &cmd[matched_prefix.len()..];
+        if !self.when.iter().all(|condition| condition.holds(args, ctx))
+            || self
+                .unless
+                .iter()
+                .any(|condition| condition.holds(args, ctx))
+        {
+            return None;
+        }
+        Some(RuleMatch::PrefixRuleMatch {
+            matched_prefix,
+            decision: self.decision,
+            justification: self.justification.clone(),
+        })
     }
 }
 
 /// Count how many rules match each provided example and error if any example is unmatched.
 pub(crate) fn validate_match_examples(rules: &[RuleRef], matches: &[Vec<String>]) -> Result<()> {
+    let ctx = MatchContext::for_examples();
     let mut unmatched_examples = Vec::new();
 
     for example in matches {
-        if rules.iter().any(|rule| rule.matches(example).is_some()) {
+        if rules
+            .iter()
+            .any(|rule| rule.matches(example, &ctx).is_some())
+        {
             continue;
         }
 
@@ -146,8 +449,12 @@ pub(crate) fn validate_not_match_examples(
     rules: &[RuleRef],
     not_matches: &[Vec<String>],
 ) -> Result<()> {
+    let ctx = MatchContext::for_examples();
     for example in not_matches {
-        if let Some(rule) = rules.iter().find(|rule| rule.matches(example).is_some()) {
+        if let Some(rule) = rules
+            .iter()
+            .find(|rule| rule.matches(example, &ctx).is_some())
+        {
             return Err(Error::ExampleDidMatch {
                 rule: format!("{rule:?}"),
                 example: try_join(example.iter().map(String::as_str))
@@ -1,4 +1,5 @@
 use std::any::Any;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use anyhow::Context;
@@ -6,10 +7,13 @@ use anyhow::Result;
 use codex_execpolicy::Decision;
 use codex_execpolicy::Error;
 use codex_execpolicy::Evaluation;
+use codex_execpolicy::ExecPolicyRevokeCommand;
+use codex_execpolicy::MatchContext;
 use codex_execpolicy::Policy;
 use codex_execpolicy::PolicyParser;
 use codex_execpolicy::RuleMatch;
 use codex_execpolicy::RuleRef;
+use codex_execpolicy::rule::Condition;
 use codex_execpolicy::rule::PatternToken;
 use codex_execpolicy::rule::PrefixPattern;
 use codex_execpolicy::rule::PrefixRule;
@@ -27,6 +31,10 @@ fn prompt_all(_: &[String]) -> Decision {
     Decision::Prompt
 }
 
+fn no_conditions() -> Arc<[Condition]> {
+    Arc::from([])
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 enum RuleSnapshot {
     Prefix(PrefixRule),
@@ -164,6 +172,8 @@ fn add_prefix_rule_extends_policy() -> Result<()> {
             },
             decision: Decision::Prompt,
             justification: None,
+            when: no_conditions(),
+            unless: no_conditions(),
         })],
         rules
     );
@@ -224,6 +234,8 @@ prefix_rule(
                 },
                 decision: Decision::Prompt,
                 justification: None,
+                when: no_conditions(),
+                unless: no_conditions(),
             }),
             RuleSnapshot::Prefix(PrefixRule {
                 pattern: PrefixPattern {
@@ -232,6 +244,8 @@ prefix_rule(
                 },
                 decision: Decision::Forbidden,
                 justification: None,
+                when: no_conditions(),
+                unless: no_conditions(),
             }),
         ],
         git_rules
@@ -298,6 +312,8 @@ prefix_rule(
             },
             decision: Decision::Allow,
             justification: None,
+            when: no_conditions(),
+            unless: no_conditions(),
         })],
         bash_rules
     );
@@ -309,6 +325,8 @@ prefix_rule(
             },
             decision: Decision::Allow,
             justification: None,
+            when: no_conditions(),
+            unless: no_conditions(),
         })],
         sh_rules
     );
@@ -368,6 +386,8 @@ prefix_rule(
             },
             decision: Decision::Allow,
             justification: None,
+            when: no_conditions(),
+            unless: no_conditions(),
         })],
         rules
     );
@@ -551,3 +571,245 @@ fn heuristics_match_is_returned_when_no_policy_matches() {
         evaluation
     );
 }
+
+#[test]
+fn unless_flag_blocks_match_anywhere_in_argv() -> Result<()> {
+    let policy_src = r#"
+prefix_rule(
+    pattern = ["git", "push"],
+    unless = [flag(["--force", "-f"])],
+    match = ["git push origin main", "git push origin -- -f"],
+    not_match = [
+        "git push --force origin main",
+        "git push origin main -f",
+        "git push --force=yes",
+        "git push -uf origin main",
+    ],
+)
+    "#;
+    let mut parser = PolicyParser::new();
+    parser.parse("test.rules", policy_src)?;
+    let policy = parser.build();
+
+    let plain = policy.check(&tokens(&["git", "push", "origin", "main"]), &prompt_all);
+    assert_eq!(Decision::Allow, plain.decision);
+    assert!(plain.is_match());
+
+    let forced = policy.check(
+        &tokens(&["git", "push", "origin", "main", "--force"]),
+        &prompt_all,
+    );
+    assert_eq!(
+        Evaluation {
+            decision: Decision::Prompt,
+            matched_rules: vec![RuleMatch::HeuristicsRuleMatch {
+                command: tokens(&["git", "push", "origin", "main", "--force"]),
+                decision: Decision::Prompt,
+            }],
+        },
+        forced
+    );
+    Ok(())
+}
+
+#[test]
+fn glob_and_regex_tokens_match_whole_token() -> Result<()> {
+    let policy_src = r#"
+prefix_rule(
+    pattern = ["cargo", glob("+nightly*"), ["build", "check"]],
+    match = ["cargo +nightly-2025-01-01 build", "cargo +nightly check"],
+    not_match = ["cargo +stable build", "cargo x+nightly build"],
+)
+prefix_rule(
+    pattern = ["kill", regex("-[0-9]+")],
+    decision = "prompt",
+    match = ["kill -9 1234"],
+    not_match = ["kill -9x 1234", "kill -TERM 1234"],
+)
+    "#;
+    let mut parser = PolicyParser::new();
+    parser.parse("test.rules", policy_src)?;
+    let policy = parser.build();
+
+    let evaluation = policy.check(&tokens(&["kill", "-15", "42"]), &allow_all);
+    assert_eq!(
+        Evaluation {
+            decision: Decision::Prompt,
+            matched_rules: vec![RuleMatch::PrefixRuleMatch {
+                matched_prefix: tokens(&["kill", "-15"]),
+                decision: Decision::Prompt,
+                justification: None,
+            }],
+        },
+        evaluation
+    );
+    Ok(())
+}
+
+#[test]
+fn glob_cannot_be_first_pattern_element() {
+    let policy_src = r#"prefix_rule(pattern = [glob("git*"), "status"])"#;
+    let mut parser = PolicyParser::new();
+    let err = parser
+        .parse("test.rules", policy_src)
+        .expect_err("expected parse error");
+    assert!(
+        err.to_string()
+            .contains("first pattern element must be a string or list of strings"),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn paths_within_resolves_arguments_against_context() -> Result<()> {
+    let policy_src = r#"
+prefix_rule(
+    pattern = ["rm"],
+    when = [paths_within(["writable_roots"])],
+    match = ["rm -rf build", "rm /workspace/target/out.o"],
+    not_match = ["rm -rf ../other", "rm /etc/passwd", "rm build -- /etc/passwd"],
+)
+    "#;
+    let mut parser = PolicyParser::new();
+    parser.parse("test.rules", policy_src)?;
+    let policy = parser.build();
+
+    let ctx = MatchContext {
+        cwd: Some(PathBuf::from("/home/user/project/src")),
+        writable_roots: vec![PathBuf::from("/home/user/project")],
+    };
+    let inside = policy.check_multiple_with_context(
+        [tokens(&["rm", "-r", "../target", "main.o"])],
+        &ctx,
+        &prompt_all,
+    );
+    assert_eq!(Decision::Allow, inside.decision);
+
+    let outside = policy.check_multiple_with_context(
+        [tokens(&["rm", "-r", "../../.ssh"])],
+        &ctx,
+        &prompt_all,
+    );
+    assert_eq!(Decision::Prompt, outside.decision);
+    assert!(!outside.is_match());
+    Ok(())
+}
+
+#[test]
+fn paths_within_rejects_shell_expansions_and_missing_paths() -> Result<()> {
+    let policy_src = r#"
+prefix_rule(
+    pattern = ["rm"],
+    when = [paths_within(["cwd"])],
+    match = ["rm build"],
+    not_match = ["rm", "rm -rf", "rm '~/build'", "rm '$HOME'", "rm 'build/*'", "rm '`pwd`'"],
+)
+    "#;
+    let mut parser = PolicyParser::new();
+    parser.parse("test.rules", policy_src)?;
+    Ok(())
+}
+
+#[test]
+fn paths_within_checks_values_attached_to_flags() -> Result<()> {
+    let policy_src = r#"
+prefix_rule(
+    pattern = ["cp"],
+    when = [paths_within(["cwd"])],
+    match = [
+        "cp -r src dest",
+        "cp --target-directory=dest src",
+        "cp -tdest src",
+    ],
+    not_match = [
+        "cp --target-directory=/etc src",
+        "cp -t/etc src",
+        "cp -C/ src",
+        "cp --output /etc/x src",
+        "cp --target-directory=../other src",
+        "cp '--target-directory=~/x' src",
+    ],
+)
+    "#;
+    let mut parser = PolicyParser::new();
+    parser.parse("test.rules", policy_src)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn paths_within_resolves_symlinks() -> Result<()> {
+    let policy_src = r#"
+prefix_rule(
+    pattern = ["rm"],
+    when = [paths_within(["cwd"])],
+)
+    "#;
+    let mut parser = PolicyParser::new();
+    parser.parse("test.rules", policy_src)?;
+    let policy = parser.build();
+
+    let workspace = tempfile::tempdir()?;
+    let outside = tempfile::tempdir()?;
+    std::os::unix::fs::symlink(outside.path(), workspace.path().join("escape"))?;
+    std::fs::create_dir(workspace.path().join("build"))?;
+    let ctx = MatchContext {
+        cwd: Some(workspace.path().to_path_buf()),
+        writable_roots: vec![workspace.path().to_path_buf()],
+    };
+
+    let inside = policy.check_multiple_with_context(
+        [tokens(&["rm", "build/out.o", "new-file"])],
+        &ctx,
+        &prompt_all,
+    );
+    assert_eq!(Decision::Allow, inside.decision);
+
+    let escaped =
+        policy.check_multiple_with_context([tokens(&["rm", "escape/secret"])], &ctx, &prompt_all);
+    assert!(!escaped.is_match());
+    Ok(())
+}
+
+#[test]
+fn conditions_require_builtin_values() {
+    let policy_src = r#"prefix_rule(pattern = ["git", "push"], unless = ["--force"])"#;
+    let mut parser = PolicyParser::new();
+    let err = parser
+        .parse("test.rules", policy_src)
+        .expect_err("expected parse error");
+    assert!(
+        err.to_string()
+            .contains("condition must be created by flag() or paths_within()"),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn revoke_fails_when_the_amendment_line_cannot_be_located() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let policy_path = tmp.path().join("default.rules");
+    // Reformatted by hand, so the line no longer carries the serialized marker.
+    let contents = r#"prefix_rule(
+    pattern = ["cat"],
+    decision = "allow",
+    amendment = {"id": "a1", "created_at": "2026-01-01T00:00:00Z"},
+)
+"#;
+    std::fs::write(&policy_path, contents)?;
+
+    let err = ExecPolicyRevokeCommand {
+        id: "a1".to_string(),
+        rules: vec![policy_path.clone()],
+    }
+    .run()
+    .expect_err("revoke should fail");
+
+    assert!(
+        err.to_string()
+            .contains("could not locate the rule for amendment a1"),
+        "unexpected error: {err}"
+    );
+    assert_eq!(std::fs::read_to_string(&policy_path)?, contents);
+    Ok(())
+}
@@ -0,0 +1,250 @@
+use anyhow::Result;
+use codex_execpolicy::Decision;
+use codex_execpolicy::MatchContext;
+use codex_execpolicy::Policy;
+use codex_execpolicy::PolicyParser;
+use codex_execpolicy::execpolicytest::CommandResult;
+use codex_execpolicy::execpolicytest::Outcome;
+use codex_execpolicy::execpolicytest::TestReport;
+use codex_execpolicy::execpolicytest::parse_rollout_corpus;
+use codex_execpolicy::execpolicytest::parse_text_corpus;
+use pretty_assertions::assert_eq;
+
+fn tokens(cmd: &[&str]) -> Vec<String> {
+    cmd.iter().map(std::string::ToString::to_string).collect()
+}
+
+fn whole_command(command: &[String]) -> Vec<Vec<String>> {
+    vec![command.to_vec()]
+}
+
+fn prompt_all(_: &[String]) -> Decision {
+    Decision::Prompt
+}
+
+/// Stand-in for Codex's shell parser: splits `bash -lc` scripts on `&&`.
+fn split_on_and(command: &[String]) -> Vec<Vec<String>> {
+    match command {
+        [shell, flag, script] if shell == "bash" && flag == "-lc" => script
+            .split("&&")
+            .map(|part| part.split_whitespace().map(str::to_string).collect())
+            .collect(),
+        _ => vec![command.to_vec()],
+    }
+}
+
+fn parse_policy() -> Result<Policy> {
+    let mut parser = PolicyParser::new();
+    parser.parse(
+        "git.rules",
+        r#"
+prefix_rule(pattern = ["git", "status"])
+prefix_rule(pattern = ["git", ["push", "fetch"]], decision = "prompt")
+prefix_rule(pattern = ["git", "push", "--force"], decision = "forbidden")
+"#,
+    )?;
+    parser.parse(
+        "npm.rules",
+        r#"
+prefix_rule(pattern = [["npm", "pnpm"], "publish"], decision = "forbidden")
+"#,
+    )?;
+    Ok(parser.build())
+}
+
+#[test]
+fn declarations_record_file_and_position() -> Result<()> {
+    let policy = parse_policy()?;
+    let declarations: Vec<(String, usize, String, Decision, usize)> = policy
+        .declarations()
+        .iter()
+        .map(|declaration| {
+            (
+                declaration.policy.clone(),
+                declaration.index,
+                declaration.pattern.clone(),
+                declaration.decision,
+                declaration.rules.len(),
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        vec![
+            (
+                "git.rules".to_string(),
+                1,
+                r#"["git", "status"]"#.to_string(),
+                Decision::Allow,
+                1
+            ),
+            (
+                "git.rules".to_string(),
+                2,
+                r#"["git", ["push", "fetch"]]"#.to_string(),
+                Decision::Prompt,
+                1
+            ),
+            (
+                "git.rules".to_string(),
+                3,
+                r#"["git", "push", "--force"]"#.to_string(),
+                Decision::Forbidden,
+                1
+            ),
+            (
+                "npm.rules".to_string(),
+                1,
+                r#"[["npm", "pnpm"], "publish"]"#.to_string(),
+                Decision::Forbidden,
+                2
+            ),
+        ],
+        declarations
+    );
+    Ok(())
+}
+
+#[test]
+fn report_picks_strictest_rule_and_lists_unused_rules() -> Result<()> {
+    let policy = parse_policy()?;
+    let corpus = parse_text_corpus(
+        "corpus.txt",
+        r#"
+# comments and blank lines are ignored
+
+allow: git status
+forbidden: git push --force origin
+prompt: git log
+ls -la
+"#,
+    )?;
+
+    let report = TestReport::evaluate(
+        &policy,
+        &corpus,
+        &MatchContext::default(),
+        &whole_command,
+        &prompt_all,
+    );
+
+    assert_eq!(
+        TestReport {
+            results: vec![
+                CommandResult {
+                    origin: "corpus.txt:4".to_string(),
+                    command: tokens(&["git", "status"]),
+                    expected: Some(Outcome::Decision(Decision::Allow)),
+                    outcome: Outcome::Decision(Decision::Allow),
+                    winner: Some(0),
+                },
+                CommandResult {
+                    origin: "corpus.txt:5".to_string(),
+                    command: tokens(&["git", "push", "--force", "origin"]),
+                    expected: Some(Outcome::Decision(Decision::Forbidden)),
+                    outcome: Outcome::Decision(Decision::Forbidden),
+                    winner: Some(2),
+                },
+                CommandResult {
+                    origin: "corpus.txt:6".to_string(),
+                    command: tokens(&["git", "log"]),
+                    expected: Some(Outcome::Decision(Decision::Prompt)),
+                    outcome: Outcome::Unmatched,
+                    winner: None,
+                },
+                CommandResult {
+                    origin: "corpus.txt:7".to_string(),
+                    command: tokens(&["ls", "-la"]),
+                    expected: None,
+                    outcome: Outcome::Unmatched,
+                    winner: None,
+                },
+            ],
+            unused: vec![3],
+        },
+        report
+    );
+    assert_eq!(
+        vec!["corpus.txt:6"],
+        report
+            .mismatches()
+            .map(|result| result.origin.as_str())
+            .collect::<Vec<_>>()
+    );
+    Ok(())
+}
+
+#[test]
+fn report_splits_scripts_and_applies_fallback_to_unmatched_parts() -> Result<()> {
+    let policy = parse_policy()?;
+    let corpus = parse_text_corpus(
+        "corpus.txt",
+        r#"
+allow: bash -lc "git status && git status"
+prompt: bash -lc "git status && make"
+forbidden: bash -lc "make && git push --force"
+unmatched: bash -lc "make && make install"
+"#,
+    )?;
+
+    let report = TestReport::evaluate(
+        &policy,
+        &corpus,
+        &MatchContext::default(),
+        &split_on_and,
+        &prompt_all,
+    );
+
+    assert_eq!(
+        vec![
+            (Outcome::Decision(Decision::Allow), Some(0)),
+            (Outcome::Decision(Decision::Prompt), None),
+            (Outcome::Decision(Decision::Forbidden), Some(2)),
+            (Outcome::Unmatched, None),
+        ],
+        report
+            .results
+            .iter()
+            .map(|result| (result.outcome, result.winner))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(0, report.mismatches().count());
+    Ok(())
+}
+
+#[test]
+fn rollout_corpus_extracts_shell_commands() -> Result<()> {
+    let rollout = [
+        r#"{"timestamp":"t","type":"session_meta","payload":{"id":"x"}}"#,
+        r#"{"timestamp":"t","type":"response_item","payload":{"type":"function_call","name":"shell","arguments":"{\"command\":[\"bash\",\"-lc\",\"git status\"]}","call_id":"1"}}"#,
+        r#"{"timestamp":"t","type":"response_item","payload":{"type":"function_call","name":"shell_command","arguments":"{\"command\":\"npm publish --dry-run\"}","call_id":"2"}}"#,
+        r#"{"timestamp":"t","type":"response_item","payload":{"type":"function_call","name":"exec_command","arguments":"{\"cmd\":\"git status | head\"}","call_id":"3"}}"#,
+        r#"{"timestamp":"t","type":"response_item","payload":{"type":"local_shell_call","call_id":"4","status":"completed","action":{"type":"exec","command":["git","fetch"]}}}"#,
+        r#"{"timestamp":"t","type":"response_item","payload":{"type":"function_call","name":"apply_patch","arguments":"{}","call_id":"5"}}"#,
+    ]
+    .join("\n");
+
+    let corpus = parse_rollout_corpus("rollout.jsonl", &rollout)?;
+
+    assert_eq!(
+        vec![
+            ("rollout.jsonl:2", tokens(&["bash", "-lc", "git status"])),
+            (
+                "rollout.jsonl:3",
+                tokens(&["bash", "-lc", "npm publish --dry-run"])
+            ),
+            (
+                "rollout.jsonl:4",
+                tokens(&["bash", "-lc", "git status | head"])
+            ),
+            ("rollout.jsonl:5", tokens(&["git", "fetch"])),
+        ],
+        corpus
+            .commands
+            .iter()
+            .map(|entry| (entry.origin.as_str(), entry.command.clone()))
+            .collect::<Vec<_>>()
+    );
+    assert!(corpus.commands.iter().all(|entry| entry.expected.is_none()));
+    Ok(())
+}
@@ -27,12 +27,18 @@ use seccompiler::apply_filter;
 
 /// Apply sandbox policies inside this thread so only the child inherits
 /// them, not the entire CLI process.
+///
+/// `network_namespace_isolated` is set when the thread already runs in a
+/// network namespace whose only egress is the allowlist proxy; TCP/IP
+/// sockets are then permitted so the command can reach the proxy bridge on
+/// loopback.
 pub(crate) fn apply_sandbox_policy_to_current_thread(
     sandbox_policy: &SandboxPolicy,
     cwd: &Path,
+    network_namespace_isolated: bool,
 ) -> Result<()> {
     if !sandbox_policy.has_full_network_access() {
-        install_network_seccomp_filter_on_current_thread()?;
+        install_network_seccomp_filter_on_current_thread(network_namespace_isolated)?;
     }
 
     if !sandbox_policy.has_full_disk_write_access() {
@@ -41,23 +47,25 @@ pub(crate) fn apply_sandbox_policy_to_current_thread(
             .into_iter()
             .map(|writable_root| writable_root.root)
             .collect();
-        install_filesystem_landlock_rules_on_current_thread(writable_roots)?;
+        // Only policies that restrict writes can restrict reads, so the read
+        // rules are installed as part of the same ruleset.
+        let readable_roots = sandbox_policy.get_readable_roots_with_cwd(cwd);
+        install_filesystem_landlock_rules_on_current_thread(writable_roots, readable_roots)?;
     }
 
-    // TODO(ragona): Add appropriate restrictions if
-    // `sandbox_policy.has_full_disk_read_access()` is `false`.
-
     Ok(())
 }
 
-/// Installs Landlock file-system rules on the current thread allowing read
-/// access to the entire file-system while restricting write access to
-/// `/dev/null` and the provided list of `writable_roots`.
+/// Installs Landlock file-system rules on the current thread restricting
+/// write access to `/dev/null` and the provided list of `writable_roots`.
+/// Read access covers the entire file-system unless `readable_roots` is set,
+/// in which case only those folders (that exist) remain readable.
 ///
 /// # Errors
 /// Returns [`CodexErr::Sandbox`] variants when the ruleset fails to apply.
 fn install_filesystem_landlock_rules_on_current_thread(
     writable_roots: Vec<AbsolutePathBuf>,
+    readable_roots: Option<Vec<AbsolutePathBuf>>,
 ) -> Result<()> {
     let abi = ABI::V5;
     let access_rw = AccessFs::from_all(abi);
@@ -66,8 +74,20 @@ fn install_filesystem_landlock_rules_on_current_thread(
     let mut ruleset = Ruleset::default()
         .set_compatibility(CompatLevel::BestEffort)
         .handle_access(access_rw)?
-        .create()?
-        .add_rules(landlock::path_beneath_rules(&["/"], access_ro))?
+        .create()?;
+
+    ruleset = match readable_roots {
+        Some(readable_roots) => {
+            let readable_roots: Vec<AbsolutePathBuf> = readable_roots
+                .into_iter()
+                .filter(|root| root.as_path().exists())
+                .collect();
+            ruleset.add_rules(landlock::path_beneath_rules(&readable_roots, access_ro))?
+        }
+        None => ruleset.add_rules(landlock::path_beneath_rules(&["/"], access_ro))?,
+    };
+
+    let mut ruleset = ruleset
         .add_rules(landlock::path_beneath_rules(&["/dev/null"], access_rw))?
         .set_no_new_privs(true);
 
@@ -86,7 +106,15 @@ fn install_filesystem_landlock_rules_on_current_thread(
 
 /// Installs a seccomp filter that blocks outbound network access except for
 /// AF_UNIX domain sockets.
-fn install_network_seccomp_filter_on_current_thread() -> std::result::Result<(), SandboxErr> {
+///
+/// With `allow_loopback_bridge`, the thread runs in a network namespace that
+/// only has loopback, so AF_INET/AF_INET6 sockets and the calls a client needs
+/// to use them are allowed. AF_UNIX sockets are denied instead, since seccomp
+/// cannot inspect the address passed to `connect` and a Unix socket could
+/// reach host services outside the namespace.
+fn install_network_seccomp_filter_on_current_thread(
+    allow_loopback_bridge: bool,
+) -> std::result::Result<(), SandboxErr> {
     // Build rule map.
     let mut rules: BTreeMap<i64, Vec<SeccompRule>> = BTreeMap::new();
 
@@ -95,23 +123,25 @@ fn install_network_seccomp_filter_on_current_thread() -> std::result::Result<(),
         rules.insert(nr, vec![]); // empty rule vec = unconditional match
     };
 
-    deny_syscall(libc::SYS_connect);
     deny_syscall(libc::SYS_accept);
     deny_syscall(libc::SYS_accept4);
     deny_syscall(libc::SYS_bind);
     deny_syscall(libc::SYS_listen);
-    deny_syscall(libc::SYS_getpeername);
-    deny_syscall(libc::SYS_getsockname);
-    deny_syscall(libc::SYS_shutdown);
-    deny_syscall(libc::SYS_sendto);
-    deny_syscall(libc::SYS_sendmmsg);
-    // NOTE: allowing recvfrom allows some tools like: `cargo clippy` to run
-    // with their socketpair + child processes for sub-proc management
-    // deny_syscall(libc::SYS_recvfrom);
-    deny_syscall(libc::SYS_recvmmsg);
-    deny_syscall(libc::SYS_getsockopt);
-    deny_syscall(libc::SYS_setsockopt);
     deny_syscall(libc::SYS_ptrace);
+    if !allow_loopback_bridge {
+        deny_syscall(libc::SYS_connect);
+        deny_syscall(libc::SYS_getpeername);
+        deny_syscall(libc::SYS_getsockname);
+        deny_syscall(libc::SYS_shutdown);
+        deny_syscall(libc::SYS_sendto);
+        deny_syscall(libc::SYS_sendmmsg);
+        // NOTE: allowing recvfrom allows some tools like: `cargo clippy` to run
+        // with their socketpair + child processes for sub-proc management
+        // deny_syscall(libc::SYS_recvfrom);
+        deny_syscall(libc::SYS_recvmmsg);
+        deny_syscall(libc::SYS_getsockopt);
+        deny_syscall(libc::SYS_setsockopt);
+    }
 
     // For `socket` we allow AF_UNIX (arg0 == AF_UNIX) and deny everything else.
     let unix_only_rule = SeccompRule::new(vec![SeccompCondition::new(
@@ -120,8 +150,28 @@ fn install_network_seccomp_filter_on_current_thread() -> std::result::Result<(),
         SeccompCmpOp::Ne,
         libc::AF_UNIX as u64,
     )?])?;
-
-    rules.insert(libc::SYS_socket, vec![unix_only_rule.clone()]);
+    // Behind the loopback bridge, `socket` is limited to AF_INET/AF_INET6.
+    let inet_only_rule = SeccompRule::new(vec![
+        SeccompCondition::new(
+            0,
+            SeccompCmpArgLen::Dword,
+            SeccompCmpOp::Ne,
+            libc::AF_INET as u64,
+        )?,
+        SeccompCondition::new(
+            0,
+            SeccompCmpArgLen::Dword,
+            SeccompCmpOp::Ne,
+            libc::AF_INET6 as u64,
+        )?,
+    ])?;
+
+    let socket_rule = if allow_loopback_bridge {
+        inet_only_rule
+    } else {
+        unix_only_rule.clone()
+    };
+    rules.insert(libc::SYS_socket, vec![socket_rule]);
     rules.insert(libc::SYS_socketpair, vec![unix_only_rule]); // always deny (Unix can use socketpair but fine, keep open?)
 
     let filter = SeccompFilter::new(
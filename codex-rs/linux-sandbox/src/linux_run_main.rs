@@ -1,8 +1,14 @@
 use clap::Parser;
+use codex_core::config::types::SandboxResourceLimits;
 use std::ffi::CString;
 use std::path::PathBuf;
 
 use crate::landlock::apply_sandbox_policy_to_current_thread;
+use crate::namespaces::enter_user_namespace;
+use crate::overlay::mount_workspace_overlay;
+use crate::proxy_bridge::set_proxy_env;
+use crate::proxy_bridge::start_proxy_bridge;
+use crate::resource_limits::apply_resource_limits;
 
 #[derive(Debug, Parser)]
 pub struct LandlockCommand {
@@ -14,6 +20,30 @@ pub struct LandlockCommand {
     #[arg(long = "sandbox-policy")]
     pub sandbox_policy: codex_core::protocol::SandboxPolicy,
 
+    /// Unix socket of the Codex network proxy. When set, the command runs in
+    /// a network namespace whose only egress is that proxy.
+    #[arg(long = "network-proxy-socket")]
+    pub network_proxy_socket: Option<PathBuf>,
+
+    /// JSON-encoded limits applied through a cgroup or `setrlimit` before the
+    /// command runs.
+    #[arg(long = "resource-limits")]
+    pub resource_limits: Option<SandboxResourceLimits>,
+
+    /// Workspace to mount a copy-on-write overlay over; writes below it land
+    /// in `--overlay-upper-dir` instead.
+    #[arg(
+        long = "overlay-lower-dir",
+        requires_all = ["overlay_upper_dir", "overlay_work_dir"]
+    )]
+    pub overlay_lower_dir: Option<PathBuf>,
+
+    #[arg(long = "overlay-upper-dir", requires = "overlay_lower_dir")]
+    pub overlay_upper_dir: Option<PathBuf>,
+
+    #[arg(long = "overlay-work-dir", requires = "overlay_lower_dir")]
+    pub overlay_work_dir: Option<PathBuf>,
+
     /// Full command args to run under landlock.
     #[arg(trailing_var_arg = true)]
     pub command: Vec<String>,
@@ -23,13 +53,60 @@ pub fn run_main() -> ! {
     let LandlockCommand {
         sandbox_policy_cwd,
         sandbox_policy,
+        network_proxy_socket,
+        resource_limits,
+        overlay_lower_dir,
+        overlay_upper_dir,
+        overlay_work_dir,
         command,
     } = LandlockCommand::parse();
 
-    if let Err(e) = apply_sandbox_policy_to_current_thread(&sandbox_policy, &sandbox_policy_cwd) {
+    let overlay = match (overlay_lower_dir, overlay_upper_dir, overlay_work_dir) {
+        (Some(lower), Some(upper), Some(work)) => Some((lower, upper, work)),
+        _ => None,
+    };
+
+    // The cgroup has to be set up before the namespaces and Landlock rules
+    // hide `/sys/fs/cgroup` from the helper.
+    if let Some(resource_limits) = resource_limits
+        && let Err(e) = apply_resource_limits(&resource_limits)
+    {
+        panic!("error applying resource limits: {e}");
+    }
+
+    if let Some((lower, upper, work)) = &overlay {
+        if let Err(e) = enter_user_namespace(libc::CLONE_NEWNS) {
+            panic!("error creating sandbox namespaces: {e}");
+        }
+        if let Err(e) = mount_workspace_overlay(lower, upper, work) {
+            panic!("error mounting workspace overlay: {e}");
+        }
+    }
+
+    // Without a network namespace the command keeps the regular network
+    // block rather than failing outright.
+    let proxy_port = network_proxy_socket.and_then(|socket| {
+        match start_proxy_bridge(&socket, overlay.is_some()) {
+            Ok(port) => Some(port),
+            Err(e) => {
+                eprintln!("codex-linux-sandbox: network proxy unavailable, blocking network: {e}");
+                None
+            }
+        }
+    });
+
+    if let Err(e) = apply_sandbox_policy_to_current_thread(
+        &sandbox_policy,
+        &sandbox_policy_cwd,
+        proxy_port.is_some(),
+    ) {
         panic!("error running landlock: {e:?}");
     }
 
+    if let Some(port) = proxy_port {
+        set_proxy_env(port);
+    }
+
     if command.is_empty() {
         panic!("No command specified to execute.");
     }
@@ -0,0 +1,21 @@
+use std::fs;
+use std::io;
+
+/// Moves the current process into a fresh user namespace together with the
+/// namespaces in `flags` (e.g. `CLONE_NEWNET`, `CLONE_NEWNS`), which an
+/// unprivileged process can only create alongside a user namespace.
+///
+/// Must run before Landlock is applied, since it writes to `/proc/self`.
+pub(crate) fn enter_user_namespace(flags: libc::c_int) -> io::Result<()> {
+    let uid = unsafe { libc::getuid() };
+    let gid = unsafe { libc::getgid() };
+    if unsafe { libc::unshare(libc::CLONE_NEWUSER | flags) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // Keep the caller's ids inside the namespace so file ownership looks the
+    // same to the sandboxed command.
+    fs::write("/proc/self/setgroups", "deny")?;
+    fs::write("/proc/self/uid_map", format!("{uid} {uid} 1"))?;
+    fs::write("/proc/self/gid_map", format!("{gid} {gid} 1"))?;
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use std::ffi::CString;
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+
+/// Mounts an overlayfs over `lower` so that writes below it land in `upper`
+/// while `lower` itself stays untouched.
+///
+/// The current process must already be in its own user and mount namespaces
+/// so the mount is invisible outside the sandbox.
+pub(crate) fn mount_workspace_overlay(lower: &Path, upper: &Path, work: &Path) -> io::Result<()> {
+    // Keep the overlay mount from propagating back to the parent namespace.
+    mount(
+        None,
+        Path::new("/"),
+        None,
+        libc::MS_REC | libc::MS_PRIVATE,
+        None,
+    )?;
+
+    let options = format!(
+        "lowerdir={},upperdir={},workdir={},userxattr",
+        escape_option(lower),
+        escape_option(upper),
+        escape_option(work),
+    );
+    mount(Some("overlay"), lower, Some("overlay"), 0, Some(&options))?;
+
+    // A cwd inside `lower` still points at the directory underneath the new
+    // mount; look it up again so relative paths go through the overlay.
+    std::env::set_current_dir(std::env::current_dir()?)
+}
+
+fn mount(
+    source: Option<&str>,
+    target: &Path,
+    fstype: Option<&str>,
+    flags: libc::c_ulong,
+    data: Option<&str>,
+) -> io::Result<()> {
+    let to_cstring = |value: &[u8]| CString::new(value).map_err(io::Error::other);
+    let source = source.map(|s| to_cstring(s.as_bytes())).transpose()?;
+    let target = to_cstring(target.as_os_str().as_bytes())?;
+    let fstype = fstype.map(|s| to_cstring(s.as_bytes())).transpose()?;
+    let data = data.map(|s| to_cstring(s.as_bytes())).transpose()?;
+    let result = unsafe {
+        libc::mount(
+            source.as_ref().map_or(std::ptr::null(), |s| s.as_ptr()),
+            target.as_ptr(),
+            fstype.as_ref().map_or(std::ptr::null(), |s| s.as_ptr()),
+            flags,
+            data.as_ref()
+                .map_or(std::ptr::null(), |s| s.as_ptr().cast()),
+        )
+    };
+    if result != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Escapes the characters overlayfs treats as separators in its options.
+fn escape_option(path: &Path) -> String {
+    let mut escaped = String::new();
+    for c in path.to_string_lossy().chars() {
+        if matches!(c, '\\' | ',' | ':') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
@@ -0,0 +1,135 @@
+use std::ffi::CString;
+use std::io;
+use std::net::Ipv4Addr;
+use std::net::Shutdown;
+use std::net::TcpListener;
+use std::net::TcpStream;
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::path::PathBuf;
+use std::thread;
+
+use crate::namespaces::enter_user_namespace;
+
+/// Environment variables pointed at the loopback bridge so that common HTTP
+/// clients (curl, cargo, npm, pip, git) send their traffic through the proxy.
+const PROXY_ENV_VARS: &[&str] = &[
+    "HTTP_PROXY",
+    "HTTPS_PROXY",
+    "ALL_PROXY",
+    "http_proxy",
+    "https_proxy",
+    "all_proxy",
+];
+
+/// Moves the current process into a fresh network namespace, brings up its
+/// loopback and forks a bridge process that forwards connections made to
+/// `127.0.0.1:<port>` to the Codex network proxy at `proxy_socket`. Returns
+/// the bridge port.
+///
+/// `in_user_namespace` is set when the process already entered a user
+/// namespace, in which case only the network namespace is created. Fails when
+/// the kernel does not allow unprivileged namespaces.
+pub(crate) fn start_proxy_bridge(proxy_socket: &Path, in_user_namespace: bool) -> io::Result<u16> {
+    if in_user_namespace {
+        if unsafe { libc::unshare(libc::CLONE_NEWNET) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+    } else {
+        enter_user_namespace(libc::CLONE_NEWNET)?;
+    }
+    bring_up_loopback()?;
+
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    let port = listener.local_addr()?.port();
+
+    match unsafe { libc::fork() } {
+        -1 => Err(io::Error::last_os_error()),
+        0 => {
+            // The bridge only lives as long as the sandboxed command.
+            unsafe {
+                libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGKILL);
+            }
+            run_bridge(listener, proxy_socket.to_path_buf());
+            unsafe { libc::_exit(0) }
+        }
+        _ => Ok(port),
+    }
+}
+
+/// Exports the proxy variables for the command about to be exec'd.
+pub(crate) fn set_proxy_env(port: u16) {
+    let proxy_url = format!("http://127.0.0.1:{port}");
+    // SAFETY: the helper is single-threaded at this point.
+    unsafe {
+        for name in PROXY_ENV_VARS {
+            std::env::set_var(name, &proxy_url);
+        }
+        std::env::set_var("NO_PROXY", "localhost,127.0.0.1,::1");
+        std::env::set_var("no_proxy", "localhost,127.0.0.1,::1");
+    }
+}
+
+fn bring_up_loopback() -> io::Result<()> {
+    let socket = unsafe { libc::socket(libc::AF_INET, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, 0) };
+    if socket < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let mut request: libc::ifreq = unsafe { std::mem::zeroed() };
+    let name = CString::new("lo").map_err(io::Error::other)?;
+    for (dst, src) in request
+        .ifr_name
+        .iter_mut()
+        .zip(name.as_bytes_with_nul().iter())
+    {
+        *dst = libc::c_char::from_ne_bytes([*src]);
+    }
+
+    let result = unsafe {
+        if libc::ioctl(socket, libc::SIOCGIFFLAGS, &mut request as *mut libc::ifreq) < 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            request.ifr_ifru.ifru_flags |= libc::IFF_UP as libc::c_short;
+            if libc::ioctl(socket, libc::SIOCSIFFLAGS, &request as *const libc::ifreq) < 0 {
+                Err(io::Error::last_os_error())
+            } else {
+                Ok(())
+            }
+        }
+    };
+    unsafe {
+        libc::close(socket);
+    }
+    result
+}
+
+fn run_bridge(listener: TcpListener, proxy_socket: PathBuf) {
+    for client in listener.incoming() {
+        let Ok(client) = client else {
+            continue;
+        };
+        let proxy_socket = proxy_socket.clone();
+        thread::spawn(move || {
+            if let Ok(upstream) = UnixStream::connect(&proxy_socket) {
+                let _ = forward(client, upstream);
+            }
+        });
+    }
+}
+
+fn forward(client: TcpStream, upstream: UnixStream) -> io::Result<()> {
+    let mut client_reader = client.try_clone()?;
+    let mut upstream_writer = upstream.try_clone()?;
+    let to_upstream = thread::spawn(move || {
+        let _ = io::copy(&mut client_reader, &mut upstream_writer);
+        let _ = upstream_writer.shutdown(Shutdown::Write);
+    });
+
+    let mut upstream_reader = upstream;
+    let mut client_writer = client;
+    let _ = io::copy(&mut upstream_reader, &mut client_writer);
+    let _ = client_writer.shutdown(Shutdown::Write);
+    let _ = to_upstream.join();
+    Ok(())
+}
@@ -0,0 +1,123 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use codex_core::config::types::SandboxResourceLimits;
+use codex_core::landlock::command_cgroup_dir;
+
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+/// Seconds of CPU time between `SIGXCPU` at the soft limit and `SIGKILL` at
+/// the hard limit, so the command can be told it ran out of time and exit.
+const CPU_HARD_LIMIT_GRACE_SECONDS: u64 = 5;
+
+// glibc declares the resource argument as an enum, musl as a plain int.
+#[cfg(target_env = "gnu")]
+type RlimitResource = libc::__rlimit_resource_t;
+#[cfg(not(target_env = "gnu"))]
+type RlimitResource = libc::c_int;
+
+/// Applies `limits` to the current process. They are inherited across
+/// `execvp` and by every process the command spawns.
+///
+/// Memory and process limits are enforced by moving the helper into its own
+/// cgroup (see [`command_cgroup_dir`]) with `memory.max` and `pids.max` set,
+/// which bounds the resident memory and process count of the whole command.
+/// That needs cgroup v2 with the `memory` and `pids` controllers delegated to
+/// the user. Without it memory falls back to `RLIMIT_DATA`, which bounds each
+/// process's heap rather than the command's memory, and the process limit is
+/// not enforced: `RLIMIT_NPROC` counts every process the user owns, so the
+/// command's first `fork` would fail in any busy session.
+///
+/// Must run before Landlock is applied, since it writes to `/sys/fs/cgroup`.
+/// Limits are only ever lowered: if the helper already runs under a stricter
+/// hard limit, that limit is kept.
+pub(crate) fn apply_resource_limits(limits: &SandboxResourceLimits) -> io::Result<()> {
+    let cgroup = if limits.max_memory_mb.is_some() || limits.max_processes.is_some() {
+        enter_limited_cgroup(limits)
+    } else {
+        Ok(())
+    };
+    if let Err(err) = cgroup {
+        if let Some(max_memory_mb) = limits.max_memory_mb {
+            let bytes = max_memory_mb.saturating_mul(BYTES_PER_MB);
+            set_rlimit(libc::RLIMIT_DATA, bytes, bytes)?;
+        }
+        if limits.max_processes.is_some() {
+            eprintln!("codex-linux-sandbox: cgroup unavailable, not limiting processes: {err}");
+        }
+    }
+    if let Some(max_cpu_seconds) = limits.max_cpu_seconds {
+        set_rlimit(
+            libc::RLIMIT_CPU,
+            max_cpu_seconds,
+            max_cpu_seconds.saturating_add(CPU_HARD_LIMIT_GRACE_SECONDS),
+        )?;
+    }
+    if let Some(max_file_size_mb) = limits.max_file_size_mb {
+        let bytes = max_file_size_mb.saturating_mul(BYTES_PER_MB);
+        set_rlimit(libc::RLIMIT_FSIZE, bytes, bytes)?;
+    }
+    Ok(())
+}
+
+/// Creates the command's cgroup, sets its limits and moves the current
+/// process into it. The cgroup is removed again if any step fails.
+fn enter_limited_cgroup(limits: &SandboxResourceLimits) -> io::Result<()> {
+    let dir = command_cgroup_dir(std::process::id())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::Unsupported, "cgroup v2 is not mounted"))?;
+    fs::create_dir(&dir)?;
+    let result = configure_cgroup(&dir, limits);
+    if result.is_err() {
+        let _ = fs::remove_dir(&dir);
+    }
+    result
+}
+
+fn configure_cgroup(dir: &Path, limits: &SandboxResourceLimits) -> io::Result<()> {
+    let controllers = fs::read_to_string(dir.join("cgroup.controllers"))?;
+    let has_controller = |name: &str| controllers.split_whitespace().any(|c| c == name);
+
+    if let Some(max_memory_mb) = limits.max_memory_mb {
+        if !has_controller("memory") {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "memory controller is not delegated",
+            ));
+        }
+        let bytes = max_memory_mb.saturating_mul(BYTES_PER_MB);
+        fs::write(dir.join("memory.max"), bytes.to_string())?;
+        // Without this the kernel swaps the command out instead of stopping it.
+        let _ = fs::write(dir.join("memory.swap.max"), "0");
+    }
+    if let Some(max_processes) = limits.max_processes {
+        if !has_controller("pids") {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "pids controller is not delegated",
+            ));
+        }
+        fs::write(dir.join("pids.max"), max_processes.to_string())?;
+    }
+    fs::write(dir.join("cgroup.procs"), "0")
+}
+
+fn set_rlimit(resource: RlimitResource, soft: libc::rlim_t, hard: libc::rlim_t) -> io::Result<()> {
+    let mut current = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    if unsafe { libc::getrlimit(resource, &mut current) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let hard = hard.min(current.rlim_max);
+    let limit = libc::rlimit {
+        rlim_cur: soft.min(hard),
+        rlim_max: hard,
+    };
+    if unsafe { libc::setrlimit(resource, &limit) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
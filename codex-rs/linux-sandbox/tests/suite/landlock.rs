@@ -34,8 +34,17 @@ fn create_env_from_core_vars() -> HashMap<String, String> {
     create_env(&policy)
 }
 
-#[expect(clippy::print_stdout, clippy::expect_used, clippy::unwrap_used)]
 async fn run_cmd(cmd: &[&str], writable_roots: &[PathBuf], timeout_ms: u64) {
+    run_cmd_with_readable_roots(cmd, writable_roots, None, timeout_ms).await;
+}
+
+#[expect(clippy::print_stdout, clippy::expect_used, clippy::unwrap_used)]
+async fn run_cmd_with_readable_roots(
+    cmd: &[&str],
+    writable_roots: &[PathBuf],
+    readable_roots: Option<&[PathBuf]>,
+    timeout_ms: u64,
+) {
     let cwd = std::env::current_dir().expect("cwd should exist");
     let sandbox_cwd = cwd.clone();
     let params = ExecParams {
@@ -59,6 +68,12 @@ async fn run_cmd(cmd: &[&str], writable_roots: &[PathBuf], timeout_ms: u64) {
         // writing to in the sandbox.
         exclude_tmpdir_env_var: true,
         exclude_slash_tmp: true,
+        readable_roots: readable_roots.map(|roots| {
+            roots
+                .iter()
+                .map(|p| AbsolutePathBuf::try_from(p.as_path()).unwrap())
+                .collect()
+        }),
     };
     let sandbox_program = env!("CARGO_BIN_EXE_codex-linux-sandbox");
     let codex_linux_sandbox_exe = Some(PathBuf::from(sandbox_program));
@@ -127,6 +142,36 @@ async fn test_writable_root() {
     .await;
 }
 
+#[tokio::test]
+async fn test_readable_root_read() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let file_path = tmpdir.path().join("notes.txt");
+    std::fs::write(&file_path, "hello").unwrap();
+    run_cmd_with_readable_roots(
+        &["cat", &file_path.to_string_lossy()],
+        &[],
+        Some(&[tmpdir.path().to_path_buf()]),
+        LONG_TIMEOUT_MS,
+    )
+    .await;
+}
+
+#[tokio::test]
+#[should_panic]
+async fn test_read_outside_readable_roots() {
+    let secret_dir = tempfile::tempdir().unwrap();
+    let secret_path = secret_dir.path().join("credentials");
+    std::fs::write(&secret_path, "secret").unwrap();
+    let allowed_dir = tempfile::tempdir().unwrap();
+    run_cmd_with_readable_roots(
+        &["cat", &secret_path.to_string_lossy()],
+        &[],
+        Some(&[allowed_dir.path().to_path_buf()]),
+        LONG_TIMEOUT_MS,
+    )
+    .await;
+}
+
 #[tokio::test]
 #[should_panic(expected = "Sandbox(Timeout")]
 async fn test_timeout() {
@@ -0,0 +1,26 @@
+use std::net::SocketAddr;
+
+use clap::Parser;
+
+#[derive(Parser, Debug, Default)]
+pub struct Cli {
+    /// Also expose Codex's built-in tools (`read_file`, `grep_files`,
+    /// `list_dir`, `apply_patch`, `shell`) as individual MCP tools, and
+    /// recorded sessions as MCP resources.
+    #[arg(long = "toolset", default_value_t = false)]
+    pub toolset: bool,
+
+    /// Serve MCP over streamable HTTP at `http://<ADDR>/mcp` instead of
+    /// stdio, e.g. `127.0.0.1:8765`.
+    #[arg(long = "listen", value_name = "ADDR")]
+    pub listen: Option<SocketAddr>,
+
+    /// Environment variable holding the bearer token HTTP clients must send
+    /// in the `Authorization` header. Required with `--listen`.
+    #[arg(
+        long = "bearer-token-env-var",
+        value_name = "ENV_VAR",
+        requires = "listen"
+    )]
+    pub bearer_token_env_var: Option<String>,
+}
@@ -3,6 +3,7 @@
 //! and to make future feature-growth easier to manage.
 
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use crate::exec_approval::handle_exec_approval_request;
@@ -43,6 +44,7 @@ pub async fn run_codex_tool_session(
     outgoing: Arc<OutgoingMessageSender>,
     thread_manager: Arc<ThreadManager>,
     running_requests_id_to_codex_uuid: Arc<Mutex<HashMap<RequestId, ThreadId>>>,
+    session_thread_ids: Arc<Mutex<HashSet<ThreadId>>>,
 ) {
     let NewThread {
         thread_id,
@@ -65,6 +67,8 @@ pub async fn run_codex_tool_session(
         }
     };
 
+    session_thread_ids.lock().await.insert(thread_id);
+
     let session_configured_event = Event {
         // Use a fake id value for now.
         id: "".to_string(),
@@ -200,7 +204,9 @@ async fn run_codex_tool_session_inner(
                         outgoing.send_response(request_id.clone(), result).await;
                         break;
                     }
-                    EventMsg::Warning(_) => {
+                    EventMsg::Warning(_)
+                    | EventMsg::ModelFallback(_)
+                    | EventMsg::SecretsRedacted(_) => {
                         continue;
                     }
                     EventMsg::ElicitationRequest(_) => {
@@ -275,6 +281,8 @@ async fn run_codex_tool_session_inner(
                     | EventMsg::McpToolCallEnd(_)
                     | EventMsg::McpListToolsResponse(_)
                     | EventMsg::ListCustomPromptsResponse(_)
+                    | EventMsg::GetMcpPromptResponse(_)
+                    | EventMsg::CallToolResponse(_)
                     | EventMsg::ListSkillsResponse(_)
                     | EventMsg::ExecCommandBegin(_)
                     | EventMsg::TerminalInteraction(_)
@@ -285,6 +293,8 @@ async fn run_codex_tool_session_inner(
                     | EventMsg::PatchApplyBegin(_)
                     | EventMsg::PatchApplyEnd(_)
                     | EventMsg::TurnDiff(_)
+                    | EventMsg::WorkspaceOverlayDiff(_)
+                    | EventMsg::WorkspaceOverlayResolved(_)
                     | EventMsg::WebSearchBegin(_)
                     | EventMsg::WebSearchEnd(_)
                     | EventMsg::GetHistoryEntryResponse(_)
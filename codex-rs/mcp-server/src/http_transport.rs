@@ -0,0 +1,485 @@
+//! Streamable HTTP transport (`codex mcp-server --listen <ADDR>`).
+//!
+//! Each MCP session gets its own [`MessageProcessor`], so clients only see
+//! the threads they started while sharing one `ThreadManager`. Responses to
+//! a POSTed request are streamed back as SSE on that POST; server-initiated
+//! requests and notifications go to the session's GET stream when the client
+//! has opened one, and otherwise ride along on any open POST stream.
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::io::Result as IoResult;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::Router;
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::HeaderMap;
+use axum::http::HeaderValue;
+use axum::http::StatusCode;
+use axum::http::header::AUTHORIZATION;
+use axum::http::header::WWW_AUTHENTICATE;
+use axum::response::IntoResponse;
+use axum::response::Response;
+use axum::response::sse::Event as SseEvent;
+use axum::response::sse::KeepAlive;
+use axum::response::sse::Sse;
+use axum::routing::post;
+use codex_core::ThreadManager;
+use codex_core::config::Config;
+use mcp_types::JSONRPCMessage;
+use mcp_types::ModelContextProtocolRequest;
+use mcp_types::RequestId;
+use tokio::net::TcpListener;
+use tokio::sync::Mutex;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tokio_stream::StreamExt;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tracing::error;
+use tracing::info;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::CHANNEL_CAPACITY;
+use crate::SESSION_SHUTDOWN_TIMEOUT;
+use crate::message_processor::MessageProcessor;
+use crate::outgoing_message::OutgoingMessage;
+use crate::outgoing_message::OutgoingMessageSender;
+
+const MCP_PATH: &str = "/mcp";
+const MCP_SESSION_ID_HEADER: &str = "mcp-session-id";
+/// Sessions whose client has sent nothing for this long, with no request in
+/// flight and no stream open, are closed.
+const SESSION_IDLE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+const SESSION_IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+pub(crate) async fn run_http_server(
+    listen: SocketAddr,
+    bearer_token: String,
+    codex_linux_sandbox_exe: Option<PathBuf>,
+    config: Arc<Config>,
+    thread_manager: Arc<ThreadManager>,
+    toolset: bool,
+) -> IoResult<()> {
+    let state = Arc::new(HttpState {
+        bearer_token,
+        codex_linux_sandbox_exe,
+        config,
+        thread_manager,
+        toolset,
+        sessions: Mutex::new(HashMap::new()),
+    });
+    let app = Router::new()
+        .route(
+            MCP_PATH,
+            post(handle_post).get(handle_get).delete(handle_delete),
+        )
+        .with_state(state.clone());
+
+    let listener = TcpListener::bind(listen).await?;
+    info!(
+        "MCP server listening on http://{}{MCP_PATH}",
+        listener.local_addr()?
+    );
+    let idle_sessions_handle = tokio::spawn({
+        let state = state.clone();
+        async move {
+            let mut interval = tokio::time::interval(SESSION_IDLE_CHECK_INTERVAL);
+            loop {
+                interval.tick().await;
+                state.close_idle_sessions().await;
+            }
+        }
+    });
+    let served = axum::serve(listener, app)
+        .with_graceful_shutdown(async move {
+            shutdown_signal().await;
+            info!("shutting down MCP HTTP server");
+            // Closing sessions ends their SSE streams, which would otherwise
+            // keep the server waiting on open connections.
+            state.close_all_sessions().await;
+        })
+        .await;
+    idle_sessions_handle.abort();
+    served
+}
+
+struct HttpState {
+    bearer_token: String,
+    codex_linux_sandbox_exe: Option<PathBuf>,
+    config: Arc<Config>,
+    thread_manager: Arc<ThreadManager>,
+    toolset: bool,
+    sessions: Mutex<HashMap<String, Arc<HttpSession>>>,
+}
+
+impl HttpState {
+    fn authorize(&self, headers: &HeaderMap) -> Result<(), Response> {
+        let expected = &self.bearer_token;
+        let provided = headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+        if provided.is_some_and(|token| constant_time_eq(token.as_bytes(), expected.as_bytes())) {
+            Ok(())
+        } else {
+            Err((
+                StatusCode::UNAUTHORIZED,
+                [(WWW_AUTHENTICATE, "Bearer")],
+                "missing or invalid bearer token",
+            )
+                .into_response())
+        }
+    }
+
+    async fn session(&self, headers: &HeaderMap) -> Result<(String, Arc<HttpSession>), Response> {
+        let Some(session_id) = headers
+            .get(MCP_SESSION_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+        else {
+            return Err((StatusCode::BAD_REQUEST, "missing Mcp-Session-Id header").into_response());
+        };
+        let session = self.sessions.lock().await.get(session_id).cloned();
+        match session {
+            Some(session) => {
+                *session.last_seen.lock().await = Instant::now();
+                Ok((session_id.to_string(), session))
+            }
+            None => Err((StatusCode::NOT_FOUND, "unknown MCP session").into_response()),
+        }
+    }
+
+    /// Start a session with its own message processor and register it.
+    async fn start_session(&self) -> (String, Arc<HttpSession>) {
+        let session_id = Uuid::new_v4().to_string();
+        let (incoming_tx, mut incoming_rx) = mpsc::channel::<JSONRPCMessage>(CHANNEL_CAPACITY);
+        let (outgoing_tx, mut outgoing_rx) = mpsc::unbounded_channel::<OutgoingMessage>();
+        let session = Arc::new(HttpSession {
+            incoming_tx: Mutex::new(Some(incoming_tx)),
+            pending: Mutex::new(HashMap::new()),
+            standalone: Mutex::new(None),
+            processor_handle: Mutex::new(None),
+            last_seen: Mutex::new(Instant::now()),
+        });
+
+        let mut processor = MessageProcessor::new(
+            OutgoingMessageSender::new(outgoing_tx),
+            self.codex_linux_sandbox_exe.clone(),
+            self.config.clone(),
+            self.thread_manager.clone(),
+            self.toolset,
+        );
+        let processor_handle = tokio::spawn({
+            let session_id = session_id.clone();
+            async move {
+                while let Some(msg) = incoming_rx.recv().await {
+                    processor.process_message(msg).await;
+                }
+
+                processor.shutdown_threads().await;
+                info!("MCP session {session_id} closed");
+            }
+        });
+        *session.processor_handle.lock().await = Some(processor_handle);
+
+        tokio::spawn({
+            let session = session.clone();
+            async move {
+                while let Some(outgoing_message) = outgoing_rx.recv().await {
+                    session.route(outgoing_message.into()).await;
+                }
+            }
+        });
+
+        self.sessions
+            .lock()
+            .await
+            .insert(session_id.clone(), session.clone());
+        info!("MCP session {session_id} started");
+        (session_id, session)
+    }
+
+    /// Close sessions that have been idle for [`SESSION_IDLE_TIMEOUT`]. Their
+    /// threads shut down in the background, as after a `DELETE`.
+    async fn close_idle_sessions(&self) {
+        let mut idle = Vec::new();
+        {
+            let mut sessions = self.sessions.lock().await;
+            let mut idle_ids = Vec::new();
+            for (session_id, session) in sessions.iter() {
+                if session.is_idle().await {
+                    idle_ids.push(session_id.clone());
+                }
+            }
+            for session_id in idle_ids {
+                if let Some(session) = sessions.remove(&session_id) {
+                    idle.push((session_id, session));
+                }
+            }
+        }
+        for (session_id, session) in idle {
+            info!("closing idle MCP session {session_id}");
+            session.close().await;
+        }
+    }
+
+    /// Close every session and wait, up to a deadline, for their threads to
+    /// report `ShutdownComplete`.
+    async fn close_all_sessions(&self) {
+        let sessions: Vec<Arc<HttpSession>> = self
+            .sessions
+            .lock()
+            .await
+            .drain()
+            .map(|(_, session)| session)
+            .collect();
+        // Close all sessions first so their threads shut down concurrently.
+        for session in &sessions {
+            session.close().await;
+        }
+        let deadline = Instant::now() + SESSION_SHUTDOWN_TIMEOUT;
+        for session in sessions {
+            if let Some(handle) = session.processor_handle.lock().await.take()
+                && tokio::time::timeout_at(deadline, handle).await.is_err()
+            {
+                warn!("timed out waiting for MCP session to shut down");
+            }
+        }
+    }
+}
+
+/// Message routing for one client session.
+struct HttpSession {
+    /// Feeds the session's message processor; `None` once closed.
+    incoming_tx: Mutex<Option<mpsc::Sender<JSONRPCMessage>>>,
+    /// POST streams waiting for the response to a request, keyed by its id.
+    pending: Mutex<HashMap<RequestId, mpsc::UnboundedSender<JSONRPCMessage>>>,
+    /// GET stream for server-initiated messages, if the client opened one.
+    standalone: Mutex<Option<mpsc::UnboundedSender<JSONRPCMessage>>>,
+    processor_handle: Mutex<Option<JoinHandle<()>>>,
+    /// When the client last sent a request to this session.
+    last_seen: Mutex<Instant>,
+}
+
+impl HttpSession {
+    async fn send(&self, message: JSONRPCMessage) -> bool {
+        let incoming_tx = self.incoming_tx.lock().await.clone();
+        match incoming_tx {
+            Some(incoming_tx) => incoming_tx.send(message).await.is_ok(),
+            None => false,
+        }
+    }
+
+    /// Whether the client has been silent for [`SESSION_IDLE_TIMEOUT`] and is
+    /// neither waiting on a response nor listening on the GET stream.
+    async fn is_idle(&self) -> bool {
+        self.last_seen.lock().await.elapsed() >= SESSION_IDLE_TIMEOUT
+            && self
+                .pending
+                .lock()
+                .await
+                .values()
+                .all(mpsc::UnboundedSender::is_closed)
+            && self
+                .standalone
+                .lock()
+                .await
+                .as_ref()
+                .is_none_or(mpsc::UnboundedSender::is_closed)
+    }
+
+    /// Stop accepting messages and end all open streams. The processor then
+    /// exits and shuts down the session's threads.
+    async fn close(&self) {
+        self.incoming_tx.lock().await.take();
+        self.pending.lock().await.clear();
+        self.standalone.lock().await.take();
+    }
+
+    async fn route(&self, message: JSONRPCMessage) {
+        let response_id = match &message {
+            JSONRPCMessage::Response(response) => Some(response.id.clone()),
+            JSONRPCMessage::Error(error) => Some(error.id.clone()),
+            JSONRPCMessage::Request(_) | JSONRPCMessage::Notification(_) => None,
+        };
+        if let Some(id) = response_id {
+            // Removing the entry drops the sender, which ends the POST stream
+            // once it has delivered the response.
+            match self.pending.lock().await.remove(&id) {
+                Some(stream) => {
+                    let _ = stream.send(message);
+                }
+                None => warn!("no open stream for response to {id:?}"),
+            }
+            return;
+        }
+
+        let mut message = message;
+        {
+            let mut standalone = self.standalone.lock().await;
+            if let Some(stream) = standalone.take() {
+                match stream.send(message) {
+                    Ok(()) => {
+                        *standalone = Some(stream);
+                        return;
+                    }
+                    Err(mpsc::error::SendError(unsent)) => message = unsent,
+                }
+            }
+        }
+        for stream in self.pending.lock().await.values() {
+            match stream.send(message) {
+                Ok(()) => return,
+                Err(mpsc::error::SendError(unsent)) => message = unsent,
+            }
+        }
+        warn!("dropping server message with no open stream: {message:?}");
+    }
+}
+
+async fn handle_post(
+    State(state): State<Arc<HttpState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    if let Err(response) = state.authorize(&headers) {
+        return response;
+    }
+    let message = match serde_json::from_slice::<JSONRPCMessage>(&body) {
+        Ok(message) => message,
+        Err(err) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                format!("invalid JSON-RPC message: {err}"),
+            )
+                .into_response();
+        }
+    };
+
+    let is_initialize = matches!(
+        &message,
+        JSONRPCMessage::Request(request)
+            if request.method == mcp_types::InitializeRequest::METHOD
+    );
+    let (session_id, session) = if is_initialize && !headers.contains_key(MCP_SESSION_ID_HEADER) {
+        state.start_session().await
+    } else {
+        match state.session(&headers).await {
+            Ok(session) => session,
+            Err(response) => return response,
+        }
+    };
+
+    let request_id = match &message {
+        JSONRPCMessage::Request(request) => Some(request.id.clone()),
+        _ => None,
+    };
+    let Some(request_id) = request_id else {
+        // Notifications and responses (e.g. to elicitations) have no reply.
+        return if session.send(message).await {
+            StatusCode::ACCEPTED.into_response()
+        } else {
+            (StatusCode::NOT_FOUND, "MCP session closed").into_response()
+        };
+    };
+
+    let (stream_tx, stream_rx) = mpsc::unbounded_channel();
+    session
+        .pending
+        .lock()
+        .await
+        .insert(request_id.clone(), stream_tx);
+    if !session.send(message).await {
+        session.pending.lock().await.remove(&request_id);
+        return (StatusCode::NOT_FOUND, "MCP session closed").into_response();
+    }
+
+    let mut response = sse_response(stream_rx);
+    if let Ok(value) = HeaderValue::from_str(&session_id) {
+        response.headers_mut().insert(MCP_SESSION_ID_HEADER, value);
+    }
+    response
+}
+
+async fn handle_get(State(state): State<Arc<HttpState>>, headers: HeaderMap) -> Response {
+    if let Err(response) = state.authorize(&headers) {
+        return response;
+    }
+    let (_, session) = match state.session(&headers).await {
+        Ok(session) => session,
+        Err(response) => return response,
+    };
+
+    let (stream_tx, stream_rx) = mpsc::unbounded_channel();
+    *session.standalone.lock().await = Some(stream_tx);
+    sse_response(stream_rx)
+}
+
+async fn handle_delete(State(state): State<Arc<HttpState>>, headers: HeaderMap) -> Response {
+    if let Err(response) = state.authorize(&headers) {
+        return response;
+    }
+    let (session_id, session) = match state.session(&headers).await {
+        Ok(session) => session,
+        Err(response) => return response,
+    };
+
+    state.sessions.lock().await.remove(&session_id);
+    session.close().await;
+    StatusCode::OK.into_response()
+}
+
+fn sse_response(stream_rx: mpsc::UnboundedReceiver<JSONRPCMessage>) -> Response {
+    let stream = UnboundedReceiverStream::new(stream_rx).filter_map(|message| {
+        match serde_json::to_string(&message) {
+            Ok(json) => Some(Ok::<_, Infallible>(
+                SseEvent::default().event("message").data(json),
+            )),
+            Err(e) => {
+                error!("Failed to serialize JSONRPCMessage: {e}");
+                None
+            }
+        }
+    });
+    Sse::new(stream)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            error!("failed to listen for Ctrl-C: {e}");
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                error!("failed to listen for SIGTERM: {e}");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
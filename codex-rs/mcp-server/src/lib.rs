@@ -4,9 +4,14 @@
 use std::io::ErrorKind;
 use std::io::Result as IoResult;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 
 use codex_common::CliConfigOverrides;
+use codex_core::AuthManager;
+use codex_core::ThreadManager;
 use codex_core::config::Config;
+use codex_protocol::protocol::SessionSource;
 
 use mcp_types::JSONRPCMessage;
 use tokio::io::AsyncBufReadExt;
@@ -19,18 +24,22 @@ use tracing::error;
 use tracing::info;
 use tracing_subscriber::EnvFilter;
 
+mod cli;
 mod codex_tool_config;
 mod codex_tool_runner;
 mod error_code;
 mod exec_approval;
+mod http_transport;
 pub(crate) mod message_processor;
 mod outgoing_message;
 mod patch_approval;
+mod toolset;
 
 use crate::message_processor::MessageProcessor;
 use crate::outgoing_message::OutgoingMessage;
 use crate::outgoing_message::OutgoingMessageSender;
 
+pub use crate::cli::Cli;
 pub use crate::codex_tool_config::CodexToolCallParam;
 pub use crate::codex_tool_config::CodexToolCallReplyParam;
 pub use crate::exec_approval::ExecApprovalElicitRequestParams;
@@ -43,9 +52,14 @@ pub use crate::patch_approval::PatchApprovalResponse;
 /// plenty for an interactive CLI.
 const CHANNEL_CAPACITY: usize = 128;
 
+/// Upper bound on how long exiting waits for a client's threads to report
+/// `ShutdownComplete`.
+const SESSION_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
 pub async fn run_main(
     codex_linux_sandbox_exe: Option<PathBuf>,
     cli_config_overrides: CliConfigOverrides,
+    cli: Cli,
 ) -> IoResult<()> {
     // Install a simple subscriber so `tracing` output is visible.  Users can
     // control the log level with `RUST_LOG`.
@@ -54,6 +68,74 @@ pub async fn run_main(
         .with_env_filter(EnvFilter::from_default_env())
         .init();
 
+    // Parse CLI overrides once and derive the base Config eagerly so later
+    // components do not need to work with raw TOML values.
+    let cli_kv_overrides = cli_config_overrides.parse_overrides().map_err(|e| {
+        std::io::Error::new(
+            ErrorKind::InvalidInput,
+            format!("error parsing -c overrides: {e}"),
+        )
+    })?;
+    let config = Config::load_with_cli_overrides(cli_kv_overrides)
+        .await
+        .map_err(|e| {
+            std::io::Error::new(ErrorKind::InvalidData, format!("error loading config: {e}"))
+        })?;
+    let config = Arc::new(config);
+
+    // A single thread manager serves every client; each client only sees the
+    // threads it started.
+    let auth_manager = AuthManager::shared(
+        config.codex_home.clone(),
+        false,
+        config.cli_auth_credentials_store_mode,
+    );
+    let thread_manager = Arc::new(ThreadManager::new(
+        config.codex_home.clone(),
+        auth_manager,
+        SessionSource::Mcp,
+    ));
+
+    if let Some(listen) = cli.listen {
+        // Any local process or web page can reach a loopback port, so a token
+        // is required even there.
+        let Some(bearer_token_env_var) = cli.bearer_token_env_var.as_deref() else {
+            return Err(std::io::Error::new(
+                ErrorKind::InvalidInput,
+                format!("refusing to listen on {listen} without --bearer-token-env-var"),
+            ));
+        };
+        let bearer_token = read_bearer_token(bearer_token_env_var)?;
+        return http_transport::run_http_server(
+            listen,
+            bearer_token,
+            codex_linux_sandbox_exe,
+            config,
+            thread_manager,
+            cli.toolset,
+        )
+        .await;
+    }
+
+    run_stdio_server(codex_linux_sandbox_exe, config, thread_manager, cli.toolset).await
+}
+
+fn read_bearer_token(env_var: &str) -> IoResult<String> {
+    match std::env::var(env_var) {
+        Ok(token) if !token.is_empty() => Ok(token),
+        _ => Err(std::io::Error::new(
+            ErrorKind::InvalidInput,
+            format!("environment variable {env_var} must be set to a non-empty bearer token"),
+        )),
+    }
+}
+
+async fn run_stdio_server(
+    codex_linux_sandbox_exe: Option<PathBuf>,
+    config: Arc<Config>,
+    thread_manager: Arc<ThreadManager>,
+    toolset: bool,
+) -> IoResult<()> {
     // Set up channels.
     let (incoming_tx, mut incoming_rx) = mpsc::channel::<JSONRPCMessage>(CHANNEL_CAPACITY);
     let (outgoing_tx, mut outgoing_rx) = mpsc::unbounded_channel::<OutgoingMessage>();
@@ -81,38 +163,22 @@ pub async fn run_main(
         }
     });
 
-    // Parse CLI overrides once and derive the base Config eagerly so later
-    // components do not need to work with raw TOML values.
-    let cli_kv_overrides = cli_config_overrides.parse_overrides().map_err(|e| {
-        std::io::Error::new(
-            ErrorKind::InvalidInput,
-            format!("error parsing -c overrides: {e}"),
-        )
-    })?;
-    let config = Config::load_with_cli_overrides(cli_kv_overrides)
-        .await
-        .map_err(|e| {
-            std::io::Error::new(ErrorKind::InvalidData, format!("error loading config: {e}"))
-        })?;
-
     // Task: process incoming messages.
     let processor_handle = tokio::spawn({
         let outgoing_message_sender = OutgoingMessageSender::new(outgoing_tx);
         let mut processor = MessageProcessor::new(
             outgoing_message_sender,
             codex_linux_sandbox_exe,
-            std::sync::Arc::new(config),
+            config,
+            thread_manager,
+            toolset,
         );
         async move {
             while let Some(msg) = incoming_rx.recv().await {
-                match msg {
-                    JSONRPCMessage::Request(r) => processor.process_request(r).await,
-                    JSONRPCMessage::Response(r) => processor.process_response(r).await,
-                    JSONRPCMessage::Notification(n) => processor.process_notification(n).await,
-                    JSONRPCMessage::Error(e) => processor.process_error(e),
-                }
+                processor.process_message(msg).await;
             }
 
+            processor.shutdown_threads().await;
             info!("processor task exited (channel closed)");
         }
     });
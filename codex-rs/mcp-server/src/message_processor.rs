@@ -1,16 +1,18 @@
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::path::PathBuf;
 
+use crate::SESSION_SHUTDOWN_TIMEOUT;
 use crate::codex_tool_config::CodexToolCallParam;
 use crate::codex_tool_config::CodexToolCallReplyParam;
 use crate::codex_tool_config::create_tool_for_codex_tool_call_param;
 use crate::codex_tool_config::create_tool_for_codex_tool_call_reply_param;
 use crate::error_code::INVALID_REQUEST_ERROR_CODE;
 use crate::outgoing_message::OutgoingMessageSender;
+use crate::toolset::Toolset;
 use codex_protocol::ThreadId;
-use codex_protocol::protocol::SessionSource;
+use codex_protocol::protocol::AgentStatus;
 
-use codex_core::AuthManager;
 use codex_core::ThreadManager;
 use codex_core::config::Config;
 use codex_core::default_client::USER_AGENT_SUFFIX;
@@ -22,12 +24,14 @@ use mcp_types::ClientRequest as McpClientRequest;
 use mcp_types::ContentBlock;
 use mcp_types::JSONRPCError;
 use mcp_types::JSONRPCErrorError;
+use mcp_types::JSONRPCMessage;
 use mcp_types::JSONRPCNotification;
 use mcp_types::JSONRPCRequest;
 use mcp_types::JSONRPCResponse;
 use mcp_types::ListToolsResult;
 use mcp_types::ModelContextProtocolRequest;
 use mcp_types::RequestId;
+use mcp_types::ServerCapabilitiesResources;
 use mcp_types::ServerCapabilitiesTools;
 use mcp_types::ServerNotification;
 use mcp_types::TextContent;
@@ -42,6 +46,11 @@ pub(crate) struct MessageProcessor {
     codex_linux_sandbox_exe: Option<PathBuf>,
     thread_manager: Arc<ThreadManager>,
     running_requests_id_to_codex_uuid: Arc<Mutex<HashMap<RequestId, ThreadId>>>,
+    /// Threads started on behalf of this client. The `ThreadManager` may be
+    /// shared with other clients, which must not see these threads.
+    session_thread_ids: Arc<Mutex<HashSet<ThreadId>>>,
+    /// Present when running with `--toolset`.
+    toolset: Option<Arc<Toolset>>,
 }
 
 impl MessageProcessor {
@@ -51,24 +60,66 @@ impl MessageProcessor {
         outgoing: OutgoingMessageSender,
         codex_linux_sandbox_exe: Option<PathBuf>,
         config: Arc<Config>,
+        thread_manager: Arc<ThreadManager>,
+        toolset: bool,
     ) -> Self {
         let outgoing = Arc::new(outgoing);
-        let auth_manager = AuthManager::shared(
-            config.codex_home.clone(),
-            false,
-            config.cli_auth_credentials_store_mode,
-        );
-        let thread_manager = Arc::new(ThreadManager::new(
-            config.codex_home.clone(),
-            auth_manager,
-            SessionSource::Mcp,
-        ));
+        let session_thread_ids = Arc::new(Mutex::new(HashSet::new()));
+        let toolset = toolset.then(|| {
+            Arc::new(Toolset::new(
+                config,
+                thread_manager.clone(),
+                session_thread_ids.clone(),
+            ))
+        });
         Self {
             outgoing,
             initialized: false,
             codex_linux_sandbox_exe,
             thread_manager,
             running_requests_id_to_codex_uuid: Arc::new(Mutex::new(HashMap::new())),
+            session_thread_ids,
+            toolset,
+        }
+    }
+
+    /// Shut down every thread started on behalf of this client and wait, up
+    /// to [`SESSION_SHUTDOWN_TIMEOUT`], for each to report `ShutdownComplete`.
+    pub(crate) async fn shutdown_threads(&self) {
+        let thread_ids: Vec<ThreadId> = self.session_thread_ids.lock().await.drain().collect();
+        let mut shutting_down = Vec::new();
+        for thread_id in thread_ids {
+            let Some(thread) = self.thread_manager.remove_thread(&thread_id).await else {
+                continue;
+            };
+            // Tool calls may still be reading the thread's events, so watch
+            // its status rather than waiting for the event itself.
+            let status = thread.subscribe_status();
+            match thread.submit(codex_core::protocol::Op::Shutdown).await {
+                Ok(_) => shutting_down.push((thread_id, status, thread)),
+                Err(e) => tracing::warn!("Failed to shut down thread {thread_id}: {e}"),
+            }
+        }
+        let deadline = tokio::time::Instant::now() + SESSION_SHUTDOWN_TIMEOUT;
+        for (thread_id, mut status, _thread) in shutting_down {
+            let shutdown = status.wait_for(|status| matches!(status, AgentStatus::Shutdown));
+            match tokio::time::timeout_at(deadline, shutdown).await {
+                Ok(Ok(_)) => {}
+                Ok(Err(_)) => {
+                    tracing::warn!("Thread {thread_id} exited without completing shutdown");
+                }
+                Err(_) => tracing::warn!("Timed out waiting for thread {thread_id} to shut down"),
+            }
+        }
+    }
+
+    /// Route a message received from the client to the matching handler.
+    pub(crate) async fn process_message(&mut self, message: JSONRPCMessage) {
+        match message {
+            JSONRPCMessage::Request(r) => self.process_request(r).await,
+            JSONRPCMessage::Response(r) => self.process_response(r).await,
+            JSONRPCMessage::Notification(n) => self.process_notification(n).await,
+            JSONRPCMessage::Error(e) => self.process_error(e),
         }
     }
 
@@ -93,13 +144,13 @@ impl MessageProcessor {
                 self.handle_ping(request_id, params).await;
             }
             McpClientRequest::ListResourcesRequest(params) => {
-                self.handle_list_resources(params);
+                self.handle_list_resources(request_id, params).await;
             }
             McpClientRequest::ListResourceTemplatesRequest(params) => {
                 self.handle_list_resource_templates(params);
             }
             McpClientRequest::ReadResourceRequest(params) => {
-                self.handle_read_resource(params);
+                self.handle_read_resource(request_id, params).await;
             }
             McpClientRequest::SubscribeRequest(params) => {
                 self.handle_subscribe(params);
@@ -212,7 +263,10 @@ impl MessageProcessor {
                 experimental: None,
                 logging: None,
                 prompts: None,
-                resources: None,
+                resources: self.toolset.as_ref().map(|_| ServerCapabilitiesResources {
+                    list_changed: None,
+                    subscribe: None,
+                }),
                 tools: Some(ServerCapabilitiesTools {
                     list_changed: Some(true),
                 }),
@@ -249,11 +303,20 @@ impl MessageProcessor {
             .await;
     }
 
-    fn handle_list_resources(
+    async fn handle_list_resources(
         &self,
+        id: RequestId,
         params: <mcp_types::ListResourcesRequest as mcp_types::ModelContextProtocolRequest>::Params,
     ) {
         tracing::info!("resources/list -> params: {:?}", params);
+        let Some(toolset) = &self.toolset else {
+            return;
+        };
+
+        // Every session this client started fits in a single page.
+        let result = toolset.list_resources().await;
+        self.send_response::<mcp_types::ListResourcesRequest>(id, result)
+            .await;
     }
 
     fn handle_list_resource_templates(
@@ -264,11 +327,23 @@ impl MessageProcessor {
         tracing::info!("resources/templates/list -> params: {:?}", params);
     }
 
-    fn handle_read_resource(
+    async fn handle_read_resource(
         &self,
+        id: RequestId,
         params: <mcp_types::ReadResourceRequest as mcp_types::ModelContextProtocolRequest>::Params,
     ) {
         tracing::info!("resources/read -> params: {:?}", params);
+        let Some(toolset) = &self.toolset else {
+            return;
+        };
+
+        match toolset.read_resource(params.uri).await {
+            Ok(result) => {
+                self.send_response::<mcp_types::ReadResourceRequest>(id, result)
+                    .await;
+            }
+            Err(error) => self.outgoing.send_error(id, error).await,
+        }
     }
 
     fn handle_subscribe(
@@ -305,11 +380,15 @@ impl MessageProcessor {
         params: <mcp_types::ListToolsRequest as mcp_types::ModelContextProtocolRequest>::Params,
     ) {
         tracing::trace!("tools/list -> {params:?}");
+        let mut tools = vec![
+            create_tool_for_codex_tool_call_param(),
+            create_tool_for_codex_tool_call_reply_param(),
+        ];
+        if self.toolset.is_some() {
+            tools.extend(Toolset::tools());
+        }
         let result = ListToolsResult {
-            tools: vec![
-                create_tool_for_codex_tool_call_param(),
-                create_tool_for_codex_tool_call_reply_param(),
-            ],
+            tools,
             next_cursor: None,
         };
 
@@ -331,6 +410,9 @@ impl MessageProcessor {
                 self.handle_tool_call_codex_session_reply(id, arguments)
                     .await
             }
+            name if self.toolset.is_some() && Toolset::handles_tool(name) => {
+                self.handle_tool_call_toolset(id, name.to_string(), arguments)
+            }
             _ => {
                 let result = CallToolResult {
                     content: vec![ContentBlock::TextContent(TextContent {
@@ -346,6 +428,34 @@ impl MessageProcessor {
             }
         }
     }
+
+    fn handle_tool_call_toolset(
+        &self,
+        id: RequestId,
+        name: String,
+        arguments: Option<serde_json::Value>,
+    ) {
+        let Some(toolset) = self.toolset.clone() else {
+            return;
+        };
+        let outgoing = self.outgoing.clone();
+        let running_requests_id_to_codex_uuid = self.running_requests_id_to_codex_uuid.clone();
+
+        // Tool calls may wait on approvals, so run them off the message loop.
+        task::spawn(async move {
+            let result = toolset
+                .call_tool(
+                    id.clone(),
+                    name,
+                    arguments,
+                    outgoing.clone(),
+                    running_requests_id_to_codex_uuid,
+                )
+                .await;
+            outgoing.send_response(id, result).await;
+        });
+    }
+
     async fn handle_tool_call_codex(&self, id: RequestId, arguments: Option<serde_json::Value>) {
         let (initial_prompt, config): (String, Config) = match arguments {
             Some(json_val) => match serde_json::from_value::<CodexToolCallParam>(json_val) {
@@ -408,6 +518,7 @@ impl MessageProcessor {
         let outgoing = self.outgoing.clone();
         let thread_manager = self.thread_manager.clone();
         let running_requests_id_to_codex_uuid = self.running_requests_id_to_codex_uuid.clone();
+        let session_thread_ids = self.session_thread_ids.clone();
 
         // Spawn an async task to handle the Codex session so that we do not
         // block the synchronous message-processing loop.
@@ -420,6 +531,7 @@ impl MessageProcessor {
                 outgoing,
                 thread_manager,
                 running_requests_id_to_codex_uuid,
+                session_thread_ids,
             )
             .await;
         });
@@ -496,9 +608,19 @@ impl MessageProcessor {
         let outgoing = self.outgoing.clone();
         let running_requests_id_to_codex_uuid = self.running_requests_id_to_codex_uuid.clone();
 
-        let codex = match self.thread_manager.get_thread(conversation_id).await {
-            Ok(c) => c,
-            Err(_) => {
+        let owned_by_session = self
+            .session_thread_ids
+            .lock()
+            .await
+            .contains(&conversation_id);
+        let thread = if owned_by_session {
+            self.thread_manager.get_thread(conversation_id).await.ok()
+        } else {
+            None
+        };
+        let codex = match thread {
+            Some(c) => c,
+            None => {
                 tracing::warn!("Session not found for conversation_id: {conversation_id}");
                 let result = CallToolResult {
                     content: vec![ContentBlock::TextContent(TextContent {
@@ -0,0 +1,312 @@
+//! Opt-in `--toolset` mode: exposes Codex's built-in tools as individual MCP
+//! tools and recorded sessions as MCP resources.
+//!
+//! Tool calls run inside a single Codex thread started on first use, so they
+//! go through the same sandbox, approval and exec policy checks as tool calls
+//! made by the model.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use codex_core::CodexThread;
+use codex_core::DIRECT_TOOL_NAMES;
+use codex_core::NewThread;
+use codex_core::ThreadManager;
+use codex_core::config::Config;
+use codex_core::direct_mcp_tools;
+use codex_core::find_thread_path_by_id_str;
+use codex_core::protocol::ApplyPatchApprovalRequestEvent;
+use codex_core::protocol::CallToolResponseEvent;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::ExecApprovalRequestEvent;
+use codex_core::protocol::Op;
+use codex_core::read_head_for_summary;
+use codex_protocol::ThreadId;
+use codex_protocol::protocol::SessionMetaLine;
+use mcp_types::CallToolResult;
+use mcp_types::ContentBlock;
+use mcp_types::JSONRPCErrorError;
+use mcp_types::ListResourcesResult;
+use mcp_types::ReadResourceResult;
+use mcp_types::ReadResourceResultContents;
+use mcp_types::RequestId;
+use mcp_types::Resource;
+use mcp_types::TextContent;
+use mcp_types::TextResourceContents;
+use mcp_types::Tool;
+use tokio::sync::Mutex;
+use tokio::sync::OnceCell;
+
+use crate::error_code::INTERNAL_ERROR_CODE;
+use crate::error_code::RESOURCE_NOT_FOUND_ERROR_CODE;
+use crate::exec_approval::handle_exec_approval_request;
+use crate::outgoing_message::OutgoingMessageSender;
+use crate::patch_approval::handle_patch_approval_request;
+
+/// URI prefix for session rollouts, followed by the thread id.
+const SESSION_RESOURCE_URI_PREFIX: &str = "codex://sessions/";
+const SESSION_RESOURCE_MIME_TYPE: &str = "application/jsonl";
+
+pub(crate) struct Toolset {
+    config: Arc<Config>,
+    thread_manager: Arc<ThreadManager>,
+    thread: OnceCell<(ThreadId, Arc<CodexThread>)>,
+    session_thread_ids: Arc<Mutex<HashSet<ThreadId>>>,
+    /// Each call runs as a task on the shared thread, and starting a task
+    /// aborts the one in flight, so calls are handled one at a time.
+    call_lock: Mutex<()>,
+}
+
+impl Toolset {
+    pub(crate) fn new(
+        config: Arc<Config>,
+        thread_manager: Arc<ThreadManager>,
+        session_thread_ids: Arc<Mutex<HashSet<ThreadId>>>,
+    ) -> Self {
+        Self {
+            config,
+            thread_manager,
+            thread: OnceCell::new(),
+            session_thread_ids,
+            call_lock: Mutex::new(()),
+        }
+    }
+
+    pub(crate) fn tools() -> Vec<Tool> {
+        direct_mcp_tools()
+    }
+
+    pub(crate) fn handles_tool(name: &str) -> bool {
+        DIRECT_TOOL_NAMES.contains(&name)
+    }
+
+    /// Run `name` on the toolset thread and wait for its result, forwarding
+    /// any approval requests to the client as elicitations.
+    pub(crate) async fn call_tool(
+        &self,
+        request_id: RequestId,
+        name: String,
+        arguments: Option<serde_json::Value>,
+        outgoing: Arc<OutgoingMessageSender>,
+        running_requests_id_to_codex_uuid: Arc<Mutex<HashMap<RequestId, ThreadId>>>,
+    ) -> CallToolResult {
+        let _guard = self.call_lock.lock().await;
+        let (thread_id, thread) = match self.thread().await {
+            Ok(thread) => thread,
+            Err(message) => return error_result(message),
+        };
+
+        let call_id = match &request_id {
+            RequestId::String(s) => s.clone(),
+            RequestId::Integer(n) => n.to_string(),
+        };
+        let arguments = arguments
+            .unwrap_or_else(|| serde_json::json!({}))
+            .to_string();
+        if let Err(e) = thread
+            .submit(Op::CallTool {
+                call_id: call_id.clone(),
+                tool: name,
+                arguments,
+            })
+            .await
+        {
+            return error_result(format!("Failed to submit tool call: {e}"));
+        }
+
+        // Register the call so `notifications/cancelled` interrupts it.
+        running_requests_id_to_codex_uuid
+            .lock()
+            .await
+            .insert(request_id.clone(), thread_id);
+        let result = loop {
+            let event = match thread.next_event().await {
+                Ok(event) => event,
+                Err(e) => break error_result(format!("Codex session failed: {e}")),
+            };
+            match event.msg {
+                EventMsg::CallToolResponse(CallToolResponseEvent {
+                    call_id: response_call_id,
+                    result,
+                }) if response_call_id == call_id => {
+                    break match result {
+                        Ok(output) => text_result(output, None),
+                        Err(message) => error_result(message),
+                    };
+                }
+                EventMsg::ExecApprovalRequest(ExecApprovalRequestEvent {
+                    call_id: approval_call_id,
+                    command,
+                    cwd,
+                    parsed_cmd,
+                    ..
+                }) => {
+                    handle_exec_approval_request(
+                        command,
+                        cwd,
+                        outgoing.clone(),
+                        thread.clone(),
+                        request_id.clone(),
+                        call_id.clone(),
+                        event.id.clone(),
+                        approval_call_id,
+                        parsed_cmd,
+                    )
+                    .await;
+                }
+                EventMsg::ApplyPatchApprovalRequest(ApplyPatchApprovalRequestEvent {
+                    call_id: approval_call_id,
+                    reason,
+                    grant_root,
+                    changes,
+                    ..
+                }) => {
+                    handle_patch_approval_request(
+                        approval_call_id,
+                        reason,
+                        grant_root,
+                        changes,
+                        outgoing.clone(),
+                        thread.clone(),
+                        request_id.clone(),
+                        call_id.clone(),
+                        event.id.clone(),
+                    )
+                    .await;
+                }
+                EventMsg::Error(err) => break error_result(err.message),
+                _ => {}
+            }
+        };
+        running_requests_id_to_codex_uuid
+            .lock()
+            .await
+            .remove(&request_id);
+        result
+    }
+
+    async fn thread(&self) -> Result<(ThreadId, Arc<CodexThread>), String> {
+        self.thread
+            .get_or_try_init(|| async {
+                let NewThread {
+                    thread_id, thread, ..
+                } = self
+                    .thread_manager
+                    .start_thread(self.config.as_ref().clone())
+                    .await
+                    .map_err(|e| format!("Failed to start Codex session: {e}"))?;
+                self.session_thread_ids.lock().await.insert(thread_id);
+                Ok((thread_id, thread))
+            })
+            .await
+            .cloned()
+    }
+
+    /// Lists the sessions started on behalf of this client. Rollouts of other
+    /// clients and of interactive sessions are not exposed.
+    pub(crate) async fn list_resources(&self) -> ListResourcesResult {
+        let mut thread_ids: Vec<String> = self
+            .session_thread_ids
+            .lock()
+            .await
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        thread_ids.sort();
+
+        let mut resources = Vec::new();
+        for thread_id in thread_ids {
+            // A thread's rollout may not have been written yet.
+            let Ok(Some(path)) =
+                find_thread_path_by_id_str(&self.config.codex_home, &thread_id).await
+            else {
+                continue;
+            };
+            let Some(SessionMetaLine { meta, .. }) = read_head_for_summary(&path)
+                .await
+                .ok()
+                .and_then(|head| serde_json::from_value(head.first()?.clone()).ok())
+            else {
+                continue;
+            };
+            resources.push(Resource {
+                annotations: None,
+                description: Some(format!(
+                    "Codex session started {} in {}",
+                    meta.timestamp,
+                    meta.cwd.display()
+                )),
+                mime_type: Some(SESSION_RESOURCE_MIME_TYPE.to_string()),
+                name: thread_id.clone(),
+                size: None,
+                title: None,
+                uri: format!("{SESSION_RESOURCE_URI_PREFIX}{thread_id}"),
+            });
+        }
+
+        ListResourcesResult {
+            next_cursor: None,
+            resources,
+        }
+    }
+
+    pub(crate) async fn read_resource(
+        &self,
+        uri: String,
+    ) -> Result<ReadResourceResult, JSONRPCErrorError> {
+        let not_found = || JSONRPCErrorError {
+            code: RESOURCE_NOT_FOUND_ERROR_CODE,
+            message: format!("resource not found: {uri}"),
+            data: None,
+        };
+        let thread_id = uri
+            .strip_prefix(SESSION_RESOURCE_URI_PREFIX)
+            .and_then(|id| ThreadId::from_string(id).ok())
+            .ok_or_else(not_found)?;
+        if !self.session_thread_ids.lock().await.contains(&thread_id) {
+            return Err(not_found());
+        }
+        let path = find_thread_path_by_id_str(&self.config.codex_home, &thread_id.to_string())
+            .await
+            .map_err(|err| JSONRPCErrorError {
+                code: INTERNAL_ERROR_CODE,
+                message: format!("failed to locate session {thread_id}: {err}"),
+                data: None,
+            })?
+            .ok_or_else(not_found)?;
+        let text = tokio::fs::read_to_string(&path)
+            .await
+            .map_err(|err| JSONRPCErrorError {
+                code: INTERNAL_ERROR_CODE,
+                message: format!("failed to read {}: {err}", path.display()),
+                data: None,
+            })?;
+
+        Ok(ReadResourceResult {
+            contents: vec![ReadResourceResultContents::TextResourceContents(
+                TextResourceContents {
+                    mime_type: Some(SESSION_RESOURCE_MIME_TYPE.to_string()),
+                    text,
+                    uri: uri.clone(),
+                },
+            )],
+        })
+    }
+}
+
+fn text_result(text: String, is_error: Option<bool>) -> CallToolResult {
+    CallToolResult {
+        content: vec![ContentBlock::TextContent(TextContent {
+            r#type: "text".to_string(),
+            text,
+            annotations: None,
+        })],
+        is_error,
+        structured_content: None,
+    }
+}
+
+fn error_result(message: String) -> CallToolResult {
+    text_result(message, Some(true))
+}
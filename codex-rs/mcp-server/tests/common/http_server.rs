@@ -0,0 +1,54 @@
+use std::net::SocketAddr;
+use std::net::TcpListener;
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+
+use anyhow::Context;
+use tokio::net::TcpStream;
+use tokio::process::Child;
+use tokio::process::Command;
+
+/// Environment variable the spawned server reads its bearer token from.
+pub const MCP_HTTP_TOKEN_ENV_VAR: &str = "CODEX_MCP_TEST_TOKEN";
+
+/// Spawns `codex-mcp-server --listen` on a free loopback port, requiring
+/// `bearer_token`, and waits until it accepts connections.
+pub async fn spawn_mcp_http_server(
+    codex_home: &Path,
+    bearer_token: &str,
+) -> anyhow::Result<(Child, SocketAddr)> {
+    let addr = free_loopback_addr()?;
+    let child = mcp_http_server_command(codex_home, addr)?
+        .args(["--bearer-token-env-var", MCP_HTTP_TOKEN_ENV_VAR])
+        .env(MCP_HTTP_TOKEN_ENV_VAR, bearer_token)
+        .spawn()
+        .context("codex-mcp-server proc should start")?;
+
+    for _ in 0..100 {
+        if TcpStream::connect(addr).await.is_ok() {
+            return Ok((child, addr));
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    anyhow::bail!("codex-mcp-server did not start listening on {addr}")
+}
+
+/// Returns a loopback address with a port that is currently free.
+pub fn free_loopback_addr() -> anyhow::Result<SocketAddr> {
+    Ok(TcpListener::bind("127.0.0.1:0")?.local_addr()?)
+}
+
+/// Builds a `codex-mcp-server --listen <addr>` command without auth flags.
+pub fn mcp_http_server_command(codex_home: &Path, addr: SocketAddr) -> anyhow::Result<Command> {
+    let program = codex_utils_cargo_bin::cargo_bin("codex-mcp-server")
+        .context("should find binary for codex-mcp-server")?;
+    let mut command = Command::new(program);
+    command
+        .args(["--listen", &addr.to_string()])
+        .env("CODEX_HOME", codex_home)
+        .env("RUST_LOG", "debug")
+        .stdin(Stdio::null())
+        .kill_on_drop(true);
+    Ok(command)
+}
@@ -1,3 +1,4 @@
+mod http_server;
 mod mcp_process;
 mod mock_model_server;
 mod responses;
@@ -5,6 +6,10 @@ mod responses;
 pub use core_test_support::format_with_current_shell;
 pub use core_test_support::format_with_current_shell_display_non_login;
 pub use core_test_support::format_with_current_shell_non_login;
+pub use http_server::MCP_HTTP_TOKEN_ENV_VAR;
+pub use http_server::free_loopback_addr;
+pub use http_server::mcp_http_server_command;
+pub use http_server::spawn_mcp_http_server;
 pub use mcp_process::McpProcess;
 use mcp_types::JSONRPCResponse;
 pub use mock_model_server::create_mock_chat_completions_server;
@@ -37,6 +37,9 @@ pub struct McpProcess {
     process: Child,
     stdin: ChildStdin,
     stdout: BufReader<ChildStdout>,
+    /// Whether the server was started with `--toolset`, which also
+    /// advertises the resources capability.
+    toolset: bool,
 }
 
 impl McpProcess {
@@ -52,11 +55,27 @@ impl McpProcess {
     pub async fn new_with_env(
         codex_home: &Path,
         env_overrides: &[(&str, Option<&str>)],
+    ) -> anyhow::Result<Self> {
+        Self::spawn(codex_home, &[], env_overrides).await
+    }
+
+    /// Creates a new MCP process with extra command-line arguments, e.g.
+    /// `--toolset`.
+    pub async fn new_with_args(codex_home: &Path, args: &[&str]) -> anyhow::Result<Self> {
+        Self::spawn(codex_home, args, &[]).await
+    }
+
+    async fn spawn(
+        codex_home: &Path,
+        args: &[&str],
+        env_overrides: &[(&str, Option<&str>)],
     ) -> anyhow::Result<Self> {
         let program = codex_utils_cargo_bin::cargo_bin("codex-mcp-server")
             .context("should find binary for codex-mcp-server")?;
         let mut cmd = Command::new(program);
 
+        cmd.args(args);
+
         cmd.stdin(Stdio::piped());
         cmd.stdout(Stdio::piped());
         cmd.stderr(Stdio::piped());
@@ -103,6 +122,7 @@ impl McpProcess {
             process,
             stdin,
             stdout,
+            toolset: args.contains(&"--toolset"),
         })
     }
 
@@ -144,16 +164,20 @@ impl McpProcess {
             os_info.architecture().unwrap_or("unknown"),
             codex_core::terminal::user_agent()
         );
+        let mut capabilities = json!({
+            "tools": {
+                "listChanged": true
+            },
+        });
+        if self.toolset {
+            capabilities["resources"] = json!({});
+        }
         assert_eq!(
             JSONRPCMessage::Response(JSONRPCResponse {
                 jsonrpc: JSONRPC_VERSION.into(),
                 id: RequestId::Integer(request_id),
                 result: json!({
-                    "capabilities": {
-                        "tools": {
-                            "listChanged": true
-                        },
-                    },
+                    "capabilities": capabilities,
                     "serverInfo": {
                         "name": "codex-mcp-server",
                         "title": "Codex",
@@ -194,6 +218,28 @@ impl McpProcess {
         .await
     }
 
+    /// Sends a `tools/call` request for `name` and returns its request id.
+    pub async fn send_tool_call(
+        &mut self,
+        name: &str,
+        arguments: serde_json::Value,
+    ) -> anyhow::Result<i64> {
+        let params = CallToolRequestParams {
+            name: name.to_string(),
+            arguments: Some(arguments),
+        };
+        self.send_request(
+            mcp_types::CallToolRequest::METHOD,
+            Some(serde_json::to_value(params)?),
+        )
+        .await
+    }
+
+    pub async fn send_list_tools_request(&mut self) -> anyhow::Result<i64> {
+        self.send_request(mcp_types::ListToolsRequest::METHOD, None)
+            .await
+    }
+
     async fn send_request(
         &mut self,
         method: &str,
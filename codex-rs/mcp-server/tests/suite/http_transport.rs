@@ -0,0 +1,138 @@
+use std::net::SocketAddr;
+
+use mcp_test_support::free_loopback_addr;
+use mcp_test_support::mcp_http_server_command;
+use mcp_test_support::spawn_mcp_http_server;
+use mcp_types::JSONRPCMessage;
+use mcp_types::JSONRPCResponse;
+use pretty_assertions::assert_eq;
+use reqwest::StatusCode;
+use serde_json::json;
+use tempfile::TempDir;
+
+const TOKEN: &str = "test-token";
+const SESSION_HEADER: &str = "mcp-session-id";
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn http_transport_requires_token_and_isolates_sessions() -> anyhow::Result<()> {
+    let codex_home = TempDir::new()?;
+    let (_server, addr) = spawn_mcp_http_server(codex_home.path(), TOKEN).await?;
+    let client = reqwest::Client::new();
+    let url = format!("http://{addr}/mcp");
+
+    let unauthorized = client.post(&url).json(&initialize_request()).send().await?;
+    assert_eq!(unauthorized.status(), StatusCode::UNAUTHORIZED);
+
+    let first = initialize(&client, addr).await?;
+    let second = initialize(&client, addr).await?;
+    assert_ne!(first, second);
+
+    let list_tools = client
+        .post(&url)
+        .bearer_auth(TOKEN)
+        .header(SESSION_HEADER, &first)
+        .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": "tools/list" }))
+        .send()
+        .await?;
+    assert_eq!(list_tools.status(), StatusCode::OK);
+    let response = read_sse_response(list_tools).await?;
+    let names: Vec<&str> = response.result["tools"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|tool| tool["name"].as_str())
+        .collect();
+    assert_eq!(names, vec!["codex", "codex-reply"]);
+
+    let deleted = client
+        .delete(&url)
+        .bearer_auth(TOKEN)
+        .header(SESSION_HEADER, &first)
+        .send()
+        .await?;
+    assert_eq!(deleted.status(), StatusCode::OK);
+
+    let after_delete = client
+        .post(&url)
+        .bearer_auth(TOKEN)
+        .header(SESSION_HEADER, &first)
+        .json(&json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" }))
+        .send()
+        .await?;
+    assert_eq!(after_delete.status(), StatusCode::NOT_FOUND);
+
+    let still_open = client
+        .post(&url)
+        .bearer_auth(TOKEN)
+        .header(SESSION_HEADER, &second)
+        .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": "tools/list" }))
+        .send()
+        .await?;
+    assert_eq!(still_open.status(), StatusCode::OK);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn http_transport_refuses_to_start_without_token() -> anyhow::Result<()> {
+    let codex_home = TempDir::new()?;
+    let output = tokio::time::timeout(
+        std::time::Duration::from_secs(20),
+        mcp_http_server_command(codex_home.path(), free_loopback_addr()?)?.output(),
+    )
+    .await??;
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("without --bearer-token-env-var"),
+        "unexpected stderr: {stderr}"
+    );
+
+    Ok(())
+}
+
+fn initialize_request() -> serde_json::Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "capabilities": {},
+            "clientInfo": { "name": "http-test", "version": "0.0.0" },
+            "protocolVersion": mcp_types::MCP_SCHEMA_VERSION,
+        },
+    })
+}
+
+/// Starts a session and returns its `Mcp-Session-Id`.
+async fn initialize(client: &reqwest::Client, addr: SocketAddr) -> anyhow::Result<String> {
+    let response = client
+        .post(format!("http://{addr}/mcp"))
+        .bearer_auth(TOKEN)
+        .json(&initialize_request())
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let session_id = response
+        .headers()
+        .get(SESSION_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_owned)
+        .ok_or_else(|| anyhow::format_err!("initialize response missing session id"))?;
+    read_sse_response(response).await?;
+    Ok(session_id)
+}
+
+/// Reads the SSE stream of a POSTed request until its JSON-RPC response.
+async fn read_sse_response(response: reqwest::Response) -> anyhow::Result<JSONRPCResponse> {
+    let body = response.text().await?;
+    body.lines()
+        .filter_map(|line| line.strip_prefix("data:"))
+        .filter_map(|data| serde_json::from_str::<JSONRPCMessage>(data.trim()).ok())
+        .find_map(|message| match message {
+            JSONRPCMessage::Response(response) => Some(response),
+            _ => None,
+        })
+        .ok_or_else(|| anyhow::format_err!("no JSON-RPC response in SSE body: {body}"))
+}
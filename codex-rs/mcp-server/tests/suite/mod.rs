@@ -1 +1,3 @@
 mod codex_tool;
+mod http_transport;
+mod toolset;
@@ -0,0 +1,139 @@
+use std::path::Path;
+
+use codex_core::DIRECT_TOOL_NAMES;
+use mcp_test_support::McpProcess;
+use mcp_test_support::create_mock_chat_completions_server;
+use mcp_test_support::to_response;
+use mcp_types::CallToolResult;
+use mcp_types::ContentBlock;
+use mcp_types::ListToolsResult;
+use mcp_types::RequestId;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(20);
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn toolset_exposes_builtin_tools_and_runs_read_file() -> anyhow::Result<()> {
+    let server = create_mock_chat_completions_server(Vec::new()).await;
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path(), &server.uri(), "")?;
+    let workspace = TempDir::new()?;
+    let notes = workspace.path().join("notes.txt");
+    std::fs::write(&notes, "hello from the toolset\n")?;
+
+    let mut mcp = McpProcess::new_with_args(codex_home.path(), &["--toolset"]).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let list_id = mcp.send_list_tools_request().await?;
+    let list_response = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(list_id)),
+    )
+    .await??;
+    let ListToolsResult { tools, .. } = to_response(list_response)?;
+    let names: Vec<&str> = tools.iter().map(|tool| tool.name.as_str()).collect();
+    for expected in ["codex", "codex-reply"]
+        .iter()
+        .chain(DIRECT_TOOL_NAMES.iter())
+    {
+        assert!(names.contains(expected), "missing {expected} in {names:?}");
+    }
+
+    let call_id = mcp
+        .send_tool_call("read_file", json!({ "file_path": notes.to_string_lossy() }))
+        .await?;
+    let call_response = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(call_id)),
+    )
+    .await??;
+    let CallToolResult {
+        content, is_error, ..
+    } = to_response(call_response)?;
+    assert_eq!(is_error, None);
+    let [ContentBlock::TextContent(text)] = content.as_slice() else {
+        panic!("expected a single text block, got {content:?}");
+    };
+    assert_eq!(text.text, "L1: hello from the toolset");
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn toolset_read_file_rejects_paths_outside_readable_roots() -> anyhow::Result<()> {
+    let server = create_mock_chat_completions_server(Vec::new()).await;
+    let codex_home = TempDir::new()?;
+    let workspace = TempDir::new()?;
+    let outside = TempDir::new()?;
+    let secret = outside.path().join("secret.txt");
+    std::fs::write(&secret, "not for the client\n")?;
+    create_config_toml(
+        codex_home.path(),
+        &server.uri(),
+        &format!(
+            "[sandbox_read_only]\nreadable_roots = [{:?}]\n",
+            workspace.path().to_string_lossy()
+        ),
+    )?;
+
+    let mut mcp = McpProcess::new_with_args(codex_home.path(), &["--toolset"]).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let call_id = mcp
+        .send_tool_call(
+            "read_file",
+            json!({ "file_path": secret.to_string_lossy() }),
+        )
+        .await?;
+    let call_response = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(call_id)),
+    )
+    .await??;
+    let CallToolResult {
+        content, is_error, ..
+    } = to_response(call_response)?;
+    assert_eq!(is_error, Some(true));
+    let [ContentBlock::TextContent(text)] = content.as_slice() else {
+        panic!("expected a single text block, got {content:?}");
+    };
+    assert!(
+        text.text.contains("outside the sandbox's readable roots"),
+        "unexpected error: {}",
+        text.text
+    );
+
+    Ok(())
+}
+
+fn create_config_toml(
+    codex_home: &Path,
+    server_uri: &str,
+    extra_toml: &str,
+) -> std::io::Result<()> {
+    let config_toml = codex_home.join("config.toml");
+    std::fs::write(
+        config_toml,
+        format!(
+            r#"
+model = "mock-model"
+approval_policy = "never"
+sandbox_mode = "read-only"
+
+model_provider = "mock_provider"
+
+[model_providers.mock_provider]
+name = "Mock provider for test"
+base_url = "{server_uri}/v1"
+wire_api = "chat"
+request_max_retries = 0
+stream_max_retries = 0
+
+{extra_toml}
+"#
+        ),
+    )
+}
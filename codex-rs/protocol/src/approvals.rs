@@ -37,6 +37,19 @@ impl From<Vec<String>> for ExecPolicyAmendment {
     }
 }
 
+/// Where an execpolicy amendment applies.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum ExecPolicyAmendmentScope {
+    /// Keep the rule in memory for the current session only.
+    Session,
+    /// Persist the rule, applying it only inside the current repository.
+    Repo,
+    /// Persist the rule for every session.
+    #[default]
+    Global,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct ExecApprovalRequestEvent {
     /// Identifier for the associated exec call, if available.
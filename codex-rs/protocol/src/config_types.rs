@@ -46,6 +46,53 @@ pub enum Verbosity {
     High,
 }
 
+/// Sampling controls forwarded to the model. Unset fields are omitted from the
+/// request so the server default applies.
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq, JsonSchema, TS)]
+#[schemars(deny_unknown_fields)]
+pub struct SamplingParams {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub temperature: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub top_p: Option<f64>,
+    /// Upper bound on generated tokens (`max_output_tokens` on the Responses API).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional, type = "number")]
+    pub max_tokens: Option<u64>,
+    /// Chat Completions only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub presence_penalty: Option<f64>,
+    /// Chat Completions only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub frequency_penalty: Option<f64>,
+    /// Chat Completions only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub stop: Option<Vec<String>>,
+}
+
+impl SamplingParams {
+    /// Returns `self` with every unset field taken from `fallback`.
+    pub fn or(self, fallback: &SamplingParams) -> Self {
+        Self {
+            temperature: self.temperature.or(fallback.temperature),
+            top_p: self.top_p.or(fallback.top_p),
+            max_tokens: self.max_tokens.or(fallback.max_tokens),
+            presence_penalty: self.presence_penalty.or(fallback.presence_penalty),
+            frequency_penalty: self.frequency_penalty.or(fallback.frequency_penalty),
+            stop: self.stop.or_else(|| fallback.stop.clone()),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
 #[derive(
     Deserialize, Debug, Clone, Copy, PartialEq, Default, Serialize, Display, JsonSchema, TS,
 )]
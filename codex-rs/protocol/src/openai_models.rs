@@ -127,6 +127,15 @@ pub enum ApplyPatchToolType {
     Function,
 }
 
+/// Vocabulary used to count tokens for a model before a request is sent.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, TS, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenizerKind {
+    O200kBase,
+    Cl100kBase,
+    DeepSeek,
+}
+
 /// Server-provided truncation policy metadata for a model.
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, TS, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -197,6 +206,14 @@ pub struct ModelInfo {
     #[serde(default = "default_effective_context_window_percent")]
     pub effective_context_window_percent: i64,
     pub experimental_supported_tools: Vec<String>,
+    /// Whether assistant messages replayed over Chat Completions must carry
+    /// `reasoning_content` (DeepSeek rejects tool-call turns without it).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub requires_reasoning_content: bool,
+    /// Tokenizer used for local token estimates. When omitted, core falls
+    /// back to a byte-length heuristic.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokenizer: Option<TokenizerKind>,
 }
 
 impl ModelInfo {
@@ -13,6 +13,7 @@ use std::time::Duration;
 use crate::ThreadId;
 use crate::approvals::ElicitationRequestEvent;
 use crate::config_types::ReasoningSummary as ReasoningSummaryConfig;
+use crate::config_types::SamplingParams;
 use crate::custom_prompts::CustomPrompt;
 use crate::items::TurnItem;
 use crate::message_history::HistoryEntry;
@@ -25,6 +26,8 @@ use crate::plan_tool::UpdatePlanArgs;
 use crate::user_input::UserInput;
 use codex_utils_absolute_path::AbsolutePathBuf;
 use mcp_types::CallToolResult;
+use mcp_types::GetPromptResult;
+use mcp_types::Prompt as McpPrompt;
 use mcp_types::RequestId;
 use mcp_types::Resource as McpResource;
 use mcp_types::ResourceTemplate as McpResourceTemplate;
@@ -42,6 +45,7 @@ pub use crate::approvals::ApplyPatchApprovalRequestEvent;
 pub use crate::approvals::ElicitationAction;
 pub use crate::approvals::ExecApprovalRequestEvent;
 pub use crate::approvals::ExecPolicyAmendment;
+pub use crate::approvals::ExecPolicyAmendmentScope;
 
 /// Open/close tags for special user-input blocks. Used across crates to avoid
 /// duplicated hardcoded strings.
@@ -149,6 +153,11 @@ pub enum Op {
         /// Updated reasoning summary preference (honored only for reasoning-capable models).
         #[serde(skip_serializing_if = "Option::is_none")]
         summary: Option<ReasoningSummaryConfig>,
+
+        /// Updated sampling parameters. Fields that are set replace the
+        /// session's current values; unset fields keep them.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        sampling: Option<SamplingParams>,
     },
 
     /// Approve a command execution
@@ -196,9 +205,34 @@ pub enum Op {
     /// Request MCP servers to reinitialize and refresh cached tool lists.
     RefreshMcpServers { config: McpServerRefreshConfig },
 
-    /// Request the list of available custom prompts.
+    /// Request the list of available custom prompts, including the prompts
+    /// offered by connected MCP servers.
     ListCustomPrompts,
 
+    /// Fetch a prompt from an MCP server with the given arguments.
+    /// Reply is delivered via `EventMsg::GetMcpPromptResponse`.
+    GetMcpPrompt {
+        /// Name of the MCP server that offers the prompt.
+        server: String,
+        /// Prompt name as listed by the server.
+        name: String,
+        /// Argument values keyed by argument name.
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        arguments: HashMap<String, String>,
+    },
+
+    /// Run one of Codex's built-in tools directly, outside of a model turn.
+    /// The call is subject to the session's sandbox, approval and exec
+    /// policies. Reply is delivered via `EventMsg::CallToolResponse`.
+    CallTool {
+        /// Identifier echoed back in the response event.
+        call_id: String,
+        /// Built-in tool name, e.g. `read_file` or `shell`.
+        tool: String,
+        /// JSON-encoded tool arguments.
+        arguments: String,
+    },
+
     /// Request the list of skills for the provided `cwd` values or the session default.
     ListSkills {
         /// Working directories to scope repo skills discovery.
@@ -220,6 +254,15 @@ pub enum Op {
     /// Request Codex to undo a turn (turn are stacked so it is the same effect as CMD + Z).
     Undo,
 
+    /// Copy the changes sandboxed commands made in the workspace overlay into
+    /// the real workspace. Reply is delivered via
+    /// `EventMsg::WorkspaceOverlayResolved`.
+    CommitWorkspaceOverlay,
+
+    /// Drop the changes sandboxed commands made in the workspace overlay.
+    /// Reply is delivered via `EventMsg::WorkspaceOverlayResolved`.
+    DiscardWorkspaceOverlay,
+
     /// Request Codex to drop the last N user turns from in-memory context.
     ///
     /// This does not attempt to revert local filesystem changes. Clients are
@@ -314,9 +357,16 @@ pub enum SandboxPolicy {
     #[serde(rename = "danger-full-access")]
     DangerFullAccess,
 
-    /// Read-only access to the entire file-system.
+    /// Read-only access to the entire file-system, or only to
+    /// `readable_roots` when set.
     #[serde(rename = "read-only")]
-    ReadOnly,
+    ReadOnly {
+        /// When set, reads are limited to these folders plus the defaults
+        /// returned by [`SandboxPolicy::get_readable_roots_with_cwd`].
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        readable_roots: Option<Vec<AbsolutePathBuf>>,
+    },
 
     /// Indicates the process is already in an external sandbox. Allows full
     /// disk access while honoring the provided network setting.
@@ -336,6 +386,13 @@ pub enum SandboxPolicy {
         #[serde(default, skip_serializing_if = "Vec::is_empty")]
         writable_roots: Vec<AbsolutePathBuf>,
 
+        /// When set, reads are limited to these folders plus the defaults
+        /// returned by [`SandboxPolicy::get_readable_roots_with_cwd`], which
+        /// include the writable roots.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        readable_roots: Option<Vec<AbsolutePathBuf>>,
+
         /// When set to `true`, outbound network access is allowed. `false` by
         /// default.
         #[serde(default)]
@@ -385,6 +442,59 @@ impl WritableRoot {
     }
 }
 
+/// System directories that stay readable when a policy restricts reads, so
+/// that shells, interpreters and their shared libraries keep working. `/run` is
+/// left out because it holds host sockets such as Docker's and D-Bus's.
+const DEFAULT_READABLE_SYSTEM_ROOTS: &[&str] = &[
+    "/bin",
+    "/sbin",
+    "/usr",
+    "/lib",
+    "/lib32",
+    "/lib64",
+    "/etc",
+    "/opt",
+    "/nix",
+    // Landlock denies ptrace-style access to processes outside the sandbox,
+    // so `/proc/<pid>/environ` of the host's processes stays unreadable.
+    "/proc",
+    "/sys",
+    "/dev",
+    "/System",
+    "/Library",
+    "/private/etc",
+    "/private/var/db",
+    "/Applications/Xcode.app",
+];
+
+/// Toolchain directories under `$HOME` that stay readable when a policy
+/// restricts reads. These are chosen so that credentials stored next to them
+/// (for example `~/.cargo/credentials.toml` or `~/.npmrc`) are not included.
+const DEFAULT_READABLE_HOME_ROOTS: &[&str] = &[
+    ".cargo/bin",
+    ".cargo/registry",
+    ".cargo/git",
+    ".rustup",
+    ".npm/_cacache",
+    ".nvm",
+    ".pyenv",
+    ".rbenv",
+    ".volta",
+    ".bun",
+    "go/pkg",
+    ".local/bin",
+    ".local/lib",
+    ".local/share/uv",
+    ".cache/uv",
+    ".cache/pip",
+    ".cache/go-build",
+    ".cache/pnpm",
+    ".cache/yarn",
+    ".m2/repository",
+    ".gradle/caches",
+    ".gradle/wrapper",
+];
+
 impl FromStr for SandboxPolicy {
     type Err = serde_json::Error;
 
@@ -396,7 +506,9 @@ impl FromStr for SandboxPolicy {
 impl SandboxPolicy {
     /// Returns a policy with read-only disk access and no network.
     pub fn new_read_only_policy() -> Self {
-        SandboxPolicy::ReadOnly
+        SandboxPolicy::ReadOnly {
+            readable_roots: None,
+        }
     }
 
     /// Returns a policy that can read the entire disk, but can only write to
@@ -405,22 +517,28 @@ impl SandboxPolicy {
     pub fn new_workspace_write_policy() -> Self {
         SandboxPolicy::WorkspaceWrite {
             writable_roots: vec![],
+            readable_roots: None,
             network_access: false,
             exclude_tmpdir_env_var: false,
             exclude_slash_tmp: false,
         }
     }
 
-    /// Always returns `true`; restricting read access is not supported.
+    /// Returns `false` when reads are limited to `readable_roots`.
     pub fn has_full_disk_read_access(&self) -> bool {
-        true
+        match self {
+            SandboxPolicy::DangerFullAccess => true,
+            SandboxPolicy::ExternalSandbox { .. } => true,
+            SandboxPolicy::ReadOnly { readable_roots }
+            | SandboxPolicy::WorkspaceWrite { readable_roots, .. } => readable_roots.is_none(),
+        }
     }
 
     pub fn has_full_disk_write_access(&self) -> bool {
         match self {
             SandboxPolicy::DangerFullAccess => true,
             SandboxPolicy::ExternalSandbox { .. } => true,
-            SandboxPolicy::ReadOnly => false,
+            SandboxPolicy::ReadOnly { .. } => false,
             SandboxPolicy::WorkspaceWrite { .. } => false,
         }
     }
@@ -429,7 +547,7 @@ impl SandboxPolicy {
         match self {
             SandboxPolicy::DangerFullAccess => true,
             SandboxPolicy::ExternalSandbox { network_access } => network_access.is_enabled(),
-            SandboxPolicy::ReadOnly => false,
+            SandboxPolicy::ReadOnly { .. } => false,
             SandboxPolicy::WorkspaceWrite { network_access, .. } => *network_access,
         }
     }
@@ -441,11 +559,12 @@ impl SandboxPolicy {
         match self {
             SandboxPolicy::DangerFullAccess => Vec::new(),
             SandboxPolicy::ExternalSandbox { .. } => Vec::new(),
-            SandboxPolicy::ReadOnly => Vec::new(),
+            SandboxPolicy::ReadOnly { .. } => Vec::new(),
             SandboxPolicy::WorkspaceWrite {
                 writable_roots,
                 exclude_tmpdir_env_var,
                 exclude_slash_tmp,
+                readable_roots: _,
                 network_access: _,
             } => {
                 // Start from explicitly configured writable roots.
@@ -529,6 +648,59 @@ impl SandboxPolicy {
             }
         }
     }
+
+    /// Returns the folders that remain readable when the policy restricts
+    /// reads, or `None` when the whole disk is readable. The list holds the
+    /// configured `readable_roots`, the writable roots (including `cwd`),
+    /// the system directories needed to run programs, and the per-user
+    /// toolchain directories that exist. Home directory dotfiles such as
+    /// `~/.ssh` or `~/.aws` are only readable when listed explicitly.
+    pub fn get_readable_roots_with_cwd(&self, cwd: &Path) -> Option<Vec<AbsolutePathBuf>> {
+        let configured = match self {
+            SandboxPolicy::DangerFullAccess | SandboxPolicy::ExternalSandbox { .. } => {
+                return None;
+            }
+            SandboxPolicy::ReadOnly { readable_roots }
+            | SandboxPolicy::WorkspaceWrite { readable_roots, .. } => readable_roots.as_ref()?,
+        };
+
+        let mut roots: Vec<AbsolutePathBuf> = configured.clone();
+        match AbsolutePathBuf::from_absolute_path(cwd) {
+            Ok(cwd) => roots.push(cwd),
+            Err(e) => {
+                error!("Ignoring invalid cwd {cwd:?} for sandbox readable root: {e}");
+            }
+        }
+        roots.extend(
+            self.get_writable_roots_with_cwd(cwd)
+                .into_iter()
+                .map(|writable_root| writable_root.root),
+        );
+
+        let home = std::env::var_os("HOME").map(PathBuf::from);
+        let defaults = DEFAULT_READABLE_SYSTEM_ROOTS
+            .iter()
+            .map(PathBuf::from)
+            .chain(home.iter().flat_map(|home| {
+                DEFAULT_READABLE_HOME_ROOTS
+                    .iter()
+                    .map(move |subpath| home.join(subpath))
+            }))
+            .filter(|path| path.exists());
+        for path in defaults {
+            if let Ok(path) = AbsolutePathBuf::from_absolute_path(path) {
+                roots.push(path);
+            }
+        }
+
+        let mut deduped: Vec<AbsolutePathBuf> = Vec::with_capacity(roots.len());
+        for root in roots {
+            if !deduped.contains(&root) {
+                deduped.push(root);
+            }
+        }
+        Some(deduped)
+    }
 }
 
 /// Event Queue Entry - events from agent
@@ -554,6 +726,14 @@ pub enum EventMsg {
     /// indicates the turn continued but the user should still be notified.
     Warning(WarningEvent),
 
+    /// The turn switched to a `fallback_models` entry because the active
+    /// provider kept failing.
+    ModelFallback(ModelFallbackEvent),
+
+    /// Secrets were masked in tool output before it reached the history,
+    /// the rollout file and clients.
+    SecretsRedacted(SecretsRedactedEvent),
+
     /// Conversation history was compacted (either automatically or manually).
     ContextCompacted(ContextCompactedEvent),
 
@@ -657,6 +837,15 @@ pub enum EventMsg {
 
     TurnDiff(TurnDiffEvent),
 
+    /// Changes made by sandboxed commands that are held in the workspace
+    /// overlay, waiting for `Op::CommitWorkspaceOverlay` or
+    /// `Op::DiscardWorkspaceOverlay`. Sent at the end of each turn while the
+    /// overlay has pending changes.
+    WorkspaceOverlayDiff(WorkspaceOverlayDiffEvent),
+
+    /// The pending workspace overlay changes were committed or discarded.
+    WorkspaceOverlayResolved(WorkspaceOverlayResolvedEvent),
+
     /// Response to GetHistoryEntryRequest.
     GetHistoryEntryResponse(GetHistoryEntryResponseEvent),
 
@@ -666,6 +855,12 @@ pub enum EventMsg {
     /// List of custom prompts available to the agent.
     ListCustomPromptsResponse(ListCustomPromptsResponseEvent),
 
+    /// Response to `Op::GetMcpPrompt`.
+    GetMcpPromptResponse(GetMcpPromptResponseEvent),
+
+    /// Response to `Op::CallTool`.
+    CallToolResponse(CallToolResponseEvent),
+
     /// List of skills available to the agent.
     ListSkillsResponse(ListSkillsResponseEvent),
 
@@ -878,6 +1073,26 @@ pub struct WarningEvent {
     pub message: String,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ModelFallbackEvent {
+    pub from_model: String,
+    /// `model_providers` key of the provider that failed.
+    pub from_provider: String,
+    pub to_model: String,
+    /// `model_providers` key of the provider serving the rest of the turn.
+    pub to_provider: String,
+    /// Error that triggered the switch.
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct SecretsRedactedEvent {
+    /// Tool calls whose output was masked.
+    pub call_ids: Vec<String>,
+    /// Number of masked values.
+    pub redactions: u64,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct ContextCompactedEvent;
 
@@ -1019,6 +1234,15 @@ impl TokenUsage {
         (self.input_tokens - self.cached_input()).max(0)
     }
 
+    /// Fraction of input tokens served from the provider's prompt cache, or
+    /// `None` when the request reported no input tokens.
+    pub fn cache_hit_ratio(&self) -> Option<f64> {
+        if self.input_tokens <= 0 {
+            return None;
+        }
+        Some(self.cached_input().min(self.input_tokens) as f64 / self.input_tokens as f64)
+    }
+
     /// Primary count for display as a single absolute value: non-cached input + output.
     pub fn blended_total(&self) -> i64 {
         (self.non_cached_input() + self.output_tokens.max(0)).max(0)
@@ -1386,6 +1610,10 @@ pub struct TurnContextItem {
     pub final_output_json_schema: Option<Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub truncation_policy: Option<TruncationPolicy>,
+    /// `model_providers` key that served the turn; differs from the session
+    /// provider when a `fallback_models` entry took over.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_provider: Option<String>,
 }
 
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
@@ -1708,6 +1936,19 @@ pub struct TurnDiffEvent {
     pub unified_diff: String,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct WorkspaceOverlayDiffEvent {
+    /// Aggregated diff between the real workspace and the overlay.
+    pub unified_diff: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct WorkspaceOverlayResolvedEvent {
+    /// `true` when the changes were copied into the workspace, `false` when
+    /// they were discarded.
+    pub committed: bool,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct GetHistoryEntryResponseEvent {
     pub offset: usize,
@@ -1725,6 +1966,9 @@ pub struct McpListToolsResponseEvent {
     pub resources: std::collections::HashMap<String, Vec<McpResource>>,
     /// Known resource templates grouped by server name.
     pub resource_templates: std::collections::HashMap<String, Vec<McpResourceTemplate>>,
+    /// Known prompts grouped by server name.
+    #[serde(default)]
+    pub prompts: std::collections::HashMap<String, Vec<McpPrompt>>,
     /// Authentication status for each configured MCP server.
     pub auth_statuses: std::collections::HashMap<String, McpAuthStatus>,
 }
@@ -1786,6 +2030,26 @@ impl fmt::Display for McpAuthStatus {
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct ListCustomPromptsResponseEvent {
     pub custom_prompts: Vec<CustomPrompt>,
+    /// Prompts offered by MCP servers, grouped by server name.
+    #[serde(default)]
+    pub mcp_prompts: HashMap<String, Vec<McpPrompt>>,
+}
+
+/// Response payload for `Op::GetMcpPrompt`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct GetMcpPromptResponseEvent {
+    pub server: String,
+    pub name: String,
+    /// Messages returned by the server, or the error that prevented them.
+    pub result: Result<GetPromptResult, String>,
+}
+
+/// Response payload for `Op::CallTool`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct CallToolResponseEvent {
+    pub call_id: String,
+    /// Tool output, or the error reported by the tool.
+    pub result: Result<String, String>,
 }
 
 /// Response payload for `Op::ListSkills`.
@@ -1877,6 +2141,16 @@ pub enum ReviewDecision {
     /// amendment so future matching commands are permitted.
     ApprovedExecpolicyAmendment {
         proposed_execpolicy_amendment: ExecPolicyAmendment,
+        /// Where the rule applies. `None` uses `exec_policy_amendments.scope`
+        /// from config.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        scope: Option<ExecPolicyAmendmentScope>,
+        /// Who approved the rule, as reported by the approving client. Stored
+        /// with persisted rules.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        approved_by: Option<String>,
     },
 
     /// User has approved this command and wants to automatically approve any
@@ -1970,6 +2244,50 @@ mod tests {
         assert!(enabled.has_full_network_access());
     }
 
+    #[test]
+    fn readable_roots_restrict_disk_read_access() -> Result<()> {
+        let workspace = tempfile::tempdir()?;
+        let extra = tempfile::tempdir()?;
+        let extra_root = AbsolutePathBuf::from_absolute_path(extra.path())?;
+        let cwd_root = AbsolutePathBuf::from_absolute_path(workspace.path())?;
+
+        assert!(SandboxPolicy::new_read_only_policy().has_full_disk_read_access());
+        assert_eq!(
+            None,
+            SandboxPolicy::new_workspace_write_policy()
+                .get_readable_roots_with_cwd(workspace.path())
+        );
+
+        let policy = SandboxPolicy::WorkspaceWrite {
+            writable_roots: vec![],
+            readable_roots: Some(vec![extra_root.clone()]),
+            network_access: false,
+            exclude_tmpdir_env_var: true,
+            exclude_slash_tmp: true,
+        };
+        assert!(!policy.has_full_disk_read_access());
+        let roots = policy
+            .get_readable_roots_with_cwd(workspace.path())
+            .expect("reads should be restricted");
+        assert_eq!(vec![extra_root, cwd_root], roots[..2].to_vec());
+        if let Some(home) = std::env::var_os("HOME") {
+            let ssh = PathBuf::from(home).join(".ssh");
+            assert!(!roots.iter().any(|root| ssh.starts_with(root.as_path())));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn read_only_policy_without_readable_roots_round_trips() -> Result<()> {
+        let policy: SandboxPolicy = serde_json::from_value(json!({ "type": "read-only" }))?;
+        assert_eq!(SandboxPolicy::new_read_only_policy(), policy);
+        assert_eq!(
+            json!({ "type": "read-only" }),
+            serde_json::to_value(&policy)?
+        );
+        Ok(())
+    }
+
     #[test]
     fn item_started_event_from_web_search_emits_begin_event() {
         let event = ItemStartedEvent {
@@ -2069,7 +2387,7 @@ mod tests {
                 model: "codex-mini-latest".to_string(),
                 model_provider_id: "openai".to_string(),
                 approval_policy: AskForApproval::Never,
-                sandbox_policy: SandboxPolicy::ReadOnly,
+                sandbox_policy: SandboxPolicy::new_read_only_policy(),
                 cwd: PathBuf::from("/home/user/project"),
                 reasoning_effort: Some(ReasoningEffortConfig::default()),
                 history_log_id: 0,
@@ -2161,4 +2479,17 @@ mod tests {
         assert_eq!(value["msg"]["cancelled"][0], "c");
         Ok(())
     }
+
+    #[test]
+    fn cache_hit_ratio_is_share_of_cached_input() {
+        let usage = TokenUsage {
+            input_tokens: 400,
+            cached_input_tokens: 300,
+            output_tokens: 50,
+            reasoning_output_tokens: 0,
+            total_tokens: 450,
+        };
+        assert_eq!(usage.cache_hit_ratio(), Some(0.75));
+        assert_eq!(TokenUsage::default().cache_hit_ratio(), None);
+    }
 }
@@ -66,6 +66,8 @@ async fn rmcp_client_can_list_and_read_resources() -> anyhow::Result<()> {
                 }
                 .boxed()
             }),
+            None,
+            None,
         )
         .await?;
 
@@ -91,8 +91,10 @@ use super::paste_burst::CharDecision;
 use super::paste_burst::PasteBurst;
 use super::skill_popup::SkillPopup;
 use crate::bottom_pane::paste_burst::FlushResult;
+use crate::bottom_pane::prompt_args::McpPromptCommand;
 use crate::bottom_pane::prompt_args::expand_custom_prompt;
 use crate::bottom_pane::prompt_args::expand_if_numeric_with_positional_args;
+use crate::bottom_pane::prompt_args::parse_mcp_prompt_invocation;
 use crate::bottom_pane::prompt_args::parse_slash_name;
 use crate::bottom_pane::prompt_args::prompt_argument_names;
 use crate::bottom_pane::prompt_args::prompt_command_with_arg_placeholders;
@@ -143,6 +145,12 @@ pub enum InputResult {
     Queued(String),
     Command(SlashCommand),
     CommandWithArgs(SlashCommand, String),
+    /// An MCP server prompt to fetch and submit.
+    McpPrompt {
+        server: String,
+        name: String,
+        arguments: HashMap<String, String>,
+    },
     None,
 }
 
@@ -187,6 +195,7 @@ pub(crate) struct ChatComposer {
     // When true, disables paste-burst logic and inserts characters immediately.
     disable_paste_burst: bool,
     custom_prompts: Vec<CustomPrompt>,
+    mcp_prompts: Vec<McpPromptCommand>,
     footer_mode: FooterMode,
     footer_hint_override: Option<Vec<(String, String)>>,
     context_window_percent: Option<i64>,
@@ -238,6 +247,7 @@ impl ChatComposer {
             paste_burst: PasteBurst::default(),
             disable_paste_burst: false,
             custom_prompts: Vec::new(),
+            mcp_prompts: Vec::new(),
             footer_mode: FooterMode::ShortcutSummary,
             footer_hint_override: None,
             context_window_percent: None,
@@ -719,6 +729,19 @@ impl ChatComposer {
                                 }
                             }
                         }
+                        CommandItem::McpPrompt(idx) => {
+                            if let Some(command) = popup.mcp_prompt(idx) {
+                                let (text, cursor) = if command.has_arguments() {
+                                    command.command_with_arg_placeholders()
+                                } else {
+                                    let text = format!("/{}", command.command());
+                                    let cursor = text.len();
+                                    (text, cursor)
+                                };
+                                self.textarea.set_text(&text);
+                                cursor_target = Some(cursor);
+                            }
+                        }
                     }
                     if let Some(pos) = cursor_target {
                         self.textarea.set_cursor(pos);
@@ -772,6 +795,24 @@ impl ChatComposer {
                             }
                             return (InputResult::None, true);
                         }
+                        CommandItem::McpPrompt(idx) => {
+                            // Submission parses the arguments once the full
+                            // command is in the composer.
+                            if let Some(command) = popup.mcp_prompt(idx) {
+                                let typed_command = parse_slash_name(first_line)
+                                    .is_some_and(|(name, _)| name == command.command());
+                                if !typed_command {
+                                    if command.has_arguments() {
+                                        let (text, cursor) =
+                                            command.command_with_arg_placeholders();
+                                        self.textarea.set_text(&text);
+                                        self.textarea.set_cursor(cursor);
+                                        return (InputResult::None, true);
+                                    }
+                                    self.textarea.set_text(&format!("/{}", command.command()));
+                                }
+                            }
+                        }
                     }
                 }
                 // Fallback to default newline handling if no command selected.
@@ -1417,6 +1458,9 @@ impl ChatComposer {
         if let Some(result) = self.try_dispatch_slash_command_with_args() {
             return (result, true);
         }
+        if let Some(result) = self.try_dispatch_mcp_prompt() {
+            return (result, true);
+        }
 
         if let Some(text) = self.prepare_submission_text() {
             if should_queue {
@@ -1475,6 +1519,41 @@ impl ChatComposer {
         None
     }
 
+    /// Check if the input invokes an MCP prompt (e.g., /server:name key=value) and dispatch it.
+    /// Returns Some(InputResult) if the input named a known MCP prompt, None otherwise.
+    fn try_dispatch_mcp_prompt(&mut self) -> Option<InputResult> {
+        let mut text = self.textarea.text().to_string();
+        if text.starts_with(' ') {
+            return None;
+        }
+        for (placeholder, actual) in &self.pending_pastes {
+            if text.contains(placeholder) {
+                text = text.replace(placeholder, actual);
+            }
+        }
+        let text = text.trim();
+        match parse_mcp_prompt_invocation(text, &self.mcp_prompts) {
+            Ok(Some((command, arguments))) => {
+                let result = InputResult::McpPrompt {
+                    server: command.server.clone(),
+                    name: command.prompt.name.clone(),
+                    arguments,
+                };
+                self.history.record_local_submission(text);
+                self.textarea.set_text("");
+                self.pending_pastes.clear();
+                Some(result)
+            }
+            Ok(None) => None,
+            Err(err) => {
+                self.app_event_tx.send(AppEvent::InsertHistoryCell(Box::new(
+                    history_cell::new_error_event(err.user_message()),
+                )));
+                Some(InputResult::None)
+            }
+        }
+    }
+
     /// Handle key event when no popup is visible.
     fn handle_key_event_without_popup(&mut self, key_event: KeyEvent) -> (InputResult, bool) {
         if self.handle_shortcut_overlay_key(&key_event) {
@@ -1929,6 +2008,10 @@ impl ChatComposer {
         self.custom_prompts
             .iter()
             .any(|p| fuzzy_match(&format!("{prompt_prefix}{}", p.name), name).is_some())
+            || self
+                .mcp_prompts
+                .iter()
+                .any(|p| fuzzy_match(&p.command(), name).is_some())
     }
 
     /// Synchronize `self.command_popup` with the current text in the
@@ -1974,6 +2057,7 @@ impl ChatComposer {
                     let skills_enabled = self.skills_enabled();
                     let mut command_popup =
                         CommandPopup::new(self.custom_prompts.clone(), skills_enabled);
+                    command_popup.set_mcp_prompts(self.mcp_prompts.clone());
                     command_popup.on_composer_text_change(first_line.to_string());
                     self.active_popup = ActivePopup::Command(command_popup);
                 }
@@ -1988,6 +2072,13 @@ impl ChatComposer {
         }
     }
 
+    pub(crate) fn set_mcp_prompts(&mut self, prompts: Vec<McpPromptCommand>) {
+        self.mcp_prompts = prompts.clone();
+        if let ActivePopup::Command(popup) = &mut self.active_popup {
+            popup.set_mcp_prompts(prompts);
+        }
+    }
+
     /// Synchronize `self.file_search_popup` with the current text in the textarea.
     /// Note this is only called when self.active_popup is NOT Command.
     fn sync_file_search_popup(&mut self, query: String) {
@@ -3306,7 +3397,7 @@ mod tests {
                 Some(CommandItem::Builtin(cmd)) => {
                     assert_eq!(cmd.command(), "model")
                 }
-                Some(CommandItem::UserPrompt(_)) => {
+                Some(CommandItem::UserPrompt(_) | CommandItem::McpPrompt(_)) => {
                     panic!("unexpected prompt selected for '/mo'")
                 }
                 None => panic!("no selected command for '/mo'"),
@@ -3363,7 +3454,7 @@ mod tests {
                 Some(CommandItem::Builtin(cmd)) => {
                     assert_eq!(cmd.command(), "resume")
                 }
-                Some(CommandItem::UserPrompt(_)) => {
+                Some(CommandItem::UserPrompt(_) | CommandItem::McpPrompt(_)) => {
                     panic!("unexpected prompt selected for '/res'")
                 }
                 None => panic!("no selected command for '/res'"),
@@ -4172,6 +4263,52 @@ mod tests {
         assert!(composer.textarea.is_empty());
     }
 
+    #[test]
+    fn mcp_prompt_submission_dispatches_arguments() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let sender = AppEventSender::new(tx);
+        let mut composer = ChatComposer::new(
+            true,
+            sender,
+            false,
+            "Ask Codex to do anything".to_string(),
+            false,
+        );
+        composer.set_steer_enabled(true);
+
+        composer.set_mcp_prompts(vec![McpPromptCommand {
+            server: "docs".to_string(),
+            prompt: mcp_types::Prompt {
+                arguments: Some(vec![mcp_types::PromptArgument {
+                    description: None,
+                    name: "topic".to_string(),
+                    required: Some(true),
+                    title: None,
+                }]),
+                description: None,
+                name: "summarize".to_string(),
+                title: None,
+            },
+        }]);
+
+        composer
+            .textarea
+            .set_text("/docs:summarize topic=\"release notes\"");
+
+        let (result, _needs_redraw) =
+            composer.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+        assert_eq!(
+            InputResult::McpPrompt {
+                server: "docs".to_string(),
+                name: "summarize".to_string(),
+                arguments: HashMap::from([("topic".to_string(), "release notes".to_string())]),
+            },
+            result
+        );
+        assert!(composer.textarea.is_empty());
+    }
+
     #[test]
     fn custom_prompt_submission_accepts_quoted_values() {
         let (tx, _rx) = unbounded_channel::<AppEvent>();
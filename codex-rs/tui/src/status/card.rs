@@ -52,6 +52,7 @@ pub(crate) struct StatusTokenUsageData {
     total: i64,
     input: i64,
     output: i64,
+    cache_hit_ratio: Option<f64>,
     context_window: Option<StatusContextWindowData>,
 }
 
@@ -59,6 +60,7 @@ pub(crate) struct StatusTokenUsageData {
 struct StatusHistoryCell {
     model_name: String,
     model_details: Vec<String>,
+    sampling: Option<String>,
     directory: PathBuf,
     approval: String,
     sandbox: String,
@@ -113,6 +115,10 @@ impl StatusHistoryCell {
     ) -> Self {
         let config_entries = create_config_summary_entries(config, model_name);
         let (model_name, model_details) = compose_model_display(model_name, &config_entries);
+        let sampling = config_entries
+            .iter()
+            .find(|(k, _)| *k == "sampling")
+            .map(|(_, v)| v.clone());
         let approval = config_entries
             .iter()
             .find(|(k, _)| *k == "approval")
@@ -120,7 +126,7 @@ impl StatusHistoryCell {
             .unwrap_or_else(|| "<unknown>".to_string());
         let sandbox = match config.sandbox_policy.get() {
             SandboxPolicy::DangerFullAccess => "danger-full-access".to_string(),
-            SandboxPolicy::ReadOnly => "read-only".to_string(),
+            SandboxPolicy::ReadOnly { .. } => "read-only".to_string(),
             SandboxPolicy::WorkspaceWrite { .. } => "workspace-write".to_string(),
             SandboxPolicy::ExternalSandbox { network_access } => {
                 if matches!(network_access, NetworkAccess::Enabled) {
@@ -149,6 +155,7 @@ impl StatusHistoryCell {
             total: total_usage.blended_total(),
             input: total_usage.non_cached_input(),
             output: total_usage.output_tokens,
+            cache_hit_ratio: token_info.and_then(|info| info.last_token_usage.cache_hit_ratio()),
             context_window,
         };
         let rate_limits = compose_rate_limit_data(rate_limits, now);
@@ -156,6 +163,7 @@ impl StatusHistoryCell {
         Self {
             model_name,
             model_details,
+            sampling,
             directory: config.cwd.clone(),
             approval,
             sandbox,
@@ -345,6 +353,9 @@ impl HistoryCell for StatusHistoryCell {
         if self.model_provider.is_some() {
             push_label(&mut labels, &mut seen, "Model provider");
         }
+        if self.sampling.is_some() {
+            push_label(&mut labels, &mut seen, "Sampling");
+        }
         if account_value.is_some() {
             push_label(&mut labels, &mut seen, "Account");
         }
@@ -352,6 +363,9 @@ impl HistoryCell for StatusHistoryCell {
             push_label(&mut labels, &mut seen, "Session");
         }
         push_label(&mut labels, &mut seen, "Token usage");
+        if self.token_usage.cache_hit_ratio.is_some() {
+            push_label(&mut labels, &mut seen, "Prompt cache");
+        }
         if self.token_usage.context_window.is_some() {
             push_label(&mut labels, &mut seen, "Context window");
         }
@@ -391,6 +405,9 @@ impl HistoryCell for StatusHistoryCell {
         if let Some(model_provider) = self.model_provider.as_ref() {
             lines.push(formatter.line("Model provider", vec![Span::from(model_provider.clone())]));
         }
+        if let Some(sampling) = self.sampling.as_ref() {
+            lines.push(formatter.line("Sampling", vec![Span::from(sampling.clone())]));
+        }
         lines.push(formatter.line("Directory", vec![Span::from(directory_value)]));
         lines.push(formatter.line("Approval", vec![Span::from(self.approval.clone())]));
         lines.push(formatter.line("Sandbox", vec![Span::from(self.sandbox.clone())]));
@@ -408,6 +425,15 @@ impl HistoryCell for StatusHistoryCell {
         // Hide token usage only for ChatGPT subscribers
         if !matches!(self.account, Some(StatusAccountDisplay::ChatGpt { .. })) {
             lines.push(formatter.line("Token usage", self.token_usage_spans()));
+            if let Some(ratio) = self.token_usage.cache_hit_ratio {
+                lines.push(formatter.line(
+                    "Prompt cache",
+                    vec![
+                        Span::from(format!("{:.0}%", ratio * 100.0)),
+                        Span::from(" of last turn's input cached").dim(),
+                    ],
+                ));
+            }
         }
 
         if let Some(spans) = self.context_window_spans() {
@@ -100,6 +100,7 @@ async fn status_snapshot_includes_reasoning_details() {
         .sandbox_policy
         .set(SandboxPolicy::WorkspaceWrite {
             writable_roots: Vec::new(),
+            readable_roots: None,
             network_access: false,
             exclude_tmpdir_env_var: false,
             exclude_slash_tmp: false,
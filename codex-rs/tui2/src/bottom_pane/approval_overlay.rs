@@ -20,6 +20,7 @@ use codex_core::features::Feature;
 use codex_core::features::Features;
 use codex_core::protocol::ElicitationAction;
 use codex_core::protocol::ExecPolicyAmendment;
+use codex_core::protocol::ExecPolicyAmendmentScope;
 use codex_core::protocol::FileChange;
 use codex_core::protocol::Op;
 use codex_core::protocol::ReviewDecision;
@@ -467,19 +468,40 @@ fn exec_options(
                     return None;
                 }
 
-                Some(ApprovalOption {
-                    label: format!(
-                        "Yes, and don't ask again for commands that start with `{rendered_prefix}`"
-                    ),
-                    decision: ApprovalDecision::Review(
-                        ReviewDecision::ApprovedExecpolicyAmendment {
-                            proposed_execpolicy_amendment: prefix,
-                        },
-                    ),
-                    display_shortcut: None,
-                    additional_shortcuts: vec![key_hint::plain(KeyCode::Char('p'))],
-                })
-            }),
+                let approved_by = local_approver();
+                Some([
+                    ApprovalOption {
+                        label: format!(
+                            "Yes, and don't ask again for commands that start with `{rendered_prefix}`"
+                        ),
+                        decision: ApprovalDecision::Review(
+                            ReviewDecision::ApprovedExecpolicyAmendment {
+                                proposed_execpolicy_amendment: prefix.clone(),
+                                scope: None,
+                                approved_by: approved_by.clone(),
+                            },
+                        ),
+                        display_shortcut: None,
+                        additional_shortcuts: vec![key_hint::plain(KeyCode::Char('p'))],
+                    },
+                    ApprovalOption {
+                        label: format!(
+                            "Yes, and don't ask again this session for commands that start with `{rendered_prefix}`"
+                        ),
+                        decision: ApprovalDecision::Review(
+                            ReviewDecision::ApprovedExecpolicyAmendment {
+                                proposed_execpolicy_amendment: prefix,
+                                scope: Some(ExecPolicyAmendmentScope::Session),
+                                approved_by,
+                            },
+                        ),
+                        display_shortcut: None,
+                        additional_shortcuts: vec![key_hint::plain(KeyCode::Char('s'))],
+                    },
+                ])
+            })
+            .into_iter()
+            .flatten(),
     )
     .chain([ApprovalOption {
         label: "No, and tell Codex what to do differently".to_string(),
@@ -490,6 +512,13 @@ fn exec_options(
     .collect()
 }
 
+/// The person at this terminal, recorded as the approver of persisted "always allow" rules.
+fn local_approver() -> Option<String> {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .ok()
+}
+
 fn patch_options() -> Vec<ApprovalOption> {
     vec![
         ApprovalOption {
@@ -606,7 +635,9 @@ mod tests {
                     ReviewDecision::ApprovedExecpolicyAmendment {
                         proposed_execpolicy_amendment: ExecPolicyAmendment::new(vec![
                             "echo".to_string()
-                        ])
+                        ]),
+                        scope: None,
+                        approved_by: local_approver(),
                     }
                 );
                 saw_op = true;
@@ -619,6 +650,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn exec_session_prefix_option_scopes_amendment_to_session() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(
+            ApprovalRequest::Exec {
+                id: "test".to_string(),
+                command: vec!["echo".to_string()],
+                reason: None,
+                proposed_execpolicy_amendment: Some(ExecPolicyAmendment::new(vec![
+                    "echo".to_string(),
+                ])),
+            },
+            tx,
+            Features::with_defaults(),
+        );
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::NONE));
+        let mut scope = None;
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::CodexOp(Op::ExecApproval {
+                decision: ReviewDecision::ApprovedExecpolicyAmendment { scope: chosen, .. },
+                ..
+            }) = ev
+            {
+                scope = chosen;
+                break;
+            }
+        }
+        assert_eq!(scope, Some(ExecPolicyAmendmentScope::Session));
+    }
+
     #[test]
     fn exec_prefix_option_hidden_when_execpolicy_disabled() {
         let (tx, mut rx) = unbounded_channel::<AppEvent>();
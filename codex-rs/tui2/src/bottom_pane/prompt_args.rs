@@ -1,6 +1,10 @@
 use codex_protocol::custom_prompts::CustomPrompt;
 use codex_protocol::custom_prompts::PROMPTS_CMD_PREFIX;
 use lazy_static::lazy_static;
+use mcp_types::ContentBlock;
+use mcp_types::EmbeddedResourceResource;
+use mcp_types::GetPromptResult;
+use mcp_types::Prompt as McpPrompt;
 use regex_lite::Regex;
 use shlex::Shlex;
 use std::collections::HashMap;
@@ -299,7 +303,11 @@ pub fn expand_numeric_placeholders(content: &str, args: &[String]) -> String {
 /// Constructs a command text for a custom prompt with arguments.
 /// Returns the text and the cursor position (inside the first double quote).
 pub fn prompt_command_with_arg_placeholders(name: &str, args: &[String]) -> (String, usize) {
-    let mut text = format!("/{PROMPTS_CMD_PREFIX}:{name}");
+    command_with_arg_placeholders(&format!("{PROMPTS_CMD_PREFIX}:{name}"), args)
+}
+
+fn command_with_arg_placeholders(command: &str, args: &[String]) -> (String, usize) {
+    let mut text = format!("/{command}");
     let mut cursor: usize = text.len();
     for (i, arg) in args.iter().enumerate() {
         text.push_str(format!(" {arg}=\"\"").as_str());
@@ -310,6 +318,127 @@ pub fn prompt_command_with_arg_placeholders(name: &str, args: &[String]) -> (Str
     (text, cursor)
 }
 
+/// A prompt offered by an MCP server, invoked as `/server:name`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct McpPromptCommand {
+    pub server: String,
+    pub prompt: McpPrompt,
+}
+
+impl McpPromptCommand {
+    /// The command token after the leading '/'.
+    pub fn command(&self) -> String {
+        format!("{}:{}", self.server, self.prompt.name)
+    }
+
+    fn argument_names(&self) -> Vec<String> {
+        self.prompt
+            .arguments
+            .iter()
+            .flatten()
+            .map(|arg| arg.name.clone())
+            .collect()
+    }
+
+    fn required_argument_names(&self) -> Vec<String> {
+        self.prompt
+            .arguments
+            .iter()
+            .flatten()
+            .filter(|arg| arg.required.unwrap_or(false))
+            .map(|arg| arg.name.clone())
+            .collect()
+    }
+
+    /// Command text with a `name=""` placeholder per argument, and the cursor
+    /// position inside the first placeholder.
+    pub fn command_with_arg_placeholders(&self) -> (String, usize) {
+        command_with_arg_placeholders(&self.command(), &self.argument_names())
+    }
+
+    pub fn has_arguments(&self) -> bool {
+        self.prompt
+            .arguments
+            .as_ref()
+            .is_some_and(|args| !args.is_empty())
+    }
+}
+
+/// Flattens MCP prompts grouped by server into commands sorted by command name.
+/// Servers named like the custom prompt namespace are skipped so their
+/// commands cannot shadow `/prompts:*`.
+pub fn mcp_prompt_commands(prompts: HashMap<String, Vec<McpPrompt>>) -> Vec<McpPromptCommand> {
+    let mut commands: Vec<McpPromptCommand> = prompts
+        .into_iter()
+        .filter(|(server, _)| server != PROMPTS_CMD_PREFIX)
+        .flat_map(|(server, prompts)| {
+            prompts.into_iter().map(move |prompt| McpPromptCommand {
+                server: server.clone(),
+                prompt,
+            })
+        })
+        .collect();
+    commands.sort_by_key(McpPromptCommand::command);
+    commands
+}
+
+/// Parses a message of the form `/server:name key=value …` that invokes one of
+/// `commands`.
+///
+/// Returns `Ok(None)` when the text does not name a known MCP prompt. On success
+/// it returns the matching command and the parsed arguments; otherwise it
+/// returns a descriptive error. A required argument left empty, such as the
+/// `key=""` placeholder inserted by the popup, counts as missing.
+pub fn parse_mcp_prompt_invocation<'a>(
+    text: &str,
+    commands: &'a [McpPromptCommand],
+) -> Result<Option<(&'a McpPromptCommand, HashMap<String, String>)>, PromptExpansionError> {
+    let Some((name, rest)) = parse_slash_name(text) else {
+        return Ok(None);
+    };
+    let Some(command) = commands.iter().find(|command| command.command() == name) else {
+        return Ok(None);
+    };
+    let inputs = parse_prompt_inputs(rest).map_err(|error| PromptExpansionError::Args {
+        command: format!("/{name}"),
+        error,
+    })?;
+    let missing: Vec<String> = command
+        .required_argument_names()
+        .into_iter()
+        .filter(|key| inputs.get(key).is_none_or(String::is_empty))
+        .collect();
+    if !missing.is_empty() {
+        return Err(PromptExpansionError::MissingArgs {
+            command: format!("/{name}"),
+            missing,
+        });
+    }
+    Ok(Some((command, inputs)))
+}
+
+/// Joins the text content of the messages returned by `prompts/get`. Images,
+/// audio and binary resources are skipped.
+pub fn mcp_prompt_text(result: &GetPromptResult) -> String {
+    result
+        .messages
+        .iter()
+        .filter_map(|message| match &message.content {
+            ContentBlock::TextContent(content) => Some(content.text.as_str()),
+            ContentBlock::EmbeddedResource(resource) => match &resource.resource {
+                EmbeddedResourceResource::TextResourceContents(contents) => {
+                    Some(contents.text.as_str())
+                }
+                EmbeddedResourceResource::BlobResourceContents(_) => None,
+            },
+            ContentBlock::ImageContent(_)
+            | ContentBlock::AudioContent(_)
+            | ContentBlock::ResourceLink(_) => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -403,4 +532,78 @@ mod tests {
         let out = expand_custom_prompt("/prompts:my-prompt", &prompts).unwrap();
         assert_eq!(out, Some("literal $$USER".to_string()));
     }
+
+    fn mcp_command(arguments: Vec<(&str, bool)>) -> McpPromptCommand {
+        McpPromptCommand {
+            server: "docs".to_string(),
+            prompt: McpPrompt {
+                arguments: Some(
+                    arguments
+                        .into_iter()
+                        .map(|(name, required)| mcp_types::PromptArgument {
+                            description: None,
+                            name: name.to_string(),
+                            required: Some(required),
+                            title: None,
+                        })
+                        .collect(),
+                ),
+                description: None,
+                name: "summarize".to_string(),
+                title: None,
+            },
+        }
+    }
+
+    #[test]
+    fn parse_mcp_prompt_invocation_collects_arguments() {
+        let commands = vec![mcp_command(vec![("topic", true), ("tone", false)])];
+
+        let (command, arguments) =
+            parse_mcp_prompt_invocation("/docs:summarize topic=\"release notes\"", &commands)
+                .unwrap()
+                .unwrap();
+
+        assert_eq!(command.command(), "docs:summarize");
+        assert_eq!(
+            arguments,
+            HashMap::from([("topic".to_string(), "release notes".to_string())])
+        );
+        assert!(
+            parse_mcp_prompt_invocation("/docs:unknown", &commands)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn parse_mcp_prompt_invocation_reports_missing_required_arguments() {
+        let commands = vec![mcp_command(vec![("topic", true), ("tone", false)])];
+
+        let err = parse_mcp_prompt_invocation("/docs:summarize tone=dry", &commands).unwrap_err();
+
+        assert_eq!(
+            err.user_message(),
+            "Missing required args for /docs:summarize: topic. Provide as key=value (quote values with spaces)."
+        );
+
+        // The placeholder inserted by the popup does not count as a value.
+        let err = parse_mcp_prompt_invocation("/docs:summarize topic=\"\" tone=\"\"", &commands)
+            .unwrap_err();
+
+        assert_eq!(
+            err.user_message(),
+            "Missing required args for /docs:summarize: topic. Provide as key=value (quote values with spaces)."
+        );
+    }
+
+    #[test]
+    fn mcp_prompt_command_inserts_placeholders_for_all_arguments() {
+        let command = mcp_command(vec![("topic", true), ("tone", false)]);
+
+        assert_eq!(
+            command.command_with_arg_placeholders(),
+            ("/docs:summarize topic=\"\" tone=\"\"".to_string(), 23)
+        );
+    }
 }
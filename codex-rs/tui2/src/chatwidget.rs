@@ -55,6 +55,7 @@ use codex_core::protocol::ExecCommandBeginEvent;
 use codex_core::protocol::ExecCommandEndEvent;
 use codex_core::protocol::ExecCommandSource;
 use codex_core::protocol::ExitedReviewModeEvent;
+use codex_core::protocol::GetMcpPromptResponseEvent;
 use codex_core::protocol::ListCustomPromptsResponseEvent;
 use codex_core::protocol::ListSkillsResponseEvent;
 use codex_core::protocol::McpListToolsResponseEvent;
@@ -63,11 +64,13 @@ use codex_core::protocol::McpStartupStatus;
 use codex_core::protocol::McpStartupUpdateEvent;
 use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
+use codex_core::protocol::ModelFallbackEvent;
 use codex_core::protocol::Op;
 use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::RateLimitSnapshot;
 use codex_core::protocol::ReviewRequest;
 use codex_core::protocol::ReviewTarget;
+use codex_core::protocol::SecretsRedactedEvent;
 use codex_core::protocol::SkillsListEntry;
 use codex_core::protocol::StreamErrorEvent;
 use codex_core::protocol::TerminalInteractionEvent;
@@ -83,6 +86,8 @@ use codex_core::protocol::ViewImageToolCallEvent;
 use codex_core::protocol::WarningEvent;
 use codex_core::protocol::WebSearchBeginEvent;
 use codex_core::protocol::WebSearchEndEvent;
+use codex_core::protocol::WorkspaceOverlayDiffEvent;
+use codex_core::protocol::WorkspaceOverlayResolvedEvent;
 use codex_core::skills::model::SkillMetadata;
 use codex_protocol::ThreadId;
 use codex_protocol::account::PlanType;
@@ -119,6 +124,7 @@ use crate::bottom_pane::SelectionAction;
 use crate::bottom_pane::SelectionItem;
 use crate::bottom_pane::SelectionViewParams;
 use crate::bottom_pane::custom_prompt_view::CustomPromptView;
+use crate::bottom_pane::mcp_prompt_text;
 use crate::bottom_pane::popup_consts::standard_popup_hint_line;
 use crate::clipboard_paste::paste_image_to_temp_png;
 use crate::diff_render::display_path_for;
@@ -782,6 +788,20 @@ impl ChatWidget {
         self.request_redraw();
     }
 
+    fn on_model_fallback(&mut self, ev: ModelFallbackEvent) {
+        self.on_warning(format!(
+            "{} ({}) failed: {}. Falling back to {} ({}).",
+            ev.from_model, ev.from_provider, ev.reason, ev.to_model, ev.to_provider
+        ));
+    }
+
+    fn on_secrets_redacted(&mut self, ev: SecretsRedactedEvent) {
+        self.on_warning(format!(
+            "Redacted {} secret value(s) from tool output.",
+            ev.redactions
+        ));
+    }
+
     fn on_mcp_startup_update(&mut self, ev: McpStartupUpdateEvent) {
         let mut status = self.mcp_startup_status.take().unwrap_or_default();
         if let McpStartupStatus::Failed { error } = &ev.status {
@@ -845,6 +865,9 @@ impl ChatWidget {
         }
 
         self.mcp_startup_status = None;
+        // Prompts of servers that were still starting were left out of the
+        // first listing.
+        self.submit_op(Op::ListCustomPrompts);
         self.update_task_running_state();
         self.maybe_send_next_queued_input();
         self.request_redraw();
@@ -1004,6 +1027,93 @@ impl ChatWidget {
         debug!("TurnDiffEvent: {unified_diff}");
     }
 
+    fn on_workspace_overlay_diff(&mut self, unified_diff: String) {
+        let changed_files: Vec<&str> = unified_diff
+            .lines()
+            .filter_map(|line| line.strip_prefix("diff --git "))
+            .filter_map(|paths| paths.split_once(" b/").map(|(_, path)| path))
+            .collect();
+
+        let mut header_children: Vec<Box<dyn Renderable>> = Vec::new();
+        header_children.push(Box::new(Line::from("Apply sandboxed changes?").bold()));
+        header_children.push(Box::new(
+            Paragraph::new(vec![Line::from(format!(
+                "Commands ran against a copy of the workspace. {} file(s) changed; nothing has been written to the workspace yet.",
+                changed_files.len()
+            ))])
+            .wrap(Wrap { trim: false }),
+        ));
+        const MAX_LISTED_FILES: usize = 8;
+        for path in changed_files.iter().take(MAX_LISTED_FILES) {
+            header_children.push(Box::new(Line::from(format!("  {path}")).dim()));
+        }
+        if changed_files.len() > MAX_LISTED_FILES {
+            header_children.push(Box::new(
+                Line::from(format!(
+                    "  … and {} more",
+                    changed_files.len() - MAX_LISTED_FILES
+                ))
+                .dim(),
+            ));
+        }
+        let header = ColumnRenderable::with(header_children);
+
+        let review_actions: Vec<SelectionAction> = vec![Box::new(move |tx| {
+            tx.send(AppEvent::DiffResult(unified_diff.clone()));
+        })];
+        let items = vec![
+            SelectionItem {
+                name: "Apply changes".to_string(),
+                description: Some("Write the changes to the workspace".to_string()),
+                actions: vec![Box::new(|tx| {
+                    tx.send(AppEvent::CodexOp(Op::CommitWorkspaceOverlay));
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+            SelectionItem {
+                name: "Discard changes".to_string(),
+                description: Some("Drop the changes and keep the workspace as it is".to_string()),
+                actions: vec![Box::new(|tx| {
+                    tx.send(AppEvent::CodexOp(Op::DiscardWorkspaceOverlay));
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+            SelectionItem {
+                name: "Review diff".to_string(),
+                description: Some("Show the full diff before deciding".to_string()),
+                actions: review_actions,
+                dismiss_on_select: false,
+                ..Default::default()
+            },
+            SelectionItem {
+                name: "Decide later".to_string(),
+                description: Some(
+                    "Keep the changes pending; later commands keep building on them".to_string(),
+                ),
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+        ];
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            header: Box::new(header),
+            ..Default::default()
+        });
+    }
+
+    fn on_workspace_overlay_resolved(&mut self, event: WorkspaceOverlayResolvedEvent) {
+        let message = if event.committed {
+            "Sandboxed changes applied to the workspace."
+        } else {
+            "Sandboxed changes discarded."
+        };
+        self.add_info_message(message.to_string(), None);
+    }
+
     fn on_deprecation_notice(&mut self, event: DeprecationNoticeEvent) {
         let DeprecationNoticeEvent { summary, details } = event;
         self.add_to_history(history_cell::new_deprecation_notice(summary, details));
@@ -1619,6 +1729,17 @@ impl ChatWidget {
                     InputResult::CommandWithArgs(cmd, args) => {
                         self.dispatch_command_with_args(cmd, args);
                     }
+                    InputResult::McpPrompt {
+                        server,
+                        name,
+                        arguments,
+                    } => {
+                        self.submit_op(Op::GetMcpPrompt {
+                            server,
+                            name,
+                            arguments,
+                        });
+                    }
                     InputResult::None => {}
                 }
             }
@@ -2037,6 +2158,8 @@ impl ChatWidget {
                 self.on_rate_limit_snapshot(ev.rate_limits);
             }
             EventMsg::Warning(WarningEvent { message }) => self.on_warning(message),
+            EventMsg::ModelFallback(ev) => self.on_model_fallback(ev),
+            EventMsg::SecretsRedacted(ev) => self.on_secrets_redacted(ev),
             EventMsg::Error(ErrorEvent { message, .. }) => self.on_error(message),
             EventMsg::McpStartupUpdate(ev) => self.on_mcp_startup_update(ev),
             EventMsg::McpStartupComplete(ev) => self.on_mcp_startup_complete(ev),
@@ -2076,6 +2199,7 @@ impl ChatWidget {
             EventMsg::GetHistoryEntryResponse(ev) => self.on_get_history_entry_response(ev),
             EventMsg::McpListToolsResponse(ev) => self.on_list_mcp_tools(ev),
             EventMsg::ListCustomPromptsResponse(ev) => self.on_list_custom_prompts(ev),
+            EventMsg::GetMcpPromptResponse(ev) => self.on_get_mcp_prompt_response(ev),
             EventMsg::ListSkillsResponse(ev) => self.on_list_skills(ev),
             EventMsg::SkillsUpdateAvailable => {
                 self.submit_op(Op::ListSkills {
@@ -2085,6 +2209,10 @@ impl ChatWidget {
             }
             EventMsg::ShutdownComplete => self.on_shutdown_complete(),
             EventMsg::TurnDiff(TurnDiffEvent { unified_diff }) => self.on_turn_diff(unified_diff),
+            EventMsg::WorkspaceOverlayDiff(WorkspaceOverlayDiffEvent { unified_diff }) => {
+                self.on_workspace_overlay_diff(unified_diff)
+            }
+            EventMsg::WorkspaceOverlayResolved(ev) => self.on_workspace_overlay_resolved(ev),
             EventMsg::DeprecationNotice(ev) => self.on_deprecation_notice(ev),
             EventMsg::BackgroundEvent(BackgroundEventEvent { message }) => {
                 self.on_background_event(message)
@@ -2108,6 +2236,7 @@ impl ChatWidget {
             EventMsg::ContextCompacted(_) => self.on_agent_message("Context compacted".to_owned()),
             EventMsg::RawResponseItem(_)
             | EventMsg::ThreadRolledBack(_)
+            | EventMsg::CallToolResponse(_)
             | EventMsg::ItemStarted(_)
             | EventMsg::ItemCompleted(_)
             | EventMsg::AgentMessageContentDelta(_)
@@ -2345,6 +2474,7 @@ impl ChatWidget {
                 model: Some(switch_model.clone()),
                 effort: Some(Some(default_effort)),
                 summary: None,
+                sampling: None,
             }));
             tx.send(AppEvent::UpdateModel(switch_model.clone()));
             tx.send(AppEvent::UpdateReasoningEffort(Some(default_effort)));
@@ -2568,6 +2698,7 @@ impl ChatWidget {
                 model: Some(model_for_action.clone()),
                 effort: Some(effort_for_action),
                 summary: None,
+                sampling: None,
             }));
             tx.send(AppEvent::UpdateModel(model_for_action.clone()));
             tx.send(AppEvent::UpdateReasoningEffort(effort_for_action));
@@ -2739,6 +2870,7 @@ impl ChatWidget {
                 model: Some(model.clone()),
                 effort: Some(effort),
                 summary: None,
+                sampling: None,
             }));
         self.app_event_tx.send(AppEvent::UpdateModel(model.clone()));
         self.app_event_tx
@@ -2884,6 +3016,7 @@ impl ChatWidget {
                 model: None,
                 effort: None,
                 summary: None,
+                sampling: None,
             }));
             tx.send(AppEvent::UpdateAskForApprovalPolicy(approval));
             tx.send(AppEvent::UpdateSandboxPolicy(sandbox_clone));
@@ -2900,15 +3033,16 @@ impl ChatWidget {
         }
         matches!(
             (&preset.sandbox, current_sandbox),
-            (SandboxPolicy::ReadOnly, SandboxPolicy::ReadOnly)
-                | (
-                    SandboxPolicy::DangerFullAccess,
-                    SandboxPolicy::DangerFullAccess
-                )
-                | (
-                    SandboxPolicy::WorkspaceWrite { .. },
-                    SandboxPolicy::WorkspaceWrite { .. }
-                )
+            (
+                SandboxPolicy::new_read_only_policy(),
+                SandboxPolicy::new_read_only_policy()
+            ) | (
+                SandboxPolicy::DangerFullAccess,
+                SandboxPolicy::DangerFullAccess
+            ) | (
+                SandboxPolicy::WorkspaceWrite { .. },
+                SandboxPolicy::WorkspaceWrite { .. }
+            )
         )
     }
 
@@ -3021,7 +3155,7 @@ impl ChatWidget {
         let mut header_children: Vec<Box<dyn Renderable>> = Vec::new();
         let describe_policy = |policy: &SandboxPolicy| match policy {
             SandboxPolicy::WorkspaceWrite { .. } => "Agent mode",
-            SandboxPolicy::ReadOnly => "Read-Only mode",
+            SandboxPolicy::ReadOnly { .. } => "Read-Only mode",
             _ => "Agent mode",
         };
         let mode_label = preset
@@ -3401,7 +3535,7 @@ impl ChatWidget {
     /// Set the sandbox policy in the widget's config copy.
     pub(crate) fn set_sandbox_policy(&mut self, policy: SandboxPolicy) -> ConstraintResult<()> {
         #[cfg(target_os = "windows")]
-        let should_clear_downgrade = !matches!(&policy, SandboxPolicy::ReadOnly)
+        let should_clear_downgrade = !matches!(&policy, SandboxPolicy::ReadOnly { .. })
             || codex_core::get_platform_sandbox().is_some();
 
         self.config.sandbox_policy.set(policy)?;
@@ -3585,6 +3719,32 @@ impl ChatWidget {
         debug!("received {len} custom prompts");
         // Forward to bottom pane so the slash popup can show them now.
         self.bottom_pane.set_custom_prompts(ev.custom_prompts);
+        self.bottom_pane.set_mcp_prompts(ev.mcp_prompts);
+    }
+
+    fn on_get_mcp_prompt_response(&mut self, ev: GetMcpPromptResponseEvent) {
+        let GetMcpPromptResponseEvent {
+            server,
+            name,
+            result,
+        } = ev;
+        match result {
+            Ok(result) => {
+                let text = mcp_prompt_text(&result);
+                if text.is_empty() {
+                    self.add_error_message(format!(
+                        "MCP prompt /{server}:{name} returned no text content."
+                    ));
+                    return;
+                }
+                self.submit_user_message(text.into());
+            }
+            Err(err) => {
+                self.add_error_message(format!(
+                    "Failed to load MCP prompt /{server}:{name}: {err}"
+                ));
+            }
+        }
     }
 
     fn on_list_skills(&mut self, ev: ListSkillsResponseEvent) {
@@ -0,0 +1,82 @@
+#!/usr/bin/env python3
+"""Convert a Hugging Face byte-level BPE `tokenizer.json` into tiktoken format.
+
+Usage:
+
+    ./scripts/convert_hf_tokenizer.py tokenizer.json deepseek_v3.tiktoken
+
+Point CODEX_DEEPSEEK_V3_VOCAB at the output converted from
+https://huggingface.co/deepseek-ai/DeepSeek-V3/blob/main/tokenizer.json.
+
+tiktoken merges the adjacent pair whose result has the lowest rank, while the
+Hugging Face BPE model applies merges in the order they are listed. Emitting
+single bytes first and then one rank per merge, in merge order, makes the two
+produce identical tokenizations. Added (special) tokens are skipped; Codex
+only counts ordinary text.
+"""
+
+import argparse
+import base64
+import json
+import sys
+from pathlib import Path
+
+
+def bytes_to_unicode() -> dict[str, int]:
+    """Inverse of GPT-2's byte-to-printable-character table."""
+    printable = (
+        list(range(ord("!"), ord("~") + 1))
+        + list(range(ord("¡"), ord("¬") + 1))
+        + list(range(ord("®"), ord("ÿ") + 1))
+    )
+    codepoints = printable[:]
+    extra = 0
+    for byte in range(256):
+        if byte not in printable:
+            printable.append(byte)
+            codepoints.append(256 + extra)
+            extra += 1
+    return {chr(cp): byte for byte, cp in zip(printable, codepoints)}
+
+
+def decode_token(token: str, table: dict[str, int]) -> bytes:
+    return bytes(table[ch] for ch in token)
+
+
+def main() -> int:
+    parser = argparse.ArgumentParser(description=__doc__.splitlines()[0])
+    parser.add_argument("tokenizer_json", type=Path)
+    parser.add_argument("output", type=Path)
+    args = parser.parse_args()
+
+    model = json.loads(args.tokenizer_json.read_text(encoding="utf-8"))["model"]
+    if model.get("type") != "BPE":
+        print(f"unsupported model type: {model.get('type')}", file=sys.stderr)
+        return 1
+
+    table = bytes_to_unicode()
+    ranks: dict[bytes, int] = {}
+    for byte in range(256):
+        ranks[bytes([byte])] = byte
+
+    for merge in model["merges"]:
+        left, right = merge.split(" ") if isinstance(merge, str) else merge
+        merged = decode_token(left, table) + decode_token(right, table)
+        if merged not in ranks:
+            ranks[merged] = len(ranks)
+
+    vocab = {decode_token(token, table) for token in model["vocab"]}
+    missing = vocab - ranks.keys()
+    if missing:
+        print(f"{len(missing)} vocabulary entries are unreachable by merges", file=sys.stderr)
+        return 1
+
+    with args.output.open("w", encoding="ascii") as out:
+        for token, rank in sorted(ranks.items(), key=lambda item: item[1]):
+            out.write(f"{base64.b64encode(token).decode('ascii')} {rank}\n")
+    print(f"wrote {len(ranks)} tokens to {args.output}")
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,255 @@
+//! Token counting used to budget prompts before they are sent to a model.
+//!
+//! Each model family maps to an [`Encoding`]; [`Encoding::tokenizer`] returns a
+//! shared [`Tokenizer`] for it. BPE vocabularies are loaded lazily on first use,
+//! so callers that never ask for a BPE encoding pay nothing.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use tiktoken_rs::CoreBPE;
+use tiktoken_rs::Rank;
+use tracing::warn;
+
+/// Bytes per token assumed by [`ApproxTokenizer`].
+pub const APPROX_BYTES_PER_TOKEN: usize = 4;
+
+/// Counts the tokens a model would see for a piece of text.
+pub trait Tokenizer: Debug + Send + Sync {
+    fn count_tokens(&self, text: &str) -> usize;
+
+    /// Returns the byte offset at which each token of `text` ends, in order.
+    /// The last offset is `text.len()`. Offsets may fall inside a multi-byte
+    /// character when the vocabulary splits one across tokens.
+    fn token_ends(&self, text: &str) -> Vec<usize>;
+}
+
+/// Tokenizer families known to Codex.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Encoding {
+    /// Byte-length heuristic; used when the model family is unknown.
+    #[default]
+    Approximate,
+    /// `o200k_base`, used by the GPT-4o, o-series and GPT-5 families.
+    O200kBase,
+    /// `cl100k_base`, used by GPT-4 and GPT-3.5.
+    Cl100kBase,
+    /// DeepSeek V3/R1 vocabulary.
+    DeepSeek,
+}
+
+impl Encoding {
+    pub fn tokenizer(self) -> &'static dyn Tokenizer {
+        match self {
+            Encoding::Approximate => &ApproxTokenizer,
+            Encoding::O200kBase => &O200K_BASE,
+            Encoding::Cl100kBase => &CL100K_BASE,
+            Encoding::DeepSeek => &DEEPSEEK_V3,
+        }
+    }
+}
+
+/// Estimates tokens as `ceil(bytes / 4)`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ApproxTokenizer;
+
+impl Tokenizer for ApproxTokenizer {
+    fn count_tokens(&self, text: &str) -> usize {
+        text.len().div_ceil(APPROX_BYTES_PER_TOKEN)
+    }
+
+    fn token_ends(&self, text: &str) -> Vec<usize> {
+        (1..=self.count_tokens(text))
+            .map(|token| (token * APPROX_BYTES_PER_TOKEN).min(text.len()))
+            .collect()
+    }
+}
+
+static O200K_BASE: BpeTokenizer = BpeTokenizer {
+    load: tiktoken_rs::o200k_base_singleton,
+};
+static CL100K_BASE: BpeTokenizer = BpeTokenizer {
+    load: tiktoken_rs::cl100k_base_singleton,
+};
+static DEEPSEEK_V3: BpeTokenizer = BpeTokenizer {
+    load: deepseek_v3_singleton,
+};
+
+/// Exact counts from a tiktoken vocabulary.
+pub struct BpeTokenizer {
+    load: fn() -> &'static CoreBPE,
+}
+
+impl Debug for BpeTokenizer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BpeTokenizer").finish_non_exhaustive()
+    }
+}
+
+impl Tokenizer for BpeTokenizer {
+    fn count_tokens(&self, text: &str) -> usize {
+        if text.is_empty() {
+            return 0;
+        }
+        (self.load)().encode_ordinary(text).len()
+    }
+
+    fn token_ends(&self, text: &str) -> Vec<usize> {
+        if text.is_empty() {
+            return Vec::new();
+        }
+        let bpe = (self.load)();
+        let mut end = 0;
+        bpe._decode_native_and_split(bpe.encode_ordinary(text))
+            .map(|bytes| {
+                end += bytes.len();
+                end
+            })
+            .collect()
+    }
+}
+
+/// Environment variable naming a DeepSeek V3/R1 vocabulary in tiktoken format,
+/// produced from the published `tokenizer.json` by
+/// `scripts/convert_hf_tokenizer.py`. Ranks follow the merge order, so they are
+/// not DeepSeek's token ids.
+pub const DEEPSEEK_V3_VOCAB_ENV_VAR: &str = "CODEX_DEEPSEEK_V3_VOCAB";
+
+/// DeepSeek applies its pre-tokenizer splits in sequence (digits, then CJK
+/// runs, then the GPT-style pattern). A single alternation is equivalent as
+/// long as the letter class excludes the CJK ranges the second split isolates.
+const DEEPSEEK_V3_PATTERN: &str = concat!(
+    r"\p{N}{1,3}",
+    r"|[一-龥\x{3040}-ゟ゠-ヿ]+",
+    r##"|[!"#$%&'()*+,\-./:;<=>?@\[\\\]^_`{|}~][A-Za-z]+"##,
+    r"|[^\r\n\p{L}\p{P}\p{S}]?[\p{L}\p{M}--[一-龥\x{3040}-ゟ゠-ヿ]]+",
+    r"| ?[\p{P}\p{S}]+[\r\n]*",
+    r"|\s*[\r\n]+",
+    r"|\s+(?!\S)",
+    r"|\s+",
+);
+
+/// The DeepSeek vocabulary is too large to bundle, so it is read on first use
+/// from [`DEEPSEEK_V3_VOCAB_ENV_VAR`]. Without it, or when it cannot be loaded,
+/// counts fall back to `o200k_base`, which is close enough for budgeting.
+fn deepseek_v3_singleton() -> &'static CoreBPE {
+    static BPE: LazyLock<Option<CoreBPE>> = LazyLock::new(load_deepseek_v3);
+    BPE.as_ref()
+        .unwrap_or_else(|| tiktoken_rs::o200k_base_singleton())
+}
+
+fn load_deepseek_v3() -> Option<CoreBPE> {
+    let path = std::env::var_os(DEEPSEEK_V3_VOCAB_ENV_VAR)?;
+    let loaded = std::fs::read_to_string(&path)
+        .map_err(anyhow::Error::from)
+        .and_then(|vocab| load_tiktoken_vocab(&vocab, DEEPSEEK_V3_PATTERN));
+    match loaded {
+        Ok(bpe) => Some(bpe),
+        Err(err) => {
+            warn!(
+                "failed to load the DeepSeek vocabulary from {} ({DEEPSEEK_V3_VOCAB_ENV_VAR}): {err:#}; counting tokens with o200k_base",
+                Path::new(&path).display()
+            );
+            None
+        }
+    }
+}
+
+/// Parses a vocabulary in tiktoken's `<base64 token> <rank>` line format.
+fn load_tiktoken_vocab(vocab: &str, pattern: &str) -> anyhow::Result<CoreBPE> {
+    let mut encoder = HashMap::default();
+    for line in vocab.lines().filter(|line| !line.is_empty()) {
+        let Some((token, rank)) = line.split_once(' ') else {
+            anyhow::bail!("malformed vocabulary line: {line}");
+        };
+        encoder.insert(BASE64_STANDARD.decode(token)?, rank.parse::<Rank>()?);
+    }
+    CoreBPE::new(encoder, HashMap::default(), pattern)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn approx_rounds_up_bytes() {
+        assert_eq!(ApproxTokenizer.count_tokens(""), 0);
+        assert_eq!(ApproxTokenizer.count_tokens("abcde"), 2);
+    }
+
+    #[test]
+    fn bpe_counts_match_vocabulary() {
+        let tokenizer = Encoding::O200kBase.tokenizer();
+        assert_eq!(tokenizer.count_tokens(""), 0);
+        assert_eq!(tokenizer.count_tokens("hello world"), 2);
+        assert_eq!(
+            Encoding::Cl100kBase.tokenizer().count_tokens("hello world"),
+            2
+        );
+    }
+
+    #[test]
+    fn bpe_counts_cjk_per_vocabulary() {
+        let text = "请帮我检查这个函数为什么在并发场景下会死锁，并给出修复方案。";
+        assert_eq!(Encoding::O200kBase.tokenizer().count_tokens(text), 23);
+        assert_eq!(Encoding::Cl100kBase.tokenizer().count_tokens(text), 32);
+    }
+
+    #[test]
+    fn token_ends_cover_the_text() {
+        let text = "请帮我检查 hello world 😀";
+        for encoding in [
+            Encoding::Approximate,
+            Encoding::O200kBase,
+            Encoding::Cl100kBase,
+            Encoding::DeepSeek,
+        ] {
+            let tokenizer = encoding.tokenizer();
+            let ends = tokenizer.token_ends(text);
+            assert_eq!(ends.len(), tokenizer.count_tokens(text), "{encoding:?}");
+            assert_eq!(ends.last(), Some(&text.len()), "{encoding:?}");
+            assert!(ends.windows(2).all(|w| w[0] < w[1]), "{encoding:?}");
+            assert_eq!(tokenizer.token_ends(""), Vec::<usize>::new());
+        }
+    }
+
+    /// Single bytes plus merges that would join `1234` into one token and `c`
+    /// with the first byte of `你`, if the pre-tokenizer allowed it.
+    fn deepseek_test_vocab() -> String {
+        let mut tokens: Vec<Vec<u8>> = (0..=u8::MAX).map(|byte| vec![byte]).collect();
+        tokens.extend([
+            b"12".to_vec(),
+            b"123".to_vec(),
+            b"1234".to_vec(),
+            vec![b'c', 0xe4],
+        ]);
+        tokens
+            .iter()
+            .enumerate()
+            .map(|(rank, token)| format!("{} {rank}\n", BASE64_STANDARD.encode(token)))
+            .collect()
+    }
+
+    #[test]
+    fn deepseek_isolates_digits_and_cjk_runs() {
+        static BPE: LazyLock<CoreBPE> = LazyLock::new(|| {
+            load_tiktoken_vocab(&deepseek_test_vocab(), DEEPSEEK_V3_PATTERN).expect("vocab")
+        });
+        let tokenizer = BpeTokenizer { load: || &BPE };
+
+        // Without the pre-tokenizer split both merges apply.
+        let unsplit = load_tiktoken_vocab(&deepseek_test_vocab(), r"(?s).+").expect("vocab");
+        assert_eq!(unsplit.encode_ordinary("1234").len(), 1);
+        assert_eq!(unsplit.encode_ordinary("c你").len(), 3);
+
+        // Digits are split into groups of at most three before BPE runs, and
+        // CJK runs never merge with adjacent Latin letters.
+        assert_eq!(tokenizer.token_ends("1234"), vec![3, 4]);
+        assert_eq!(tokenizer.token_ends("c你"), vec![1, 2, 3, 4]);
+    }
+}
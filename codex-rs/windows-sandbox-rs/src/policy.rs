@@ -3,7 +3,7 @@ pub use codex_protocol::protocol::SandboxPolicy;
 
 pub fn parse_policy(value: &str) -> Result<SandboxPolicy> {
     match value {
-        "read-only" => Ok(SandboxPolicy::ReadOnly),
+        "read-only" => Ok(SandboxPolicy::new_read_only_policy()),
         "workspace-write" => Ok(SandboxPolicy::new_workspace_write_policy()),
         "danger-full-access" | "external-sandbox" => anyhow::bail!(
             "DangerFullAccess and ExternalSandbox are not supported for sandboxing"
@@ -18,6 +18,11 @@ pub fn parse_policy(value: &str) -> Result<SandboxPolicy> {
                     "DangerFullAccess and ExternalSandbox are not supported for sandboxing"
                 );
             }
+            // Capability SIDs only restrict writes, so a policy that limits
+            // reads to `readable_roots` cannot be enforced here.
+            if !parsed.has_full_disk_read_access() {
+                anyhow::bail!("readable_roots is not supported by the Windows sandbox");
+            }
             Ok(parsed)
         }
     }
@@ -50,8 +55,20 @@ mod tests {
             .contains("DangerFullAccess and ExternalSandbox are not supported"));
     }
 
+    #[test]
+    fn rejects_readable_roots() {
+        let payload = serde_json::to_string(&SandboxPolicy::ReadOnly {
+            readable_roots: Some(Vec::new()),
+        })
+        .unwrap();
+        let err = parse_policy(&payload).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("readable_roots is not supported by the Windows sandbox"));
+    }
+
     #[test]
     fn parses_read_only_policy() {
-        assert_eq!(parse_policy("read-only").unwrap(), SandboxPolicy::ReadOnly);
+        assert_eq!(parse_policy("read-only").unwrap(), SandboxPolicy::new_read_only_policy());
     }
 }